@@ -0,0 +1,217 @@
+//! `DATABASE_URL=mysql://... cargo bench --features mysql --bench mysql`
+//!
+//! Add `--features bench-internals` to also measure the time spent in each phase of a query.
+
+use async_std::task::block_on;
+use criterion::{criterion_group, criterion_main, Criterion};
+use sqlx::arguments::Arguments;
+use sqlx::mysql::MySqlRow;
+use sqlx::{Connection, Executor, MySql, MySqlConnection, Row};
+
+mod common;
+
+#[global_allocator]
+static ALLOCATOR: common::CountingAllocator = common::CountingAllocator;
+
+fn connect() -> MySqlConnection {
+    block_on(sqlx_test::new::<MySql>()).unwrap()
+}
+
+// 1,000 rows of 8 BIGINT and 8 TEXT columns
+fn setup_rows(conn: &mut MySqlConnection) {
+    block_on(async {
+        conn.execute(
+            "CREATE TEMPORARY TABLE bench_rows (
+                i0 BIGINT, i1 BIGINT, i2 BIGINT, i3 BIGINT,
+                i4 BIGINT, i5 BIGINT, i6 BIGINT, i7 BIGINT,
+                t0 TEXT, t1 TEXT, t2 TEXT, t3 TEXT,
+                t4 TEXT, t5 TEXT, t6 TEXT, t7 TEXT
+            )",
+        )
+        .await?;
+
+        for i in 1..=1000_i64 {
+            let text = format!("row {}", i);
+
+            sqlx::query(
+                "INSERT INTO bench_rows VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(i)
+            .bind(i)
+            .bind(i)
+            .bind(i)
+            .bind(i)
+            .bind(i)
+            .bind(i)
+            .bind(i)
+            .bind(&*text)
+            .bind(&*text)
+            .bind(&*text)
+            .bind(&*text)
+            .bind(&*text)
+            .bind(&*text)
+            .bind(&*text)
+            .bind(&*text)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        Ok::<_, sqlx::Error>(())
+    })
+    .unwrap();
+}
+
+async fn fetch_rows(conn: &mut MySqlConnection) -> sqlx::Result<Vec<(i64, String)>> {
+    sqlx::query("SELECT * FROM bench_rows")
+        .try_map(|row: MySqlRow| {
+            let mut sum = 0;
+            let mut text = String::new();
+
+            for i in 0..8 {
+                sum += row.try_get::<i64, _>(i)?;
+                text = row.try_get::<String, _>(8 + i)?;
+            }
+
+            Ok((sum, text))
+        })
+        .fetch_all(conn)
+        .await
+}
+
+fn bench_connect(c: &mut Criterion) {
+    c.bench_function("mysql: connect", |b| {
+        b.iter(|| block_on(connect().close()).unwrap())
+    });
+}
+
+fn bench_prepare(c: &mut Criterion) {
+    let mut conn = connect();
+    let mut n = 0_u64;
+
+    // a new statement each time, so it is never found in the cache
+    c.bench_function("mysql: prepare", |b| {
+        b.iter(|| {
+            n += 1;
+
+            let query = format!("SELECT {}", n);
+
+            block_on(conn.describe(&*query)).unwrap()
+        })
+    });
+}
+
+fn bench_select(c: &mut Criterion) {
+    let mut conn = connect();
+
+    c.bench_function("mysql: execute small SELECT (simple)", |b| {
+        b.iter(|| block_on(conn.execute("SELECT 1")).unwrap())
+    });
+
+    c.bench_function("mysql: execute small SELECT (prepared)", |b| {
+        b.iter(|| block_on(sqlx::query("SELECT 1").execute(&mut conn)).unwrap())
+    });
+}
+
+fn bench_fetch(c: &mut Criterion) {
+    let mut conn = connect();
+
+    setup_rows(&mut conn);
+
+    c.bench_function("mysql: fetch 1k rows x 16 cols", |b| {
+        b.iter(|| block_on(fetch_rows(&mut conn)).unwrap())
+    });
+}
+
+// 100 single-row inserts, binding new arguments to each or reusing those of the last one
+async fn insert_rows(conn: &mut MySqlConnection, reuse_arguments: bool) -> sqlx::Result<()> {
+    for i in 0..100_i64 {
+        let query = sqlx::query("INSERT INTO bench_inserts (id, name) VALUES (?, ?)");
+
+        let query = if reuse_arguments {
+            let mut arguments = conn.take_arguments();
+            arguments.add(i);
+            arguments.add("name");
+
+            query.bind_all(arguments)
+        } else {
+            query.bind(i).bind("name")
+        };
+
+        query.execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut conn = connect();
+
+    block_on(conn.execute("CREATE TEMPORARY TABLE bench_inserts (id BIGINT, name TEXT)")).unwrap();
+
+    for &(name, reuse_arguments) in &[
+        ("mysql: insert 100 rows", false),
+        ("mysql: insert 100 rows (reused arguments)", true),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                block_on(async {
+                    insert_rows(&mut conn, reuse_arguments).await?;
+
+                    conn.execute("TRUNCATE bench_inserts").await
+                })
+                .unwrap()
+            })
+        });
+
+        // criterion only reports the time, so the allocations the reused arguments save are
+        // counted separately
+        let allocations = common::allocations(|| {
+            block_on(insert_rows(&mut conn, reuse_arguments)).unwrap();
+        });
+
+        println!("{}: {} allocations", name, allocations);
+
+        block_on(conn.execute("TRUNCATE bench_inserts")).unwrap();
+    }
+}
+
+#[cfg(feature = "bench-internals")]
+fn bench_phases(c: &mut Criterion) {
+    use sqlx::bench::{take_timings, Phase};
+
+    let mut conn = connect();
+
+    setup_rows(&mut conn);
+
+    let mut group = c.benchmark_group("mysql: fetch 1k rows x 16 cols by phase");
+
+    for &phase in &Phase::ALL {
+        group.bench_function(format!("{:?}", phase), |b| {
+            b.iter_custom(|iters| {
+                let _ = take_timings();
+
+                for _ in 0..iters {
+                    block_on(fetch_rows(&mut conn)).unwrap();
+                }
+
+                take_timings().get(phase)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+#[cfg(not(feature = "bench-internals"))]
+fn bench_phases(_: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    bench_connect,
+    bench_prepare,
+    bench_select,
+    bench_fetch,
+    bench_insert,
+    bench_phases
+);
+criterion_main!(benches);
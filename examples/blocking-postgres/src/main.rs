@@ -0,0 +1,36 @@
+use sqlx::blocking::{query, Connection};
+use sqlx::postgres::{PgConnection, PgRow};
+use sqlx::Row;
+use std::env;
+
+fn main() -> anyhow::Result<()> {
+    let mut conn = Connection::<PgConnection>::connect(&env::var("DATABASE_URL")?)?;
+
+    if let Some(description) = env::args().nth(1) {
+        let id = query("INSERT INTO todos ( description ) VALUES ( $1 ) RETURNING id")
+            .bind(description)
+            .try_map(|row: PgRow| row.try_get::<i64, _>(0))
+            .fetch_one(&mut conn)?;
+
+        println!("Added new todo with id {}", id);
+    }
+
+    let todos = query("SELECT id, description, done FROM todos ORDER BY id")
+        .try_map(|row: PgRow| -> sqlx::Result<(i64, String, bool)> {
+            Ok((row.try_get(0)?, row.try_get(1)?, row.try_get(2)?))
+        })
+        .fetch_all(&mut conn)?;
+
+    for (id, description, done) in todos {
+        println!(
+            "- [{}] {}: {}",
+            if done { "x" } else { " " },
+            id,
+            description
+        );
+    }
+
+    conn.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Checks the version of `rustc` against the minimum supported version, and enables the
+//! features of the language that are used where the compiler has them.
+
+use std::env;
+use std::process::{self, Command};
+
+// Keep in sync with the `msrv` job of `.github/workflows/rust.yml`
+const MSRV: (u32, u32) = (1, 40);
+
+// The build script and bindings of the `libsqlite3-sys` that bundles SQLite 3.38 need a newer
+// compiler than the rest of the crate
+const MSRV_SQLITE: (u32, u32) = (1, 51);
+
+// The first version with const generics, used for `[u8; N]`; older compilers get the lengths
+// from 0 to 32
+const CONST_GENERICS: (u32, u32) = (1, 51);
+
+fn main() {
+    let msrv = if env::var_os("CARGO_FEATURE_SQLITE").is_some() {
+        MSRV_SQLITE
+    } else {
+        MSRV
+    };
+
+    println!("cargo:rustc-env=SQLX_MSRV={}.{}", msrv.0, msrv.1);
+    println!("cargo:rustc-check-cfg=cfg(sqlx_const_generics)");
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+
+    // if the version can't be read, assume it is supported but don't rely on newer features
+    let version = match Command::new(rustc).arg("--version").output() {
+        Ok(output) => version(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => None,
+    };
+
+    let version = match version {
+        Some(version) => version,
+        None => return,
+    };
+
+    if version < msrv {
+        eprintln!(
+            "sqlx-core requires Rust {}.{} or newer{}; this is Rust {}.{}",
+            msrv.0,
+            msrv.1,
+            if msrv == MSRV_SQLITE {
+                " with the `sqlite` feature"
+            } else {
+                ""
+            },
+            version.0,
+            version.1
+        );
+
+        process::exit(1);
+    }
+
+    if version >= CONST_GENERICS {
+        println!("cargo:rustc-cfg=sqlx_const_generics");
+    }
+}
+
+// The major and minor version of `rustc 1.40.0 (73528e339 2019-12-16)`
+fn version(output: &str) -> Option<(u32, u32)> {
+    let mut parts = output.split_whitespace().nth(1)?.split('.');
+
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+
+    Some((major, minor))
+}
@@ -12,7 +12,18 @@ pub trait Arguments: Send + Sized + Default + 'static {
     /// be added to the arguments without a reallocation.  
     fn reserve(&mut self, len: usize, size: usize);
 
+    /// Removes all values, keeping the allocated capacity so the arguments can be refilled
+    /// for another query.
+    ///
+    /// By default, the arguments are replaced with empty ones, which drops their capacity.
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+
     /// Add the value to the end of the arguments.
+    ///
+    /// If the value fails to encode (see [`Encode::try_encode`]), the query fails with that
+    /// error when it is executed, before anything is sent.
     fn add<T>(&mut self, value: T)
     where
         T: Type<Self::Database>,
@@ -0,0 +1,145 @@
+//! Time spent in each phase of the queries run by the Postgres and MySQL drivers, so the
+//! benchmarks in `benches/` can attribute time.
+//!
+//! Only available with the `bench-internals` feature. The timings of all connections are added
+//! together until they are taken with [`take_timings`].
+
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+
+/// A phase of running a query; each starts where the previous one ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Until the statement is prepared: `ParseComplete` for Postgres, the response to
+    /// `COM_STMT_PREPARE` for MySQL. Not recorded for a statement found in the cache by
+    /// Postgres, whose binding then starts with the query.
+    Prepare,
+
+    /// Until the arguments are bound: `BindComplete` for Postgres, `COM_STMT_EXECUTE` sent for
+    /// MySQL.
+    Bind,
+
+    /// Until the first row is received, or until the end of the results if there are none.
+    Execute,
+
+    /// Reading the first row until it is returned by the cursor.
+    FirstRow,
+
+    /// The rest of the results, including the time spent by the caller between rows.
+    Drain,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::Prepare,
+        Phase::Bind,
+        Phase::Execute,
+        Phase::FirstRow,
+        Phase::Drain,
+    ];
+}
+
+/// The time spent in each [`Phase`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Timings([Duration; 5]);
+
+impl Timings {
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.0[phase as usize]
+    }
+}
+
+static NANOS: [AtomicU64; 5] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+/// Returns the time spent in each phase since the last call, and starts counting from zero.
+pub fn take_timings() -> Timings {
+    let mut timings = Timings::default();
+
+    for phase in &Phase::ALL {
+        let nanos = NANOS[*phase as usize].swap(0, Ordering::Relaxed);
+
+        timings.0[*phase as usize] = Duration::from_nanos(nanos);
+    }
+
+    timings
+}
+
+// Tracks the current query of a connection; shared references to it are held while a row
+// borrows the buffer of the stream, so it is updated through atomics
+pub(crate) struct PhaseTimer {
+    base: Instant,
+
+    // When the current phase started, in nanoseconds since `base`; `NONE` outside of a query
+    last: AtomicU64,
+
+    // `ROW_RECEIVED` and `ROW_RETURNED` for the first row of the current query
+    rows: AtomicU8,
+}
+
+const NONE: u64 = std::u64::MAX;
+
+const ROW_RECEIVED: u8 = 1;
+const ROW_RETURNED: u8 = 2;
+
+impl Default for PhaseTimer {
+    fn default() -> Self {
+        Self {
+            base: Instant::now(),
+            last: AtomicU64::new(NONE),
+            rows: AtomicU8::new(0),
+        }
+    }
+}
+
+impl PhaseTimer {
+    fn now(&self) -> u64 {
+        self.base.elapsed().as_nanos() as u64
+    }
+
+    pub(crate) fn start(&self) {
+        self.last.store(self.now(), Ordering::Relaxed);
+        self.rows.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn finish(&self, phase: Phase) {
+        let last = self.last.load(Ordering::Relaxed);
+
+        if last != NONE {
+            let now = self.now();
+
+            NANOS[phase as usize].fetch_add(now - last, Ordering::Relaxed);
+
+            self.last.store(now, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn row_received(&self) {
+        if self.rows.load(Ordering::Relaxed) == 0 {
+            self.finish(Phase::Execute);
+            self.rows.store(ROW_RECEIVED, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn row_returned(&self) {
+        if self.rows.load(Ordering::Relaxed) == ROW_RECEIVED {
+            self.finish(Phase::FirstRow);
+            self.rows.store(ROW_RETURNED, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn done(&self) {
+        if self.rows.load(Ordering::Relaxed) == 0 {
+            self.finish(Phase::Execute);
+        } else {
+            self.finish(Phase::Drain);
+        }
+
+        self.last.store(NONE, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,349 @@
+//! Blocking wrappers around connections, pools and queries for programs that do not otherwise
+//! need an async runtime, such as CLI tools and build scripts.
+//!
+//! ```rust,ignore
+//! use sqlx::blocking::{query, Connection};
+//! use sqlx::postgres::{PgConnection, PgRow};
+//! use sqlx::Row;
+//!
+//! let mut conn = Connection::<PgConnection>::connect("postgres://localhost/todos")?;
+//!
+//! query("INSERT INTO todos (description) VALUES ($1)")
+//!     .bind("buy milk")
+//!     .execute(&mut conn)?;
+//!
+//! let descriptions = query("SELECT description FROM todos")
+//!     .try_map(|row: PgRow| row.try_get::<String, _>(0))
+//!     .fetch_all(&mut conn)?;
+//! ```
+//!
+//! Each [`Connection`] and [`Pool`] drives its own instance of the runtime selected with the
+//! `runtime-*` feature. Calling any of these methods from within an async task panics; use the
+//! async API there instead.
+
+use std::future::Future;
+
+use crate::connection::Connect;
+use crate::database::Database;
+use crate::encode::Encode;
+use crate::executor::{Executor as AsyncExecutor, RefExecutor};
+use crate::query::{MapRow, TryMapRow};
+use crate::types::Type;
+
+/// A blocking wrapper around a database connection.
+pub struct Connection<C> {
+    inner: C,
+    runtime: Runtime,
+}
+
+impl<C> Connection<C>
+where
+    C: Connect,
+{
+    /// Establish a new database connection, blocking until it is ready.
+    ///
+    /// See [`Connect::connect`] for the accepted connection strings.
+    pub fn connect(url: &str) -> crate::Result<Self> {
+        let runtime = Runtime::new()?;
+        let inner = runtime.block_on(C::connect(url))?;
+
+        Ok(Self { inner, runtime })
+    }
+
+    /// Execute one or more statements that do not take bind parameters and return the
+    /// number of affected rows.
+    pub fn execute(&mut self, query: &str) -> crate::Result<u64> {
+        let Self { inner, runtime } = self;
+
+        runtime.block_on(inner.execute(query))
+    }
+
+    /// Checks if a connection to the database is still valid.
+    pub fn ping(&mut self) -> crate::Result<()> {
+        let Self { inner, runtime } = self;
+
+        runtime.block_on(inner.ping())
+    }
+
+    /// Close this database connection.
+    pub fn close(self) -> crate::Result<()> {
+        let Self { inner, runtime } = self;
+
+        runtime.block_on(inner.close())
+    }
+}
+
+/// A blocking wrapper around a [connection pool](crate::pool::Pool).
+pub struct Pool<C> {
+    inner: crate::pool::Pool<C>,
+    runtime: Runtime,
+}
+
+impl<C> Pool<C>
+where
+    C: Connect,
+{
+    /// Creates a connection pool with the default configuration.
+    pub fn new(url: &str) -> crate::Result<Self> {
+        let runtime = Runtime::new()?;
+        let inner = runtime.block_on(crate::pool::Pool::new(url))?;
+
+        Ok(Self { inner, runtime })
+    }
+
+    /// Ends the use of the pool, blocking until all connections are closed.
+    pub fn close(&self) {
+        self.runtime.block_on(self.inner.close())
+    }
+}
+
+/// A connection or pool that blocking queries can be run on.
+///
+/// Implemented for `&mut Connection` and `&Pool`.
+pub trait Executor<'e> {
+    type Database: Database;
+
+    #[doc(hidden)]
+    type Inner: AsyncExecutor<Database = Self::Database>
+        + RefExecutor<'e, Database = Self::Database>;
+
+    #[doc(hidden)]
+    fn into_parts(self) -> (&'e Runtime, Self::Inner);
+}
+
+impl<'e, C> Executor<'e> for &'e mut Connection<C>
+where
+    C: Connect,
+    &'e mut C: RefExecutor<'e, Database = C::Database>,
+{
+    type Database = C::Database;
+
+    type Inner = &'e mut C;
+
+    fn into_parts(self) -> (&'e Runtime, &'e mut C) {
+        (&self.runtime, &mut self.inner)
+    }
+}
+
+impl<'e, C> Executor<'e> for &'e Pool<C>
+where
+    C: Connect,
+    &'e crate::pool::Pool<C>:
+        AsyncExecutor<Database = C::Database> + RefExecutor<'e, Database = C::Database>,
+{
+    type Database = C::Database;
+
+    type Inner = &'e crate::pool::Pool<C>;
+
+    fn into_parts(self) -> (&'e Runtime, &'e crate::pool::Pool<C>) {
+        (&self.runtime, &self.inner)
+    }
+}
+
+/// Raw SQL query with bind parameters that blocks when executed. Returned by [`query`].
+pub struct Query<'q, DB>
+where
+    DB: Database,
+{
+    inner: crate::query::Query<'q, DB>,
+}
+
+/// Make a SQL query that blocks when executed.
+///
+/// See [`crate::query::query`].
+pub fn query<DB>(sql: &str) -> Query<'_, DB>
+where
+    DB: Database,
+{
+    Query {
+        inner: crate::query::query(sql),
+    }
+}
+
+impl<'q, DB> Query<'q, DB>
+where
+    DB: Database,
+{
+    /// Bind a value for use with this SQL query.
+    pub fn bind<T>(self, value: T) -> Self
+    where
+        T: Type<DB>,
+        T: Encode<DB>,
+    {
+        Query {
+            inner: self.inner.bind(value),
+        }
+    }
+
+    /// Execute the query and return the number of affected rows.
+    pub fn execute<'e, E>(self, executor: E) -> crate::Result<u64>
+    where
+        E: Executor<'e, Database = DB>,
+    {
+        let (runtime, inner) = executor.into_parts();
+
+        runtime.block_on(self.inner.execute(inner))
+    }
+
+    /// Map each row in the result to another type.
+    pub fn map<F, O>(self, mapper: F) -> Map<'q, DB, impl TryMapRow<DB, Output = O>>
+    where
+        O: Unpin,
+        F: MapRow<DB, Output = O>,
+    {
+        Map {
+            inner: self.inner.map(mapper),
+        }
+    }
+
+    /// Map each row in the result to another type.
+    pub fn try_map<F>(self, mapper: F) -> Map<'q, DB, F>
+    where
+        F: TryMapRow<DB>,
+    {
+        Map {
+            inner: self.inner.try_map(mapper),
+        }
+    }
+}
+
+/// SQL query that blocks when executed and maps its results to owned Rust types.
+///
+/// Returned by [`Query::map`] and [`Query::try_map`].
+pub struct Map<'q, DB, F>
+where
+    DB: Database,
+{
+    inner: crate::query::Map<'q, DB, F>,
+}
+
+impl<'q, DB, F> Map<'q, DB, F>
+where
+    DB: Database,
+    F: TryMapRow<DB>,
+{
+    /// Get the first row in the result, returning [`Error::RowNotFound`] if there is none.
+    ///
+    /// [`Error::RowNotFound`]: crate::Error::RowNotFound
+    pub fn fetch_one<'e, E>(self, executor: E) -> crate::Result<F::Output>
+    where
+        E: Executor<'e, Database = DB>,
+        'q: 'e,
+    {
+        let (runtime, inner) = executor.into_parts();
+
+        runtime.block_on(self.inner.fetch_one(inner))
+    }
+
+    /// Get the first row in the result, if any.
+    pub fn fetch_optional<'e, E>(self, executor: E) -> crate::Result<Option<F::Output>>
+    where
+        E: Executor<'e, Database = DB>,
+        'q: 'e,
+    {
+        let (runtime, inner) = executor.into_parts();
+
+        runtime.block_on(self.inner.fetch_optional(inner))
+    }
+
+    /// Collect every row in the result.
+    pub fn fetch_all<'e, E>(self, executor: E) -> crate::Result<Vec<F::Output>>
+    where
+        E: Executor<'e, Database = DB>,
+        'q: 'e,
+    {
+        let (runtime, inner) = executor.into_parts();
+
+        runtime.block_on(self.inner.fetch_all(inner))
+    }
+
+    /// Collect every row in the result, with room for `capacity` rows set aside up front.
+    pub fn fetch_all_with_capacity<'e, E>(
+        self,
+        executor: E,
+        capacity: usize,
+    ) -> crate::Result<Vec<F::Output>>
+    where
+        E: Executor<'e, Database = DB>,
+        'q: 'e,
+    {
+        let (runtime, inner) = executor.into_parts();
+
+        runtime.block_on(self.inner.fetch_all_with_capacity(inner, capacity))
+    }
+}
+
+#[cfg(feature = "runtime-async-std")]
+async_std::task_local! {
+    static IN_TASK: () = ();
+}
+
+/// The runtime that drives a blocking [`Connection`] or [`Pool`].
+#[doc(hidden)]
+pub struct Runtime {
+    #[cfg(feature = "runtime-tokio")]
+    inner: std::sync::Mutex<tokio::runtime::Runtime>,
+}
+
+impl Runtime {
+    #[cfg(feature = "runtime-async-std")]
+    fn new() -> crate::Result<Self> {
+        Ok(Self {})
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    fn new() -> crate::Result<Self> {
+        let runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            inner: std::sync::Mutex::new(runtime),
+        })
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        // task-locals can only be accessed from within a task
+        #[cfg(feature = "runtime-async-std")]
+        let in_async_context = IN_TASK.try_with(|_| ()).is_ok();
+
+        #[cfg(feature = "runtime-tokio")]
+        let in_async_context = tokio::runtime::Handle::try_current().is_ok();
+
+        if in_async_context {
+            panic!(
+                "the blocking API of SQLx cannot be used from within an async runtime; \
+                 use the async API instead"
+            );
+        }
+
+        #[cfg(feature = "runtime-async-std")]
+        return async_std::task::block_on(future);
+
+        #[cfg(feature = "runtime-tokio")]
+        return self
+            .inner
+            .lock()
+            .expect("blocking runtime poisoned by a panic")
+            .block_on(future);
+    }
+}
+
+#[cfg(all(test, feature = "runtime-async-std"))]
+mod tests {
+    use super::Runtime;
+
+    #[test]
+    fn it_runs_outside_of_a_task() {
+        assert_eq!(Runtime::new().unwrap().block_on(async { 1 }), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be used from within an async runtime")]
+    fn it_panics_within_a_task() {
+        async_std::task::block_on(async {
+            Runtime::new().unwrap().block_on(async {});
+        });
+    }
+}
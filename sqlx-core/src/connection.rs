@@ -1,12 +1,17 @@
 use std::convert::TryInto;
+use std::ops::AddAssign;
 
 use futures_core::future::BoxFuture;
 
+use crate::database::Database;
+use crate::describe::TableColumns;
 use crate::executor::Executor;
 use crate::pool::{Pool, PoolConnection};
-use crate::transaction::Transaction;
+use crate::transaction::{TestTransaction, Transaction};
 use crate::url::Url;
 
+pub use crate::url::ConnectOptions;
+
 /// Represents a single database connection rather than a pool of database connections.
 ///
 /// Prefer running queries from [Pool] unless there is a specific need for a single, continuous
@@ -18,12 +23,29 @@ where
 {
     /// Starts a transaction.
     ///
-    /// Returns [`Transaction`](struct.Transaction.html).
+    /// Returns [`Transaction`](struct.Transaction.html). If the connection is already in a
+    /// transaction, e.g. one started by executing `BEGIN`, a savepoint is created within it
+    /// instead.
     fn begin(self) -> BoxFuture<'static, crate::Result<Transaction<Self>>>
     where
         Self: Sized,
     {
-        Box::pin(Transaction::new(0, self))
+        let depth = match self.transaction_status() {
+            TransactionStatus::Idle => 0,
+            _ => 1,
+        };
+
+        Box::pin(Transaction::new(depth, self))
+    }
+
+    /// Starts a transaction that is rolled back instead of committed, to isolate a test.
+    ///
+    /// See [`TestTransaction`].
+    fn begin_test(self) -> BoxFuture<'static, crate::Result<TestTransaction<Self>>>
+    where
+        Self: Sized,
+    {
+        Box::pin(TestTransaction::new(self))
     }
 
     /// Close this database connection.
@@ -31,6 +53,185 @@ where
 
     /// Verifies a connection to the database is still alive.
     fn ping(&mut self) -> BoxFuture<crate::Result<()>>;
+
+    /// Returns `true` if a future using this connection was dropped at a point where the
+    /// connection can not be brought back to a known state. Every further use returns an error.
+    ///
+    /// A [`PoolConnection`] in this state is closed instead of being returned to the pool.
+    #[doc(hidden)]
+    fn is_broken(&self) -> bool {
+        false
+    }
+
+    /// Returns `false` while results of the last query are still waiting to be read, for
+    /// instance after a cursor was dropped before it reached the end of them.
+    ///
+    /// Such results are read and discarded before the next query is run; as a cursor borrows
+    /// the connection, a query can not be run while its cursor is in use.
+    fn is_ready(&self) -> bool {
+        true
+    }
+
+    /// Returns empty arguments to bind to the next query with [`Query::bind_all`], reusing the
+    /// buffers of the arguments of a previous query.
+    ///
+    /// Connections hold on to the arguments of the last query once they are bound, so a loop
+    /// that takes its arguments from here doesn't allocate new ones for each query:
+    ///
+    /// ```rust,ignore
+    /// for (id, name) in rows {
+    ///     let mut arguments = conn.take_arguments();
+    ///     arguments.add(id);
+    ///     arguments.add(name);
+    ///
+    ///     sqlx::query("INSERT INTO users (id, name) VALUES ($1, $2)")
+    ///         .bind_all(arguments)
+    ///         .execute(&mut conn)
+    ///         .await?;
+    /// }
+    /// ```
+    ///
+    /// [`Query::bind_all`]: crate::query::Query::bind_all
+    fn take_arguments(&mut self) -> <Self::Database as Database>::Arguments {
+        Default::default()
+    }
+
+    /// Returns whether the connection is in a transaction, as of the end of the last command.
+    ///
+    /// Defaults to [`Idle`](TransactionStatus::Idle) for connections that don't track it, so
+    /// [`begin`](#method.begin) always starts a new transaction on them.
+    fn transaction_status(&self) -> TransactionStatus {
+        TransactionStatus::Idle
+    }
+
+    /// Looks up the table (or view) `name` in the catalog of the database, returning its id if
+    /// there is one.
+    ///
+    /// `name` is read as it would be in SQL: it may be qualified by a schema (a database for
+    /// MySQL, an attached database for SQLite) and either part may be quoted. An unqualified
+    /// name is found as a query would find it: on the `search_path` for Postgres, in the
+    /// current database for MySQL and in `temp`, then `main` for SQLite. Postgres folds
+    /// unquoted parts to lower case; MySQL and SQLite compare names as they do in queries.
+    ///
+    /// A name that could not be written in SQL is not found.
+    ///
+    /// The id is the OID of the table for Postgres and its name as the database stores it for
+    /// MySQL and SQLite.
+    ///
+    /// ```rust,ignore
+    /// if conn.table_id("audit.events").await?.is_none() {
+    ///     conn.execute(include_str!("audit.sql")).await?;
+    /// }
+    /// ```
+    ///
+    /// By default, this returns [`Error::Protocol`](crate::Error::Protocol) for connections that
+    /// can not look up tables.
+    fn table_id<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<Option<<Self::Database as Database>::TableId>>> {
+        let _ = name;
+
+        Box::pin(async { Err(unsupported("table_id")) })
+    }
+
+    /// Returns `true` if the table (or view) `name` exists; see [`table_id`](#method.table_id).
+    fn table_exists<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, crate::Result<bool>> {
+        Box::pin(async move { Ok(self.table_id(name).await?.is_some()) })
+    }
+
+    /// Returns the columns of the table (or view) `name` in the order they are declared in, or
+    /// `None` if there is no such table; `name` is looked up as by
+    /// [`table_id`](#method.table_id).
+    ///
+    /// The columns are read from `pg_catalog` for Postgres, `information_schema` for MySQL and
+    /// `PRAGMA table_xinfo` for SQLite, and their types from a query that selects them. The
+    /// hidden columns of a virtual table of SQLite are left out, and so are the dropped columns
+    /// of a table of Postgres, which the ordinals of its columns don't count.
+    ///
+    /// ```rust,ignore
+    /// for column in conn.describe_table("accounts").await?.unwrap_or_default() {
+    ///     println!("{}: {:?}", column.name, column.type_info);
+    /// }
+    /// ```
+    ///
+    /// By default, this returns [`Error::Protocol`](crate::Error::Protocol) for connections that
+    /// can not look up tables.
+    fn describe_table<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<TableColumns<Self::Database>>> {
+        let _ = name;
+
+        Box::pin(async { Err(unsupported("describe_table")) })
+    }
+
+    /// Takes the error that put the current transaction into the
+    /// [`Error`](TransactionStatus::Error) state, if it is known.
+    #[doc(hidden)]
+    fn take_transaction_error(&mut self) -> Option<crate::Error> {
+        None
+    }
+
+    /// Returns how often queries of this connection found their prepared statement in its
+    /// cache, and how many statements the cache holds.
+    ///
+    /// A workload that benefits from the cache has many more hits than misses; one with misses
+    /// as frequent as its queries, and a cache that keeps growing, runs too many distinct
+    /// queries for it. The stats of the connections of a pool can be added up with `+=`.
+    fn statement_cache_stats(&self) -> StatementCacheStats {
+        StatementCacheStats::default()
+    }
+}
+
+// The error of a method the connection does not implement
+fn unsupported(method: &str) -> crate::Error {
+    protocol_err!(
+        "`Connection::{}` is not supported by this connection",
+        method
+    )
+    .into()
+}
+
+/// The use of the cache of prepared statements of a connection; see
+/// [`Connection::statement_cache_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StatementCacheStats {
+    /// The number of queries that ran a statement prepared by an earlier query.
+    pub hits: u64,
+
+    /// The number of queries that prepared their statement and added it to the cache.
+    pub misses: u64,
+
+    /// The number of statements in the cache.
+    pub size: usize,
+}
+
+impl AddAssign for StatementCacheStats {
+    fn add_assign(&mut self, other: Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.size += other.size;
+    }
+}
+
+/// The transaction status of a [`Connection`].
+///
+/// A [`PoolConnection`] that is not [`Idle`](TransactionStatus::Idle) when it is dropped has its
+/// transaction rolled back before it is returned to the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Not in a transaction block.
+    Idle,
+
+    /// In a transaction block.
+    Transaction,
+
+    /// In a _failed_ transaction block. Queries will be rejected until block is ended.
+    ///
+    /// Only reported by Postgres; MySQL and SQLite carry on with a transaction after a
+    /// statement in it failed.
+    Error,
 }
 
 /// Represents a type that can directly establish a new connection.
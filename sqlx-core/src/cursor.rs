@@ -1,8 +1,10 @@
 use futures_core::future::BoxFuture;
+use futures_util::TryFutureExt;
 
 use crate::database::{Database, HasRow};
 use crate::executor::Execute;
 use crate::pool::Pool;
+use crate::row::{OwnedRow, Row};
 
 /// Represents a result set, which is generated by executing a query against the database.
 ///
@@ -37,4 +39,99 @@ where
     fn next<'cur>(
         &'cur mut self,
     ) -> BoxFuture<'cur, crate::Result<Option<<Self::Database as HasRow<'cur>>::Row>>>;
+
+    /// Fetch the next row in the result as an [`OwnedRow`], with its values copied out of the
+    /// connection. Returns `None` if there are no more rows.
+    ///
+    /// A row returned by [`next`](Cursor::next) borrows the cursor, which can't be advanced
+    /// until the row is dropped. An owned row can be kept across any `.await`, e.g. to call
+    /// another service for each row, while the cursor goes on to the next one:
+    ///
+    /// ```rust,ignore
+    /// let mut cursor = sqlx::query("SELECT id, email FROM users").fetch(&mut conn);
+    ///
+    /// while let Some(row) = cursor.next_owned().await? {
+    ///     let email: String = row.get("email");
+    ///
+    ///     mailer.send(&email).await?;
+    /// }
+    /// ```
+    ///
+    /// The values are copied for each row. When the row is mapped to a type of your own anyway,
+    /// [`Map::fetch`](crate::query::Map::fetch) (from `query_as` or `try_map`) yields values
+    /// that can be held across `.await`s just the same, without the copy.
+    fn next_owned<'cur>(
+        &'cur mut self,
+    ) -> BoxFuture<'cur, crate::Result<Option<OwnedRow<Self::Database>>>> {
+        Box::pin(self.next().map_ok(|row| row.map(Row::into_owned)))
+    }
+
+    /// The number of rows inserted, updated or deleted by the query, once `next` has returned
+    /// `None`; `None` before that.
+    ///
+    /// Unlike the count returned by [`Executor::execute`](crate::executor::Executor::execute),
+    /// rows that are only returned are not counted, so this is the count of an `INSERT ..
+    /// RETURNING` or of the statements of a MySQL procedure that also returns rows.
+    ///
+    /// * Postgres counts from the tag of each `CommandComplete`, except for a `SELECT` or
+    ///   `FETCH` that returned rows.
+    /// * MySQL adds up the `affected_rows` of each OK packet; a result set counts as none.
+    /// * SQLite adds up `sqlite3_changes()` after each statement that is not read-only.
+    ///
+    /// Defaults to `None`, for cursors that don't count the rows.
+    fn rows_affected(&self) -> Option<u64> {
+        None
+    }
+}
+
+// The first allocation for the rows of a result set; `Vec` would otherwise reallocate at 4, 8, 16..
+const INITIAL_ROWS: usize = 64;
+
+/// Makes room in `rows` for one more row, starting at [`INITIAL_ROWS`] and doubling from there
+/// (or from the capacity the caller asked for) as the result set grows.
+pub(crate) fn reserve_row<T>(rows: &mut Vec<T>) {
+    if rows.len() == rows.capacity() {
+        rows.reserve(INITIAL_ROWS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reserve_row, INITIAL_ROWS};
+
+    fn growth(rows: usize, capacity: usize) -> Vec<usize> {
+        let mut out = Vec::<u8>::with_capacity(capacity);
+        let mut capacities = vec![];
+
+        for _ in 0..rows {
+            reserve_row(&mut out);
+            out.push(0);
+
+            if capacities.last() != Some(&out.capacity()) {
+                capacities.push(out.capacity());
+            }
+        }
+
+        capacities
+    }
+
+    #[test]
+    fn it_doubles_from_the_initial_capacity() {
+        assert_eq!(growth(1, 0), [INITIAL_ROWS]);
+        assert_eq!(growth(300, 0), [64, 128, 256, 512]);
+    }
+
+    #[test]
+    fn it_keeps_doubling_for_large_result_sets() {
+        let capacities = growth(1 << 20, 0);
+
+        assert_eq!(capacities.len(), 15);
+        assert_eq!(capacities.last(), Some(&(1 << 20)));
+    }
+
+    #[test]
+    fn it_keeps_a_requested_capacity() {
+        assert_eq!(growth(1000, 1000), [1000]);
+        assert_eq!(growth(1001, 1000), [1000, 2000]);
+    }
 }
@@ -3,6 +3,9 @@ use std::fmt::Display;
 use crate::arguments::Arguments;
 use crate::connection::Connect;
 use crate::cursor::Cursor;
+#[doc(hidden)]
+pub use crate::named::ParameterStyle;
+use crate::quote::{self, InvalidIdentifier};
 use crate::row::Row;
 use crate::types::TypeInfo;
 
@@ -30,6 +33,61 @@ where
     type TableId: Display + Clone;
 
     type RawBuffer;
+
+    /// The syntax of the bind parameters of this database, which named parameters are
+    /// rewritten to.
+    #[doc(hidden)]
+    const PARAMETER_STYLE: ParameterStyle = ParameterStyle::Question;
+
+    /// The SQL function returning the length of a text or binary value, in characters or
+    /// bytes respectively; see [`LargeValue`](crate::query::LargeValue).
+    #[doc(hidden)]
+    const LENGTH_FUNCTION: &'static str = "length";
+
+    /// The SQL function returning a part of a text or binary value, given the position of its
+    /// first character or byte, starting at 1, and its length.
+    #[doc(hidden)]
+    const SUBSTRING_FUNCTION: &'static str = "substr";
+
+    /// The most bind parameters a single query can have with this database.
+    ///
+    /// A query with more fails with [`Error::ParameterLimitExceeded`] before it is sent. Rows
+    /// that are inserted or matched in bulk can be split into queries that stay under it:
+    ///
+    /// ```rust,ignore
+    /// // 3 parameters for each row
+    /// for chunk in rows.chunks(DB::PARAMETER_LIMIT / 3) {
+    ///     // INSERT INTO items (a, b, c) VALUES (?, ?, ?), (?, ?, ?), ...
+    /// }
+    /// ```
+    ///
+    /// Defaults to `usize::MAX`, for databases without a limit of their own.
+    ///
+    /// [`Error::ParameterLimitExceeded`]: crate::Error::ParameterLimitExceeded
+    const PARAMETER_LIMIT: usize = std::usize::MAX;
+
+    /// Returns the statement that calls the stored procedure (or, if `function` is set, the
+    /// function) `name` with `params` bind parameters.
+    #[doc(hidden)]
+    fn call_statement(name: &str, params: usize, function: bool) -> String {
+        let params = vec!["?"; params].join(", ");
+
+        if function {
+            format!("SELECT {}({})", name, params)
+        } else {
+            format!("CALL {}({})", name, params)
+        }
+    }
+
+    /// Quotes `ident` so it can be spliced into a query as the name of a table, column or
+    /// other object, whatever characters it contains; e.g. `my "table"` becomes
+    /// `"my ""table"""`. See [`Identifier`](crate::quote::Identifier) for schema-qualified names.
+    ///
+    /// Returns an error if the database does not accept `ident` as an identifier at all, such
+    /// as when it is empty or contains a NUL character.
+    fn quote_identifier(ident: &str) -> Result<String, InvalidIdentifier> {
+        quote::quote_with(ident, '"')
+    }
 }
 
 pub trait HasRawValue<'c> {
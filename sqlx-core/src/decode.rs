@@ -3,6 +3,13 @@
 use crate::database::HasRawValue;
 
 /// Decode a single value from the database.
+///
+/// `'de` is the lifetime of the row the value is read from. Types such as `&'de str` and
+/// `&'de [u8]` borrow from the buffer of the row instead of copying it, so they can only be
+/// decoded from a row that is still around, like one returned by a [`Cursor`]. Types that own
+/// their data, such as `String` and `Vec<u8>`, decode for any `'de` and are [`DecodeOwned`].
+///
+/// [`Cursor`]: crate::cursor::Cursor
 pub trait Decode<'de, DB>
 where
     Self: Sized + 'de,
@@ -10,3 +17,21 @@ where
 {
     fn decode(value: DB::RawValue) -> crate::Result<Self>;
 }
+
+/// A type that can be decoded without borrowing from the row it is read from.
+///
+/// This is implemented for every type that implements [`Decode`] for all lifetimes. It is the
+/// bound to use in generic code for values that outlive their row, such as those returned by
+/// `fetch_one` or `fetch_all`; decoding `&str` there would not compile, so decode `String`.
+pub trait DecodeOwned<DB>: for<'de> Decode<'de, DB> + 'static
+where
+    DB: for<'de> HasRawValue<'de>,
+{
+}
+
+impl<DB, T> DecodeOwned<DB> for T
+where
+    DB: for<'de> HasRawValue<'de>,
+    T: for<'de> Decode<'de, DB> + 'static,
+{
+}
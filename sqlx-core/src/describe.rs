@@ -3,6 +3,7 @@
 use std::fmt::{self, Debug};
 
 use crate::database::Database;
+use crate::executor::Executor;
 
 /// The return type of [Executor::describe].
 #[non_exhaustive]
@@ -42,6 +43,27 @@ where
     pub type_info: DB::TypeInfo,
     /// Whether or not the column cannot be `NULL` (or if that is even knowable).
     pub non_null: Option<bool>,
+    /// Properties of the table column behind this one, as far as the database reports them;
+    /// currently only filled in by MySQL and SQLite.
+    pub flags: ColumnFlags,
+}
+
+bitflags::bitflags! {
+    /// Properties of a table column that decide whether a value should be given for it in an
+    /// `INSERT`.
+    pub struct ColumnFlags: u8 {
+        /// The column is part of the primary key.
+        const PRIMARY_KEY = 1;
+
+        /// The column is given the next value of a sequence if no value is inserted.
+        const AUTO_INCREMENT = 2;
+
+        /// The column is computed from other columns and can't be written to.
+        const GENERATED = 4;
+
+        /// The column is left out of `SELECT *`.
+        const INVISIBLE = 8;
+    }
 }
 
 impl<DB> Debug for Column<DB>
@@ -56,6 +78,100 @@ where
             .field("table_id", &self.table_id)
             .field("type_id", &self.type_info)
             .field("nonnull", &self.non_null)
+            .field("flags", &self.flags)
             .finish()
     }
 }
+
+/// The columns of a table, as returned by [`Connection::describe_table`]; `None` if there is no
+/// such table.
+///
+/// [`Connection::describe_table`]: crate::connection::Connection::describe_table
+pub type TableColumns<DB> = Option<Vec<TableColumn<DB>>>;
+
+/// A column of a table, as returned by [`Connection::describe_table`].
+///
+/// [`Connection::describe_table`]: crate::connection::Connection::describe_table
+#[non_exhaustive]
+pub struct TableColumn<DB>
+where
+    DB: Database,
+{
+    /// The name of the column, as the database stores it.
+    pub name: Box<str>,
+    /// The position of the column among the columns of the table, starting at 1.
+    pub ordinal: u32,
+    /// The type of the column, as a query that selects it describes it.
+    pub type_info: DB::TypeInfo,
+    /// Whether the column accepts `NULL`.
+    pub nullable: bool,
+    /// Whether the database fills in the column when an `INSERT` leaves it out, from a default
+    /// other than `NULL`, a sequence or an expression the column is generated from.
+    pub has_default: bool,
+    /// Whether the column is part of the primary key of the table.
+    pub primary_key: bool,
+}
+
+impl<DB> Debug for TableColumn<DB>
+where
+    DB: Database,
+    DB::TypeInfo: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TableColumn")
+            .field("name", &self.name)
+            .field("ordinal", &self.ordinal)
+            .field("type_info", &self.type_info)
+            .field("nullable", &self.nullable)
+            .field("has_default", &self.has_default)
+            .field("primary_key", &self.primary_key)
+            .finish()
+    }
+}
+
+// What the catalog of a database tells of a column of a table: its name, whether it is
+// nullable, whether it has a default and whether it is part of the primary key
+pub(crate) type CatalogColumn = (String, bool, bool, bool);
+
+// Completes the columns of `table` (already quoted), listed from the catalog in order, with the
+// types of the result columns of a query that selects them
+pub(crate) async fn table_columns<C>(
+    conn: &mut C,
+    table: &str,
+    columns: Vec<CatalogColumn>,
+) -> crate::Result<Vec<TableColumn<C::Database>>>
+where
+    C: Executor,
+{
+    // Postgres allows a table without columns, which can't be selected from this way
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut selected = Vec::with_capacity(columns.len());
+
+    for (name, ..) in &columns {
+        selected.push(
+            C::Database::quote_identifier(name).map_err(|err| crate::Error::Encode(err.into()))?,
+        );
+    }
+
+    let query = format!("SELECT {} FROM {}", selected.join(", "), table);
+    let described = conn.describe(&*query).await?;
+
+    Ok(columns
+        .into_iter()
+        .zip(described.result_columns.into_vec())
+        .zip(1..)
+        .map(
+            |(((name, nullable, has_default, primary_key), column), ordinal)| TableColumn {
+                name: name.into(),
+                ordinal,
+                type_info: column.type_info,
+                nullable,
+                has_default,
+                primary_key,
+            },
+        )
+        .collect())
+}
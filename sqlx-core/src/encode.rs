@@ -4,7 +4,7 @@ use crate::database::Database;
 use crate::types::Type;
 use std::mem;
 
-/// The return type of [Encode::encode].
+/// The return type of [Encode::encode_nullable] and [Encode::try_encode].
 pub enum IsNull {
     /// The value is null; no data was written.
     Yes,
@@ -29,6 +29,17 @@ where
         IsNull::No
     }
 
+    /// Writes the value of `self` into `buf`, or returns an [`Error::Encode`] if it can't be
+    /// represented in the database; the query is then not sent.
+    ///
+    /// Implement this for values that may not fit, and let [`encode`](#tymethod.encode) panic
+    /// on such values.
+    ///
+    /// [`Error::Encode`]: crate::Error::Encode
+    fn try_encode(&self, buf: &mut DB::RawBuffer) -> crate::Result<IsNull> {
+        Ok(self.encode_nullable(buf))
+    }
+
     fn size_hint(&self) -> usize {
         mem::size_of_val(self)
     }
@@ -48,6 +59,10 @@ where
         (*self).encode_nullable(buf)
     }
 
+    fn try_encode(&self, buf: &mut DB::RawBuffer) -> crate::Result<IsNull> {
+        (*self).try_encode(buf)
+    }
+
     fn size_hint(&self) -> usize {
         (*self).size_hint()
     }
@@ -74,6 +89,14 @@ where
         }
     }
 
+    fn try_encode(&self, buf: &mut DB::RawBuffer) -> crate::Result<IsNull> {
+        if let Some(self_) = self {
+            self_.try_encode(buf)
+        } else {
+            Ok(IsNull::Yes)
+        }
+    }
+
     fn size_hint(&self) -> usize {
         self.as_ref().map_or(0, Encode::size_hint)
     }
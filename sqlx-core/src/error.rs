@@ -1,9 +1,15 @@
 //! Error and Result types.
 
+use std::convert::Infallible;
 use std::error::Error as StdError;
 use std::fmt::{self, Debug, Display};
 use std::io;
 
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+use crate::database::Database;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+use crate::types::Type;
+
 /// A specialized `Result` type for SQLx.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -50,9 +56,109 @@ pub enum Error {
 
     /// An error occurred decoding data received from the database.
     Decode(Box<dyn StdError + Send + Sync>),
+
+    /// A bind argument could not be encoded for the database; the query was not sent.
+    Encode(Box<dyn StdError + Send + Sync>),
+
+    /// A [`Transaction`](crate::transaction::Transaction) could not be committed as a statement
+    /// failed in it, and the error of that statement is not known; it was rolled back instead.
+    TransactionFailed,
+
+    /// A query was given more bind parameters than the database accepts; it was not sent.
+    /// See [`Database::PARAMETER_LIMIT`](crate::database::Database::PARAMETER_LIMIT).
+    ParameterLimitExceeded { limit: usize, got: usize },
+
+    /// An error returned by user code that runs within SQLx, such as a [`FromRow`]
+    /// implementation or a closure given to [`Query::try_map`]; see [`Error::custom`].
+    ///
+    /// [`FromRow`]: crate::row::FromRow
+    /// [`Query::try_map`]: crate::query::Query::try_map
+    External(Box<dyn StdError + Send + Sync>),
 }
 
 impl Error {
+    /// Wraps an error of user code, e.g. one that failed to parse a column in a [`FromRow`]
+    /// implementation, so it can be returned where SQLx expects its own errors.
+    ///
+    /// The error stays reachable through [`source`](StdError::source) to be downcast.
+    ///
+    /// ```rust,ignore
+    /// let version: Version = row.try_get::<&str, _>("version")?
+    ///     .parse()
+    ///     .map_err(sqlx::Error::custom)?;
+    /// ```
+    ///
+    /// [`FromRow`]: crate::row::FromRow
+    pub fn custom<E>(err: E) -> Self
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        Error::External(err.into())
+    }
+
+    /// Returns `true` if this is the error of decoding a `NULL` into a type that is not an
+    /// `Option`, whether or not it names the column.
+    pub fn is_unexpected_null(&self) -> bool {
+        let error = match self {
+            Error::Decode(error) => &**error,
+            _ => return false,
+        };
+
+        let error = match error.downcast_ref::<ColumnDecodeError>() {
+            Some(error) => &*error.source,
+            None => error,
+        };
+
+        error.is::<UnexpectedNullError>()
+    }
+
+    /// Returns `true` if this is the error of a SQLite query that was stopped with
+    /// [`SqliteInterruptHandle::interrupt`](crate::sqlite::SqliteInterruptHandle::interrupt).
+    #[cfg(feature = "sqlite")]
+    pub fn is_interrupted(&self) -> bool {
+        match self {
+            // SQLITE_INTERRUPT
+            Error::Database(error) => error.code() == Some("9"),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the operation that failed with this error may succeed when it is tried
+    /// again, from the start of its transaction if it was in one.
+    ///
+    /// These errors are retryable:
+    ///
+    /// * [`Io`](Error::Io): the connection was lost or could not be opened
+    /// * [`PoolTimedOut`](Error::PoolTimedOut): no connection became available in time
+    /// * [`Database`](Error::Database) errors that [`DatabaseError::is_retryable`] says are
+    ///   transient: serialization failures, deadlocks, lock timeouts, a database that is busy,
+    ///   shutting down or has too many connections, and the other connection exceptions
+    ///   (SQLSTATE class `08`)
+    ///
+    /// Everything else is not, as trying again would fail the same way: syntax errors,
+    /// constraint violations, authentication failures and other database errors, a pool that
+    /// was closed, a TLS or URL misconfiguration, and errors decoding or encoding values.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Io(_) | Error::PoolTimedOut(_) => true,
+
+            Error::Database(error) => error.is_retryable(),
+
+            Error::UrlParse(_)
+            | Error::RowNotFound
+            | Error::ColumnNotFound(_)
+            | Error::ColumnIndexOutOfBounds { .. }
+            | Error::Protocol(_)
+            | Error::PoolClosed
+            | Error::TransactionFailed
+            | Error::Tls(_)
+            | Error::Decode(_)
+            | Error::Encode(_)
+            | Error::ParameterLimitExceeded { .. }
+            | Error::External(_) => false,
+        }
+    }
+
     #[allow(dead_code)]
     pub(crate) fn decode<E>(err: E) -> Self
     where
@@ -69,7 +175,9 @@ impl StdError for Error {
             Error::UrlParse(error) => Some(error),
             Error::PoolTimedOut(Some(error)) => Some(&**error),
             Error::Decode(error) => Some(&**error),
+            Error::Encode(error) => Some(&**error),
             Error::Tls(error) => Some(&**error),
+            Error::External(error) => Some(&**error),
 
             _ => None,
         }
@@ -87,6 +195,10 @@ impl Display for Error {
 
             Error::Decode(error) => write!(f, "{}", error),
 
+            Error::Encode(error) => write!(f, "{}", error),
+
+            Error::External(error) => write!(f, "{}", error),
+
             Error::Database(error) => Display::fmt(error, f),
 
             Error::RowNotFound => f.write_str("found no row when we expected at least one"),
@@ -111,8 +223,18 @@ impl Display for Error {
                 write!(f, "timed out while waiting for an open connection")
             }
 
+            Error::ParameterLimitExceeded { limit, got } => write!(
+                f,
+                "too many bind parameters: the database accepts at most {} but {} were given",
+                limit, got
+            ),
+
             Error::PoolClosed => f.write_str("attempted to acquire a connection on a closed pool"),
 
+            Error::TransactionFailed => {
+                f.write_str("a statement of the transaction failed; it was rolled back")
+            }
+
             Error::Tls(ref err) => write!(f, "error during TLS upgrade: {}", err),
         }
     }
@@ -139,6 +261,20 @@ impl From<url::ParseError> for Error {
     }
 }
 
+impl From<Box<dyn StdError + Send + Sync>> for Error {
+    #[inline]
+    fn from(err: Box<dyn StdError + Send + Sync>) -> Self {
+        Error::External(err)
+    }
+}
+
+impl From<Infallible> for Error {
+    #[inline]
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
 impl From<ProtocolError<'_>> for Error {
     #[inline]
     fn from(err: ProtocolError) -> Self {
@@ -182,6 +318,29 @@ pub trait DatabaseError: Display + Debug + Send + Sync {
         None
     }
 
+    /// The class of the SQLSTATE code for the error; its first two characters.
+    ///
+    /// Errors in the same class share a category, e.g. `23` for integrity constraint
+    /// violations or `40` for transaction rollbacks. See [`SqlStateClass`].
+    fn code_class(&self) -> Option<&str> {
+        self.code()
+            .filter(|code| code.len() == 5 && code.is_ascii())
+            .map(|code| &code[..2])
+    }
+
+    /// The class of the SQLSTATE code for the error, if it is one of the standard classes.
+    fn sql_state_class(&self) -> Option<SqlStateClass> {
+        self.code_class().and_then(SqlStateClass::from_class)
+    }
+
+    /// Returns `true` if the error is transient, so the query or transaction that failed may
+    /// succeed when it is run again; see [`Error::is_retryable`].
+    ///
+    /// By default, this is decided by the SQLSTATE [`code`](DatabaseError::code).
+    fn is_retryable(&self) -> bool {
+        self.code().map_or(false, is_retryable_sql_state)
+    }
+
     fn details(&self) -> Option<&str> {
         None
     }
@@ -203,6 +362,122 @@ pub trait DatabaseError: Display + Debug + Send + Sync {
     }
 }
 
+// Whether a SQLSTATE is that of an error that may not happen again
+pub(crate) fn is_retryable_sql_state(code: &str) -> bool {
+    match code {
+        // serialization_failure, deadlock_detected
+        "40001" | "40P01" => true,
+
+        // too_many_connections, lock_not_available
+        "53300" | "55P03" => true,
+
+        // admin_shutdown, crash_shutdown, cannot_connect_now
+        "57P01" | "57P02" | "57P03" => true,
+
+        // connection exceptions
+        _ => code.len() == 5 && code.starts_with("08"),
+    }
+}
+
+/// The standard classes of SQLSTATE codes that are common to Postgres and MySQL.
+///
+/// https://www.postgresql.org/docs/current/errcodes-appendix.html
+/// https://dev.mysql.com/doc/refman/8.0/en/error-message-elements.html#error-code-ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SqlStateClass {
+    /// `00`
+    SuccessfulCompletion,
+
+    /// `01`
+    Warning,
+
+    /// `02`
+    NoData,
+
+    /// `08`
+    ConnectionException,
+
+    /// `0A`
+    FeatureNotSupported,
+
+    /// `21`
+    CardinalityViolation,
+
+    /// `22`
+    DataException,
+
+    /// `23`
+    IntegrityConstraintViolation,
+
+    /// `25`
+    InvalidTransactionState,
+
+    /// `28`
+    InvalidAuthorizationSpecification,
+
+    /// `40`
+    TransactionRollback,
+
+    /// `42`
+    SyntaxErrorOrAccessRuleViolation,
+
+    /// `53`
+    InsufficientResources,
+
+    /// `57`
+    OperatorIntervention,
+
+    /// `58`
+    SystemError,
+}
+
+impl SqlStateClass {
+    /// Returns the class for the first two characters of a SQLSTATE code.
+    pub fn from_class(class: &str) -> Option<Self> {
+        Some(match class {
+            "00" => SqlStateClass::SuccessfulCompletion,
+            "01" => SqlStateClass::Warning,
+            "02" => SqlStateClass::NoData,
+            "08" => SqlStateClass::ConnectionException,
+            "0A" => SqlStateClass::FeatureNotSupported,
+            "21" => SqlStateClass::CardinalityViolation,
+            "22" => SqlStateClass::DataException,
+            "23" => SqlStateClass::IntegrityConstraintViolation,
+            "25" => SqlStateClass::InvalidTransactionState,
+            "28" => SqlStateClass::InvalidAuthorizationSpecification,
+            "40" => SqlStateClass::TransactionRollback,
+            "42" => SqlStateClass::SyntaxErrorOrAccessRuleViolation,
+            "53" => SqlStateClass::InsufficientResources,
+            "57" => SqlStateClass::OperatorIntervention,
+            "58" => SqlStateClass::SystemError,
+
+            _ => return None,
+        })
+    }
+
+    /// The first two characters of SQLSTATE codes in this class.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SqlStateClass::SuccessfulCompletion => "00",
+            SqlStateClass::Warning => "01",
+            SqlStateClass::NoData => "02",
+            SqlStateClass::ConnectionException => "08",
+            SqlStateClass::FeatureNotSupported => "0A",
+            SqlStateClass::CardinalityViolation => "21",
+            SqlStateClass::DataException => "22",
+            SqlStateClass::IntegrityConstraintViolation => "23",
+            SqlStateClass::InvalidTransactionState => "25",
+            SqlStateClass::InvalidAuthorizationSpecification => "28",
+            SqlStateClass::TransactionRollback => "40",
+            SqlStateClass::SyntaxErrorOrAccessRuleViolation => "42",
+            SqlStateClass::InsufficientResources => "53",
+            SqlStateClass::OperatorIntervention => "57",
+            SqlStateClass::SystemError => "58",
+        }
+    }
+}
+
 /// Used by the `protocol_error!()` macro for a lazily evaluated conversion to
 /// `crate::Error::Protocol` so we can use the macro with `.ok_or()` without Clippy complaining.
 pub(crate) struct ProtocolError<'a> {
@@ -238,8 +513,8 @@ macro_rules! impl_fmt_error {
 
 /// An unexpected `NULL` was encountered during decoding.
 ///
-/// Returned from `Row::get` if the value from the database is `NULL`
-/// and you are not decoding into an `Option`.
+/// Returned from `Row::try_get`, as the source of a [`ColumnDecodeError`], if the value from
+/// the database is `NULL` and you are not decoding into an `Option`.
 #[derive(Debug, Clone, Copy)]
 pub struct UnexpectedNullError;
 
@@ -250,3 +525,132 @@ impl Display for UnexpectedNullError {
 }
 
 impl StdError for UnexpectedNullError {}
+
+/// The SQL type of a value is not compatible with the Rust type it was decoded into.
+///
+/// Returned from `Row::try_get`. Enable strict type checking on the connection to only
+/// accept the exact SQL type of the Rust type.
+#[derive(Debug, Clone)]
+pub struct MismatchedTypeError {
+    rust_type: &'static str,
+    rust_sql_type: String,
+    sql_type: String,
+}
+
+impl MismatchedTypeError {
+    #[cfg(any(feature = "postgres", feature = "mysql"))]
+    pub(crate) fn new<DB, T>(sql_type: &DB::TypeInfo) -> Self
+    where
+        DB: Database,
+        T: Type<DB> + ?Sized,
+    {
+        Self {
+            rust_type: std::any::type_name::<T>(),
+            rust_sql_type: T::type_info().to_string(),
+            sql_type: sql_type.to_string(),
+        }
+    }
+}
+
+impl Display for MismatchedTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mismatched types; Rust type `{}` (as SQL type {}) is not compatible with SQL type {}",
+            self.rust_type, self.rust_sql_type, self.sql_type
+        )
+    }
+}
+
+impl StdError for MismatchedTypeError {}
+
+/// A value could not be decoded from a column of a row; names the column and the Rust type
+/// it was decoded into.
+///
+/// Returned in an [`Error::Decode`] from `Row::try_get`. The error of the value itself, such
+/// as an [`UnexpectedNullError`] or a [`MismatchedTypeError`], is its
+/// [`source`](StdError::source).
+#[derive(Debug)]
+pub struct ColumnDecodeError {
+    column: Box<str>,
+    rust_type: &'static str,
+    source: Box<dyn StdError + Send + Sync>,
+}
+
+impl ColumnDecodeError {
+    #[cfg(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "json"
+    ))]
+    pub(crate) fn new<T>(column: Box<str>, source: Box<dyn StdError + Send + Sync>) -> Self
+    where
+        T: ?Sized,
+    {
+        Self {
+            column,
+            rust_type: std::any::type_name::<T>(),
+            source,
+        }
+    }
+
+    /// The name of the column, or its index when it has none.
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    pub fn rust_type(&self) -> &'static str {
+        self.rust_type
+    }
+}
+
+impl Display for ColumnDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to decode column `{}` as `{}`: {}",
+            self.column, self.rust_type, self.source
+        )
+    }
+}
+
+impl StdError for ColumnDecodeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+/// A field of a struct deriving `FromRow` could not be decoded; names the field.
+///
+/// Returned in an [`Error::Decode`] for a field marked `#[sqlx(json)]`. The error of decoding
+/// its column, a [`ColumnDecodeError`], is its [`source`](StdError::source).
+#[derive(Debug)]
+pub struct FieldDecodeError {
+    field: &'static str,
+    source: Box<dyn StdError + Send + Sync>,
+}
+
+impl FieldDecodeError {
+    #[doc(hidden)]
+    pub fn new(field: &'static str, source: Box<dyn StdError + Send + Sync>) -> Self {
+        Self { field, source }
+    }
+
+    /// The field as `Struct.field`.
+    pub fn field(&self) -> &'static str {
+        self.field
+    }
+}
+
+impl Display for FieldDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.source)
+    }
+}
+
+impl StdError for FieldDecodeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&*self.source)
+    }
+}
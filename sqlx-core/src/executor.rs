@@ -23,7 +23,8 @@ where
     /// Executes the query for its side-effects and
     /// discarding any potential result rows.
     ///
-    /// Returns the number of rows affected, or 0 if not applicable.
+    /// Returns the number of rows affected, or 0 if not applicable. A query without any
+    /// statement, such as an empty string or only a comment, affects 0 rows.
     fn execute<'e, 'q: 'e, 'c: 'e, E: 'e>(
         &'c mut self,
         query: E,
@@ -74,6 +75,39 @@ where
     /// prepare the query. Returning `Some(Default::default())` is an empty arguments object that
     /// will be prepared (and cached) before execution.
     fn into_parts(self) -> (&'q str, Option<DB::Arguments>);
+
+    /// Returns `true` if only the first row of the results will be read, so the driver may
+    /// skip sending the others.
+    ///
+    /// Postgres skips them only for a query with arguments (see [`into_parts`]); one without is
+    /// still read to the end, as the simple query protocol has no row limit.
+    ///
+    /// [`into_parts`]: Execute::into_parts
+    #[doc(hidden)]
+    #[inline]
+    fn first_row_only(&self) -> bool {
+        false
+    }
+}
+
+// Used by `fetch_one` and `fetch_optional` to read only the first row of a query; these always
+// bind arguments, so their queries are prepared and get the row limit on Postgres
+pub(crate) struct FirstRow<E>(pub(crate) E);
+
+impl<'q, DB, E> Execute<'q, DB> for FirstRow<E>
+where
+    DB: Database,
+    E: Execute<'q, DB>,
+{
+    #[inline]
+    fn into_parts(self) -> (&'q str, Option<DB::Arguments>) {
+        self.0.into_parts()
+    }
+
+    #[inline]
+    fn first_row_only(&self) -> bool {
+        true
+    }
 }
 
 impl<'q, DB> Execute<'q, DB> for &'q str
@@ -0,0 +1,71 @@
+//! Query plans, returned by [`Query::explain`](crate::query::Query::explain).
+
+use serde_json::Value;
+
+use crate::database::Database;
+
+/// The plan the database chose to run a query.
+#[derive(Debug, Clone)]
+pub struct ExplainPlan {
+    /// The top-level steps of the plan. Postgres and MySQL return a single step that the
+    /// others are nested in; SQLite may return several, such as one per table scanned.
+    pub nodes: Vec<ExplainNode>,
+
+    /// The plan as returned by the database: the JSON document from Postgres or MySQL, or the
+    /// rows of `EXPLAIN QUERY PLAN` from SQLite as an array of `{"id", "parent", "detail"}`
+    /// objects.
+    pub raw: Value,
+}
+
+/// A step of an [`ExplainPlan`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExplainNode {
+    /// What the step does: the node type from Postgres (`Seq Scan`, `Hash Join`), the name of
+    /// the operation from MySQL (`query_block`, `table`, `nested_loop`) or the description of
+    /// the step from SQLite (`SCAN TABLE users`).
+    pub node_type: String,
+
+    /// The table read by the step, if any. Not given by SQLite.
+    pub relation: Option<String>,
+
+    /// The estimated cost of the step, in the arbitrary units of the database. Not given by
+    /// SQLite.
+    pub cost: Option<f64>,
+
+    /// The estimated number of rows the step produces (Postgres) or reads (MySQL). Not given
+    /// by SQLite.
+    pub rows: Option<f64>,
+
+    /// The number of rows the step actually produced, in a plan from
+    /// [`Query::explain_analyze`](crate::query::Query::explain_analyze).
+    pub actual_rows: Option<f64>,
+
+    pub children: Vec<ExplainNode>,
+}
+
+#[doc(hidden)]
+pub trait Explain: Database {
+    // The statement that returns the plan of `query`
+    fn explain_statement(query: &str, analyze: bool) -> crate::Result<String>;
+
+    // Parses the rows returned by the statement, with every column as text
+    fn parse_plan(rows: Vec<Vec<String>>) -> crate::Result<ExplainPlan>;
+}
+
+// Parses the JSON document in the first column of the first row
+#[allow(dead_code)]
+pub(crate) fn parse_json(rows: &[Vec<String>]) -> crate::Result<Value> {
+    let text = rows.first().and_then(|row| row.first()).ok_or_else(|| {
+        crate::Error::Protocol("expected a query plan but received no rows".into())
+    })?;
+
+    serde_json::from_str(text).map_err(crate::Error::decode)
+}
+
+// A number that may be formatted as a string, as MySQL does for costs
+#[allow(dead_code)]
+pub(crate) fn number(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
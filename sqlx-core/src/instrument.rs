@@ -0,0 +1,377 @@
+//! `tracing` spans around the queries sent to the database, for the `tracing` feature.
+//!
+//! Every query that is executed or fetched runs in an `INFO` span named `query`, with the
+//! `sqlx::query` target, that is a child of the span current when the query was made. When the
+//! query is fetched from a [`Pool`](crate::Pool), acquiring the connection is part of it. The
+//! span has the fields of the OpenTelemetry conventions for databases:
+//!
+//! * `db.system`: `postgresql`, `mysql` or `sqlite`
+//! * `db.statement`: the SQL, as set by [`record_statements`]
+//! * `db.operation`: the first keyword of the SQL, such as `SELECT`
+//! * `db.rows`: the rows fetched or, for `execute`, affected, once the query is done
+//! * `error.code`: the `SQLSTATE` (or database specific code) of a database error
+//! * `otel.status_code`: `ERROR` if the query failed
+//!
+//! With the `protocol-trace` feature, the frames are then logged as `TRACE` events in the span
+//! instead of as `log` records, so they aren't seen twice by a subscriber that also collects
+//! `log` records.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+
+use tracing::field::{Empty, Value};
+use tracing::{Instrument, Span};
+
+/// How much of the SQL of a query its span records in `db.statement`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementRecording {
+    /// The SQL as it was given; the default. Bind parameters are never recorded.
+    Full,
+
+    /// The SQL with each string and number literal replaced by `?`, for queries that splice
+    /// in values that should not leave the application.
+    Redacted,
+
+    /// Nothing; `db.operation` is still recorded.
+    Off,
+}
+
+static RECORDING: AtomicU8 = AtomicU8::new(StatementRecording::Full as u8);
+
+/// Sets how much of the SQL the spans of the queries made from now on record, for the whole
+/// process.
+pub fn record_statements(recording: StatementRecording) {
+    RECORDING.store(recording as u8, Ordering::Relaxed);
+}
+
+fn recording() -> StatementRecording {
+    match RECORDING.load(Ordering::Relaxed) {
+        0 => StatementRecording::Full,
+        1 => StatementRecording::Redacted,
+        _ => StatementRecording::Off,
+    }
+}
+
+// The span of a query, shared by each step of its cursor
+#[derive(Clone)]
+pub(crate) struct QuerySpan {
+    span: Span,
+    rows: Arc<AtomicU64>,
+}
+
+impl QuerySpan {
+    pub(crate) fn new(system: &'static str, sql: &str) -> Self {
+        let span = tracing::info_span!(
+            target: "sqlx::query",
+            "query",
+            db.system = system,
+            db.statement = Empty,
+            db.operation = operation(sql),
+            db.rows = Empty,
+            error.code = Empty,
+            otel.status_code = Empty,
+        );
+
+        match recording() {
+            StatementRecording::Full => {
+                record(&span, "db.statement", &sql);
+            }
+
+            StatementRecording::Redacted => {
+                record(
+                    &span,
+                    "db.statement",
+                    &redact(sql, system == "mysql").as_str(),
+                );
+            }
+
+            StatementRecording::Off => {}
+        }
+
+        Self {
+            span,
+            rows: Arc::default(),
+        }
+    }
+
+    /// Runs a step of a cursor in the span, counting the rows it returns.
+    pub(crate) async fn next<R>(
+        self,
+        next: impl Future<Output = crate::Result<Option<R>>>,
+    ) -> crate::Result<Option<R>> {
+        let result = next.instrument(self.span.clone()).await;
+
+        match &result {
+            Ok(Some(_)) => {
+                self.rows.fetch_add(1, Ordering::Relaxed);
+            }
+
+            Ok(None) => {
+                record(&self.span, "db.rows", &self.rows.load(Ordering::Relaxed));
+            }
+
+            Err(error) => self.fail(error),
+        }
+
+        result
+    }
+
+    /// Runs a query that returns the rows it affected in the span.
+    pub(crate) async fn execute(
+        self,
+        execute: impl Future<Output = crate::Result<u64>>,
+    ) -> crate::Result<u64> {
+        let result = execute.instrument(self.span.clone()).await;
+
+        match &result {
+            Ok(rows) => {
+                record(&self.span, "db.rows", rows);
+            }
+
+            Err(error) => self.fail(error),
+        }
+
+        result
+    }
+
+    fn fail(&self, error: &crate::Error) {
+        record(&self.span, "otel.status_code", &"ERROR");
+
+        if let crate::Error::Database(error) = error {
+            if let Some(code) = error.code() {
+                record(&self.span, "error.code", &code);
+            }
+        }
+    }
+}
+
+// `Span::record` takes the value by reference up to tracing 0.1.35 and by value after, which
+// a reference is as well
+fn record<V: Value>(span: &Span, field: &str, value: &V) {
+    span.record(field, value);
+}
+
+// The first keyword of a statement, after any whitespace and comments
+fn operation(sql: &str) -> &str {
+    let mut rest = sql.trim_start();
+
+    loop {
+        if rest.starts_with("--") {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]).trim_start();
+        } else if rest.starts_with("/*") {
+            rest = rest
+                .find("*/")
+                .map_or("", |end| &rest[end + 2..])
+                .trim_start();
+        } else {
+            break;
+        }
+    }
+
+    let end = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+
+    &rest[..end]
+}
+
+// Replaces the string and number literals of `sql` with `?`, keeping quoted identifiers. In
+// MySQL, strings may be double quoted as well, and a backslash escapes the next character.
+fn redact(sql: &str, mysql: bool) -> String {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+
+    // Whether the last character continues an identifier or a parameter, such as `t1`, `$1` or
+    // `?1`, whose digits are kept
+    let mut in_word = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' if c == '\'' || mysql => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' if mysql => {
+                            chars.next();
+                        }
+
+                        // a quote is escaped by doubling it
+                        _ if next == c && chars.peek() == Some(&c) => {
+                            chars.next();
+                        }
+
+                        _ if next == c => break,
+
+                        _ => {}
+                    }
+                }
+
+                redacted.push('?');
+                in_word = false;
+            }
+
+            '"' | '`' => {
+                redacted.push(c);
+
+                for next in &mut chars {
+                    redacted.push(next);
+
+                    if next == c {
+                        break;
+                    }
+                }
+
+                in_word = false;
+            }
+
+            '0'..='9' if !in_word => {
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '.' {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                redacted.push('?');
+            }
+
+            _ => {
+                redacted.push(c);
+                in_word = c.is_alphanumeric() || "_$?:@".contains(c);
+            }
+        }
+    }
+
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+
+    use futures_util::FutureExt;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use super::{operation, redact, QuerySpan};
+
+    // Collects the fields recorded on every span
+    #[derive(Default, Clone)]
+    struct Fields(Arc<Mutex<Vec<(&'static str, String)>>>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((field.name(), format!("{:?}", value)));
+        }
+    }
+
+    impl Fields {
+        fn get(&self, name: &str) -> Option<String> {
+            let fields = self.0.lock().unwrap();
+            let (_, value) = fields.iter().rev().find(|(field, _)| *field == name)?;
+
+            Some(value.clone())
+        }
+    }
+
+    impl Subscriber for Fields {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes) -> Id {
+            span.record(&mut self.clone());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, values: &Record) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn it_records_the_query_and_its_outcome() {
+        let fields = Fields::default();
+
+        tracing::subscriber::with_default(fields.clone(), || {
+            let span = QuerySpan::new("postgresql", "SELECT * FROM users");
+
+            for row in &[Some(1), Some(2), None] {
+                let next = span.clone().next(async move { Ok(*row) });
+                next.now_or_never().unwrap().unwrap();
+            }
+        });
+
+        assert_eq!(fields.get("db.system").as_deref(), Some("\"postgresql\""));
+        assert_eq!(fields.get("db.operation").as_deref(), Some("\"SELECT\""));
+        assert_eq!(
+            fields.get("db.statement").as_deref(),
+            Some("\"SELECT * FROM users\"")
+        );
+        assert_eq!(fields.get("db.rows").as_deref(), Some("2"));
+        assert_eq!(fields.get("otel.status_code"), None);
+
+        let fields = Fields::default();
+
+        tracing::subscriber::with_default(fields.clone(), || {
+            let execute = QuerySpan::new("mysql", "DELETE FROM users")
+                .execute(async { Err(crate::Error::RowNotFound) });
+
+            assert!(execute.now_or_never().unwrap().is_err());
+        });
+
+        assert_eq!(fields.get("db.rows"), None);
+        assert_eq!(fields.get("otel.status_code").as_deref(), Some("\"ERROR\""));
+    }
+
+    #[test]
+    fn it_finds_the_operation() {
+        assert_eq!(operation("SELECT 1"), "SELECT");
+        assert_eq!(operation("\n  insert into t values (1)"), "insert");
+        assert_eq!(operation("-- a\n/* b */ UPDATE t SET a = 1"), "UPDATE");
+        assert_eq!(operation("BEGIN;"), "BEGIN");
+        assert_eq!(operation(""), "");
+    }
+
+    #[test]
+    fn it_redacts_literals() {
+        assert_eq!(
+            redact(
+                "SELECT * FROM t1 WHERE a = 'it''s' AND b = 4.5 AND c = $1",
+                false
+            ),
+            "SELECT * FROM t1 WHERE a = ? AND b = ? AND c = $1"
+        );
+
+        assert_eq!(
+            redact("UPDATE t SET a = 'x\\'y', b = 0x1F WHERE id = ?", true),
+            "UPDATE t SET a = ?, b = ? WHERE id = ?"
+        );
+
+        assert_eq!(
+            redact("SELECT \"col 2\" FROM t WHERE a = 3", false),
+            "SELECT \"col 2\" FROM t WHERE a = ?"
+        );
+
+        assert_eq!(
+            redact("SELECT `col 2` FROM t WHERE a = \"3\"", true),
+            "SELECT `col 2` FROM t WHERE a = ?"
+        );
+
+        assert_eq!(redact("SELECT ?1 + 1.5e3", false), "SELECT ?1 + ?");
+    }
+}
@@ -8,7 +8,15 @@ use futures_util::ready;
 
 use crate::runtime::{AsyncRead, AsyncReadExt, AsyncWrite};
 
-const RBUF_SIZE: usize = 8 * 1024;
+/// Initial size of the buffer used when reading incoming messages.
+pub const DEFAULT_READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Initial size of the buffer used when sending outgoing messages.
+pub const DEFAULT_WRITE_BUFFER_SIZE: usize = 1024;
+
+/// Largest incoming message that is read; a longer one fails the connection instead of
+/// allocating a buffer for it.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
 
 pub struct BufStream<S> {
     pub(crate) stream: S,
@@ -23,6 +31,9 @@ pub struct BufStream<S> {
     rbuf: Vec<u8>,
     rbuf_rindex: usize,
     rbuf_windex: usize,
+
+    // Size the read buffer is reset to once it has been consumed
+    rbuf_size: usize,
 }
 
 pub struct GuardedFlush<'a, S: 'a> {
@@ -34,14 +45,18 @@ impl<S> BufStream<S>
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
-    pub fn new(stream: S) -> Self {
+    /// Creates a stream with the given initial sizes of the read and write buffers.
+    ///
+    /// Both buffers grow as needed to fit a whole message.
+    pub fn with_capacity(stream: S, read_size: usize, write_size: usize) -> Self {
         Self {
             stream,
             stream_eof: false,
-            wbuf: Vec::with_capacity(1024),
-            rbuf: vec![0; RBUF_SIZE],
+            wbuf: Vec::with_capacity(write_size),
+            rbuf: vec![0; read_size],
             rbuf_rindex: 0,
             rbuf_windex: 0,
+            rbuf_size: read_size,
         }
     }
 
@@ -101,7 +116,7 @@ where
                     self.rbuf_windex = 0;
                 } else {
                     // Allocate a new buffer
-                    let mut new_rbuf = Vec::with_capacity(RBUF_SIZE);
+                    let mut new_rbuf = Vec::with_capacity(self.rbuf_size);
 
                     // Take the minimum of the read and write indexes
                     let min_index = self.rbuf_rindex.min(self.rbuf_windex);
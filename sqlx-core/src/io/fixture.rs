@@ -0,0 +1,389 @@
+//! Scripted byte exchanges with a database server, to test the drivers without one.
+//!
+//! A fixture is a text file with one chunk of bytes per line, in hexadecimal:
+//!
+//! ```text
+//! # the server sends its handshake
+//! < 4a0000000a382e302e3138...
+//! # the client is expected to answer with this; `??` matches any byte
+//! > 5c000001 ??*32 00
+//! ```
+//!
+//! Lines starting with `<` are sent by the server, each one to a separate read, so a packet can
+//! be split across reads by splitting it across lines. Lines starting with `>` are what the
+//! client is expected to send next; consecutive ones are joined, however the client happens to
+//! write them. `??` matches any byte and `??*N` any `N` bytes, e.g. for a random nonce. Anything
+//! after a `#` is a comment.
+//!
+//! With the `protocol-capture` feature, every connection that is opened while
+//! `SQLX_PROTOCOL_CAPTURE` names a directory writes what it sends and receives to a new fixture
+//! in that directory. Only what is exchanged before a TLS upgrade can be replayed, so capture
+//! with TLS turned off (`sslmode=disable`, `ssl-mode=DISABLED`).
+
+#[cfg(feature = "protocol-capture")]
+pub(crate) use capture::Capture;
+#[cfg(test)]
+pub(crate) use replay::{block_on, FixtureStream};
+
+#[cfg(test)]
+mod replay {
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures_util::task::noop_waker_ref;
+
+    use crate::runtime::{AsyncRead, AsyncWrite};
+
+    #[derive(Debug)]
+    enum Step {
+        // Bytes the client is expected to send; `None` matches any byte
+        Send(VecDeque<Option<u8>>),
+
+        // Bytes the server sends in a single read
+        Receive(VecDeque<u8>),
+    }
+
+    /// A stream that replays a fixture; see the [module documentation](self).
+    ///
+    /// A read or write the fixture does not expect fails with an `InvalidData` error that says
+    /// what was expected, and reading past the end of the fixture returns end-of-file.
+    #[derive(Debug)]
+    pub(crate) struct FixtureStream {
+        steps: VecDeque<Step>,
+
+        // How many bytes the client has sent, to point at where it went wrong
+        sent: usize,
+    }
+
+    impl FixtureStream {
+        /// Parses a fixture; panics if it is malformed.
+        pub(crate) fn new(fixture: &str) -> Self {
+            let mut steps = VecDeque::<Step>::new();
+
+            for (index, line) in fixture.lines().enumerate() {
+                let line = line.split('#').next().unwrap_or_default().trim();
+
+                if line.is_empty() {
+                    continue;
+                }
+
+                let (direction, bytes) = line.split_at(1);
+                let bytes = parse_bytes(bytes)
+                    .unwrap_or_else(|err| panic!("fixture line {}: {}", index + 1, err));
+
+                match direction {
+                    ">" => match steps.back_mut() {
+                        Some(Step::Send(expected)) => expected.extend(bytes),
+                        _ => steps.push_back(Step::Send(bytes.into_iter().collect())),
+                    },
+
+                    "<" => {
+                        let bytes = bytes.into_iter().collect::<Option<VecDeque<u8>>>();
+                        let bytes = bytes.unwrap_or_else(|| {
+                            panic!("fixture line {}: the server can't send `??`", index + 1)
+                        });
+
+                        steps.push_back(Step::Receive(bytes));
+                    }
+
+                    _ => panic!(
+                        "fixture line {}: expected `<` or `>`, found {:?}",
+                        index + 1,
+                        direction
+                    ),
+                }
+            }
+
+            Self { steps, sent: 0 }
+        }
+
+        /// Panics if the client did not go through the whole fixture.
+        pub(crate) fn assert_done(&self) {
+            if let Some(step) = self.steps.front() {
+                panic!(
+                    "fixture not done after {} bytes sent; next: {:?}",
+                    self.sent, step
+                );
+            }
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let received = match self.steps.front_mut() {
+                Some(Step::Receive(received)) => received,
+
+                Some(Step::Send(expected)) => {
+                    return Err(mismatch(format!(
+                        "the client read after sending {} bytes, but {} more were expected first",
+                        self.sent,
+                        expected.len()
+                    )));
+                }
+
+                // the server closed the connection
+                None => return Ok(0),
+            };
+
+            let len = received.len().min(buf.len());
+
+            for (byte, value) in buf.iter_mut().zip(received.drain(..len)) {
+                *byte = value;
+            }
+
+            if received.is_empty() {
+                self.steps.pop_front();
+            }
+
+            Ok(len)
+        }
+
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            for &byte in buf {
+                let expected = match self.steps.front_mut() {
+                    Some(Step::Send(expected)) => expected,
+
+                    Some(Step::Receive(received)) => {
+                        return Err(mismatch(format!(
+                            "the client sent {:02x} at byte {} while the server had {} bytes \
+                             to send",
+                            byte,
+                            self.sent,
+                            received.len()
+                        )));
+                    }
+
+                    None => {
+                        return Err(mismatch(format!(
+                            "the client sent {:02x} at byte {}, after the end of the fixture",
+                            byte, self.sent
+                        )));
+                    }
+                };
+
+                match expected.pop_front() {
+                    Some(Some(value)) if value != byte => {
+                        return Err(mismatch(format!(
+                            "the client sent {:02x} at byte {}, but {:02x} was expected",
+                            byte, self.sent, value
+                        )));
+                    }
+
+                    _ => {}
+                }
+
+                if expected.is_empty() {
+                    self.steps.pop_front();
+                }
+
+                self.sent += 1;
+            }
+
+            Ok(buf.len())
+        }
+    }
+
+    fn mismatch(message: String) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, format!("fixture: {}", message))
+    }
+
+    fn parse_bytes(line: &str) -> Result<Vec<Option<u8>>, String> {
+        let mut bytes = Vec::new();
+
+        for token in line.split_whitespace() {
+            if token == "??" {
+                bytes.push(None);
+            } else if token.starts_with("??*") {
+                let count = token
+                    .trim_start_matches("??*")
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid repetition {:?}", token))?;
+
+                bytes.extend((0..count).map(|_| None));
+            } else {
+                let decoded = hex::decode(token).map_err(|err| format!("{:?}: {}", token, err))?;
+
+                bytes.extend(decoded.into_iter().map(Some));
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    impl AsyncRead for FixtureStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(self.get_mut().read(buf))
+        }
+    }
+
+    impl AsyncWrite for FixtureStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(self.get_mut().write(buf))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        #[cfg(feature = "runtime-async-std")]
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        #[cfg(feature = "runtime-tokio")]
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Runs a future that only waits on a [`FixtureStream`], which never blocks, to completion.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future waited on something other than a fixture"),
+        }
+    }
+
+    mod tests {
+        use super::FixtureStream;
+
+        #[test]
+        fn it_replays_the_server_one_line_per_read() {
+            let mut stream = FixtureStream::new("< 0102 # first\n< 03\n\n# done\n");
+            let mut buf = [0; 8];
+
+            assert_eq!(stream.read(&mut buf).unwrap(), 2);
+            assert_eq!(buf[..2], [1, 2]);
+            assert_eq!(stream.read(&mut buf[..0]).unwrap(), 0);
+            assert_eq!(stream.read(&mut buf).unwrap(), 1);
+            assert_eq!(buf[0], 3);
+
+            stream.assert_done();
+            assert_eq!(stream.read(&mut buf).unwrap(), 0);
+        }
+
+        #[test]
+        fn it_matches_what_the_client_sends() {
+            let mut stream = FixtureStream::new("> 01 ??*2\n> 04\n< 05");
+
+            assert_eq!(stream.write(&[1, 0xAA]).unwrap(), 2);
+            assert_eq!(stream.write(&[0xBB, 4]).unwrap(), 2);
+
+            let mut buf = [0; 8];
+            assert_eq!(stream.read(&mut buf).unwrap(), 1);
+
+            stream.assert_done();
+        }
+
+        #[test]
+        fn it_fails_on_unexpected_bytes() {
+            let mut stream = FixtureStream::new("> 0102\n< 03");
+            let mut buf = [0; 8];
+
+            let err = stream.write(&[1, 3]).unwrap_err();
+            assert!(err.to_string().contains("sent 03 at byte 1"), "{}", err);
+
+            let mut stream = FixtureStream::new("> 0102\n< 03");
+            let err = stream.read(&mut buf).unwrap_err();
+            assert!(err.to_string().contains("2 more were expected"), "{}", err);
+
+            let mut stream = FixtureStream::new("< 03");
+            assert!(stream.write(&[1]).is_err());
+        }
+    }
+}
+
+#[cfg(feature = "protocol-capture")]
+mod capture {
+    use std::fmt::Write as _;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // Tells apart the fixtures of the connections opened in the same millisecond
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    /// Writes the bytes exchanged on a connection to a fixture.
+    ///
+    /// This writes to the file directly as bytes are exchanged, which blocks; it is only meant
+    /// for recording a few connections.
+    pub(crate) struct Capture {
+        file: File,
+    }
+
+    impl Capture {
+        /// Starts a fixture for a new connection to `host`, if `SQLX_PROTOCOL_CAPTURE` is set.
+        pub(crate) fn start(host: &str) -> Option<Self> {
+            let dir = PathBuf::from(std::env::var_os("SQLX_PROTOCOL_CAPTURE")?);
+
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis());
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let path = dir.join(format!("{}-{}.fixture", millis, id));
+
+            let mut file = match File::create(&path) {
+                Ok(file) => file,
+
+                Err(error) => {
+                    log::warn!("could not capture to {}: {}", path.display(), error);
+                    return None;
+                }
+            };
+
+            let _ = writeln!(file, "# captured from {}", host);
+
+            Some(Self { file })
+        }
+
+        pub(crate) fn sent(&mut self, bytes: &[u8]) {
+            self.line('>', bytes);
+        }
+
+        pub(crate) fn received(&mut self, bytes: &[u8]) {
+            self.line('<', bytes);
+        }
+
+        pub(crate) fn note(&mut self, note: &str) {
+            let _ = writeln!(self.file, "# {}", note);
+        }
+
+        fn line(&mut self, direction: char, bytes: &[u8]) {
+            if bytes.is_empty() {
+                return;
+            }
+
+            let mut line = String::with_capacity(bytes.len() * 2 + 3);
+            line.push(direction);
+            line.push(' ');
+
+            for byte in bytes {
+                let _ = write!(line, "{:02x}", byte);
+            }
+
+            if let Err(error) = writeln!(self.file, "{}", line) {
+                log::warn!("could not write a captured exchange: {}", error);
+            }
+        }
+    }
+
+    impl Drop for Capture {
+        fn drop(&mut self) {
+            let _ = self.file.flush();
+        }
+    }
+}
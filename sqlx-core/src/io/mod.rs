@@ -6,10 +6,18 @@ mod buf_mut;
 mod byte_str;
 mod tls;
 
+#[cfg(feature = "protocol-trace")]
+pub(crate) mod trace;
+
+#[cfg(any(test, feature = "protocol-capture"))]
+pub(crate) mod fixture;
+
 pub use self::{
     buf::{Buf, ToBuf},
     buf_mut::BufMut,
-    buf_stream::BufStream,
+    buf_stream::{
+        BufStream, DEFAULT_MAX_MESSAGE_SIZE, DEFAULT_READ_BUFFER_SIZE, DEFAULT_WRITE_BUFFER_SIZE,
+    },
     byte_str::ByteStr,
     tls::MaybeTlsStream,
 };
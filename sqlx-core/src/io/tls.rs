@@ -3,6 +3,10 @@ use std::net::Shutdown;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+#[cfg(feature = "protocol-capture")]
+use crate::io::fixture::Capture;
+#[cfg(test)]
+use crate::io::fixture::FixtureStream;
 use crate::runtime::{AsyncRead, AsyncWrite, TcpStream};
 use crate::url::Url;
 
@@ -10,6 +14,9 @@ use self::Inner::*;
 
 pub struct MaybeTlsStream {
     inner: Inner,
+
+    #[cfg(feature = "protocol-capture")]
+    capture: Option<Capture>,
 }
 
 enum Inner {
@@ -18,6 +25,8 @@ enum Inner {
     Tls(async_native_tls::TlsStream<TcpStream>),
     #[cfg(feature = "tls")]
     Upgrading,
+    #[cfg(test)]
+    Fixture(FixtureStream),
 }
 
 impl MaybeTlsStream {
@@ -25,9 +34,29 @@ impl MaybeTlsStream {
         let conn = TcpStream::connect((url.host(), url.port(default_port))).await?;
         Ok(Self {
             inner: Inner::NotTls(conn),
+            #[cfg(feature = "protocol-capture")]
+            capture: Capture::start(url.host()),
         })
     }
 
+    // A stream that replays a fixture instead of talking to a server
+    #[cfg(test)]
+    pub(crate) fn fixture(fixture: &str) -> Self {
+        Self {
+            inner: Inner::Fixture(FixtureStream::new(fixture)),
+            #[cfg(feature = "protocol-capture")]
+            capture: None,
+        }
+    }
+
+    // Panics if this replays a fixture that was not gone through completely
+    #[cfg(test)]
+    pub(crate) fn assert_fixture_done(&self) {
+        if let Inner::Fixture(ref fixture) = self.inner {
+            fixture.assert_done();
+        }
+    }
+
     #[allow(dead_code)]
     pub fn is_tls(&self) -> bool {
         match self.inner {
@@ -36,6 +65,23 @@ impl MaybeTlsStream {
             Inner::Tls(_) => true,
             #[cfg(feature = "tls")]
             Inner::Upgrading => false,
+            #[cfg(test)]
+            Inner::Fixture(_) => false,
+        }
+    }
+
+    // The hash of the certificate of the server, as the `tls-server-end-point` channel binding
+    // of RFC 5929
+    #[allow(dead_code)]
+    pub fn tls_server_end_point(&self) -> Option<Vec<u8>> {
+        match self.inner {
+            Inner::NotTls(_) => None,
+            #[cfg(feature = "tls")]
+            Inner::Tls(ref conn) => conn.tls_server_end_point().ok().and_then(|hash| hash),
+            #[cfg(feature = "tls")]
+            Inner::Upgrading => None,
+            #[cfg(test)]
+            Inner::Fixture(_) => None,
         }
     }
 
@@ -50,8 +96,20 @@ impl MaybeTlsStream {
             NotTls(conn) => conn,
             Tls(_) => return Err(tls_err!("connection already upgraded").into()),
             Upgrading => return Err(tls_err!("connection already failed to upgrade").into()),
+            #[cfg(test)]
+            Fixture(fixture) => {
+                self.inner = Fixture(fixture);
+                return Err(tls_err!("a fixture can't be upgraded to TLS").into());
+            }
         };
 
+        #[cfg(feature = "protocol-capture")]
+        {
+            if let Some(capture) = &mut self.capture {
+                capture.note("upgraded to TLS; what follows can't be replayed");
+            }
+        }
+
         self.inner = Tls(connector.connect(url.host(), conn).await?);
 
         Ok(())
@@ -65,6 +123,8 @@ impl MaybeTlsStream {
             #[cfg(feature = "tls")]
             // connection already closed
             Upgrading => Ok(()),
+            #[cfg(test)]
+            Fixture(_) => Ok(()),
         }
     }
 }
@@ -77,6 +137,8 @@ macro_rules! forward_pin (
             Tls(ref mut conn) => Pin::new(conn).$method($($arg),*),
             #[cfg(feature = "tls")]
             Upgrading => Err(io::Error::new(io::ErrorKind::Other, "connection broken; TLS upgrade failed")).into(),
+            #[cfg(test)]
+            Fixture(ref mut conn) => Pin::new(conn).$method($($arg),*),
         }
     )
 );
@@ -87,7 +149,16 @@ impl AsyncRead for MaybeTlsStream {
         cx: &mut Context,
         buf: &mut [u8],
     ) -> Poll<io::Result<usize>> {
-        forward_pin!(self.poll_read(cx, buf))
+        let read = forward_pin!(self.poll_read(cx, buf));
+
+        #[cfg(feature = "protocol-capture")]
+        {
+            if let (Some(capture), Poll::Ready(Ok(n))) = (&mut self.capture, &read) {
+                capture.received(&buf[..*n]);
+            }
+        }
+
+        read
     }
 
     #[cfg(feature = "runtime-async-std")]
@@ -106,7 +177,16 @@ impl AsyncWrite for MaybeTlsStream {
         cx: &mut Context,
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
-        forward_pin!(self.poll_write(cx, buf))
+        let written = forward_pin!(self.poll_write(cx, buf));
+
+        #[cfg(feature = "protocol-capture")]
+        {
+            if let (Some(capture), Poll::Ready(Ok(n))) = (&mut self.capture, &written) {
+                capture.sent(&buf[..*n]);
+            }
+        }
+
+        written
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
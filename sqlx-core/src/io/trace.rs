@@ -0,0 +1,105 @@
+//! Logging of the frames exchanged with the database server, at the `trace` level with the
+//! `sqlx::protocol` target, for the `protocol-trace` feature. With the `tracing` feature, the
+//! frames are `tracing` events in the span of their query rather than `log` records.
+
+use std::fmt::Write;
+
+// The most bytes of a frame that are shown
+const MAX_DUMP_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Send,
+    Receive,
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn enabled() -> bool {
+    log::log_enabled!(target: "sqlx::protocol", log::Level::Trace)
+}
+
+// `tracing` can only tell if an event of a target is enabled from 0.1.30, which needs a newer
+// compiler than sqlx-core; the event itself is still filtered by its target
+#[cfg(feature = "tracing")]
+pub(crate) fn enabled() -> bool {
+    tracing::level_enabled!(tracing::Level::TRACE)
+}
+
+/// Logs a frame; the contents of a `sensitive` frame, such as one with a password, are left out.
+pub(crate) fn frame(direction: Direction, name: &str, frame: &[u8], sensitive: bool) {
+    #[cfg(not(feature = "tracing"))]
+    log::trace!(
+        target: "sqlx::protocol",
+        "{}",
+        format_frame(direction, name, frame, sensitive)
+    );
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        target: "sqlx::protocol",
+        "{}",
+        format_frame(direction, name, frame, sensitive)
+    );
+}
+
+fn format_frame(direction: Direction, name: &str, frame: &[u8], sensitive: bool) -> String {
+    let arrow = match direction {
+        Direction::Send => "->",
+        Direction::Receive => "<-",
+    };
+
+    let mut line = format!("{} {} ({} bytes)", arrow, name, frame.len());
+
+    if sensitive {
+        line.push_str(" <redacted>");
+        return line;
+    }
+
+    if !frame.is_empty() {
+        line.push(' ');
+    }
+
+    for byte in frame.iter().take(MAX_DUMP_LEN) {
+        let _ = write!(line, "{:02x}", byte);
+    }
+
+    if frame.len() > MAX_DUMP_LEN {
+        line.push_str("...");
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_frame, Direction};
+
+    #[test]
+    fn it_formats_frames() {
+        assert_eq!(
+            format_frame(Direction::Send, "Query", b"SELECT 1\0", false),
+            "-> Query (9 bytes) 53454c454354203100"
+        );
+
+        assert_eq!(
+            format_frame(Direction::Receive, "ReadyForQuery", b"", false),
+            "<- ReadyForQuery (0 bytes)"
+        );
+    }
+
+    #[test]
+    fn it_bounds_the_dump() {
+        let line = format_frame(Direction::Receive, "DataRow", &[0xAB; 100], false);
+
+        assert!(line.starts_with("<- DataRow (100 bytes) abab"));
+        assert!(line.ends_with("ab..."));
+        assert_eq!(line.matches("ab").count(), 64);
+    }
+
+    #[test]
+    fn it_redacts_sensitive_frames() {
+        let line = format_frame(Direction::Send, "PasswordMessage", b"hunter2\0", true);
+
+        assert_eq!(line, "-> PasswordMessage (8 bytes) <redacted>");
+    }
+}
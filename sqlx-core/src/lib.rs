@@ -16,6 +16,7 @@ pub mod error;
 mod io;
 
 mod maybe_owned;
+mod named;
 
 pub mod connection;
 pub mod cursor;
@@ -35,12 +36,41 @@ pub mod arguments;
 pub mod decode;
 pub mod describe;
 pub mod encode;
+pub mod notice;
 pub mod pool;
 pub mod query;
+pub mod quote;
+
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+mod path;
+
+#[cfg(any(feature = "mysql", feature = "postgres"))]
+mod system_time;
+
+#[cfg(feature = "bench-internals")]
+#[doc(hidden)]
+pub mod bench;
+
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub mod blocking;
+
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub mod explain;
+
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util;
+
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub mod instrument;
 
 #[macro_use]
 pub mod query_as;
 
+#[macro_use]
 pub mod types;
 
 #[macro_use]
@@ -60,6 +90,12 @@ pub mod sqlite;
 
 pub use error::{Error, Result};
 
+/// The oldest version of Rust this crate builds with, as `major.minor`; the build fails with a
+/// message naming it on older compilers.
+///
+/// It depends on the enabled features: `sqlite` needs Rust 1.51, for its bundled SQLite.
+pub const MSRV: &str = env!("SQLX_MSRV");
+
 // Named Lifetimes:
 //  'c: connection
 //  'q: query string (and arguments)
@@ -8,7 +8,28 @@ use crate::types::Type;
 pub struct MySqlArguments {
     pub(crate) param_types: Vec<MySqlTypeInfo>,
     pub(crate) params: Vec<u8>,
-    pub(crate) null_bitmap: Vec<u8>,
+
+    // Whether each parameter is NULL, in order; packed into the bitmap of
+    // COM_STMT_EXECUTE only once every parameter has been added
+    pub(crate) nulls: Vec<bool>,
+
+    // The first value that failed to encode; the query fails with it instead of being sent
+    pub(crate) error: Option<crate::Error>,
+}
+
+impl MySqlArguments {
+    // The NULL bitmap of COM_STMT_EXECUTE, where bit `i % 8` of byte `i / 8`
+    // is set when parameter `i` is NULL
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_execute.html
+    pub(crate) fn null_bitmap(&self) -> Vec<u8> {
+        let mut bitmap = vec![0_u8; (self.nulls.len() + 7) / 8];
+
+        for (index, _) in self.nulls.iter().enumerate().filter(|(_, &null)| null) {
+            bitmap[index / 8] |= 1 << (index % 8);
+        }
+
+        bitmap
+    }
 }
 
 impl Arguments for MySqlArguments {
@@ -16,13 +37,15 @@ impl Arguments for MySqlArguments {
 
     fn reserve(&mut self, len: usize, size: usize) {
         self.param_types.reserve(len);
+        self.nulls.reserve(len);
         self.params.reserve(size);
+    }
 
-        // ensure we have enough size in the bitmap to hold at least `len` extra bits
-        // the second `& 7` gives us 0 spare bits when param_types.len() is a multiple of 8
-        let spare_bits = (8 - (self.param_types.len()) & 7) & 7;
-        // ensure that if there are no spare bits left, `len = 1` reserves another byte
-        self.null_bitmap.reserve((len + 7 - spare_bits) / 8);
+    fn clear(&mut self) {
+        self.param_types.clear();
+        self.params.clear();
+        self.nulls.clear();
+        self.error = None;
     }
 
     fn add<T>(&mut self, value: T)
@@ -30,14 +53,135 @@ impl Arguments for MySqlArguments {
         T: Type<Self::Database>,
         T: Encode<Self::Database>,
     {
-        let type_id = <T as Type<MySql>>::type_info();
-        let index = self.param_types.len();
+        self.param_types.push(<T as Type<MySql>>::type_info());
+
+        let pos = self.params.len();
+
+        let null = match value.try_encode(&mut self.params) {
+            Ok(IsNull::No) => false,
+
+            Ok(IsNull::Yes) => {
+                // the server reads no value for a NULL parameter
+                self.params.truncate(pos);
+
+                true
+            }
+
+            Err(error) => {
+                self.params.truncate(pos);
+                self.error.get_or_insert(error);
+
+                false
+            }
+        };
+
+        self.nulls.push(null);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MySqlArguments;
+    use crate::arguments::Arguments;
+    use crate::mysql::protocol::{Capabilities, ComStmtExecute, Cursor, Encode};
+
+    // xorshift, to spread the NULL parameters the same way on each run
+    fn next(state: &mut u32) -> u32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        *state
+    }
 
-        self.param_types.push(type_id);
-        self.null_bitmap.resize((index / 8) + 1, 0);
+    #[test]
+    fn it_sets_a_null_bit_for_each_none() {
+        let mut state = 0x9E37_79B9;
 
-        if let IsNull::Yes = value.encode_nullable(&mut self.params) {
-            self.null_bitmap[index / 8] |= (1 << index % 8) as u8;
+        for count in 1..=80 {
+            for _ in 0..16 {
+                let nulls: Vec<bool> = (0..count).map(|_| next(&mut state) % 3 == 0).collect();
+
+                let mut args = MySqlArguments::default();
+                let mut expected_params = Vec::new();
+
+                for (index, &null) in nulls.iter().enumerate() {
+                    if null {
+                        args.add(None::<i32>);
+                    } else {
+                        args.add(Some(index as i32));
+                        expected_params.extend_from_slice(&(index as i32).to_le_bytes());
+                    }
+                }
+
+                let bitmap = args.null_bitmap();
+
+                assert_eq!(bitmap.len(), (count + 7) / 8);
+                assert_eq!(args.param_types.len(), count);
+                assert_eq!(args.params, expected_params);
+
+                for (index, &null) in nulls.iter().enumerate() {
+                    assert_eq!(
+                        bitmap[index / 8] & (1 << (index % 8)) != 0,
+                        null,
+                        "parameter {} of {}",
+                        index,
+                        count
+                    );
+                }
+
+                // the bits past the last parameter stay clear
+                if count % 8 != 0 {
+                    assert_eq!(bitmap[count / 8] >> (count % 8), 0);
+                }
+            }
         }
     }
+
+    #[test]
+    fn it_starts_over_after_clear() {
+        let mut args = MySqlArguments::default();
+
+        for _ in 0..20 {
+            args.add(None::<i32>);
+        }
+
+        args.clear();
+        args.add(Some(1_i32));
+        args.add(None::<i32>);
+
+        assert_eq!(args.null_bitmap(), vec![0b10]);
+        assert_eq!(args.params, 1_i32.to_le_bytes());
+    }
+
+    #[test]
+    fn it_encodes_the_bitmap_before_the_types() {
+        let mut args = MySqlArguments::default();
+
+        for index in 0..9 {
+            if index == 0 || index == 8 {
+                args.add(None::<i32>);
+            } else {
+                args.add(Some(7_i32));
+            }
+        }
+
+        let null_bitmap = args.null_bitmap();
+        let mut buf = Vec::new();
+
+        ComStmtExecute {
+            statement_id: 1,
+            cursor: Cursor::NO_CURSOR,
+            params: &args.params,
+            null_bitmap: &null_bitmap,
+            param_types: &args.param_types,
+        }
+        .encode(&mut buf, Capabilities::empty());
+
+        // command, statement id, cursor and iterations come first
+        assert_eq!(&buf[10..12], &[0b0000_0001, 0b0000_0001]);
+
+        // then the flag to send types, a type and flag for each parameter and the 7 values
+        assert_eq!(buf[12], 1);
+        assert_eq!(buf.len(), 13 + 9 * 2 + 7 * 4);
+    }
 }
@@ -6,8 +6,10 @@ use futures_core::future::BoxFuture;
 use crate::connection::ConnectionSource;
 use crate::cursor::Cursor;
 use crate::executor::Execute;
+#[cfg(feature = "tracing")]
+use crate::instrument::QuerySpan;
 use crate::mysql::protocol::{ColumnCount, ColumnDefinition, Row, Status, TypeId};
-use crate::mysql::{MySql, MySqlArguments, MySqlConnection, MySqlRow};
+use crate::mysql::{MySql, MySqlArguments, MySqlConnection, MySqlRow, MySqlTypeInfo};
 use crate::pool::Pool;
 
 pub struct MySqlCursor<'c, 'q> {
@@ -15,7 +17,15 @@ pub struct MySqlCursor<'c, 'q> {
     query: Option<(&'q str, Option<MySqlArguments>)>,
     column_names: Arc<HashMap<Box<str>, u16>>,
     column_types: Vec<TypeId>,
+    column_type_infos: Arc<[MySqlTypeInfo]>,
     binary: bool,
+
+    // Added up from each OK packet; see [Cursor::rows_affected]
+    affected_rows: u64,
+    done: bool,
+
+    #[cfg(feature = "tracing")]
+    span: QuerySpan,
 }
 
 impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
@@ -27,12 +37,19 @@ impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
         Self: Sized,
         E: Execute<'q, MySql>,
     {
+        let query = query.into_parts();
+
         Self {
+            #[cfg(feature = "tracing")]
+            span: QuerySpan::new("mysql", query.0),
             source: ConnectionSource::Pool(pool.clone()),
             column_names: Arc::default(),
             column_types: Vec::new(),
+            column_type_infos: Arc::new([] as [MySqlTypeInfo; 0]),
             binary: true,
-            query: Some(query.into_parts()),
+            query: Some(query),
+            affected_rows: 0,
+            done: false,
         }
     }
 
@@ -42,18 +59,39 @@ impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
         Self: Sized,
         E: Execute<'q, MySql>,
     {
+        let query = query.into_parts();
+
         Self {
+            #[cfg(feature = "tracing")]
+            span: QuerySpan::new("mysql", query.0),
             source: ConnectionSource::ConnectionRef(conn),
             column_names: Arc::default(),
             column_types: Vec::new(),
+            column_type_infos: Arc::new([] as [MySqlTypeInfo; 0]),
             binary: true,
-            query: Some(query.into_parts()),
+            query: Some(query),
+            affected_rows: 0,
+            done: false,
         }
     }
 
+    #[cfg(not(feature = "tracing"))]
     fn next(&mut self) -> BoxFuture<crate::Result<Option<MySqlRow<'_>>>> {
         Box::pin(next(self))
     }
+
+    #[cfg(feature = "tracing")]
+    fn next(&mut self) -> BoxFuture<crate::Result<Option<MySqlRow<'_>>>> {
+        Box::pin(self.span.clone().next(next(self)))
+    }
+
+    fn rows_affected(&self) -> Option<u64> {
+        if self.done {
+            Some(self.affected_rows)
+        } else {
+            None
+        }
+    }
 }
 
 async fn next<'a, 'c: 'a, 'q: 'a>(
@@ -75,6 +113,14 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
         false
     };
 
+    // Nothing was sent for a prepared query without any statement, and there is nothing
+    // left to read once the results are done
+    if conn.is_ready {
+        cursor.done = true;
+
+        return Ok(None);
+    }
+
     loop {
         let packet_id = conn.stream.receive().await?[0];
 
@@ -83,23 +129,46 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
             0x00 | 0xFE
                 if conn.stream.packet().len() < 0xFF_FF_FF && (packet_id != 0x00 || initial) =>
             {
-                let status = if let Some(eof) = conn.stream.maybe_handle_eof()? {
-                    eof.status
+                let status = if packet_id == 0xFE {
+                    conn.stream.handle_result_set_end()?.status
                 } else {
-                    conn.stream.handle_ok()?.status
+                    let ok = conn.stream.handle_ok()?;
+
+                    cursor.affected_rows += ok.affected_rows;
+
+                    ok.status
                 };
 
+                conn.in_result_rows = false;
+
                 if status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                     // There is more to this query
                     initial = true;
                 } else {
+                    #[cfg(feature = "bench-internals")]
+                    conn.stream.timer.done();
+
                     conn.is_ready = true;
+                    cursor.done = true;
+                    conn.report_warnings().await?;
+
                     return Ok(None);
                 }
             }
 
+            // ERR packet for a query without any statement; it has no results
+            0xFF if conn.stream.is_empty_query_err() => {
+                conn.in_result_rows = false;
+                conn.is_ready = true;
+                cursor.done = true;
+                conn.report_warnings().await?;
+
+                return Ok(None);
+            }
+
             // ERR packet
             0xFF => {
+                conn.in_result_rows = false;
                 conn.is_ready = true;
                 return conn.stream.handle_err();
             }
@@ -117,11 +186,16 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                 cursor.column_types.reserve(cc.columns as usize);
 
                 let mut column_names = HashMap::with_capacity(cc.columns as usize);
+                let mut column_type_infos = Vec::with_capacity(cc.columns as usize);
+
+                // the column definitions can't be told apart from rows without context
+                conn.stream.is_broken = true;
 
                 for i in 0..cc.columns {
                     let column = ColumnDefinition::read(conn.stream.receive().await?)?;
 
                     cursor.column_types.push(column.type_id);
+                    column_type_infos.push(MySqlTypeInfo::from_column_def(&column));
 
                     if let Some(name) = column.name() {
                         column_names.insert(name.to_owned().into_boxed_str(), i as u16);
@@ -132,11 +206,18 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                     conn.stream.maybe_receive_eof().await?;
                 }
 
+                conn.stream.is_broken = false;
+                conn.in_result_rows = true;
+
                 cursor.column_names = Arc::new(column_names);
+                cursor.column_type_infos = Arc::from(column_type_infos);
                 initial = false;
             }
 
             _ if !cursor.binary || packet_id == 0x00 => {
+                #[cfg(feature = "bench-internals")]
+                conn.stream.timer.row_received();
+
                 let row = Row::read(
                     conn.stream.packet(),
                     &cursor.column_types,
@@ -147,8 +228,13 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                 let row = MySqlRow {
                     row,
                     columns: Arc::clone(&cursor.column_names),
+                    types: Arc::clone(&cursor.column_type_infos),
+                    strict_types: conn.strict_types,
                 };
 
+                #[cfg(feature = "bench-internals")]
+                conn.stream.timer.row_returned();
+
                 return Ok(Some(row));
             }
 
@@ -1,4 +1,5 @@
 use crate::database::{Database, HasCursor, HasRawValue, HasRow};
+use crate::quote::{self, InvalidIdentifier};
 
 /// **MySQL** database driver.
 pub struct MySql;
@@ -13,6 +14,33 @@ impl Database for MySql {
     type TableId = Box<str>;
 
     type RawBuffer = Vec<u8>;
+
+    // The count of parameters of a prepared statement is an `int<2>`
+    const PARAMETER_LIMIT: usize = 65535;
+
+    // `LENGTH` counts the bytes of a text value too
+    const LENGTH_FUNCTION: &'static str = "CHAR_LENGTH";
+
+    const SUBSTRING_FUNCTION: &'static str = "SUBSTRING";
+
+    // https://dev.mysql.com/doc/refman/8.0/en/identifiers.html
+    fn quote_identifier(ident: &str) -> Result<String, InvalidIdentifier> {
+        if ident.chars().any(|ch| ch > '\u{FFFF}') {
+            return Err(InvalidIdentifier::new(
+                ident,
+                "identifier contains a character outside the Basic Multilingual Plane",
+            ));
+        }
+
+        if ident.ends_with(' ') {
+            return Err(InvalidIdentifier::new(
+                ident,
+                "identifier ends with a space",
+            ));
+        }
+
+        quote::quote_with(ident, '`')
+    }
 }
 
 impl<'c> HasRow<'c> for MySql {
@@ -30,3 +58,40 @@ impl<'c, 'q> HasCursor<'c, 'q> for MySql {
 impl<'c> HasRawValue<'c> for MySql {
     type RawValue = Option<super::MySqlValue<'c>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MySql;
+    use crate::database::Database;
+    use crate::quote::Identifier;
+
+    #[test]
+    fn it_quotes_identifiers_with_backticks() {
+        assert_eq!(MySql::quote_identifier("users").unwrap(), "`users`");
+        assert_eq!(
+            MySql::quote_identifier("a`; DROP TABLE users; --").unwrap(),
+            "`a``; DROP TABLE users; --`"
+        );
+        assert_eq!(
+            MySql::quote_identifier("naïve \"表\"").unwrap(),
+            "`naïve \"表\"`"
+        );
+
+        assert!(MySql::quote_identifier("emoji 🦀").is_err());
+        assert!(MySql::quote_identifier("trailing ").is_err());
+        assert!(MySql::quote_identifier("").is_err());
+    }
+
+    #[test]
+    fn it_quotes_qualified_identifiers() {
+        assert_eq!(
+            Identifier::qualified("shop", "orders.2020")
+                .quote::<MySql>()
+                .unwrap(),
+            "`shop`.`orders.2020`"
+        );
+        assert!(Identifier::qualified("", "orders")
+            .quote::<MySql>()
+            .is_err());
+    }
+}
@@ -1,11 +1,18 @@
 use std::fmt::{self, Display};
 
-use crate::error::DatabaseError;
+use crate::error::{is_retryable_sql_state, DatabaseError};
 use crate::mysql::protocol::ErrPacket;
 
 #[derive(Debug)]
 pub struct MySqlError(pub(super) ErrPacket);
 
+impl MySqlError {
+    /// The MySQL-specific error number, e.g. `1062` for a duplicate entry in a unique index.
+    pub fn number(&self) -> u16 {
+        self.0.error_code
+    }
+}
+
 impl Display for MySqlError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.pad(self.message())
@@ -17,7 +24,142 @@ impl DatabaseError for MySqlError {
         &*self.0.error_message
     }
 
+    /// The SQLSTATE sent by the server or, if it did not send one, the SQLSTATE that MySQL
+    /// documents for the error number.
     fn code(&self) -> Option<&str> {
-        self.0.sql_state.as_deref()
+        Some(
+            self.0
+                .sql_state
+                .as_deref()
+                .unwrap_or_else(|| sql_state_for_number(self.0.error_code)),
+        )
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self.number() {
+            // ER_LOCK_WAIT_TIMEOUT, which has the generic SQLSTATE `HY000`
+            1205 => true,
+
+            _ => self.code().map_or(false, is_retryable_sql_state),
+        }
+    }
+}
+
+// https://dev.mysql.com/doc/mysql-errors/8.0/en/server-error-reference.html
+fn sql_state_for_number(number: u16) -> &'static str {
+    match number {
+        // ER_CON_COUNT_ERROR
+        1040 => "08004",
+
+        // ER_DBACCESS_DENIED_ERROR, ER_BAD_DB_ERROR, ER_PARSE_ERROR
+        1044 | 1049 | 1064 => "42000",
+
+        // ER_ACCESS_DENIED_ERROR
+        1045 => "28000",
+
+        // ER_DUP_KEY, ER_BAD_NULL_ERROR, ER_DUP_ENTRY, ER_ROW_IS_REFERENCED_2,
+        // ER_NO_REFERENCED_ROW_2
+        1022 | 1048 | 1062 | 1451 | 1452 => "23000",
+
+        // ER_TABLE_EXISTS_ERROR
+        1050 => "42S01",
+
+        // ER_SERVER_SHUTDOWN, ER_NET_PACKETS_OUT_OF_ORDER
+        1053 | 1156 => "08S01",
+
+        // ER_BAD_FIELD_ERROR
+        1054 => "42S22",
+
+        // ER_WRONG_VALUE_COUNT_ON_ROW
+        1136 => "21S01",
+
+        // ER_NO_SUCH_TABLE
+        1146 => "42S02",
+
+        // ER_LOCK_DEADLOCK
+        1213 => "40001",
+
+        // ER_SUBQUERY_NO_1_ROW
+        1242 => "21000",
+
+        // ER_WARN_DATA_OUT_OF_RANGE
+        1264 => "22003",
+
+        // ER_DIVISION_BY_ZERO
+        1365 => "22012",
+
+        // ER_DATA_TOO_LONG
+        1406 => "22001",
+
+        // the server uses this for every error without a more specific SQLSTATE
+        _ => "HY000",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MySqlError;
+    use crate::error::{DatabaseError, SqlStateClass};
+    use crate::mysql::protocol::{Capabilities, ErrPacket};
+
+    #[test]
+    fn it_uses_the_sql_state_from_the_packet() {
+        let packet = ErrPacket::read(
+            b"\xff\x26\x04#23000Duplicate entry '1' for key 'PRIMARY'",
+            Capabilities::PROTOCOL_41,
+        )
+        .unwrap();
+
+        let err = MySqlError(packet);
+
+        assert_eq!(err.number(), 1062);
+        assert_eq!(err.code(), Some("23000"));
+        assert_eq!(err.code_class(), Some("23"));
+        assert_eq!(
+            err.sql_state_class(),
+            Some(SqlStateClass::IntegrityConstraintViolation)
+        );
+    }
+
+    #[test]
+    fn it_synthesizes_a_sql_state_from_the_number() {
+        let packet = ErrPacket::read(
+            b"\xff\xbd\x04Deadlock found when trying to get lock",
+            Capabilities::empty(),
+        )
+        .unwrap();
+
+        let err = MySqlError(packet);
+
+        assert_eq!(err.number(), 1213);
+        assert_eq!(err.code(), Some("40001"));
+        assert_eq!(
+            err.sql_state_class(),
+            Some(SqlStateClass::TransactionRollback)
+        );
+
+        let packet = ErrPacket::read(
+            b"\xff\x84\x04Got packets out of order",
+            Capabilities::empty(),
+        )
+        .unwrap();
+
+        assert_eq!(MySqlError(packet).code_class(), Some("08"));
+    }
+
+    #[test]
+    fn it_classifies_transient_errors_as_retryable() {
+        let error =
+            |bytes: &[u8]| MySqlError(ErrPacket::read(bytes, Capabilities::empty()).unwrap());
+
+        // ER_LOCK_DEADLOCK, ER_LOCK_WAIT_TIMEOUT, ER_CON_COUNT_ERROR
+        assert!(error(b"\xff\xbd\x04Deadlock found when trying to get lock").is_retryable());
+        assert!(error(b"\xff\xb5\x04Lock wait timeout exceeded").is_retryable());
+        assert!(error(b"\xff\x10\x04Too many connections").is_retryable());
+
+        // ER_PARSE_ERROR, ER_DUP_ENTRY, ER_ACCESS_DENIED_ERROR
+        assert!(!error(b"\xff\x28\x04You have an error in your SQL syntax").is_retryable());
+        assert!(!error(b"\xff\x26\x04Duplicate entry '1' for key 'PRIMARY'").is_retryable());
+        assert!(!error(b"\xff\x15\x04Access denied for user").is_retryable());
     }
 }
@@ -1,55 +1,208 @@
 use futures_core::future::BoxFuture;
 
+use crate::arguments::Arguments;
 use crate::cursor::Cursor;
-use crate::describe::{Column, Describe};
+use crate::database::Database;
+use crate::describe::{Column, ColumnFlags, Describe};
 use crate::executor::{Execute, Executor, RefExecutor};
+#[cfg(feature = "tracing")]
+use crate::instrument::QuerySpan;
 use crate::mysql::protocol::{
-    self, ColumnDefinition, ComQuery, ComStmtExecute, ComStmtPrepare, ComStmtPrepareOk, FieldFlags,
-    Status,
+    self, ColumnCount, ColumnDefinition, ComQuery, ComStmtExecute, ComStmtPrepare,
+    ComStmtPrepareOk, FieldFlags, Status,
 };
-use crate::mysql::{MySql, MySqlArguments, MySqlCursor, MySqlTypeInfo};
+use crate::mysql::{MySql, MySqlArguments, MySqlCursor, MySqlRow, MySqlTypeInfo};
+use crate::notice::{Notice, NoticeSeverity};
+use crate::row::Row;
 
 impl super::MySqlConnection {
-    async fn wait_until_ready(&mut self) -> crate::Result<()> {
-        if !self.is_ready {
-            loop {
-                let packet_id = self.stream.receive().await?[0];
-                match packet_id {
+    // Reads what is left of the response to the previous command; for instance, if the future
+    // reading its results was dropped
+    pub(super) async fn wait_until_ready(&mut self) -> crate::Result<()> {
+        self.stream.check_broken()?;
+
+        while !self.is_ready {
+            match self.read_to_end().await {
+                // an error from the previous query is of no interest to the next one
+                Ok(_) | Err(crate::Error::Database(_)) => {}
+
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
+    // Reads the rest of the result sets of the current command and returns the number of
+    // rows affected by its statements
+    pub(super) async fn read_to_end(&mut self) -> crate::Result<u64> {
+        let mut rows = 0;
+
+        self.read_results(|affected_rows| rows += affected_rows)
+            .await?;
+
+        Ok(rows)
+    }
+
+    // Reads the rest of the result sets of the current command, passing the number of rows
+    // affected by each statement to `on_result`; statements that return rows affect none.
+    // `is_ready` and `in_result_rows` are kept up to date after each packet so this can be
+    // picked up again if the future is dropped.
+    async fn read_results(&mut self, mut on_result: impl FnMut(u64)) -> crate::Result<()> {
+        while !self.is_ready {
+            let id = self.stream.receive().await?[0];
+
+            if self.in_result_rows {
+                match id {
+                    // ResultSet row can begin with 0xfe byte (when using text protocol
+                    // with a field length > 0xffffff)
                     0xFE if self.stream.packet().len() < 0xFF_FF_FF => {
-                        // OK or EOF packet
+                        let status = self.stream.handle_result_set_end()?.status;
+
+                        self.in_result_rows = false;
+                        self.is_ready = !status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+
+                        on_result(0);
+                    }
+
+                    0xFF => {
+                        self.in_result_rows = false;
+                        self.is_ready = true;
+
+                        return self.stream.handle_err();
+                    }
+
+                    _ => {
+                        // a row; skip
+                        #[cfg(feature = "bench-internals")]
+                        self.stream.timer.row_received();
+                    }
+                }
+            } else {
+                match id {
+                    0x00 => {
+                        let ok = self.stream.handle_ok()?;
+
+                        on_result(ok.affected_rows);
+                        self.is_ready = !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+                    }
+
+                    // a query without any statement affects no rows
+                    0xFF if self.stream.is_empty_query_err() => {
                         self.is_ready = true;
-                        break;
                     }
 
                     0xFF => {
-                        // ERR packet
                         self.is_ready = true;
+
                         return self.stream.handle_err();
                     }
 
                     _ => {
-                        // Something else; skip
+                        let columns = ColumnCount::read(self.stream.packet())?.columns;
+
+                        self.stream.is_broken = true;
+                        self.drop_column_defs(columns as usize).await?;
+                        self.stream.is_broken = false;
+
+                        self.in_result_rows = true;
                     }
                 }
             }
         }
 
+        #[cfg(feature = "bench-internals")]
+        self.stream.timer.done();
+
+        self.report_warnings().await
+    }
+
+    // Passes the warnings raised by the last statement to the notice handler, if there is one;
+    // MySQL only sends how many there are, so they are read with `SHOW WARNINGS`
+    pub(super) async fn report_warnings(&mut self) -> crate::Result<()> {
+        if self.stream.warnings == 0 || self.notice_handler.is_none() {
+            return Ok(());
+        }
+
+        self.stream.warnings = 0;
+
+        let mut notices = Vec::new();
+        let mut cursor = self.fetch("SHOW WARNINGS");
+
+        while let Some(row) = cursor.next().await? {
+            let level: String = row.try_get(0)?;
+            let code: u32 = row.try_get(1)?;
+            let message: String = row.try_get(2)?;
+
+            notices.push(Notice {
+                severity: match &*level {
+                    "Note" => NoticeSeverity::Notice,
+                    "Error" => NoticeSeverity::Error,
+                    _ => NoticeSeverity::Warning,
+                },
+                code: code.to_string().into(),
+                message: message.into(),
+            });
+        }
+
+        if let Some(handler) = &mut self.notice_handler {
+            notices.into_iter().for_each(handler);
+        }
+
         Ok(())
     }
 
-    // Creates a prepared statement for the passed query string
-    async fn prepare(&mut self, query: &str) -> crate::Result<ComStmtPrepareOk> {
+    /// Executes a query made of several statements separated by `;`, returning the number of
+    /// rows affected by each of them in order.
+    ///
+    /// Statements that return rows, such as `SELECT`, are counted as affecting no rows. If a
+    /// statement fails, the error is returned and the statements after it are not executed.
+    /// MySQL can only prepare a single statement, so the query can't have bind parameters.
+    ///
+    /// ```rust,ignore
+    /// let counts = conn
+    ///     .execute_many("UPDATE accounts SET active = FALSE; DELETE FROM sessions")
+    ///     .await?;
+    /// ```
+    pub async fn execute_many<'q, E>(&mut self, query: E) -> crate::Result<Vec<u64>>
+    where
+        E: Execute<'q, MySql>,
+    {
+        let (query, arguments) = query.into_parts();
+        let mut counts = Vec::new();
+
+        self.run(query, arguments).await?;
+        self.read_results(|affected_rows| counts.push(affected_rows))
+            .await?;
+
+        Ok(counts)
+    }
+
+    // Creates a prepared statement for the passed query string; returns `None` if the query has
+    // no statement to prepare, such as an empty string or only a comment
+    //
+    // The column definitions that follow must be read before the connection can be used again
+    async fn prepare(&mut self, query: &str) -> crate::Result<Option<ComStmtPrepareOk>> {
         // https://dev.mysql.com/doc/dev/mysql-server/8.0.11/page_protocol_com_stmt_prepare.html
         self.stream.send(ComStmtPrepare { query }, true).await?;
 
+        // the parts of the response can't be told apart from each other without context
+        self.stream.is_broken = true;
+
         // Should receive a COM_STMT_PREPARE_OK or ERR_PACKET
         let packet = self.stream.receive().await?;
 
         if packet[0] == 0xFF {
+            self.stream.is_broken = false;
+
+            if self.stream.is_empty_query_err() {
+                return Ok(None);
+            }
+
             return self.stream.handle_err();
         }
 
-        ComStmtPrepareOk::read(packet)
+        ComStmtPrepareOk::read(packet).map(Some)
     }
 
     async fn drop_column_defs(&mut self, count: usize) -> crate::Result<()> {
@@ -66,11 +219,18 @@ impl super::MySqlConnection {
 
     // Gets a cached prepared statement ID _or_ prepares the statement if not in the cache
     // At the end we should have [cache_statement] and [cache_statement_columns] filled
-    async fn get_or_prepare(&mut self, query: &str) -> crate::Result<u32> {
+    async fn get_or_prepare(&mut self, query: &str) -> crate::Result<Option<u32>> {
         if let Some(&id) = self.cache_statement.get(query) {
-            Ok(id)
+            self.cache_statement_stats.hits += 1;
+
+            Ok(Some(id))
         } else {
-            let stmt = self.prepare(query).await?;
+            self.cache_statement_stats.misses += 1;
+
+            let stmt = match self.prepare(query).await? {
+                Some(stmt) => stmt,
+                None => return Ok(None),
+            };
 
             self.cache_statement.insert(query.into(), stmt.statement_id);
 
@@ -82,20 +242,56 @@ impl super::MySqlConnection {
             // We just drop these as we get these when we execute the query
             self.drop_column_defs(stmt.columns as usize).await?;
 
-            Ok(stmt.statement_id)
+            self.stream.is_broken = false;
+
+            Ok(Some(stmt.statement_id))
         }
     }
 
+    // Sends the query, returning the ID of its prepared statement if it has arguments. A query
+    // with arguments but no statement is not sent at all and leaves `is_ready` set, as there
+    // is no result to read.
     pub(crate) async fn run(
         &mut self,
         query: &str,
         arguments: Option<MySqlArguments>,
     ) -> crate::Result<Option<u32>> {
         self.wait_until_ready().await?;
-        self.is_ready = false;
 
-        if let Some(arguments) = arguments {
-            let statement_id = self.get_or_prepare(query).await?;
+        #[cfg(feature = "bench-internals")]
+        self.stream.timer.start();
+
+        if let Some(mut arguments) = arguments {
+            if let Some(error) = arguments.error.take() {
+                return Err(error);
+            }
+
+            if arguments.param_types.len() > MySql::PARAMETER_LIMIT {
+                return Err(crate::Error::ParameterLimitExceeded {
+                    limit: MySql::PARAMETER_LIMIT,
+                    got: arguments.param_types.len(),
+                });
+            }
+
+            let statement_id = match self.get_or_prepare(query).await? {
+                Some(statement_id) => statement_id,
+
+                None => {
+                    arguments.clear();
+                    self.spare_arguments = arguments;
+
+                    return Ok(None);
+                }
+            };
+
+            #[cfg(feature = "bench-internals")]
+            self.stream.timer.finish(crate::bench::Phase::Prepare);
+
+            // the response to preparing the statement has been read completely by now
+            self.is_ready = false;
+            self.in_result_rows = false;
+
+            let null_bitmap = arguments.null_bitmap();
 
             // https://dev.mysql.com/doc/dev/mysql-server/8.0.11/page_protocol_com_stmt_execute.html
             self.stream
@@ -104,15 +300,25 @@ impl super::MySqlConnection {
                         cursor: protocol::Cursor::NO_CURSOR,
                         statement_id,
                         params: &arguments.params,
-                        null_bitmap: &arguments.null_bitmap,
+                        null_bitmap: &null_bitmap,
                         param_types: &arguments.param_types,
                     },
                     true,
                 )
                 .await?;
 
+            #[cfg(feature = "bench-internals")]
+            self.stream.timer.finish(crate::bench::Phase::Bind);
+
+            // keep the arguments for the next query
+            arguments.clear();
+            self.spare_arguments = arguments;
+
             Ok(Some(statement_id))
         } else {
+            self.is_ready = false;
+            self.in_result_rows = false;
+
             // https://dev.mysql.com/doc/dev/mysql-server/8.0.11/page_protocol_com_query.html
             self.stream.send(ComQuery { query }, true).await?;
 
@@ -120,49 +326,21 @@ impl super::MySqlConnection {
         }
     }
 
-    async fn affected_rows(&mut self) -> crate::Result<u64> {
-        let mut rows = 0;
-
-        loop {
-            let id = self.stream.receive().await?[0];
-
-            match id {
-                0x00 | 0xFE if self.stream.packet().len() < 0xFF_FF_FF => {
-                    // ResultSet row can begin with 0xfe byte (when using text protocol
-                    // with a field length > 0xffffff)
-
-                    let status = if let Some(eof) = self.stream.maybe_handle_eof()? {
-                        eof.status
-                    } else {
-                        let ok = self.stream.handle_ok()?;
-
-                        rows += ok.affected_rows;
-                        ok.status
-                    };
-
-                    if !status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
-                        self.is_ready = true;
-                        break;
-                    }
-                }
-
-                0xFF => {
-                    return self.stream.handle_err();
-                }
-
-                _ => {}
-            }
-        }
-
-        Ok(rows)
-    }
-
     // method is not named describe to work around an intellijrust bug
     // otherwise it marks someone trying to describe the connection as "method is private"
     async fn do_describe(&mut self, query: &str) -> crate::Result<Describe<MySql>> {
         self.wait_until_ready().await?;
 
-        let stmt = self.prepare(query).await?;
+        let stmt = match self.prepare(query).await? {
+            Some(stmt) => stmt,
+
+            None => {
+                return Ok(Describe {
+                    param_types: Box::new([]),
+                    result_columns: Box::new([]),
+                })
+            }
+        };
 
         let mut param_types = Vec::with_capacity(stmt.params as usize);
         let mut result_columns = Vec::with_capacity(stmt.columns as usize);
@@ -176,15 +354,40 @@ impl super::MySqlConnection {
             self.stream.maybe_receive_eof().await?;
         }
 
+        // the schema, table and name of the table column behind each result column
+        let mut origins = Vec::new();
+
         for _ in 0..stmt.columns {
             let column = ColumnDefinition::read(self.stream.receive().await?)?;
 
+            let mut flags = ColumnFlags::empty();
+            flags.set(
+                ColumnFlags::PRIMARY_KEY,
+                column.flags.contains(FieldFlags::PRIMARY_KEY),
+            );
+            flags.set(
+                ColumnFlags::AUTO_INCREMENT,
+                column.flags.contains(FieldFlags::AUTO_INCREMENT),
+            );
+
+            if let (Some(schema), Some(table), Some(name)) =
+                (&column.schema, &column.table, &column.column)
+            {
+                origins.push((
+                    result_columns.len(),
+                    schema.clone(),
+                    table.clone(),
+                    name.clone(),
+                ));
+            }
+
             result_columns.push(Column::<MySql> {
                 type_info: MySqlTypeInfo::from_column_def(&column),
                 name: column.column_alias.or(column.column),
                 table_id: column.table_alias.or(column.table),
                 // TODO(@abonander): Should this be None in some cases?
                 non_null: Some(column.flags.contains(FieldFlags::NOT_NULL)),
+                flags,
             });
         }
 
@@ -192,6 +395,11 @@ impl super::MySqlConnection {
             self.stream.maybe_receive_eof().await?;
         }
 
+        self.stream.is_broken = false;
+
+        self.describe_column_extras(&mut result_columns, &origins)
+            .await?;
+
         Ok(Describe {
             param_types: param_types.into_boxed_slice(),
             result_columns: result_columns.into_boxed_slice(),
@@ -199,6 +407,62 @@ impl super::MySqlConnection {
     }
 }
 
+// The index of a result column, and the schema, table and name of the table column behind it
+type ColumnOrigin = (usize, Box<str>, Box<str>, Box<str>);
+
+impl super::MySqlConnection {
+    // Generated and invisible columns are not flagged in a column definition, only in the
+    // `EXTRA` column of `information_schema.COLUMNS`
+    async fn describe_column_extras(
+        &mut self,
+        columns: &mut [Column<MySql>],
+        origins: &[ColumnOrigin],
+    ) -> crate::Result<()> {
+        let mut tables: Vec<(&str, &str)> = origins
+            .iter()
+            .map(|(_, schema, table, _)| (&**schema, &**table))
+            .collect();
+
+        tables.sort();
+        tables.dedup();
+
+        for (schema, table) in tables {
+            let extras: Vec<(String, String)> = crate::query::query(
+                "SELECT COLUMN_NAME, EXTRA FROM information_schema.COLUMNS \
+                 WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+            )
+            .bind(schema)
+            .bind(table)
+            .try_map(|row: MySqlRow| Ok((row.try_get(0)?, row.try_get(1)?)))
+            .fetch_all(&mut *self)
+            .await?;
+
+            let origins = origins
+                .iter()
+                .filter(|(_, schema_, table_, _)| **schema_ == *schema && **table_ == *table);
+
+            for (index, _, _, name) in origins {
+                let extra = extras
+                    .iter()
+                    .find(|(column, _)| column.eq_ignore_ascii_case(name))
+                    .map(|(_, extra)| extra);
+
+                // for instance, `STORED GENERATED` or `VIRTUAL GENERATED INVISIBLE`; note that
+                // `DEFAULT_GENERATED` only marks a column with an expression as its default
+                for word in extra.into_iter().flat_map(|extra| extra.split_whitespace()) {
+                    if word.eq_ignore_ascii_case("GENERATED") {
+                        columns[*index].flags |= ColumnFlags::GENERATED;
+                    } else if word.eq_ignore_ascii_case("INVISIBLE") {
+                        columns[*index].flags |= ColumnFlags::INVISIBLE;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Executor for super::MySqlConnection {
     type Database = MySql;
 
@@ -209,12 +473,17 @@ impl Executor for super::MySqlConnection {
     where
         E: Execute<'q, Self::Database>,
     {
-        Box::pin(async move {
-            let (query, arguments) = query.into_parts();
+        let (query, arguments) = query.into_parts();
 
+        let execute = async move {
             self.run(query, arguments).await?;
-            self.affected_rows().await
-        })
+            self.read_to_end().await
+        };
+
+        #[cfg(feature = "tracing")]
+        let execute = QuerySpan::new("mysql", query).execute(execute);
+
+        Box::pin(execute)
     }
 
     fn fetch<'q, E>(&mut self, query: E) -> MySqlCursor<'_, 'q>
@@ -0,0 +1,164 @@
+use serde_json::Value;
+
+use crate::explain::{number, parse_json, Explain, ExplainNode, ExplainPlan};
+use crate::mysql::MySql;
+
+impl Explain for MySql {
+    fn explain_statement(query: &str, analyze: bool) -> crate::Result<String> {
+        if analyze {
+            // MySQL only returns the plan of `EXPLAIN ANALYZE` as a tree of text
+            return Err(protocol_err!("EXPLAIN ANALYZE is not supported for MySQL").into());
+        }
+
+        Ok(format!("EXPLAIN FORMAT=JSON {}", query))
+    }
+
+    fn parse_plan(rows: Vec<Vec<String>>) -> crate::Result<ExplainPlan> {
+        // {"query_block": {"select_id": 1, "cost_info": {..}, "table": {..}}}
+        let raw = parse_json(&rows)?;
+
+        let plan = match &raw["query_block"] {
+            Value::Object(_) => node("query_block", &raw["query_block"]),
+            _ => return Err(protocol_err!("unexpected query plan: {}", raw).into()),
+        };
+
+        Ok(ExplainPlan {
+            nodes: vec![plan],
+            raw,
+        })
+    }
+}
+
+fn node(name: &str, value: &Value) -> ExplainNode {
+    let cost_info = &value["cost_info"];
+
+    ExplainNode {
+        node_type: name.to_owned(),
+        relation: value["table_name"].as_str().map(ToOwned::to_owned),
+        cost: number(&cost_info["query_cost"]).or_else(|| number(&cost_info["prefix_cost"])),
+        // MariaDB has `rows` where MySQL has `rows_examined_per_scan`
+        rows: number(&value["rows_examined_per_scan"]).or_else(|| number(&value["rows"])),
+        actual_rows: None,
+        children: children(value),
+    }
+}
+
+// Every object in an operation is an operation nested in it, except for its cost; arrays of
+// objects, such as the tables of a `nested_loop`, hold an operation in each object
+fn children(value: &Value) -> Vec<ExplainNode> {
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => return Vec::new(),
+    };
+
+    let mut children = Vec::new();
+
+    for (key, value) in object {
+        match value {
+            _ if key == "cost_info" => {}
+
+            Value::Object(_) => children.push(node(key, value)),
+
+            Value::Array(items) if items.iter().any(Value::is_object) => {
+                children.push(ExplainNode {
+                    node_type: key.clone(),
+                    children: items.iter().flat_map(self::children).collect(),
+                    ..ExplainNode::default()
+                });
+            }
+
+            _ => {}
+        }
+    }
+
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = r#"{
+  "query_block": {
+    "select_id": 1,
+    "cost_info": {
+      "query_cost": "2.50"
+    },
+    "nested_loop": [
+      {
+        "table": {
+          "table_name": "users",
+          "access_type": "ALL",
+          "rows_examined_per_scan": 3,
+          "rows_produced_per_join": 3,
+          "filtered": "100.00",
+          "cost_info": {
+            "read_cost": "0.25",
+            "eval_cost": "0.30",
+            "prefix_cost": "0.55",
+            "data_read_per_join": "96"
+          },
+          "used_columns": ["id", "name"]
+        }
+      },
+      {
+        "table": {
+          "table_name": "posts",
+          "access_type": "ref",
+          "possible_keys": ["user_id"],
+          "rows_examined_per_scan": 1,
+          "rows_produced_per_join": 3,
+          "filtered": "100.00",
+          "cost_info": {
+            "read_cost": "1.05",
+            "eval_cost": "0.30",
+            "prefix_cost": "1.90",
+            "data_read_per_join": "48"
+          }
+        }
+      }
+    ]
+  }
+}"#;
+
+    fn table(name: &str, cost: f64, rows: f64) -> ExplainNode {
+        ExplainNode {
+            node_type: "table".into(),
+            relation: Some(name.into()),
+            cost: Some(cost),
+            rows: Some(rows),
+            ..ExplainNode::default()
+        }
+    }
+
+    #[test]
+    fn it_parses_a_plan() -> crate::Result<()> {
+        let plan = MySql::parse_plan(vec![vec![PLAN.to_owned()]])?;
+
+        assert_eq!(
+            plan.nodes,
+            vec![ExplainNode {
+                node_type: "query_block".into(),
+                cost: Some(2.5),
+                children: vec![ExplainNode {
+                    node_type: "nested_loop".into(),
+                    children: vec![table("users", 0.55, 3.0), table("posts", 1.9, 1.0)],
+                    ..ExplainNode::default()
+                }],
+                ..ExplainNode::default()
+            }]
+        );
+
+        assert_eq!(plan.raw["query_block"]["select_id"], 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_refuses_to_analyze() {
+        match MySql::explain_statement("SELECT 1", true) {
+            Err(crate::Error::Protocol(msg)) => assert!(msg.contains("EXPLAIN ANALYZE")),
+            res => panic!("expected a protocol error, got {:?}", res),
+        }
+    }
+}
@@ -6,7 +6,7 @@ pub use cursor::MySqlCursor;
 pub use database::MySql;
 pub use error::MySqlError;
 pub use row::{MySqlRow, MySqlValue};
-pub use types::MySqlTypeInfo;
+pub use types::{MySqlGeometry, MySqlTypeInfo, MySqlYear};
 
 mod arguments;
 mod connection;
@@ -14,6 +14,8 @@ mod cursor;
 mod database;
 mod error;
 mod executor;
+#[cfg(feature = "json")]
+mod explain;
 mod io;
 mod protocol;
 mod row;
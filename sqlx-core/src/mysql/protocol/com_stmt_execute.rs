@@ -1,7 +1,7 @@
 use byteorder::LittleEndian;
 
 use crate::io::BufMut;
-use crate::mysql::protocol::{Capabilities, Encode};
+use crate::mysql::protocol::{Capabilities, Encode, TypeId};
 use crate::mysql::types::MySqlTypeInfo;
 
 bitflags::bitflags! {
@@ -48,7 +48,17 @@ impl Encode for ComStmtExecute<'_> {
 
             for ty in self.param_types {
                 // field type : byte<1>
-                buf.put_u8(ty.id.0);
+                buf.put_u8(match ty.id {
+                    // the server reads no parameter as a `YEAR`; it is bound as the integer
+                    // it is
+                    TypeId::YEAR => TypeId::SMALL_INT.0,
+
+                    // nor as a spatial value; a geometry column accepts one in its storage
+                    // format as a `BLOB`
+                    TypeId::GEOMETRY => TypeId::TEXT.0,
+
+                    id => id.0,
+                });
 
                 // parameter flag : byte<1>
                 buf.put_u8(if ty.is_unsigned { 0x80 } else { 0 });
@@ -1,7 +1,7 @@
 use byteorder::LittleEndian;
 
 use crate::io::Buf;
-use crate::mysql::protocol::Status;
+use crate::mysql::protocol::{Capabilities, OkPacket, Status};
 
 // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_eof_packet.html
 // https://mariadb.com/kb/en/eof_packet/
@@ -33,3 +33,53 @@ impl EofPacket {
         })
     }
 }
+
+// Reads the packet that ends the rows of a result set: an EOF packet, or an OK packet with the
+// header of one once DEPRECATE_EOF is negotiated. An EOF packet is given as an OK packet that
+// affected no rows.
+pub(crate) fn read_result_set_end(
+    buf: &[u8],
+    capabilities: Capabilities,
+) -> crate::Result<OkPacket> {
+    if capabilities.contains(Capabilities::DEPRECATE_EOF) {
+        return OkPacket::read(buf, capabilities);
+    }
+
+    let eof = EofPacket::read(buf)?;
+
+    Ok(OkPacket {
+        affected_rows: 0,
+        last_insert_id: 0,
+        status: eof.status,
+        warnings: eof.warnings,
+        info: Box::default(),
+        session_state: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_result_set_end, Capabilities, Status};
+
+    const EOF_RESULT_SET_END: &[u8] = b"\xfe\x01\x00\x22\x00";
+
+    const OK_RESULT_SET_END: &[u8] = b"\xfe\x00\x00\x22\x00\x01\x00";
+
+    #[test]
+    fn it_reads_eof_at_result_set_end() {
+        let p = read_result_set_end(EOF_RESULT_SET_END, Capabilities::PROTOCOL_41).unwrap();
+
+        assert_eq!(p.warnings, 1);
+        assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
+        assert!(!p.status.contains(Status::SERVER_MORE_RESULTS_EXISTS));
+    }
+
+    #[test]
+    fn it_reads_ok_at_result_set_end_with_deprecate_eof() {
+        let capabilities = Capabilities::PROTOCOL_41 | Capabilities::DEPRECATE_EOF;
+        let p = read_result_set_end(OK_RESULT_SET_END, capabilities).unwrap();
+
+        assert_eq!(p.warnings, 1);
+        assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
+    }
+}
@@ -38,10 +38,10 @@ pub(crate) use auth_switch::AuthSwitch;
 pub(crate) use column_count::ColumnCount;
 pub(crate) use column_def::ColumnDefinition;
 pub(crate) use com_stmt_prepare_ok::ComStmtPrepareOk;
-pub(crate) use eof::EofPacket;
+pub(crate) use eof::{read_result_set_end, EofPacket};
 pub(crate) use err::ErrPacket;
 pub(crate) use handshake_response::HandshakeResponse;
-pub(crate) use ok::OkPacket;
+pub(crate) use ok::{OkPacket, SessionStateChange};
 pub(crate) use row::Row;
 #[cfg_attr(not(feature = "tls"), allow(unused_imports, dead_code))]
 pub(crate) use ssl_request::SslRequest;
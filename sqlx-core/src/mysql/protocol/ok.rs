@@ -2,7 +2,7 @@ use byteorder::LittleEndian;
 
 use crate::io::Buf;
 use crate::mysql::io::BufExt;
-use crate::mysql::protocol::Status;
+use crate::mysql::protocol::{Capabilities, Status};
 
 // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_ok_packet.html
 // https://mariadb.com/kb/en/ok_packet/
@@ -13,10 +13,28 @@ pub(crate) struct OkPacket {
     pub(crate) status: Status,
     pub(crate) warnings: u16,
     pub(crate) info: Box<str>,
+    pub(crate) session_state: Vec<SessionStateChange>,
+}
+
+// A change to the state of the session reported with SESSION_TRACK
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_ok_packet.html#sect_protocol_basic_ok_packet_sessinfo
+#[derive(Debug, PartialEq)]
+pub(crate) enum SessionStateChange {
+    // SESSION_TRACK_SYSTEM_VARIABLES; only the variables in `session_track_system_variables`
+    SystemVariable { name: Box<str>, value: Box<str> },
+
+    // SESSION_TRACK_SCHEMA
+    Schema(Box<str>),
+
+    // SESSION_TRACK_GTIDS; only with `session_track_gtids` enabled
+    Gtids(Box<str>),
+
+    // SESSION_TRACK_STATE_CHANGE and the transaction state; of no use to us
+    Other(u8),
 }
 
 impl OkPacket {
-    pub(crate) fn read(mut buf: &[u8]) -> crate::Result<Self>
+    pub(crate) fn read(mut buf: &[u8], capabilities: Capabilities) -> crate::Result<Self>
     where
         Self: Sized,
     {
@@ -32,7 +50,30 @@ impl OkPacket {
         let last_insert_id = buf.get_uint_lenenc::<LittleEndian>()?.unwrap_or(0); // 2
         let status = Status::from_bits_truncate(buf.get_u16::<LittleEndian>()?); //
         let warnings = buf.get_u16::<LittleEndian>()?;
-        let info = buf.get_str(buf.len())?.into();
+
+        let mut session_state = Vec::new();
+
+        let info = if !capabilities.contains(Capabilities::SESSION_TRACK) {
+            buf.get_str(buf.len())?.into()
+        } else if buf.is_empty() {
+            // the server leaves out the rest of the packet when it has nothing to say
+            Box::default()
+        } else {
+            let info = buf
+                .get_str_lenenc::<LittleEndian>()?
+                .unwrap_or_default()
+                .into();
+
+            if status.contains(Status::SERVER_SESSION_STATE_CHANGED) && !buf.is_empty() {
+                let mut changes = buf.get_bytes_lenenc::<LittleEndian>()?.unwrap_or_default();
+
+                while !changes.is_empty() {
+                    session_state.push(SessionStateChange::read(&mut changes)?);
+                }
+            }
+
+            info
+        };
 
         Ok(Self {
             affected_rows,
@@ -40,19 +81,73 @@ impl OkPacket {
             status,
             warnings,
             info,
+            session_state,
+        })
+    }
+}
+
+impl SessionStateChange {
+    fn read(buf: &mut &[u8]) -> crate::Result<Self> {
+        let kind = buf.get_u8()?;
+        let mut data = buf.get_bytes_lenenc::<LittleEndian>()?.unwrap_or_default();
+
+        Ok(match kind {
+            0x00 => {
+                let name = data
+                    .get_str_lenenc::<LittleEndian>()?
+                    .unwrap_or_default()
+                    .into();
+                let value = data
+                    .get_str_lenenc::<LittleEndian>()?
+                    .unwrap_or_default()
+                    .into();
+
+                SessionStateChange::SystemVariable { name, value }
+            }
+
+            0x01 => {
+                let schema = data.get_str_lenenc::<LittleEndian>()?.unwrap_or_default();
+
+                SessionStateChange::Schema(schema.into())
+            }
+
+            0x03 => {
+                // the encoding specification; 0 is the only one defined, a list of GTIDs
+                let _encoding = data.get_u8()?;
+                let gtids = data.get_str_lenenc::<LittleEndian>()?.unwrap_or_default();
+
+                SessionStateChange::Gtids(gtids.into())
+            }
+
+            kind => SessionStateChange::Other(kind),
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{OkPacket, Status};
+    use super::{Capabilities, OkPacket, SessionStateChange, Status};
 
     const OK_HANDSHAKE: &[u8] = b"\x00\x00\x00\x02@\x00\x00";
 
+    // `USE sqlx` with SESSION_TRACK; the schema and that the state changed
+    const OK_USE_SCHEMA: &[u8] =
+        b"\x00\x00\x00\x02\x40\x00\x00\x00\x0b\x01\x05\x04sqlx\x02\x02\x011";
+
+    // `SET time_zone = '+01:00'` with SESSION_TRACK
+    const OK_SET_VARIABLE: &[u8] =
+        b"\x00\x00\x00\x02\x40\x00\x00\x00\x13\x00\x11\x09time_zone\x06+01:00";
+
+    // a commit with `session_track_gtids = OWN_GTID`
+    const OK_COMMIT_GTIDS: &[u8] = b"\x00\x00\x00\x02\x40\x00\x00\x00\x2b\x03\x29\x00\x27\
+        3E11FA47-71CA-11E1-9E33-C80AA9429562:23";
+
+    // the end of a result set with DEPRECATE_EOF and SESSION_TRACK
+    const OK_RESULT_SET_END: &[u8] = b"\xfe\x00\x00\x22\x00\x00\x00";
+
     #[test]
     fn it_decodes_ok_handshake() {
-        let p = OkPacket::read(OK_HANDSHAKE).unwrap();
+        let p = OkPacket::read(OK_HANDSHAKE, Capabilities::PROTOCOL_41).unwrap();
 
         assert_eq!(p.affected_rows, 0);
         assert_eq!(p.last_insert_id, 0);
@@ -60,5 +155,83 @@ mod tests {
         assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
         assert!(p.status.contains(Status::SERVER_SESSION_STATE_CHANGED));
         assert!(p.info.is_empty());
+        assert!(p.session_state.is_empty());
+    }
+
+    #[test]
+    fn it_decodes_ok_handshake_with_session_track() {
+        let p = OkPacket::read(
+            OK_HANDSHAKE,
+            Capabilities::PROTOCOL_41 | Capabilities::SESSION_TRACK,
+        )
+        .unwrap();
+
+        assert!(p.status.contains(Status::SERVER_SESSION_STATE_CHANGED));
+        assert!(p.info.is_empty());
+        assert!(p.session_state.is_empty());
+    }
+
+    #[test]
+    fn it_decodes_schema_change() {
+        let p = OkPacket::read(
+            OK_USE_SCHEMA,
+            Capabilities::PROTOCOL_41 | Capabilities::SESSION_TRACK,
+        )
+        .unwrap();
+
+        assert_eq!(
+            p.session_state,
+            vec![
+                SessionStateChange::Schema("sqlx".into()),
+                SessionStateChange::Other(0x02)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_decodes_system_variable_change() {
+        let p = OkPacket::read(
+            OK_SET_VARIABLE,
+            Capabilities::PROTOCOL_41 | Capabilities::SESSION_TRACK,
+        )
+        .unwrap();
+
+        assert_eq!(
+            p.session_state,
+            vec![SessionStateChange::SystemVariable {
+                name: "time_zone".into(),
+                value: "+01:00".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn it_decodes_gtids() {
+        let p = OkPacket::read(
+            OK_COMMIT_GTIDS,
+            Capabilities::PROTOCOL_41 | Capabilities::SESSION_TRACK,
+        )
+        .unwrap();
+
+        assert_eq!(
+            p.session_state,
+            vec![SessionStateChange::Gtids(
+                "3E11FA47-71CA-11E1-9E33-C80AA9429562:23".into()
+            )]
+        );
+    }
+
+    #[test]
+    fn it_decodes_result_set_end() {
+        let p = OkPacket::read(
+            OK_RESULT_SET_END,
+            Capabilities::PROTOCOL_41 | Capabilities::DEPRECATE_EOF | Capabilities::SESSION_TRACK,
+        )
+        .unwrap();
+
+        assert_eq!(p.affected_rows, 0);
+        assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
+        assert!(p.status.contains(Status::SERVER_QUERY_NO_INDEX_USED));
+        assert!(p.info.is_empty());
     }
 }
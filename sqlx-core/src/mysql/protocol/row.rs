@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::ops::Range;
 
 use byteorder::{ByteOrder, LittleEndian};
@@ -6,8 +7,8 @@ use crate::io::Buf;
 use crate::mysql::protocol::TypeId;
 
 pub(crate) struct Row<'c> {
-    buffer: &'c [u8],
-    values: &'c [Option<Range<usize>>],
+    buffer: Cow<'c, [u8]>,
+    values: Cow<'c, [Option<Range<usize>>]>,
     pub(crate) binary: bool,
 }
 
@@ -16,11 +17,29 @@ impl<'c> Row<'c> {
         self.values.len()
     }
 
-    pub(crate) fn get(&self, index: usize) -> Option<&'c [u8]> {
+    pub(crate) fn get(&self, index: usize) -> Option<&[u8]> {
         let range = self.values[index].as_ref()?;
 
         Some(&self.buffer[(range.start as usize)..(range.end as usize)])
     }
+
+    // A row without any values
+    pub(crate) fn empty() -> Row<'static> {
+        Row {
+            buffer: Cow::Borrowed(&[]),
+            values: Cow::Borrowed(&[]),
+            binary: true,
+        }
+    }
+
+    // Copies the packet out of the buffer of the connection
+    pub(crate) fn into_owned(self) -> Row<'static> {
+        Row {
+            buffer: Cow::Owned(self.buffer.into_owned()),
+            values: Cow::Owned(self.values.into_owned()),
+            binary: self.binary,
+        }
+    }
 }
 
 fn get_lenenc(buf: &[u8]) -> (usize, Option<usize>) {
@@ -80,8 +99,8 @@ impl<'c> Row<'c> {
             }
 
             return Ok(Self {
-                buffer,
-                values: &*values,
+                buffer: Cow::Borrowed(buffer),
+                values: Cow::Borrowed(values),
                 binary: false,
             });
         }
@@ -116,6 +135,9 @@ impl<'c> Row<'c> {
                     TypeId::INT | TypeId::FLOAT => (0, 4),
                     TypeId::BIG_INT | TypeId::DOUBLE => (0, 8),
 
+                    // the full year, as an `int<2>`
+                    TypeId::YEAR => (0, 2),
+
                     TypeId::DATE => (0, 5),
                     TypeId::TIME => (0, 1 + buffer[index] as usize),
 
@@ -126,7 +148,11 @@ impl<'c> Row<'c> {
                     | TypeId::LONG_BLOB
                     | TypeId::CHAR
                     | TypeId::TEXT
-                    | TypeId::VAR_CHAR => {
+                    | TypeId::VAR_CHAR
+                    | TypeId::DECIMAL
+                    | TypeId::NEW_DECIMAL
+                    | TypeId::JSON
+                    | TypeId::GEOMETRY => {
                         let (len_size, len) = get_lenenc(&buffer[index..]);
 
                         (len_size, len.unwrap_or_default())
@@ -143,8 +169,8 @@ impl<'c> Row<'c> {
         }
 
         Ok(Self {
-            buffer: buf,
-            values: &*values,
+            buffer: Cow::Borrowed(buf),
+            values: Cow::Borrowed(values),
             binary,
         })
     }
@@ -314,3 +340,22 @@ impl<'c> Row<'c> {
 //         Ok(())
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::{Row, TypeId};
+
+    #[test]
+    fn it_reads_year_json_and_spatial_values_of_a_binary_row() {
+        // the header, the NULL bitmap, then 2020, `[1]` and the SRID 0 with one byte of WKB
+        let packet = b"\x00\x00\xe4\x07\x03[1]\x05\x00\x00\x00\x00\x01";
+        let columns = [TypeId::YEAR, TypeId::JSON, TypeId::GEOMETRY];
+        let mut values = Vec::new();
+
+        let row = Row::read(packet, &columns, &mut values, true).unwrap();
+
+        assert_eq!(row.get(0), Some(&b"\xe4\x07"[..]));
+        assert_eq!(row.get(1), Some(&b"[1]"[..]));
+        assert_eq!(row.get(2), Some(&b"\x00\x00\x00\x00\x01"[..]));
+    }
+}
@@ -55,11 +55,25 @@ type_id_consts! {
     pub const FLOAT: TypeId = TypeId(4);
     pub const DOUBLE: TypeId = TypeId(5);
 
+    // Numeric: DECIMAL, NUMERIC
+    pub const DECIMAL: TypeId = TypeId(0);
+    pub const NEW_DECIMAL: TypeId = TypeId(246);
+
     // Date/Time: DATE, TIME, DATETIME, TIMESTAMP
     pub const DATE: TypeId = TypeId(10);
     pub const TIME: TypeId = TypeId(11);
     pub const DATETIME: TypeId = TypeId(12);
     pub const TIMESTAMP: TypeId = TypeId(7);
+
+    // YEAR; a year from 1901 to 2155, or 0
+    pub const YEAR: TypeId = TypeId(13);
+
+    // JSON; values are sent as text
+    pub const JSON: TypeId = TypeId(245);
+
+    // Spatial: GEOMETRY, POINT, LINESTRING, POLYGON and their collections; values are sent as
+    // a 4-byte little-endian SRID followed by the WKB of the geometry
+    pub const GEOMETRY: TypeId = TypeId(255);
 }
 
 impl Default for TypeId {
@@ -2,10 +2,12 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
+use crate::decode::Decode;
 use crate::error::UnexpectedNullError;
 use crate::mysql::protocol;
-use crate::mysql::MySql;
-use crate::row::{ColumnIndex, Row};
+use crate::mysql::{MySql, MySqlTypeInfo};
+use crate::row::{self, ColumnIndex, Row};
+use crate::types::Type;
 
 #[derive(Debug)]
 pub enum MySqlValue<'c> {
@@ -28,6 +30,8 @@ impl<'c> TryFrom<Option<MySqlValue<'c>>> for MySqlValue<'c> {
 pub struct MySqlRow<'c> {
     pub(super) row: protocol::Row<'c>,
     pub(super) columns: Arc<HashMap<Box<str>, u16>>,
+    pub(super) types: Arc<[MySqlTypeInfo]>,
+    pub(super) strict_types: bool,
 }
 
 impl<'c> Row<'c> for MySqlRow<'c> {
@@ -37,6 +41,19 @@ impl<'c> Row<'c> for MySqlRow<'c> {
         self.row.len()
     }
 
+    fn try_get<'r, T, I>(&'r self, index: I) -> crate::Result<T>
+    where
+        'c: 'r,
+        T: Type<Self::Database>,
+        I: ColumnIndex<Self::Database>,
+        T: Decode<'r, Self::Database>,
+    {
+        let index = index.resolve(self)?;
+
+        self.decode::<T>(index)
+            .map_err(|err| row::column_error::<T, _>(err, || self.column_name(index)))
+    }
+
     fn try_get_raw<'r, I>(&'r self, index: I) -> crate::Result<Option<MySqlValue<'r>>>
     where
         'c: 'r,
@@ -52,4 +69,63 @@ impl<'c> Row<'c> for MySqlRow<'c> {
             }
         }))
     }
+
+    fn into_owned(self) -> MySqlRow<'static> {
+        MySqlRow {
+            row: self.row.into_owned(),
+            columns: self.columns,
+            types: self.types,
+            strict_types: self.strict_types,
+        }
+    }
+}
+
+impl<'c> MySqlRow<'c> {
+    fn decode<'r, T>(&'r self, index: usize) -> crate::Result<T>
+    where
+        'c: 'r,
+        T: Type<MySql>,
+        T: Decode<'r, MySql>,
+    {
+        let value = self.try_get_raw(index)?;
+        let info = self.types.get(index);
+
+        // a value in the text protocol is parsed, which rejects anything that is not valid
+        // for `T`, while the bytes of a binary value would be misread; the bytes of a binary
+        // string are not rejected as text unless they are not valid UTF-8
+        let check = match value {
+            Some(MySqlValue::Binary(_)) => true,
+            Some(MySqlValue::Text(_)) => {
+                self.strict_types || info.map_or(false, MySqlTypeInfo::is_binary_string)
+            }
+            None => false,
+        };
+
+        if check {
+            if let Some(info) = info {
+                if info.is_binary_string() && T::type_info().is_text_string() {
+                    return Err(crate::Error::Decode(
+                        format!(
+                            "the column is a binary string ({}); decode it as `Vec<u8>` or \
+                                `&[u8]` instead",
+                            info
+                        )
+                        .into(),
+                    ));
+                }
+
+                row::check_type::<MySql, T>(info, self.strict_types)?;
+            }
+        }
+
+        Decode::decode(value)
+    }
+
+    // The name of the column at `index`, or the index if it has none
+    fn column_name(&self, index: usize) -> Box<str> {
+        self.columns
+            .iter()
+            .find(|&(_, &i)| usize::from(i) == index)
+            .map_or_else(|| index.to_string().into(), |(name, _)| name.clone())
+    }
 }
@@ -1,14 +1,30 @@
+use std::collections::HashMap;
+use std::io;
 use std::net::Shutdown;
 
 use byteorder::{ByteOrder, LittleEndian};
 
-use crate::io::{Buf, BufMut, BufStream, MaybeTlsStream};
-use crate::mysql::protocol::{Capabilities, Encode, EofPacket, ErrPacket, OkPacket};
+use crate::io::{
+    Buf, BufMut, BufStream, MaybeTlsStream, DEFAULT_MAX_MESSAGE_SIZE, DEFAULT_READ_BUFFER_SIZE,
+    DEFAULT_WRITE_BUFFER_SIZE,
+};
+use crate::mysql::protocol::{
+    read_result_set_end, Capabilities, Encode, EofPacket, ErrPacket, OkPacket, SessionStateChange,
+    Status,
+};
 use crate::mysql::MySqlError;
+
+#[cfg(feature = "protocol-trace")]
+use crate::io::trace::{self, Direction};
 use crate::url::Url;
 
-// Size before a packet is split
-const MAX_PACKET_SIZE: u32 = 1024;
+// The longest payload of a packet; a longer one is split across packets, and a payload of
+// exactly this length is followed by an empty packet
+const MAX_PAYLOAD_LEN: usize = 0xFF_FF_FF;
+
+// The largest packet the server may send, which the server's `max_allowed_packet` bounds as
+// well; 16 MiB, its default
+const DEFAULT_MAX_PACKET_SIZE: u32 = 16 * 1024 * 1024;
 
 pub(crate) struct MySqlStream {
     pub(super) stream: BufStream<MaybeTlsStream>,
@@ -16,6 +32,13 @@ pub(crate) struct MySqlStream {
     // Active capabilities
     pub(super) capabilities: Capabilities,
 
+    // Sent to the server while connecting; see [DEFAULT_MAX_PACKET_SIZE]
+    pub(super) max_packet_size: u32,
+
+    // Longest payload that is read, once joined from the packets it is split across; see
+    // [DEFAULT_MAX_MESSAGE_SIZE]
+    max_message_size: usize,
+
     // Packets in a command sequence have an incrementing sequence number
     // This number must be 0 at the start of each command
     pub(super) seq_no: u8,
@@ -25,12 +48,37 @@ pub(crate) struct MySqlStream {
     // decoding
     packet_buf: Vec<u8>,
     packet_len: usize,
+
+    // Set while the connection is somewhere it can't be brought back to a known state from,
+    // such as part way through sending a packet; if that future is dropped, the connection
+    // can no longer be used
+    pub(super) is_broken: bool,
+
+    // Server status from the most recent OK or EOF packet
+    pub(super) status: Status,
+
+    // Number of warnings raised by the last statement, from its OK or EOF packet
+    pub(super) warnings: u16,
+
+    // Session state reported by the server with SESSION_TRACK: the current schema, the
+    // tracked system variables and the GTIDs of the last transaction
+    pub(super) schema: Option<Box<str>>,
+    pub(super) system_variables: HashMap<Box<str>, Box<str>>,
+    pub(super) gtids: Option<Box<str>>,
+
+    #[cfg(feature = "bench-internals")]
+    pub(super) timer: crate::bench::PhaseTimer,
 }
 
 impl MySqlStream {
     pub(super) async fn new(url: &Url) -> crate::Result<Self> {
         let stream = MaybeTlsStream::connect(&url, 5432).await?;
 
+        Ok(Self::with_stream(stream, url))
+    }
+
+    // Wraps a stream that is connected to the server, before the handshake
+    pub(super) fn with_stream(stream: MaybeTlsStream, url: &Url) -> Self {
         let mut capabilities = Capabilities::PROTOCOL_41
             | Capabilities::IGNORE_SPACE
             | Capabilities::DEPRECATE_EOF
@@ -40,7 +88,9 @@ impl MySqlStream {
             | Capabilities::PLUGIN_AUTH_LENENC_DATA
             | Capabilities::MULTI_STATEMENTS
             | Capabilities::MULTI_RESULTS
-            | Capabilities::PLUGIN_AUTH;
+            | Capabilities::PS_MULTI_RESULTS
+            | Capabilities::PLUGIN_AUTH
+            | Capabilities::SESSION_TRACK;
 
         if url.database().is_some() {
             capabilities |= Capabilities::CONNECT_WITH_DB;
@@ -50,13 +100,26 @@ impl MySqlStream {
             capabilities |= Capabilities::SSL;
         }
 
-        Ok(Self {
+        let read_buffer_size = url.param_or("read-buffer-size", DEFAULT_READ_BUFFER_SIZE);
+        let write_buffer_size = url.param_or("write-buffer-size", DEFAULT_WRITE_BUFFER_SIZE);
+
+        Self {
             capabilities,
-            stream: BufStream::new(stream),
-            packet_buf: Vec::with_capacity(MAX_PACKET_SIZE as usize),
+            max_packet_size: url.param_or("max-packet-size", DEFAULT_MAX_PACKET_SIZE),
+            max_message_size: url.param_or("max-message-size", DEFAULT_MAX_MESSAGE_SIZE),
+            stream: BufStream::with_capacity(stream, read_buffer_size, write_buffer_size),
+            packet_buf: Vec::new(),
             packet_len: 0,
             seq_no: 0,
-        })
+            is_broken: false,
+            status: Status::empty(),
+            warnings: 0,
+            schema: url.database().map(Into::into),
+            system_variables: HashMap::new(),
+            gtids: None,
+            #[cfg(feature = "bench-internals")]
+            timer: Default::default(),
+        }
     }
 
     pub(super) fn is_tls(&self) -> bool {
@@ -80,9 +143,24 @@ impl MySqlStream {
         self.flush().await
     }
 
-    #[inline]
     pub(super) async fn flush(&mut self) -> crate::Result<()> {
-        Ok(self.stream.flush().await?)
+        self.is_broken = true;
+        self.stream.flush().await?;
+        self.is_broken = false;
+
+        Ok(())
+    }
+
+    pub(super) fn check_broken(&self) -> crate::Result<()> {
+        if self.is_broken {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "connection was left in an unknown state by a dropped query and must be closed",
+            )
+            .into());
+        }
+
+        Ok(())
     }
 
     /// Write the packet to the buffered stream ( do not send to the server )
@@ -104,9 +182,24 @@ impl MySqlStream {
         // and write to allocated header
 
         let len = buf.len() - header_offset - 4;
-        let mut header = &mut buf[header_offset..];
 
-        LittleEndian::write_u32(&mut header, len as u32);
+        #[cfg(feature = "protocol-trace")]
+        {
+            if trace::enabled() {
+                let (name, sensitive) = client_packet_name(self.seq_no, &buf[header_offset + 4..]);
+
+                trace::frame(Direction::Send, name, &buf[header_offset + 4..], sensitive);
+            }
+        }
+
+        if len >= MAX_PAYLOAD_LEN {
+            self.seq_no = split_payload(buf, header_offset, self.seq_no);
+            return;
+        }
+
+        let header = &mut buf[header_offset..];
+
+        LittleEndian::write_u32(header, len as u32);
 
         // Take the last sequence number received, if any, and increment by 1
         // If there was no sequence number, we only increment if we split packets
@@ -125,29 +218,58 @@ impl MySqlStream {
         self.packet_buf.clear();
         self.packet_len = 0;
 
-        // Read the packet header which contains the length and the sequence number
-        // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
-        // https://mariadb.com/kb/en/library/0-packet/#standard-packet
-        let mut header = self.stream.peek(4_usize).await?;
-
-        self.packet_len = header.get_uint::<LittleEndian>(3)? as usize;
-        self.seq_no = header.get_u8()?.wrapping_add(1);
-
-        self.stream.consume(4);
-
-        // Read the packet body and copy it into our internal buf
-        // We must have a separate buffer around the stream as we can't operate directly
-        // on bytes returned from the stream. We have various kinds of payload manipulation
-        // that must be handled before decoding.
-        let payload = self.stream.peek(self.packet_len).await?;
+        loop {
+            // Read the packet header which contains the length and the sequence number
+            // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
+            // https://mariadb.com/kb/en/library/0-packet/#standard-packet
+            let mut header = self.stream.peek(4_usize).await?;
+
+            let len = header.get_uint::<LittleEndian>(3)? as usize;
+            self.seq_no = header.get_u8()?.wrapping_add(1);
+
+            self.stream.consume(4);
+
+            if self.packet_len + len > self.max_message_size {
+                // the rest of the payload is left unread, so the connection can't go on
+                self.is_broken = true;
+
+                return Err(protocol_err!(
+                    "payload of at least {} bytes exceeds the maximum message size of {} bytes",
+                    self.packet_len + len,
+                    self.max_message_size
+                )
+                .into());
+            }
+
+            // Read the packet body and copy it into our internal buf
+            // We must have a separate buffer around the stream as we can't operate directly
+            // on bytes returned from the stream. We have various kinds of payload manipulation
+            // that must be handled before decoding.
+            let payload = self.stream.peek(len).await?;
+
+            self.packet_buf.reserve(payload.len());
+            self.packet_buf.extend_from_slice(payload);
+            self.packet_len += len;
+
+            self.stream.consume(len);
+
+            // A payload of 16 MiB or more is split across packets, the last of which is
+            // shorter, if only by being empty
+            if len < MAX_PAYLOAD_LEN {
+                break;
+            }
+        }
 
-        self.packet_buf.reserve(payload.len());
-        self.packet_buf.extend_from_slice(payload);
+        #[cfg(feature = "protocol-trace")]
+        {
+            if trace::enabled() {
+                let name = server_packet_name(self.packet());
 
-        self.stream.consume(self.packet_len);
+                trace::frame(Direction::Receive, name, self.packet(), false);
+            }
+        }
 
         // TODO: Implement packet compression
-        // TODO: Implement packet joining
 
         Ok(())
     }
@@ -169,23 +291,122 @@ impl MySqlStream {
         Ok(())
     }
 
-    pub(crate) fn maybe_handle_eof(&mut self) -> crate::Result<Option<EofPacket>> {
-        if !self.capabilities.contains(Capabilities::DEPRECATE_EOF) && self.packet()[0] == 0xFE {
-            Ok(Some(EofPacket::read(self.packet())?))
-        } else {
-            Ok(None)
-        }
+    // Handles the packet that ends the rows of a result set, in either framing
+    pub(crate) fn handle_result_set_end(&mut self) -> crate::Result<OkPacket> {
+        let ok = read_result_set_end(self.packet(), self.capabilities)?;
+        self.track(&ok);
+
+        Ok(ok)
     }
 
     pub(crate) fn handle_unexpected<T>(&mut self) -> crate::Result<T> {
         Err(protocol_err!("unexpected packet identifier 0x{:X?}", self.packet()[0]).into())
     }
 
+    // Whether the current packet is the ERR the server sends for a query without any statement,
+    // such as an empty string or only a comment (ER_EMPTY_QUERY)
+    pub(crate) fn is_empty_query_err(&self) -> bool {
+        let packet = self.packet();
+
+        packet.len() >= 3 && packet[0] == 0xFF && u16::from_le_bytes([packet[1], packet[2]]) == 1065
+    }
+
     pub(crate) fn handle_err<T>(&mut self) -> crate::Result<T> {
         Err(MySqlError(ErrPacket::read(self.packet(), self.capabilities)?).into())
     }
 
     pub(crate) fn handle_ok(&mut self) -> crate::Result<OkPacket> {
-        OkPacket::read(self.packet())
+        let ok = OkPacket::read(self.packet(), self.capabilities)?;
+        self.track(&ok);
+
+        Ok(ok)
+    }
+
+    fn track(&mut self, ok: &OkPacket) {
+        self.status = ok.status;
+        self.warnings = ok.warnings;
+
+        for change in &ok.session_state {
+            match change {
+                SessionStateChange::Schema(schema) if schema.is_empty() => self.schema = None,
+                SessionStateChange::Schema(schema) => self.schema = Some(schema.clone()),
+
+                SessionStateChange::SystemVariable { name, value } => {
+                    self.system_variables.insert(name.clone(), value.clone());
+                }
+
+                SessionStateChange::Gtids(gtids) => self.gtids = Some(gtids.clone()),
+
+                SessionStateChange::Other(_) => {}
+            }
+        }
+    }
+}
+
+// Splits the payload encoded after `header_offset`, which is too long for one packet, into
+// packets of [MAX_PAYLOAD_LEN] bytes and a shorter last one, numbered from `seq_no`; returns the
+// next sequence number
+fn split_payload(buf: &mut Vec<u8>, header_offset: usize, mut seq_no: u8) -> u8 {
+    let payload = buf.split_off(header_offset + 4);
+    buf.truncate(header_offset);
+
+    let mut chunks = payload.chunks(MAX_PAYLOAD_LEN).collect::<Vec<_>>();
+
+    if payload.len() % MAX_PAYLOAD_LEN == 0 {
+        chunks.push(&[]);
+    }
+
+    for chunk in chunks {
+        let mut header = [0_u8; 4];
+
+        LittleEndian::write_u32(&mut header, chunk.len() as u32);
+        header[3] = seq_no;
+        seq_no = seq_no.wrapping_add(1);
+
+        buf.extend_from_slice(&header);
+        buf.extend_from_slice(chunk);
+    }
+
+    seq_no
+}
+
+// Names a packet sent by the client. A command starts a sequence and is named by its first
+// byte; the packets later in a sequence are sent while connecting and carry credentials.
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_command_phase.html
+#[cfg(feature = "protocol-trace")]
+fn client_packet_name(seq_no: u8, packet: &[u8]) -> (&'static str, bool) {
+    if seq_no != 0 {
+        return ("HandshakeResponse or auth data", true);
+    }
+
+    let name = match packet.first() {
+        Some(0x01) => "COM_QUIT",
+        Some(0x02) => "COM_INIT_DB",
+        Some(0x03) => "COM_QUERY",
+        Some(0x0E) => "COM_PING",
+        Some(0x16) => "COM_STMT_PREPARE",
+        Some(0x17) => "COM_STMT_EXECUTE",
+        Some(0x19) => "COM_STMT_CLOSE",
+        Some(0x1A) => "COM_STMT_RESET",
+        Some(0x1F) => "COM_RESET_CONNECTION",
+
+        _ => "COM_UNKNOWN",
+    };
+
+    (name, false)
+}
+
+// Names a packet sent by the server from its first byte; some can't be told apart without
+// the context of the command
+#[cfg(feature = "protocol-trace")]
+fn server_packet_name(packet: &[u8]) -> &'static str {
+    match packet.first() {
+        Some(0x00) => "OK or binary row",
+        Some(0xFE) if packet.len() < 0xFF_FF_FF => "EOF, OK or auth switch",
+        Some(0xFF) => "ERR",
+        Some(0xFB) => "LOCAL INFILE or NULL",
+        None => "empty",
+
+        _ => "data",
     }
 }
@@ -110,7 +110,7 @@ async fn try_upgrade(
         .send(
             SslRequest {
                 client_collation: super::connection::COLLATE_UTF8MB4_UNICODE_CI,
-                max_packet_size: super::connection::MAX_PACKET_SIZE,
+                max_packet_size: stream.max_packet_size,
             },
             false,
         )
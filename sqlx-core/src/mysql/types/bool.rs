@@ -1,4 +1,5 @@
 use std::convert::TryInto;
+use std::str;
 
 use crate::decode::Decode;
 use crate::encode::Encode;
@@ -19,18 +20,21 @@ impl Encode<MySql> for bool {
     }
 }
 
+// A boolean is a `TINYINT(1)`, which can hold any other small integer; like MySQL, any value
+// other than zero is true
 impl<'de> Decode<'de, MySql> for bool {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
         match value.try_into()? {
-            MySqlValue::Binary(buf) => Ok(buf.get(0).map(|&b| b != 0).unwrap_or_default()),
+            // an integer of any size, little-endian
+            MySqlValue::Binary(buf) => Ok(buf.iter().any(|&b| b != 0)),
 
-            MySqlValue::Text(b"0") => Ok(false),
-
-            MySqlValue::Text(b"1") => Ok(true),
-
-            MySqlValue::Text(s) => Err(crate::Error::Decode(
-                format!("unexpected value {:?} for boolean", s).into(),
-            )),
+            MySqlValue::Text(s) => str::from_utf8(s)
+                .ok()
+                .and_then(|s| s.parse::<i128>().ok())
+                .map(|int| int != 0)
+                .ok_or_else(|| {
+                    crate::Error::Decode(format!("unexpected value {:?} for boolean", s).into())
+                }),
         }
     }
 }
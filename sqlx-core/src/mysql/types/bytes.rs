@@ -4,18 +4,17 @@ use crate::decode::Decode;
 use crate::encode::Encode;
 use crate::mysql::io::BufMutExt;
 use crate::mysql::protocol::TypeId;
-use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::types::{MySqlTypeInfo, BINARY_CHAR_SET};
 use crate::mysql::{MySql, MySqlValue};
-use crate::types::Type;
+use crate::types::{copy_to_byte_array, Type};
 use std::convert::TryInto;
 
 impl Type<MySql> for [u8] {
     fn type_info() -> MySqlTypeInfo {
         MySqlTypeInfo {
             id: TypeId::TEXT,
-            is_binary: true,
             is_unsigned: false,
-            char_set: 63, // binary
+            char_set: BINARY_CHAR_SET,
         }
     }
 }
@@ -53,3 +52,31 @@ impl<'de> Decode<'de, MySql> for &'de [u8] {
         }
     }
 }
+
+macro_rules! impl_byte_array {
+    ([$($generics:tt)*] $len:tt) => {
+        impl<$($generics)*> Type<MySql> for [u8; $len] {
+            fn type_info() -> MySqlTypeInfo {
+                <[u8] as Type<MySql>>::type_info()
+            }
+        }
+
+        impl<$($generics)*> Encode<MySql> for [u8; $len] {
+            fn encode(&self, buf: &mut Vec<u8>) {
+                <[u8] as Encode<MySql>>::encode(self, buf);
+            }
+        }
+
+        impl<'de, $($generics)*> Decode<'de, MySql> for [u8; $len] {
+            fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+                let mut array = [0; $len];
+
+                copy_to_byte_array(&mut array, <&[u8] as Decode<MySql>>::decode(value)?)?;
+
+                Ok(array)
+            }
+        }
+    };
+}
+
+impl_for_byte_arrays!(impl_byte_array);
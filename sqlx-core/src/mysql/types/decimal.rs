@@ -0,0 +1,41 @@
+use std::convert::TryInto;
+use std::str::from_utf8;
+
+use byteorder::LittleEndian;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::mysql::io::BufMutExt;
+use crate::mysql::protocol::TypeId;
+use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlValue};
+use crate::types::{Lossy, Type};
+use crate::Error;
+
+/// `Lossy<f64>` is accepted for `DECIMAL` (and `NUMERIC`) columns.
+///
+/// The value is sent and received as a decimal string in both protocols and rounded to the
+/// nearest `f64` when decoded. Use a plain `f64` with `DOUBLE` columns instead.
+impl Type<MySql> for Lossy<f64> {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::new(TypeId::NEW_DECIMAL)
+    }
+}
+
+impl Encode<MySql> for Lossy<f64> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.put_str_lenenc::<LittleEndian>(&self.0.to_string());
+    }
+}
+
+impl<'de> Decode<'de, MySql> for Lossy<f64> {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            MySqlValue::Binary(s) | MySqlValue::Text(s) => from_utf8(s)
+                .map_err(Error::decode)?
+                .parse()
+                .map(Lossy)
+                .map_err(Error::decode),
+        }
+    }
+}
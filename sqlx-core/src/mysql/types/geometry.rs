@@ -0,0 +1,68 @@
+use std::convert::TryInto;
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::mysql::io::BufMutExt;
+use crate::mysql::protocol::TypeId;
+use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlValue};
+use crate::types::Type;
+use crate::Error;
+
+/// A `GEOMETRY`, `POINT`, `LINESTRING`, `POLYGON` or other spatial value, as its spatial
+/// reference system identifier (SRID) and its well-known binary (WKB) representation.
+///
+/// MySQL stores a spatial value as the SRID, a little-endian 4-byte integer, followed by the WKB;
+/// parse `wkb` with a crate such as `wkb` or `geozero` to get at the geometry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MySqlGeometry {
+    pub srid: u32,
+    pub wkb: Vec<u8>,
+}
+
+impl MySqlGeometry {
+    pub fn new(srid: u32, wkb: Vec<u8>) -> Self {
+        Self { srid, wkb }
+    }
+}
+
+impl Type<MySql> for MySqlGeometry {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::new(TypeId::GEOMETRY)
+    }
+}
+
+impl Encode<MySql> for MySqlGeometry {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let mut value = Vec::with_capacity(4 + self.wkb.len());
+        value.extend_from_slice(&self.srid.to_le_bytes());
+        value.extend_from_slice(&self.wkb);
+
+        buf.put_bytes_lenenc::<LittleEndian>(&value);
+    }
+}
+
+impl<'de> Decode<'de, MySql> for MySqlGeometry {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        let buf = match value.try_into()? {
+            MySqlValue::Binary(buf) | MySqlValue::Text(buf) => buf,
+        };
+
+        if buf.len() < 4 {
+            return Err(Error::Decode(
+                format!(
+                    "expected a spatial value of at least 4 bytes, got {}",
+                    buf.len()
+                )
+                .into(),
+            ));
+        }
+
+        Ok(MySqlGeometry {
+            srid: LittleEndian::read_u32(&buf[..4]),
+            wkb: buf[4..].to_vec(),
+        })
+    }
+}
@@ -1,4 +1,5 @@
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error as StdError;
 use std::str::from_utf8;
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -25,14 +26,7 @@ impl Encode<MySql> for i8 {
 
 impl<'de> Decode<'de, MySql> for i8 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_i8().map_err(Into::into),
-
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_int(value)
     }
 }
 
@@ -50,14 +44,7 @@ impl Encode<MySql> for i16 {
 
 impl<'de> Decode<'de, MySql> for i16 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_i16::<LittleEndian>().map_err(Into::into),
-
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_int(value)
     }
 }
 
@@ -75,14 +62,7 @@ impl Encode<MySql> for i32 {
 
 impl<'de> Decode<'de, MySql> for i32 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_i32::<LittleEndian>().map_err(Into::into),
-
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_int(value)
     }
 }
 
@@ -100,13 +80,30 @@ impl Encode<MySql> for i64 {
 
 impl<'de> Decode<'de, MySql> for i64 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_i64::<LittleEndian>().map_err(Into::into),
+        decode_int(value)
+    }
+}
 
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
+// Integers of any width are accepted; the value is checked to fit in `T`
+fn decode_int<T>(value: Option<MySqlValue<'_>>) -> crate::Result<T>
+where
+    T: TryFrom<i64>,
+    T::Error: StdError + Send + Sync + 'static,
+{
+    let value = match value.try_into()? {
+        MySqlValue::Binary(mut buf) => match buf.len() {
+            1 => buf.read_i8().map(i64::from),
+            2 => buf.read_i16::<LittleEndian>().map(i64::from),
+            4 => buf.read_i32::<LittleEndian>().map(i64::from),
+            _ => buf.read_i64::<LittleEndian>(),
         }
-    }
+        .map_err(Error::decode)?,
+
+        MySqlValue::Text(s) => from_utf8(s)
+            .map_err(Error::decode)?
+            .parse::<i64>()
+            .map_err(Error::decode)?,
+    };
+
+    T::try_from(value).map_err(Error::decode)
 }
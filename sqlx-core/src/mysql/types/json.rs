@@ -0,0 +1,82 @@
+use std::convert::TryInto;
+
+use byteorder::LittleEndian;
+use serde_json::Value;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::mysql::io::BufMutExt;
+use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlValue};
+use crate::types::{JsonText, Type};
+
+impl<'de> Decode<'de, MySql> for JsonText<'de> {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            MySqlValue::Binary(buf) | MySqlValue::Text(buf) => Ok(JsonText(buf)),
+        }
+    }
+}
+
+// MySQL has no arrays; a `Vec` is stored as a JSON array instead, in a `JSON` column or any
+// textual one. It is sent as text, which the server parses when the column is `JSON`.
+macro_rules! impl_json_array {
+    ($($ty:ty),*) => {
+        $(
+            impl Type<MySql> for Vec<$ty> {
+                fn type_info() -> MySqlTypeInfo {
+                    <str as Type<MySql>>::type_info()
+                }
+            }
+
+            impl Encode<MySql> for Vec<$ty> {
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    encode_json_array(self, buf)
+                }
+            }
+
+            impl<'de> Decode<'de, MySql> for Vec<$ty> {
+                fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+                    decode_json_array(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_json_array!(String, bool, i16, i32, i64, f32, f64);
+
+fn encode_json_array<T>(values: &[T], buf: &mut Vec<u8>)
+where
+    T: serde::Serialize,
+{
+    // serializing a slice of strings, integers or floats can't fail; a float that is not
+    // finite is written as `null`
+    let text = serde_json::to_string(values).expect("failed to serialize a JSON array");
+
+    buf.put_str_lenenc::<LittleEndian>(&text);
+}
+
+fn decode_json_array<T>(value: Option<MySqlValue<'_>>) -> crate::Result<Vec<T>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let buf = match value.try_into()? {
+        MySqlValue::Binary(buf) | MySqlValue::Text(buf) => buf,
+    };
+
+    let value: Value = serde_json::from_slice(buf).map_err(crate::Error::decode)?;
+
+    let kind = match &value {
+        Value::Array(_) => return serde_json::from_value(value).map_err(crate::Error::decode),
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Object(_) => "an object",
+    };
+
+    Err(crate::Error::Decode(
+        format!("expected a JSON array, found {}", kind).into(),
+    ))
+}
@@ -1,13 +1,26 @@
 mod bool;
 mod bytes;
+mod decimal;
 mod float;
+mod geometry;
 mod int;
 mod str;
+mod system_time;
 mod uint;
+mod year;
 
 #[cfg(feature = "chrono")]
 mod chrono;
 
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(feature = "messagepack")]
+mod serialized;
+
+#[cfg(feature = "uuid")]
+mod uuid;
+
 use std::fmt::{self, Debug, Display};
 
 use crate::decode::Decode;
@@ -16,11 +29,16 @@ use crate::mysql::protocol::{ColumnDefinition, FieldFlags};
 use crate::mysql::{MySql, MySqlValue};
 use crate::types::TypeInfo;
 
+pub use geometry::MySqlGeometry;
+pub use year::MySqlYear;
+
+// The collation of BINARY, VARBINARY and BLOB columns, and of values that are not strings
+pub(crate) const BINARY_CHAR_SET: u16 = 63;
+
 #[derive(Clone, Debug, Default)]
 pub struct MySqlTypeInfo {
     pub(crate) id: TypeId,
     pub(crate) is_unsigned: bool,
-    pub(crate) is_binary: bool,
     pub(crate) char_set: u16,
 }
 
@@ -29,8 +47,7 @@ impl MySqlTypeInfo {
         Self {
             id,
             is_unsigned: false,
-            is_binary: true,
-            char_set: 0,
+            char_set: BINARY_CHAR_SET,
         }
     }
 
@@ -38,8 +55,7 @@ impl MySqlTypeInfo {
         Self {
             id,
             is_unsigned: true,
-            is_binary: false,
-            char_set: 0,
+            char_set: BINARY_CHAR_SET,
         }
     }
 
@@ -47,13 +63,39 @@ impl MySqlTypeInfo {
         Self {
             id: def.type_id,
             is_unsigned: def.flags.contains(FieldFlags::UNSIGNED),
-            is_binary: def.flags.contains(FieldFlags::BINARY),
             char_set: def.char_set,
         }
     }
 
+    /// The ID of the collation of a string column, as listed in `information_schema.COLLATIONS`.
+    ///
+    /// This is 63 (`binary`) for BINARY, VARBINARY and BLOB columns, which are otherwise
+    /// reported with the same types as CHAR, VARCHAR and TEXT.
+    pub fn char_set(&self) -> u16 {
+        self.char_set
+    }
+
+    /// Returns `true` for a BINARY, VARBINARY or BLOB column, whose values are decoded as
+    /// `Vec<u8>` or `&[u8]` rather than as text.
+    pub fn is_binary_string(&self) -> bool {
+        TypeClass::of(self) == Some(TypeClass::Binary)
+    }
+
+    pub(crate) fn is_text_string(&self) -> bool {
+        TypeClass::of(self) == Some(TypeClass::Textual)
+    }
+
     #[doc(hidden)]
     pub fn type_name(&self) -> &'static str {
+        if self.is_binary_string() {
+            match self.id {
+                TypeId::CHAR => return "BINARY",
+                TypeId::VAR_CHAR => return "VAR_BINARY",
+                TypeId::TEXT => return "BLOB",
+                _ => {}
+            }
+        }
+
         self.id.type_name()
     }
 
@@ -69,7 +111,7 @@ impl MySqlTypeInfo {
 impl Display for MySqlTypeInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.id.type_name() != "<unknown>" {
-            write!(f, "{}", self.id.type_name())
+            write!(f, "{}", self.type_name())
         } else {
             write!(f, "ID {:#x}", self.id.0)
         }
@@ -78,27 +120,33 @@ impl Display for MySqlTypeInfo {
 
 impl TypeInfo for MySqlTypeInfo {
     fn compatible(&self, other: &Self) -> bool {
+        match (TypeClass::of(self), TypeClass::of(other)) {
+            (Some(a), Some(b)) => a.accepts(b),
+            _ => self.compatible_strict(other),
+        }
+    }
+
+    fn compatible_strict(&self, other: &Self) -> bool {
         match self.id {
             TypeId::VAR_CHAR
             | TypeId::TEXT
             | TypeId::CHAR
             | TypeId::TINY_BLOB
             | TypeId::MEDIUM_BLOB
-            | TypeId::LONG_BLOB
-                if (self.is_binary == other.is_binary)
-                    && match other.id {
-                        TypeId::VAR_CHAR
-                        | TypeId::TEXT
-                        | TypeId::CHAR
-                        | TypeId::TINY_BLOB
-                        | TypeId::MEDIUM_BLOB
-                        | TypeId::LONG_BLOB => true,
-
-                        _ => false,
-                    } =>
-            {
-                true
-            }
+            | TypeId::LONG_BLOB => match (TypeClass::of(self), TypeClass::of(other)) {
+                (Some(a), Some(b)) => a.accepts(b),
+                _ => false,
+            },
+
+            // DECIMAL and NEW_DECIMAL are both sent as decimal strings; signedness
+            // does not change how they are decoded
+            TypeId::DECIMAL | TypeId::NEW_DECIMAL => match other.id {
+                TypeId::DECIMAL | TypeId::NEW_DECIMAL => true,
+                _ => false,
+            },
+
+            // a YEAR column is reported as UNSIGNED, though no `YEAR` is signed
+            TypeId::YEAR => other.id == TypeId::YEAR,
 
             // Fallback to equality of only [id] and [is_unsigned]
             _ => self.id.0 == other.id.0 && self.is_unsigned == other.is_unsigned,
@@ -106,6 +154,63 @@ impl TypeInfo for MySqlTypeInfo {
     }
 }
 
+// Groups of types that are decoded the same way. Integers of different widths are only
+// grouped with integers of the same signedness as the value is extended by its own sign
+#[derive(PartialEq, Clone, Copy)]
+enum TypeClass {
+    Integral { is_unsigned: bool },
+    Textual,
+    Binary,
+    Decimal,
+    DateTime,
+}
+
+impl TypeClass {
+    fn of(info: &MySqlTypeInfo) -> Option<Self> {
+        Some(match info.id {
+            TypeId::TINY_INT | TypeId::SMALL_INT | TypeId::INT | TypeId::BIG_INT => {
+                TypeClass::Integral {
+                    is_unsigned: info.is_unsigned,
+                }
+            }
+
+            // the full year is sent, as an `int<2>`
+            TypeId::YEAR => TypeClass::Integral { is_unsigned: true },
+
+            // BINARY, VARBINARY and BLOB share their type IDs with the textual types and are
+            // told apart by their collation
+            TypeId::VAR_CHAR
+            | TypeId::TEXT
+            | TypeId::CHAR
+            | TypeId::TINY_BLOB
+            | TypeId::MEDIUM_BLOB
+            | TypeId::LONG_BLOB => {
+                if info.char_set == BINARY_CHAR_SET {
+                    TypeClass::Binary
+                } else {
+                    TypeClass::Textual
+                }
+            }
+
+            // a JSON value is sent as utf8mb4 text, though the column reports the `binary`
+            // collation
+            TypeId::JSON => TypeClass::Textual,
+
+            TypeId::DECIMAL | TypeId::NEW_DECIMAL => TypeClass::Decimal,
+
+            TypeId::DATETIME | TypeId::TIMESTAMP => TypeClass::DateTime,
+
+            _ => return None,
+        })
+    }
+
+    // Whether a value of class `actual` can be decoded as a type of this class; bytes can be
+    // read from any string, text only from a string that has a character set
+    fn accepts(self, actual: TypeClass) -> bool {
+        self == actual || (self == TypeClass::Binary && actual == TypeClass::Textual)
+    }
+}
+
 impl<'de, T> Decode<'de, MySql> for Option<T>
 where
     T: Decode<'de, MySql>,
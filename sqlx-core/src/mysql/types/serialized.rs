@@ -0,0 +1,50 @@
+use byteorder::LittleEndian;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::mysql::io::BufMutExt;
+use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlValue};
+use crate::types::serialized::{self, MsgPack};
+use crate::types::Type;
+
+// Serialized values are binary strings. They are serialized on their own first, as their
+// length comes before them.
+macro_rules! impl_serialized {
+    ($($wrapper:ident),*) => {
+        $(
+            impl<T> Type<MySql> for $wrapper<T> {
+                fn type_info() -> MySqlTypeInfo {
+                    <[u8] as Type<MySql>>::type_info()
+                }
+            }
+
+            impl<T: Serialize> Encode<MySql> for $wrapper<T> {
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    if let Err(error) = Encode::<MySql>::try_encode(self, buf) {
+                        panic!("{}", error);
+                    }
+                }
+
+                fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+                    let mut bytes = Vec::new();
+
+                    serialized::encode(self, &mut bytes)?;
+                    buf.put_bytes_lenenc::<LittleEndian>(&bytes);
+
+                    Ok(IsNull::No)
+                }
+            }
+
+            impl<'de, T: DeserializeOwned + 'de> Decode<'de, MySql> for $wrapper<T> {
+                fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+                    serialized::decode(<&'de [u8] as Decode<MySql>>::decode(value)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialized!(MsgPack);
@@ -1,13 +1,16 @@
+use std::path::{Path, PathBuf};
 use std::str;
 
 use byteorder::LittleEndian;
 
 use crate::decode::Decode;
-use crate::encode::Encode;
+use crate::encode::{Encode, IsNull};
+use crate::mysql::connection::COLLATE_UTF8MB4_UNICODE_CI;
 use crate::mysql::io::BufMutExt;
 use crate::mysql::protocol::TypeId;
 use crate::mysql::types::MySqlTypeInfo;
 use crate::mysql::{MySql, MySqlValue};
+use crate::path::path_to_str;
 use crate::types::Type;
 use std::convert::TryInto;
 use std::str::from_utf8;
@@ -16,9 +19,8 @@ impl Type<MySql> for str {
     fn type_info() -> MySqlTypeInfo {
         MySqlTypeInfo {
             id: TypeId::TEXT,
-            is_binary: false,
             is_unsigned: false,
-            char_set: 224, // utf8mb4_unicode_ci
+            char_set: COLLATE_UTF8MB4_UNICODE_CI as u16,
         }
     }
 }
@@ -56,3 +58,43 @@ impl<'de> Decode<'de, MySql> for String {
         <&'de str as Decode<MySql>>::decode(buf).map(ToOwned::to_owned)
     }
 }
+
+impl Type<MySql> for Path {
+    fn type_info() -> MySqlTypeInfo {
+        <str as Type<MySql>>::type_info()
+    }
+}
+
+impl Type<MySql> for PathBuf {
+    fn type_info() -> MySqlTypeInfo {
+        <str as Type<MySql>>::type_info()
+    }
+}
+
+impl Encode<MySql> for Path {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = Encode::<MySql>::try_encode(self, buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        Encode::<MySql>::try_encode(path_to_str(self)?, buf)
+    }
+}
+
+impl Encode<MySql> for PathBuf {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        <Path as Encode<MySql>>::encode(self.as_path(), buf)
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        <Path as Encode<MySql>>::try_encode(self.as_path(), buf)
+    }
+}
+
+impl<'de> Decode<'de, MySql> for PathBuf {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        <&'de str as Decode<MySql>>::decode(value).map(PathBuf::from)
+    }
+}
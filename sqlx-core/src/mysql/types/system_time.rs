@@ -0,0 +1,147 @@
+use std::convert::{TryFrom, TryInto};
+use std::str::from_utf8;
+use std::time::SystemTime;
+
+use byteorder::LittleEndian;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::io::{Buf, BufMut};
+use crate::mysql::protocol::TypeId;
+use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlValue};
+use crate::system_time::{from_unix_micros, to_unix_micros, DateTime};
+use crate::types::Type;
+use crate::Error;
+
+// `TIMESTAMP` and `DATETIME` values carry no time zone; they are read and written as UTC, which
+// holds for `TIMESTAMP` as the connection sets the session `time_zone` to `+00:00`
+impl Type<MySql> for SystemTime {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::new(TypeId::TIMESTAMP)
+    }
+}
+
+impl Encode<MySql> for SystemTime {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = Encode::<MySql>::try_encode(self, buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        let out_of_range =
+            || Error::Encode(format!("SystemTime out of range for MySQL: {:?}", self).into());
+
+        let date_time = to_unix_micros(*self)
+            .map(DateTime::from_unix_micros)
+            .ok_or_else(out_of_range)?;
+
+        // MySQL supports years from 1000 - 9999
+        let year = u16::try_from(date_time.year).map_err(|_| out_of_range())?;
+
+        let len = Encode::<MySql>::size_hint(self) - 1;
+        buf.push(len as u8);
+
+        buf.put_u16::<LittleEndian>(year);
+        buf.push(date_time.month as u8);
+        buf.push(date_time.day as u8);
+
+        if len > 4 {
+            buf.push(date_time.hour as u8);
+            buf.push(date_time.minute as u8);
+            buf.push(date_time.second as u8);
+        }
+
+        if len > 7 {
+            buf.put_u32::<LittleEndian>(date_time.micros);
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        let date_time = match to_unix_micros(*self) {
+            Some(micros) => DateTime::from_unix_micros(micros),
+            None => return 12,
+        };
+
+        // same compression as for `NaiveDateTime`
+        match date_time {
+            DateTime {
+                hour: 0,
+                minute: 0,
+                second: 0,
+                micros: 0,
+                ..
+            } => 5,
+
+            DateTime { micros: 0, .. } => 8,
+
+            _ => 12,
+        }
+    }
+}
+
+impl<'de> Decode<'de, MySql> for SystemTime {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        let micros = match value.try_into()? {
+            MySqlValue::Binary(mut buf) => {
+                let len = buf.get_u8()?;
+
+                // a zero date, `0000-00-00 00:00:00`, is sent without any fields
+                if len == 0 {
+                    return Err(Error::Decode(
+                        "zero timestamp can't be decoded into SystemTime".into(),
+                    ));
+                }
+
+                let year = buf.get_u16::<LittleEndian>()?;
+                let month = buf.get_u8()?;
+                let day = buf.get_u8()?;
+
+                let (hour, minute, second) = if len > 4 {
+                    (buf.get_u8()?, buf.get_u8()?, buf.get_u8()?)
+                } else {
+                    (0, 0, 0)
+                };
+
+                let micros = if len > 7 {
+                    buf.get_u32::<LittleEndian>()?
+                } else {
+                    0
+                };
+
+                let date_time = DateTime {
+                    year: year as i32,
+                    month: month as u32,
+                    day: day as u32,
+                    hour: hour as u32,
+                    minute: minute as u32,
+                    second: second as u32,
+                    micros,
+                };
+
+                // for instance, `2019-00-00` if `NO_ZERO_IN_DATE` is not set
+                if !date_time.is_valid() {
+                    return Err(Error::Decode(
+                        format!("invalid timestamp for SystemTime: {:?}", date_time).into(),
+                    ));
+                }
+
+                date_time.unix_micros()
+            }
+
+            MySqlValue::Text(buf) => {
+                let s = from_utf8(buf).map_err(Error::decode)?;
+
+                DateTime::parse(s).ok_or_else(|| {
+                    Error::Decode(format!("invalid timestamp for SystemTime: {:?}", s).into())
+                })?
+            }
+        };
+
+        from_unix_micros(micros)
+            .ok_or_else(|| Error::Decode("MySQL timestamp out of range for SystemTime".into()))
+    }
+}
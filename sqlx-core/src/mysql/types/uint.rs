@@ -1,4 +1,5 @@
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error as StdError;
 use std::str::from_utf8;
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -25,14 +26,7 @@ impl Encode<MySql> for u8 {
 
 impl<'de> Decode<'de, MySql> for u8 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_u8().map_err(Into::into),
-
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_uint(value)
     }
 }
 
@@ -50,14 +44,7 @@ impl Encode<MySql> for u16 {
 
 impl<'de> Decode<'de, MySql> for u16 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_u16::<LittleEndian>().map_err(Into::into),
-
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_uint(value)
     }
 }
 
@@ -75,14 +62,7 @@ impl Encode<MySql> for u32 {
 
 impl<'de> Decode<'de, MySql> for u32 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_u32::<LittleEndian>().map_err(Into::into),
-
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_uint(value)
     }
 }
 
@@ -100,13 +80,30 @@ impl Encode<MySql> for u64 {
 
 impl<'de> Decode<'de, MySql> for u64 {
     fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            MySqlValue::Binary(mut buf) => buf.read_u64::<LittleEndian>().map_err(Into::into),
+        decode_uint(value)
+    }
+}
 
-            MySqlValue::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
+// Integers of any width are accepted; the value is checked to fit in `T`
+pub(super) fn decode_uint<T>(value: Option<MySqlValue<'_>>) -> crate::Result<T>
+where
+    T: TryFrom<u64>,
+    T::Error: StdError + Send + Sync + 'static,
+{
+    let value = match value.try_into()? {
+        MySqlValue::Binary(mut buf) => match buf.len() {
+            1 => buf.read_u8().map(u64::from),
+            2 => buf.read_u16::<LittleEndian>().map(u64::from),
+            4 => buf.read_u32::<LittleEndian>().map(u64::from),
+            _ => buf.read_u64::<LittleEndian>(),
         }
-    }
+        .map_err(Error::decode)?,
+
+        MySqlValue::Text(s) => from_utf8(s)
+            .map_err(Error::decode)?
+            .parse::<u64>()
+            .map_err(Error::decode)?,
+    };
+
+    T::try_from(value).map_err(Error::decode)
 }
@@ -0,0 +1,67 @@
+use std::convert::TryInto;
+use std::str::FromStr;
+
+use uuid::adapter::Hyphenated;
+use uuid::Uuid;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlValue};
+use crate::types::Type;
+
+// MySQL has no UUID type; a `Uuid` is stored in a BINARY(16) column, which is also what
+// `UUID_TO_BIN` returns
+impl Type<MySql> for Uuid {
+    fn type_info() -> MySqlTypeInfo {
+        <[u8] as Type<MySql>>::type_info()
+    }
+}
+
+impl Encode<MySql> for Uuid {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        <[u8] as Encode<MySql>>::encode(self.as_bytes(), buf);
+    }
+}
+
+// A `Uuid` is also decoded from its hyphenated form in a CHAR or VARCHAR column, which is never
+// 16 bytes long
+impl<'de> Decode<'de, MySql> for Uuid {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            MySqlValue::Binary(buf) | MySqlValue::Text(buf) if buf.len() == 16 => {
+                Uuid::from_slice(buf).map_err(crate::Error::decode)
+            }
+
+            value => {
+                let s = <&'de str as Decode<MySql>>::decode(Some(value))?;
+
+                Uuid::from_str(s).map_err(crate::Error::decode)
+            }
+        }
+    }
+}
+
+impl Type<MySql> for Hyphenated {
+    fn type_info() -> MySqlTypeInfo {
+        <str as Type<MySql>>::type_info()
+    }
+}
+
+impl Encode<MySql> for Hyphenated {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let mut s = [0; Hyphenated::LENGTH];
+
+        <str as Encode<MySql>>::encode(self.encode_lower(&mut s), buf);
+    }
+}
+
+impl<'de> Decode<'de, MySql> for Hyphenated {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        let s = <&'de str as Decode<MySql>>::decode(value)?;
+
+        Uuid::from_str(s)
+            .map(Uuid::to_hyphenated)
+            .map_err(crate::Error::decode)
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::{self, Display, Formatter};
+
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::mysql::protocol::TypeId;
+use crate::mysql::types::uint::decode_uint;
+use crate::mysql::types::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlValue};
+use crate::types::Type;
+use crate::Error;
+
+/// A `YEAR`; a four-digit year from 1901 to 2155, or 0.
+///
+/// `YEAR` columns also decode into `u16`, `u32` and `u64`, without the range being checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MySqlYear(pub u16);
+
+impl MySqlYear {
+    /// Returns `true` if a `YEAR` can hold this year; MySQL stores it in a byte, as an offset
+    /// from 1900 where 0 stands for the year 0.
+    pub fn is_valid(self) -> bool {
+        self.0 == 0 || (1901..=2155).contains(&self.0)
+    }
+
+    fn check(self) -> Result<Self, String> {
+        if self.is_valid() {
+            Ok(self)
+        } else {
+            Err(format!(
+                "year {} out of range for YEAR; expected 1901 to 2155, or 0",
+                self.0
+            ))
+        }
+    }
+}
+
+impl From<u16> for MySqlYear {
+    fn from(year: u16) -> Self {
+        MySqlYear(year)
+    }
+}
+
+impl From<MySqlYear> for u16 {
+    fn from(year: MySqlYear) -> Self {
+        year.0
+    }
+}
+
+impl Display for MySqlYear {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.0)
+    }
+}
+
+impl Type<MySql> for MySqlYear {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::unsigned(TypeId::YEAR)
+    }
+}
+
+impl Encode<MySql> for MySqlYear {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = Encode::<MySql>::try_encode(self, buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        let year = self.check().map_err(|err| Error::Encode(err.into()))?;
+
+        // sent as a `SMALLINT`; see [ComStmtExecute]
+        let _ = buf.write_u16::<LittleEndian>(year.0);
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        2
+    }
+}
+
+impl<'de> Decode<'de, MySql> for MySqlYear {
+    fn decode(value: Option<MySqlValue<'de>>) -> crate::Result<Self> {
+        // the full year is sent, as an `int<2>` or as text
+        MySqlYear(decode_uint(value)?)
+            .check()
+            .map_err(|err| Error::Decode(err.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MySqlYear;
+
+    #[test]
+    fn it_checks_the_range_of_a_year() {
+        for &year in &[0, 1901, 2020, 2155] {
+            assert!(MySqlYear(year).is_valid(), "{}", year);
+        }
+
+        for &year in &[1, 99, 1900, 2156, u16::max_value()] {
+            assert!(!MySqlYear(year).is_valid(), "{}", year);
+        }
+    }
+}
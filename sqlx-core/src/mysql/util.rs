@@ -7,3 +7,110 @@ pub fn xor_eq(x: &mut [u8], y: &[u8]) {
         x[i] ^= y[i % y_len];
     }
 }
+
+// Returns the user variables (`@name`) referenced by a statement, in order of appearance and
+// without duplicates. Strings, quoted identifiers, comments and system variables (`@@name`)
+// are skipped.
+pub fn user_variables(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut variables = Vec::new();
+    let mut i = 0;
+
+    let is_name = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'.' || b == b'$';
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ b'\'' | quote @ b'"' | quote @ b'`' => {
+                i += 1;
+
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' && quote != b'`' {
+                        i += 1;
+                    }
+
+                    i += 1;
+                }
+            }
+
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+
+            b'-' if sql[i..].starts_with("-- ") => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+
+            b'/' if sql[i..].starts_with("/*") => {
+                i += sql[i + 2..].find("*/").map_or(bytes.len(), |end| end + 3);
+            }
+
+            b'@' if bytes.get(i + 1) == Some(&b'@') => {
+                i += 2;
+
+                while i < bytes.len() && is_name(bytes[i]) {
+                    i += 1;
+                }
+
+                continue;
+            }
+
+            b'@' => {
+                let start = i;
+                i += 1;
+
+                while i < bytes.len() && is_name(bytes[i]) {
+                    i += 1;
+                }
+
+                let variable = &sql[start..i];
+
+                if variable.len() > 1 && !variables.contains(&variable) {
+                    variables.push(variable);
+                }
+
+                continue;
+            }
+
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    variables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::user_variables;
+
+    #[test]
+    fn it_finds_user_variables() {
+        assert_eq!(
+            user_variables("CALL add_item(?, @id, @total)"),
+            vec!["@id", "@total"]
+        );
+
+        assert_eq!(
+            user_variables("CALL p(@a, @a, @b.c, @$d)"),
+            vec!["@a", "@b.c", "@$d"]
+        );
+
+        assert!(user_variables("CALL p(?, ?)").is_empty());
+    }
+
+    #[test]
+    fn it_skips_strings_comments_and_system_variables() {
+        assert_eq!(
+            user_variables(
+                "CALL p('a@b.com', \"it\\\"s @x\", `@y`, @@session.sql_mode, @z) \
+                 # @comment\n -- @comment\n /* @comment */"
+            ),
+            vec!["@z"]
+        );
+    }
+}
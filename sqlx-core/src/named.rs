@@ -0,0 +1,234 @@
+//! Rewriting of named parameters (`:name`) into the bind parameters of a database.
+
+/// How a database writes its bind parameters, and the parts of its syntax that matter to find
+/// named parameters outside of strings and comments.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterStyle {
+    /// `$1`, `$2`, as for Postgres; a value used more than once is bound once.
+    ///
+    /// A string prefixed with `E` escapes with a backslash and `$tag$` starts a dollar-quoted
+    /// string.
+    Dollar,
+
+    /// `?1`, `?2`, as for SQLite; a value used more than once is bound once.
+    NumberedQuestion,
+
+    /// `?`, as for MySQL; a value is bound again for each use.
+    ///
+    /// Strings (`'` or `"`) escape with a backslash and `#` starts a comment.
+    Question,
+}
+
+// Returns `sql` with each named parameter replaced by a bind parameter of `style`, and the
+// names of the values to bind in order. Strings, quoted identifiers, comments and casts (`::`)
+// are skipped.
+pub(crate) fn rewrite(sql: &str, style: ParameterStyle) -> (String, Vec<&str>) {
+    let bytes = sql.as_bytes();
+    let mut out = String::with_capacity(sql.len());
+    let mut names: Vec<&str> = Vec::new();
+
+    // the end of the part of `sql` copied to `out` so far
+    let mut copied = 0;
+    let mut i = 0;
+
+    let is_name = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let is_name_start = |b: u8| b.is_ascii_alphabetic() || b == b'_';
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ b'\'' | quote @ b'"' | quote @ b'`' => {
+                let backslash_escapes = quote != b'`'
+                    && match style {
+                        ParameterStyle::Question => true,
+
+                        // E'...'
+                        ParameterStyle::Dollar => {
+                            quote == b'\''
+                                && i > 0
+                                && (bytes[i - 1] == b'E' || bytes[i - 1] == b'e')
+                                && (i == 1 || !is_name(bytes[i - 2]))
+                        }
+
+                        ParameterStyle::NumberedQuestion => false,
+                    };
+
+                i += 1;
+
+                while i < bytes.len() && bytes[i] != quote {
+                    if bytes[i] == b'\\' && backslash_escapes {
+                        i += 1;
+                    }
+
+                    i += 1;
+                }
+            }
+
+            b'#' if style == ParameterStyle::Question => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+
+            b'-' if sql[i..].starts_with("--") => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+
+            b'/' if sql[i..].starts_with("/*") => {
+                i += sql[i + 2..].find("*/").map_or(bytes.len(), |end| end + 3);
+            }
+
+            b'$' if style == ParameterStyle::Dollar && (i == 0 || !is_name(bytes[i - 1])) => {
+                // $tag$ ... $tag$, where the tag may be empty; `$1` is a bind parameter
+                let tag_len = bytes[i + 1..]
+                    .iter()
+                    .position(|&b| !is_name(b))
+                    .unwrap_or(bytes.len() - i - 1);
+
+                let tag_end = i + 1 + tag_len;
+
+                if bytes.get(tag_end) == Some(&b'$')
+                    && (tag_len == 0 || is_name_start(bytes[i + 1]))
+                {
+                    let delimiter = &sql[i..=tag_end];
+
+                    i = sql[tag_end + 1..]
+                        .find(delimiter)
+                        .map_or(bytes.len(), |end| tag_end + 1 + end + delimiter.len());
+
+                    continue;
+                }
+            }
+
+            // a cast, `value::type`
+            b':' if bytes.get(i + 1) == Some(&b':') => {
+                i += 2;
+
+                continue;
+            }
+
+            b':' if bytes.get(i + 1).map_or(false, |&b| is_name_start(b)) => {
+                let start = i + 1;
+                let mut end = start;
+
+                while end < bytes.len() && is_name(bytes[end]) {
+                    end += 1;
+                }
+
+                let name = &sql[start..end];
+
+                out.push_str(&sql[copied..i]);
+
+                match style {
+                    ParameterStyle::Question => {
+                        names.push(name);
+                        out.push('?');
+                    }
+
+                    ParameterStyle::Dollar | ParameterStyle::NumberedQuestion => {
+                        let index = match names.iter().position(|&n| n == name) {
+                            Some(index) => index,
+
+                            None => {
+                                names.push(name);
+                                names.len() - 1
+                            }
+                        };
+
+                        out.push(if style == ParameterStyle::Dollar {
+                            '$'
+                        } else {
+                            '?'
+                        });
+
+                        out.push_str(&(index + 1).to_string());
+                    }
+                }
+
+                copied = end;
+                i = end;
+
+                continue;
+            }
+
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    out.push_str(&sql[copied..]);
+
+    (out, names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rewrite, ParameterStyle};
+
+    #[test]
+    fn it_numbers_each_name_once() {
+        let (sql, names) = rewrite(
+            "SELECT * FROM users WHERE id = :id OR parent_id = :id OR name = :name",
+            ParameterStyle::Dollar,
+        );
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM users WHERE id = $1 OR parent_id = $1 OR name = $2"
+        );
+        assert_eq!(names, vec!["id", "name"]);
+
+        let (sql, names) = rewrite("SELECT :a, :b, :a", ParameterStyle::NumberedQuestion);
+
+        assert_eq!(sql, "SELECT ?1, ?2, ?1");
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn it_binds_again_for_each_use() {
+        let (sql, names) = rewrite("SELECT :a, :b, :a", ParameterStyle::Question);
+
+        assert_eq!(sql, "SELECT ?, ?, ?");
+        assert_eq!(names, vec!["a", "b", "a"]);
+    }
+
+    #[test]
+    fn it_skips_casts_strings_and_comments() {
+        let (sql, names) = rewrite(
+            "SELECT :value::int8, ':no', \":no\", $$ :no $$, $tag$ :no $tag$, E'\\' :no', \
+             now() - interval '1 day' -- :no\n/* :no */, :yes",
+            ParameterStyle::Dollar,
+        );
+
+        assert_eq!(
+            sql,
+            "SELECT $1::int8, ':no', \":no\", $$ :no $$, $tag$ :no $tag$, E'\\' :no', \
+             now() - interval '1 day' -- :no\n/* :no */, $2"
+        );
+        assert_eq!(names, vec!["value", "yes"]);
+
+        // only an `E` string escapes with a backslash on Postgres
+        let (_, names) = rewrite("SELECT 'C:\\', :a", ParameterStyle::Dollar);
+
+        assert_eq!(names, vec!["a"]);
+
+        let (sql, names) = rewrite(
+            "SELECT 'it\\'s :no', `:no`, @x := :a # :no\n, :b",
+            ParameterStyle::Question,
+        );
+
+        assert_eq!(sql, "SELECT 'it\\'s :no', `:no`, @x := ? # :no\n, ?");
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn it_leaves_a_query_without_names_alone() {
+        let (sql, names) = rewrite("SELECT 1 WHERE 'a:b' <> ':c'", ParameterStyle::Question);
+
+        assert_eq!(sql, "SELECT 1 WHERE 'a:b' <> ':c'");
+        assert!(names.is_empty());
+    }
+}
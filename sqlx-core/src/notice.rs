@@ -0,0 +1,70 @@
+//! Notices and warnings the database server sends along with the results of a query.
+
+use std::fmt::{self, Display};
+
+/// How serious a [`Notice`] is, from least to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NoticeSeverity {
+    Debug,
+    Log,
+    Info,
+
+    /// A Postgres `NOTICE` or a MySQL `Note`.
+    Notice,
+
+    Warning,
+
+    /// A MySQL `Error` listed by `SHOW WARNINGS`; the query itself failed with it.
+    Error,
+}
+
+/// A message from the database server that does not make the query fail, such as one raised
+/// with `RAISE WARNING` in Postgres or a MySQL warning about a truncated value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    pub(crate) severity: NoticeSeverity,
+    pub(crate) code: Box<str>,
+    pub(crate) message: Box<str>,
+}
+
+impl Notice {
+    pub fn severity(&self) -> NoticeSeverity {
+        self.severity
+    }
+
+    /// The SQLSTATE code of the notice for Postgres (such as `01000` for a `RAISE WARNING`)
+    /// or its error number for MySQL (such as `1265` for a truncated value).
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Notice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}: {}", self.severity, self.code, self.message)
+    }
+}
+
+/// A connection that passes the notices of the server to a handler.
+///
+/// Postgres sends its notices on its own, down to the level set by `client_min_messages`
+/// (`notice` by default), which can be given in the connection URL. MySQL only reports a count
+/// of warnings after each statement; while a handler is set, they are read with
+/// `SHOW WARNINGS` once the statement is done.
+pub trait HandleNotices {
+    /// Passes each notice of the server to `handler`, replacing any handler set before.
+    fn set_notice_handler<F>(&mut self, handler: F)
+    where
+        F: FnMut(Notice) + Send + Sync + 'static;
+
+    /// Stops passing the notices of the server to a handler; they are dropped again.
+    fn remove_notice_handler(&mut self);
+}
+
+// A handler of notices, as kept by a connection
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) type NoticeHandler = Box<dyn FnMut(Notice) + Send + Sync>;
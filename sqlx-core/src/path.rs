@@ -0,0 +1,38 @@
+//! Paths as text, for the drivers that store them in text columns.
+//!
+//! A path is encoded as its text and decoded from any text. On Unix, a path is not guaranteed
+//! to be valid UTF-8 (on Windows it is, but for unpaired surrogates), and one that is not can't
+//! be stored in a text column without losing its bytes; encoding it fails instead.
+
+use std::path::Path;
+
+/// The text of `path`, or an [`Error::Encode`](crate::Error::Encode) if it is not valid UTF-8.
+pub(crate) fn path_to_str(path: &Path) -> crate::Result<&str> {
+    path.to_str().ok_or_else(|| {
+        crate::Error::Encode(format!("path is not valid UTF-8: {}", path.display()).into())
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    use matches::assert_matches;
+
+    use super::path_to_str;
+
+    #[test]
+    fn it_refuses_a_path_that_is_not_utf8() {
+        assert_eq!(
+            path_to_str(Path::new("/var/lib/café")).unwrap(),
+            "/var/lib/café"
+        );
+
+        let path = Path::new(OsStr::from_bytes(b"/var/lib/caf\xe9"));
+        let err = path_to_str(path).unwrap_err();
+
+        assert_matches!(err, crate::Error::Encode(_));
+    }
+}
@@ -0,0 +1,99 @@
+//! Adapters to manage connections with an external pool, [`bb8`] 0.5 or [`deadpool`] 0.5,
+//! instead of [`Pool`](super::Pool).
+//!
+//! Both pools are built on Tokio 0.2: `bb8` runs its background tasks on a Tokio runtime, while
+//! `deadpool` only needs one for the timeouts it is configured with.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use bb8::PooledConnection;
+use deadpool::managed::{RecycleError, RecycleResult};
+
+use crate::connection::{Connect, TransactionStatus};
+
+/// Opens connections of type `C` to a database URL for [`bb8`] and [`deadpool`].
+///
+/// ```rust,ignore
+/// let manager = ConnectionManager::<PgConnection>::new("postgres://localhost/db");
+///
+/// let pool = bb8::Pool::builder().build(manager).await?;
+/// let mut conn = pool.get().await?;
+///
+/// sqlx::query("SELECT 1").execute(&mut *conn).await?;
+/// ```
+pub struct ConnectionManager<C> {
+    url: String,
+    connection: PhantomData<fn() -> C>,
+}
+
+impl<C> ConnectionManager<C>
+where
+    C: Connect,
+{
+    /// Creates a manager for connections to `url`, in the syntax of the connection type.
+    ///
+    /// The URL is only parsed when a connection is opened.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            connection: PhantomData,
+        }
+    }
+}
+
+impl<C> fmt::Debug for ConnectionManager<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionManager")
+            .field("url", &self.url)
+            .finish()
+    }
+}
+
+// bb8 checks this when a connection is returned, without a way to clean it up first; a
+// connection left in a transaction is discarded rather than handed out in it
+#[async_trait]
+impl<C> bb8::ManageConnection for ConnectionManager<C>
+where
+    C: Connect,
+{
+    type Connection = C;
+    type Error = crate::Error;
+
+    async fn connect(&self) -> crate::Result<C> {
+        C::connect(&self.url).await
+    }
+
+    async fn is_valid(&self, conn: &mut PooledConnection<'_, Self>) -> crate::Result<()> {
+        conn.ping().await
+    }
+
+    fn has_broken(&self, conn: &mut C) -> bool {
+        conn.is_broken() || conn.transaction_status() != TransactionStatus::Idle
+    }
+}
+
+// deadpool recycles a connection before handing it out again, so an open transaction is rolled
+// back there like `PoolConnection` does when it is dropped
+#[async_trait]
+impl<C> deadpool::managed::Manager<C, crate::Error> for ConnectionManager<C>
+where
+    C: Connect,
+{
+    async fn create(&self) -> crate::Result<C> {
+        C::connect(&self.url).await
+    }
+
+    async fn recycle(&self, conn: &mut C) -> RecycleResult<crate::Error> {
+        if conn.is_broken() {
+            return Err(RecycleError::Message("connection is broken".into()));
+        }
+
+        if conn.transaction_status() != TransactionStatus::Idle {
+            conn.execute("ROLLBACK").await?;
+        }
+
+        Ok(conn.ping().await?)
+    }
+}
@@ -5,17 +5,34 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use super::inner::{DecrementSizeGuard, SharedPool};
-use crate::connection::{Connect, Connection};
+use super::PoolConnectionMetadata;
+use crate::connection::{Connect, Connection, StatementCacheStats, TransactionStatus};
+use crate::database::Database;
+use crate::describe::TableColumns;
+use crate::runtime::spawn;
 
 /// A connection checked out from [`Pool`][crate::Pool].
 ///
 /// Will be returned to the pool on-drop.
+///
+/// Dereferences to the underlying connection so its own methods can be called directly, and
+/// implements [`Connection`] so it can be passed to code that is generic over one:
+///
+/// * [`ping`][Connection::ping] checks the underlying connection and keeps it checked-out.
+/// * [`close`][Connection::close] detaches the connection from the pool and closes it. The pool
+///   opens a new connection in its place when one is needed, so prefer dropping the
+///   `PoolConnection` unless the connection should not be reused, e.g. because it was left
+///   in an unknown state.
 pub struct PoolConnection<C>
 where
     C: Connect,
 {
     live: Option<Live<C>>,
     pub(crate) pool: Arc<SharedPool<C>>,
+
+    // A statement that undoes a change made to the session for this checkout only; it is run
+    // before the connection is returned to the pool
+    pub(crate) reset: Option<&'static str>,
 }
 
 pub(super) struct Live<C> {
@@ -36,6 +53,32 @@ pub(super) struct Floating<'p, C> {
 
 const DEREF_ERR: &str = "(bug) connection already released to pool";
 
+impl<C> PoolConnection<C>
+where
+    C: Connect,
+{
+    /// Takes the connection out of the pool for good.
+    ///
+    /// The connection no longer counts against the size of the pool, which may open a new
+    /// connection in its place, and is not returned to it on-drop. Closing the detached
+    /// connection is up to the caller. This is useful to dedicate a connection to a
+    /// long-running task, such as listening for notifications, without monopolizing the pool.
+    ///
+    /// Session settings made for this checkout only, such as the search path of
+    /// `PgPool::acquire_with_search_path`, are reset first; the connection is closed if that
+    /// fails.
+    pub async fn detach(mut self) -> crate::Result<C> {
+        let live = self.live.take().expect("PoolConnection double-dropped");
+        let mut floating = live.float(&self.pool);
+
+        if let Some(reset) = self.reset.take() {
+            floating.inner.raw.execute(reset).await?;
+        }
+
+        Ok(floating.detach())
+    }
+}
+
 impl<C> Borrow<C> for PoolConnection<C>
 where
     C: Connect,
@@ -90,22 +133,103 @@ where
     fn ping(&mut self) -> BoxFuture<crate::Result<()>> {
         Box::pin(self.deref_mut().ping())
     }
+
+    #[inline]
+    fn is_broken(&self) -> bool {
+        self.deref().is_broken()
+    }
+
+    #[inline]
+    fn is_ready(&self) -> bool {
+        self.deref().is_ready()
+    }
+
+    #[inline]
+    fn take_arguments(&mut self) -> <C::Database as Database>::Arguments {
+        self.deref_mut().take_arguments()
+    }
+
+    #[inline]
+    fn transaction_status(&self) -> TransactionStatus {
+        self.deref().transaction_status()
+    }
+
+    #[inline]
+    fn table_id<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<Option<<C::Database as Database>::TableId>>> {
+        self.deref_mut().table_id(name)
+    }
+
+    #[inline]
+    fn describe_table<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<TableColumns<C::Database>>> {
+        self.deref_mut().describe_table(name)
+    }
+
+    #[inline]
+    fn take_transaction_error(&mut self) -> Option<crate::Error> {
+        self.deref_mut().take_transaction_error()
+    }
+
+    #[inline]
+    fn statement_cache_stats(&self) -> StatementCacheStats {
+        self.deref().statement_cache_stats()
+    }
 }
 
 /// Returns the connection to the [`Pool`][crate::Pool] it was checked-out from.
+///
+/// A connection left in an unknown state by a dropped query future is closed instead. A
+/// transaction left open on the connection is rolled back first, in the background, as are
+/// session settings made for this checkout only.
 impl<C> Drop for PoolConnection<C>
 where
     C: Connect,
 {
     fn drop(&mut self) {
-        if let Some(live) = self.live.take() {
-            self.pool.release(live.float(&self.pool));
+        if let Some(mut live) = self.live.take() {
+            if live.raw.is_broken() {
+                // dropping the guard decrements the size of the pool
+                drop(live.float(&self.pool));
+            } else if live.raw.transaction_status() != TransactionStatus::Idle
+                || self.reset.is_some()
+            {
+                let pool = Arc::clone(&self.pool);
+                let reset = self.reset.take();
+
+                spawn(async move {
+                    let mut res = Ok(0);
+
+                    if live.raw.transaction_status() != TransactionStatus::Idle {
+                        res = live.raw.execute("ROLLBACK").await;
+                    }
+
+                    if let Some(reset) = reset.filter(|_| res.is_ok()) {
+                        res = live.raw.execute(reset).await;
+                    }
+
+                    if res.is_ok() && live.raw.transaction_status() == TransactionStatus::Idle {
+                        pool.release(live.float(&pool));
+                    } else {
+                        let _ = live.float(&pool).into_idle().close().await;
+                    }
+                });
+            } else {
+                self.pool.release(live.float(&self.pool));
+            }
         }
     }
 }
 
 impl<C> Live<C> {
-    pub fn float(self, pool: &SharedPool<C>) -> Floating<Self> {
+    pub fn float(self, pool: &SharedPool<C>) -> Floating<Self>
+    where
+        C: Connection,
+    {
         Floating {
             inner: self,
             guard: DecrementSizeGuard::new(pool),
@@ -167,6 +291,7 @@ impl<'s, C> Floating<'s, Live<C>> {
         PoolConnection {
             live: Some(inner),
             pool: Arc::clone(pool),
+            reset: None,
         }
     }
 
@@ -176,10 +301,18 @@ impl<'s, C> Floating<'s, Live<C>> {
             guard: self.guard,
         }
     }
+
+    pub fn detach(self) -> C {
+        // `guard` is dropped as intended
+        self.inner.raw
+    }
 }
 
 impl<'s, C> Floating<'s, Idle<C>> {
-    pub fn from_idle(idle: Idle<C>, pool: &'s SharedPool<C>) -> Self {
+    pub fn from_idle(idle: Idle<C>, pool: &'s SharedPool<C>) -> Self
+    where
+        C: Connection,
+    {
         Self {
             inner: idle,
             guard: DecrementSizeGuard::new(pool),
@@ -193,6 +326,17 @@ impl<'s, C> Floating<'s, Idle<C>> {
         self.live.raw.ping().await
     }
 
+    pub fn raw_mut(&mut self) -> &mut C {
+        &mut self.inner.live.raw
+    }
+
+    pub fn metadata(&self) -> PoolConnectionMetadata {
+        PoolConnectionMetadata {
+            age: self.live.created.elapsed(),
+            idle_for: self.since.elapsed(),
+        }
+    }
+
     pub fn into_live(self) -> Floating<'s, Live<C>> {
         Floating {
             inner: self.inner.live,
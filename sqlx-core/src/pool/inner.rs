@@ -1,11 +1,12 @@
 use std::cmp;
+use std::collections::VecDeque;
 use std::mem;
 use std::ptr;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use crossbeam_queue::{ArrayQueue, SegQueue};
+use crossbeam_queue::ArrayQueue;
 use futures_core::task::{Poll, Waker};
 use futures_util::future;
 
@@ -19,20 +20,25 @@ use crate::{
 use super::connection::{Floating, Idle, Live};
 use super::Options;
 
+// How long [SharedPool::warm_up] waits before trying to connect again
+const WARM_UP_RETRY_DELAY: Duration = Duration::from_millis(100);
+
 pub(crate) struct SharedPool<C> {
     url: String,
     idle_conns: ArrayQueue<Idle<C>>,
-    waiters: SegQueue<Waker>,
+
+    // The tasks waiting in [acquire], oldest first
+    waiters: Mutex<VecDeque<Arc<Waiter<C>>>>,
     pub(super) size: AtomicU32,
     is_closed: AtomicBool,
-    options: Options,
+    options: Options<C>,
 }
 
 impl<C> SharedPool<C>
 where
     C: Connection,
 {
-    pub fn options(&self) -> &Options {
+    pub fn options(&self) -> &Options<C> {
         &self.options
     }
 
@@ -56,8 +62,9 @@ where
     pub(super) async fn close(&self) {
         self.is_closed.store(true, Ordering::Release);
         while let Ok(_) = self.idle_conns.pop() {}
-        while let Ok(waker) = self.waiters.pop() {
-            waker.wake();
+
+        for waiter in self.waiters.lock().unwrap().drain(..) {
+            waiter.wake(None);
         }
     }
 
@@ -75,14 +82,29 @@ where
     }
 
     pub(super) fn release(&self, floating: Floating<Live<C>>) {
-        self.idle_conns
-            .push(floating.into_idle().into_leakable())
-            .expect("BUG: connection queue overflow in release()");
-        if let Ok(waker) = self.waiters.pop() {
-            waker.wake();
+        self.release_idle(floating.into_idle().into_leakable());
+    }
+
+    // Hands the connection to the task that has waited the longest for one, so it can't be
+    // taken by a task that started waiting later; it is only put in the idle queue when no
+    // task is waiting
+    fn release_idle(&self, idle: Idle<C>) {
+        let mut waiters = self.waiters.lock().unwrap();
+
+        match waiters.pop_front() {
+            Some(waiter) => waiter.wake(Some(idle)),
+
+            None => self
+                .idle_conns
+                .push(idle)
+                .expect("BUG: connection queue overflow in release()"),
         }
     }
 
+    fn has_waiters(&self) -> bool {
+        !self.waiters.lock().unwrap().is_empty()
+    }
+
     /// Try to atomically increment the pool size for a new connection.
     ///
     /// Returns `None` if we are at max_size.
@@ -105,42 +127,179 @@ where
     /// Wait for a connection, if either `size` drops below `max_size` so we can
     /// open a new connection, or if an idle connection is returned to the pool.
     ///
-    /// Returns an error if `deadline` elapses before we are woken.
-    async fn wait_for_conn(&self, deadline: Instant) -> crate::Result<()> {
-        let mut waker_pushed = false;
+    /// Waiters are woken in the order they started waiting; one that was woken before but
+    /// lost the connection it was woken for waits at the front of the queue. Returns the
+    /// connection that was handed to us, if any, or an error if `deadline` elapses before we
+    /// are woken.
+    async fn wait_for_conn(
+        &self,
+        deadline: Instant,
+        front: bool,
+    ) -> crate::Result<Option<Idle<C>>> {
+        let mut waiting = Waiting {
+            pool: self,
+            waiter: Arc::new(Waiter::default()),
+            done: false,
+        };
+
+        {
+            let mut waiters = self.waiters.lock().unwrap();
+
+            // with no one to wake us, make sure a connection was not released, or closed,
+            // since the caller looked
+            if waiters.is_empty()
+                && (!self.idle_conns.is_empty() || self.size() < self.options.max_size)
+            {
+                waiting.done = true;
+                return Ok(None);
+            }
+
+            if front {
+                waiters.push_front(Arc::clone(&waiting.waiter));
+            } else {
+                waiters.push_back(Arc::clone(&waiting.waiter));
+            }
+        }
 
         timeout(
             deadline_as_timeout(deadline)?,
-            // `poll_fn` gets us easy access to a `Waker` that we can push to our queue
-            future::poll_fn(|ctx| -> Poll<()> {
-                if !waker_pushed {
-                    // only push the waker once
-                    self.waiters.push(ctx.waker().to_owned());
-                    waker_pushed = true;
-                    Poll::Pending
+            // `poll_fn` gets us easy access to a `Waker` to leave with our waiter
+            future::poll_fn(|ctx| {
+                let mut state = waiting.waiter.state.lock().unwrap();
+
+                if state.woken {
+                    waiting.done = true;
+                    Poll::Ready(state.conn.take())
                 } else {
-                    Poll::Ready(())
+                    state.waker = Some(ctx.waker().to_owned());
+                    Poll::Pending
                 }
             }),
         )
         .await
         .map_err(|_| crate::Error::PoolTimedOut(None))
     }
+
+    // Wakes the task that has waited the longest, to open a connection in place of one that
+    // was closed
+    fn wake_oldest(&self) {
+        if let Some(waiter) = self.waiters.lock().unwrap().pop_front() {
+            waiter.wake(None);
+        }
+    }
+}
+
+// A task waiting for a connection in [SharedPool::acquire]
+struct Waiter<C> {
+    state: Mutex<WaiterState<C>>,
+}
+
+struct WaiterState<C> {
+    waker: Option<Waker>,
+
+    // Set when the waiter is taken off the queue, along with the connection handed to it if
+    // it was not woken to open one
+    woken: bool,
+    conn: Option<Idle<C>>,
+}
+
+impl<C> Default for Waiter<C> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(WaiterState {
+                waker: None,
+                woken: false,
+                conn: None,
+            }),
+        }
+    }
+}
+
+impl<C> Waiter<C> {
+    fn wake(&self, conn: Option<Idle<C>>) {
+        let mut state = self.state.lock().unwrap();
+
+        state.woken = true;
+        state.conn = conn;
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+// Takes a waiter off the queue if it stops waiting before it is woken, e.g. because it timed
+// out or its `acquire` future was dropped. What it was woken for in the meantime is passed on
+// to the next waiter, so a connection handed to it is not lost.
+struct Waiting<'p, C>
+where
+    C: Connection,
+{
+    pool: &'p SharedPool<C>,
+    waiter: Arc<Waiter<C>>,
+    done: bool,
+}
+
+impl<C> Drop for Waiting<'_, C>
+where
+    C: Connection,
+{
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+
+        // a waiter is only woken while the queue is locked, so once it is no longer in the
+        // queue, whatever it was woken for is in its state
+        self.pool
+            .waiters
+            .lock()
+            .unwrap()
+            .retain(|waiter| !Arc::ptr_eq(waiter, &self.waiter));
+
+        let mut state = self.waiter.state.lock().unwrap();
+
+        if let Some(conn) = state.conn.take() {
+            drop(state);
+            self.pool.release_idle(conn);
+        } else if state.woken {
+            drop(state);
+            self.pool.wake_oldest();
+        }
+    }
+}
+
+// Lets a [DecrementSizeGuard], which does not know the type of connection, wake a waiter
+pub(in crate::pool) trait WakeOldest: Send + Sync {
+    fn wake_oldest(&self);
+}
+
+impl<C> WakeOldest for SharedPool<C>
+where
+    C: Connection,
+{
+    fn wake_oldest(&self) {
+        SharedPool::wake_oldest(self)
+    }
 }
 
 impl<C> SharedPool<C>
 where
     C: Connect,
 {
-    pub(super) async fn new_arc(url: &str, options: Options) -> crate::Result<Arc<Self>> {
-        let mut pool = Self {
+    fn new(url: &str, options: Options<C>) -> Self {
+        Self {
             url: url.to_owned(),
             idle_conns: ArrayQueue::new(options.max_size as usize),
-            waiters: SegQueue::new(),
+            waiters: Mutex::new(VecDeque::new()),
             size: AtomicU32::new(0),
             is_closed: AtomicBool::new(false),
             options,
-        };
+        }
+    }
+
+    pub(super) async fn new_arc(url: &str, options: Options<C>) -> crate::Result<Arc<Self>> {
+        let mut pool = Self::new(url, options);
 
         pool.init_min_connections().await?;
 
@@ -151,34 +310,56 @@ where
         Ok(pool)
     }
 
+    // does not open any connection; the first ones are opened by [acquire]
+    pub(super) fn new_lazy_arc(url: &str, options: Options<C>) -> Arc<Self> {
+        let pool = Arc::new(Self::new(url, options));
+
+        spawn_reaper(&pool);
+
+        pool
+    }
+
     pub(super) async fn acquire<'s>(&'s self) -> crate::Result<Floating<'s, Live<C>>> {
         let start = Instant::now();
         let deadline = start + self.options.connect_timeout;
 
+        // Whether we have been woken from the queue of waiters; until then, we may only go
+        // ahead of the tasks waiting in it if there are none
+        let mut woken = false;
+
         // Unless the pool has been closed ...
         while !self.is_closed() {
-            // Attempt to immediately acquire a connection. This will return Some
-            // if there is an idle connection in our channel.
-            if let Ok(conn) = self.idle_conns.pop() {
-                let conn = Floating::from_idle(conn, self);
-                if let Some(live) = check_conn(conn, &self.options).await {
-                    return Ok(live);
+            if woken || !self.has_waiters() {
+                // Attempt to immediately acquire a connection. This will return Some
+                // if there is an idle connection in our channel.
+                if let Ok(conn) = self.idle_conns.pop() {
+                    let conn = Floating::from_idle(conn, self);
+                    if let Some(live) = check_conn(conn, &self.options).await? {
+                        return Ok(live);
+                    }
                 }
-            }
 
-            if let Some(guard) = self.try_increment_size() {
-                // pool has slots available; open a new connection
-                match self.connect(deadline, guard).await {
-                    Ok(Some(conn)) => return Ok(conn),
-                    // [size] is internally decremented on _retry_ and _error_
-                    Ok(None) => continue,
-                    Err(e) => return Err(e),
+                if let Some(guard) = self.try_increment_size() {
+                    // pool has slots available; open a new connection
+                    match self.connect(deadline, guard).await {
+                        Ok(Some(conn)) => return Ok(conn),
+                        // [size] is internally decremented on _retry_ and _error_
+                        Ok(None) => continue,
+                        Err(e) => return Err(e),
+                    }
                 }
             }
 
-            // Wait for a connection to become available (or we are allowed to open a new one)
+            // Wait for a connection to be handed to us (or we are allowed to open a new one)
             // Returns an error if `deadline` passes
-            self.wait_for_conn(deadline).await?;
+            if let Some(conn) = self.wait_for_conn(deadline, woken).await? {
+                let conn = Floating::from_idle(conn, self);
+                if let Some(live) = check_conn(conn, &self.options).await? {
+                    return Ok(live);
+                }
+            }
+
+            woken = true;
         }
 
         Err(Error::PoolClosed)
@@ -204,11 +385,75 @@ where
         Ok(())
     }
 
+    // Pings the idle connections, closing those that fail, then opens connections until the
+    // pool has at least `min_size` of them, or one
+    pub(super) async fn ready(&self, deadline: Instant) -> crate::Result<()> {
+        for _ in 0..self.num_idle() {
+            let mut conn = match self.pop_idle() {
+                Some(conn) => conn,
+                None => break,
+            };
+
+            match timeout(deadline_as_timeout(deadline)?, conn.ping()).await {
+                Ok(Ok(())) => self.release(conn.into_live()),
+
+                // dropping the connection decrements the size of the pool; it is replaced below
+                _ => drop(conn),
+            }
+        }
+
+        self.warm_up(cmp::max(self.options.min_size, 1), deadline)
+            .await
+    }
+
+    // Opens connections until the pool has at least `min` of them, or is full. Failed attempts
+    // are retried until `deadline`, after which the error of the last one is returned rather
+    // than `PoolTimedOut`, as it says why the database can't be reached.
+    pub(super) async fn warm_up(&self, min: u32, deadline: Instant) -> crate::Result<()> {
+        let mut last_error = None;
+
+        while self.size() < min {
+            let guard = match self.try_increment_size() {
+                Some(guard) => guard,
+                None => break,
+            };
+
+            match self.try_connect(deadline, guard).await {
+                Ok(Ok(conn)) => self.release(conn),
+
+                Ok(Err(error)) => {
+                    last_error = Some(error);
+
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    sleep(cmp::min(WARM_UP_RETRY_DELAY, remaining)).await;
+                }
+
+                Err(Error::PoolTimedOut(error)) => {
+                    return Err(last_error.unwrap_or(Error::PoolTimedOut(error)));
+                }
+
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
     async fn connect<'s>(
         &'s self,
         deadline: Instant,
         guard: DecrementSizeGuard<'s>,
     ) -> crate::Result<Option<Floating<'s, Live<C>>>> {
+        self.try_connect(deadline, guard).await.map(Result::ok)
+    }
+
+    // Returns `Ok(Err(_))` with the error of an attempt that may be retried, such as an IO
+    // error while the database is starting up
+    async fn try_connect<'s>(
+        &'s self,
+        deadline: Instant,
+        guard: DecrementSizeGuard<'s>,
+    ) -> crate::Result<crate::Result<Floating<'s, Live<C>>>> {
         if self.is_closed() {
             return Err(Error::PoolClosed);
         }
@@ -218,16 +463,18 @@ where
         // result here is `Result<Result<C, Error>, TimeoutError>`
         match crate::runtime::timeout(timeout, C::connect(&self.url)).await {
             // successfully established connection
-            Ok(Ok(raw)) => Ok(Some(Floating::new_live(raw, guard))),
+            Ok(Ok(raw)) => Ok(Ok(Floating::new_live(raw, guard))),
 
             // an IO error while connecting is assumed to be the system starting up
-            Ok(Err(crate::Error::Io(_))) => Ok(None),
+            Ok(Err(error @ crate::Error::Io(_))) => Ok(Err(error)),
 
             // TODO: Handle other database "boot period"s
 
             // [postgres] the database system is starting up
             // TODO: Make this check actually check if this is postgres
-            Ok(Err(crate::Error::Database(error))) if error.code() == Some("57P03") => Ok(None),
+            Ok(Err(crate::Error::Database(error))) if error.code() == Some("57P03") => {
+                Ok(Err(crate::Error::Database(error)))
+            }
 
             // Any other error while connection should immediately
             // terminate and bubble the error up
@@ -241,14 +488,14 @@ where
 
 // NOTE: Function names here are bizzare. Helpful help would be appreciated.
 
-fn is_beyond_lifetime<C>(live: &Live<C>, options: &Options) -> bool {
+fn is_beyond_lifetime<C>(live: &Live<C>, options: &Options<C>) -> bool {
     // check if connection was within max lifetime (or not set)
     options
         .max_lifetime
         .map_or(false, |max| live.created.elapsed() > max)
 }
 
-fn is_beyond_idle<C>(idle: &Idle<C>, options: &Options) -> bool {
+fn is_beyond_idle<C>(idle: &Idle<C>, options: &Options<C>) -> bool {
     // if connection wasn't idle too long (or not set)
     options
         .idle_timeout
@@ -257,8 +504,8 @@ fn is_beyond_idle<C>(idle: &Idle<C>, options: &Options) -> bool {
 
 async fn check_conn<'s: 'p, 'p, C>(
     mut conn: Floating<'s, Idle<C>>,
-    options: &'p Options,
-) -> Option<Floating<'s, Live<C>>>
+    options: &'p Options<C>,
+) -> crate::Result<Option<Floating<'s, Live<C>>>>
 where
     C: Connection,
 {
@@ -268,7 +515,7 @@ where
         // we're closing the connection either way
         // close the connection but don't really care about the result
         let _ = conn.close().await;
-        return None;
+        return Ok(None);
     } else if options.test_on_acquire {
         // TODO: Check on acquire should be a configuration setting
         // Check that the connection is still live
@@ -278,12 +525,30 @@ where
             // the error itself here isn't necessarily unexpected so WARN is too strong
             log::info!("ping on idle connection returned error: {}", e);
             // connection is broken so don't try to close nicely
-            return None;
+            return Ok(None);
+        }
+    }
+
+    if let Some(before_acquire) = &options.before_acquire {
+        let meta = conn.metadata();
+
+        match before_acquire(conn.raw_mut(), meta).await {
+            Ok(true) => {}
+
+            Ok(false) => {
+                let _ = conn.close().await;
+                return Ok(None);
+            }
+
+            Err(e) => {
+                let _ = conn.close().await;
+                return Err(e);
+            }
         }
     }
 
     // No need to re-connect; connection is alive or we don't care
-    Some(conn.into_live())
+    Ok(Some(conn.into_live()))
 }
 
 /// if `max_lifetime` or `idle_timeout` is set, spawn a task that reaps senescent connections
@@ -316,9 +581,7 @@ where
 
             for conn in keep {
                 // return these connections to the pool first
-                pool.idle_conns
-                    .push(conn.into_leakable())
-                    .expect("BUG: connection queue overflow in spawn_reaper");
+                pool.release_idle(conn.into_leakable());
             }
 
             for conn in reap {
@@ -336,22 +599,25 @@ where
 /// (where the pool thinks it has more connections than it does).
 pub(in crate::pool) struct DecrementSizeGuard<'a> {
     size: &'a AtomicU32,
-    waiters: &'a SegQueue<Waker>,
+    waiters: &'a dyn WakeOldest,
     dropped: bool,
 }
 
 impl<'a> DecrementSizeGuard<'a> {
-    pub fn new<C>(pool: &'a SharedPool<C>) -> Self {
+    pub fn new<C>(pool: &'a SharedPool<C>) -> Self
+    where
+        C: Connection,
+    {
         Self {
             size: &pool.size,
-            waiters: &pool.waiters,
+            waiters: pool,
             dropped: false,
         }
     }
 
     /// Return `true` if the internal references point to the same fields in `SharedPool`.
     pub fn same_pool<C>(&self, pool: &'a SharedPool<C>) -> bool {
-        ptr::eq(self.size, &pool.size) && ptr::eq(self.waiters, &pool.waiters)
+        ptr::eq(self.size, &pool.size)
     }
 
     pub fn cancel(self) {
@@ -364,8 +630,6 @@ impl Drop for DecrementSizeGuard<'_> {
         assert!(!self.dropped, "double-dropped!");
         self.dropped = true;
         self.size.fetch_sub(1, Ordering::SeqCst);
-        if let Ok(waker) = self.waiters.pop() {
-            waker.wake();
-        }
+        self.waiters.wake_oldest();
     }
 }
@@ -7,7 +7,7 @@ use std::{
 };
 
 use crate::connection::Connect;
-use crate::transaction::Transaction;
+use crate::transaction::{TestTransaction, Transaction};
 
 use self::inner::SharedPool;
 use self::options::Options;
@@ -17,8 +17,12 @@ mod executor;
 mod inner;
 mod options;
 
+#[cfg(feature = "pool-adapter")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pool-adapter")))]
+pub mod adapter;
+
 pub use self::connection::PoolConnection;
-pub use self::options::Builder;
+pub use self::options::{Builder, PoolConnectionMetadata};
 
 /// A pool of database connections.
 pub struct Pool<C>(pub(crate) Arc<SharedPool<C>>);
@@ -38,12 +42,29 @@ where
         Self::builder().build(url).await
     }
 
-    async fn with_options(url: &str, options: Options) -> crate::Result<Self> {
+    /// Creates a connection pool with the default configuration, without opening any
+    /// connection.
+    ///
+    /// Connections are only opened when they are acquired, so this succeeds even if the
+    /// database can't be reached yet; the first [acquire][Pool::acquire] keeps trying to
+    /// connect until the connection timeout elapses. See [Builder::build_lazy].
+    ///
+    /// Must be called within the context of the async runtime, which runs the task that
+    /// closes expired connections.
+    pub fn connect_lazy(url: &str) -> Self {
+        Self::builder().build_lazy(url)
+    }
+
+    async fn with_options(url: &str, options: Options<C>) -> crate::Result<Self> {
         let inner = SharedPool::new_arc(url, options).await?;
 
         Ok(Pool(inner))
     }
 
+    fn with_options_lazy(url: &str, options: Options<C>) -> Self {
+        Pool(SharedPool::new_lazy_arc(url, options))
+    }
+
     /// Returns a [Builder] to configure a new connection pool.
     pub fn builder() -> Builder<C> {
         Builder::new()
@@ -63,11 +84,40 @@ where
         self.0.try_acquire().map(|conn| conn.attach(&self.0))
     }
 
+    /// Waits until the pool can reach the database, e.g. for a readiness probe.
+    ///
+    /// Pings the idle connections, closing those that fail, then opens connections until the
+    /// pool has at least [`min_size`][Pool::min_size] of them, or one if that is `0`. Failed
+    /// attempts to connect are retried for at most `timeout`, after which the error of the last
+    /// attempt is returned, e.g. the IO error of a refused connection, rather than
+    /// [`PoolTimedOut`][crate::Error::PoolTimedOut].
+    pub async fn ready(&self, timeout: Duration) -> crate::Result<()> {
+        self.0.ready(Instant::now() + timeout).await
+    }
+
+    /// Opens connections until the pool has at least `n` of them, or
+    /// [`max_size`][Pool::max_size], so they don't have to be opened when they are first
+    /// acquired.
+    ///
+    /// Failed attempts to connect are retried until the connection timeout elapses, after which
+    /// the error of the last attempt is returned as by [`ready`][Pool::ready].
+    pub async fn ensure(&self, n: u32) -> crate::Result<()> {
+        let deadline = Instant::now() + self.0.options().connect_timeout;
+
+        self.0.warm_up(n, deadline).await
+    }
+
     /// Retrieves a new connection and immediately begins a new transaction.
     pub async fn begin(&self) -> crate::Result<Transaction<PoolConnection<C>>> {
         Ok(Transaction::new(0, self.acquire().await?).await?)
     }
 
+    /// Retrieves a new connection and begins a transaction on it that is never committed; see
+    /// [`TestTransaction`].
+    pub async fn begin_test(&self) -> crate::Result<TestTransaction<PoolConnection<C>>> {
+        TestTransaction::new(self.acquire().await?).await
+    }
+
     /// Ends the use of a connection pool. Prevents any new connections
     /// and will close all active connections when they are returned to the pool.
     ///
@@ -1,4 +1,7 @@
-use std::{marker::PhantomData, time::Duration};
+use std::fmt;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
 
 use super::Pool;
 use crate::connection::Connect;
@@ -6,10 +9,27 @@ use crate::database::Database;
 
 /// Builder for [Pool].
 pub struct Builder<C> {
-    phantom: PhantomData<C>,
-    options: Options,
+    options: Options<C>,
+}
+
+/// What is known of a connection of the pool that is about to be acquired; see
+/// [Builder::before_acquire].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct PoolConnectionMetadata {
+    /// Time since the connection was opened.
+    pub age: Duration,
+
+    /// Time since the connection was returned to the pool.
+    pub idle_for: Duration,
 }
 
+type BeforeAcquire<C> = Box<
+    dyn for<'c> Fn(&'c mut C, PoolConnectionMetadata) -> BoxFuture<'c, crate::Result<bool>>
+        + Send
+        + Sync,
+>;
+
 impl<C> Builder<C>
 where
     C: Connect,
@@ -19,7 +39,6 @@ where
     /// See the source of this method for current defaults.
     pub(crate) fn new() -> Self {
         Self {
-            phantom: PhantomData,
             options: Options {
                 // pool a maximum of 10 connections to the same database
                 max_size: 10,
@@ -35,6 +54,7 @@ where
                 idle_timeout: None,
                 // If true, test the health of a connection on acquire
                 test_on_acquire: true,
+                before_acquire: None,
             },
         }
     }
@@ -100,6 +120,44 @@ where
         self
     }
 
+    /// Sets a callback that validates an idle connection before it is handed out by
+    /// [Pool::acquire], after the ping of [test_on_acquire][Builder::test_on_acquire].
+    ///
+    /// Returning `Ok(true)` hands the connection out. Returning `Ok(false)` closes it and makes
+    /// `acquire` try another one. Returning an error closes the connection and fails `acquire`
+    /// with that error.
+    ///
+    /// The metadata gives the age and idle time of the connection, e.g. to retire connections
+    /// on a policy of your own. The callback is not called for a connection that was just
+    /// opened.
+    ///
+    /// ```rust,ignore
+    /// let pool = PgPool::builder()
+    ///     .before_acquire(|conn, meta| {
+    ///         Box::pin(async move {
+    ///             if meta.idle_for > Duration::from_secs(60) {
+    ///                 // a connection that sat idle for a while may have been dropped by a
+    ///                 // firewall in between; make sure it still works
+    ///                 conn.execute("SELECT 1").await?;
+    ///             }
+    ///
+    ///             Ok(true)
+    ///         })
+    ///     })
+    ///     .build(&url)
+    ///     .await?;
+    /// ```
+    pub fn before_acquire<F>(mut self, callback: F) -> Self
+    where
+        F: for<'c> Fn(&'c mut C, PoolConnectionMetadata) -> BoxFuture<'c, crate::Result<bool>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.options.before_acquire = Some(Box::new(callback));
+        self
+    }
+
     /// Spin up the connection pool.
     ///
     /// If [min_size] was set to a non-zero value, that many connections will be immediately
@@ -110,6 +168,17 @@ where
     {
         Pool::with_options(url, self.options).await
     }
+
+    /// Spin up the connection pool without opening any connection.
+    ///
+    /// Unlike [build][Builder::build], this returns right away even if the database is down;
+    /// connections, including the [min_size] ones, are only opened as they are acquired.
+    pub fn build_lazy(self, url: &str) -> Pool<C>
+    where
+        C: Connect,
+    {
+        Pool::with_options_lazy(url, self.options)
+    }
 }
 
 impl<C, DB> Default for Builder<C>
@@ -122,12 +191,26 @@ where
     }
 }
 
-#[derive(Debug)]
-pub(crate) struct Options {
+pub(crate) struct Options<C> {
     pub max_size: u32,
     pub connect_timeout: Duration,
     pub min_size: u32,
     pub max_lifetime: Option<Duration>,
     pub idle_timeout: Option<Duration>,
     pub test_on_acquire: bool,
+    pub before_acquire: Option<BeforeAcquire<C>>,
+}
+
+impl<C> fmt::Debug for Options<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Options")
+            .field("max_size", &self.max_size)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("min_size", &self.min_size)
+            .field("max_lifetime", &self.max_lifetime)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("test_on_acquire", &self.test_on_acquire)
+            .field("before_acquire", &self.before_acquire.is_some())
+            .finish()
+    }
 }
@@ -3,6 +3,7 @@ use byteorder::{ByteOrder, NetworkEndian};
 use crate::arguments::Arguments;
 use crate::encode::{Encode, IsNull};
 use crate::io::BufMut;
+use crate::postgres::types::SharedStr;
 use crate::postgres::Postgres;
 use crate::types::Type;
 
@@ -13,6 +14,26 @@ pub struct PgArguments {
 
     // Write buffer for serializing bind values
     pub(super) values: Vec<u8>,
+
+    // Indexes and names of the bind parameters of a type with an OID that is not fixed (0),
+    // to be looked up in the types registered on the connection
+    pub(super) named_types: Vec<(usize, SharedStr)>,
+
+    // The first value that failed to encode; the query fails with it instead of being sent
+    pub(super) error: Option<crate::Error>,
+}
+
+impl PgArguments {
+    // A copy of the values, to bind them again; the arguments are known to have encoded
+    #[cfg(feature = "auto-explain")]
+    pub(super) fn duplicate(&self) -> Self {
+        Self {
+            types: self.types.clone(),
+            values: self.values.clone(),
+            named_types: self.named_types.clone(),
+            error: None,
+        }
+    }
 }
 
 impl Arguments for PgArguments {
@@ -23,6 +44,13 @@ impl Arguments for PgArguments {
         self.values.reserve(size);
     }
 
+    fn clear(&mut self) {
+        self.types.clear();
+        self.values.clear();
+        self.named_types.clear();
+        self.error = None;
+    }
+
     fn add<T>(&mut self, value: T)
     where
         T: Type<Self::Database>,
@@ -31,19 +59,32 @@ impl Arguments for PgArguments {
         // TODO: When/if we receive types that do _not_ support BINARY, we need to check here
         // TODO: There is no need to be explicit unless we are expecting mixed BINARY / TEXT
 
-        self.types.push(<T as Type<Postgres>>::type_info().id.0);
+        let type_info = <T as Type<Postgres>>::type_info();
+
+        if let (0, Some(name)) = (type_info.id.0, type_info.name) {
+            self.named_types.push((self.types.len(), name));
+        }
+
+        self.types.push(type_info.id.0);
 
         let pos = self.values.len();
 
         self.values.put_i32::<NetworkEndian>(0);
 
-        let len = if let IsNull::No = value.encode_nullable(&mut self.values) {
-            (self.values.len() - pos - 4) as i32
-        } else {
+        let len = match value.try_encode(&mut self.values) {
+            Ok(IsNull::No) => (self.values.len() - pos - 4) as i32,
+
             // Write a -1 for the len to indicate NULL
             // TODO: It is illegal for [encode] to write any data
             //       if IsSql::No; fail a debug assertion
-            -1
+            Ok(IsNull::Yes) => -1,
+
+            Err(error) => {
+                self.values.truncate(pos + 4);
+                self.error.get_or_insert(error);
+
+                -1
+            }
         };
 
         // Write-back the len to the beginning of this frame (not including the len of len)
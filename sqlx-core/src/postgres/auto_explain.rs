@@ -0,0 +1,238 @@
+//! Logging of the plans of slow queries; see "Slow Queries" on [`PgConnection`].
+
+use std::time::Instant;
+
+use crate::connection::{Connection, TransactionStatus};
+use crate::postgres::protocol::{DataRow, Message};
+use crate::postgres::{PgArguments, PgConnection};
+
+// A read-only query that is running, to be explained if it turns out to be slow
+pub(super) struct TimedQuery {
+    query: Box<str>,
+    arguments: Option<PgArguments>,
+    started: Instant,
+}
+
+impl PgConnection {
+    // Starts timing `query` if it is read-only and slow queries are explained; called before the
+    // query is sent
+    pub(super) fn time_query(&mut self, query: &str, arguments: Option<&PgArguments>) {
+        self.timed_query = match self.auto_explain {
+            Some(_) if is_read_only(query) => Some(TimedQuery {
+                query: query.into(),
+                arguments: arguments.map(PgArguments::duplicate),
+                started: Instant::now(),
+            }),
+
+            _ => None,
+        };
+    }
+
+    // Logs the plan of the query that just finished, if it took at least the threshold. This
+    // runs it again, so an error only prevents the plan from being logged.
+    pub(super) async fn explain_if_slow(&mut self) {
+        let (timed, threshold) = match (self.timed_query.take(), self.auto_explain) {
+            (Some(timed), Some(threshold)) => (timed, threshold),
+            _ => return,
+        };
+
+        let elapsed = timed.started.elapsed();
+
+        if elapsed < threshold {
+            return;
+        }
+
+        match self.explain_analyze(&timed.query, timed.arguments).await {
+            Ok(plan) => log::warn!(
+                target: "sqlx::auto_explain",
+                "slow query took {:?}: {}\n{}",
+                elapsed,
+                timed.query,
+                plan
+            ),
+
+            Err(error) => log::warn!(
+                target: "sqlx::auto_explain",
+                "slow query took {:?}: {}\ncould not explain it: {}",
+                elapsed,
+                timed.query,
+                error
+            ),
+        }
+    }
+
+    // Runs the query with `EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON)` in a transaction, or a
+    // savepoint of the current one, that is rolled back, and returns the plan; the savepoint
+    // is released afterwards so it doesn't pile up in the transaction of the caller
+    async fn explain_analyze(
+        &mut self,
+        query: &str,
+        arguments: Option<PgArguments>,
+    ) -> crate::Result<String> {
+        let (begin, rollback) = match self.transaction_status() {
+            TransactionStatus::Idle => ("BEGIN READ ONLY", "ROLLBACK"),
+
+            TransactionStatus::Transaction => (
+                "SAVEPOINT _sqlx_auto_explain",
+                "ROLLBACK TO SAVEPOINT _sqlx_auto_explain; RELEASE SAVEPOINT _sqlx_auto_explain",
+            ),
+
+            // the transaction already failed; nothing can run until it is rolled back
+            _ => return Err(crate::Error::Protocol("the transaction has failed".into())),
+        };
+
+        self.run(begin, None, false).await?;
+        self.affected_rows().await?;
+
+        let statement = format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {}", query);
+        let plan = self.fetch_plan(&statement, arguments).await;
+
+        self.run(rollback, None, false).await?;
+        self.affected_rows().await?;
+
+        plan
+    }
+
+    // Returns the lines of the plan, one per row
+    async fn fetch_plan(
+        &mut self,
+        statement: &str,
+        arguments: Option<PgArguments>,
+    ) -> crate::Result<String> {
+        self.run(statement, arguments, false).await?;
+
+        let mut plan = String::new();
+
+        loop {
+            match self.stream.receive().await? {
+                Message::DataRow => {
+                    let row = DataRow::read(self.stream.buffer(), &mut self.current_row_values)?;
+
+                    if !plan.is_empty() {
+                        plan.push('\n');
+                    }
+
+                    plan.push_str(&String::from_utf8_lossy(row.get(0).unwrap_or_default()));
+                }
+
+                Message::ReadyForQuery => {
+                    self.is_ready = true;
+                    break;
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(plan)
+    }
+}
+
+// Whether `sql` is a single statement that only reads, which can be run again to explain it.
+// This errs on the side of caution: a query that names a keyword of a statement that writes
+// anywhere, such as `SELECT ... FOR UPDATE` or a CTE with an `INSERT`, is not explained.
+pub(super) fn is_read_only(sql: &str) -> bool {
+    const READS: &[&str] = &["SELECT", "WITH", "VALUES", "TABLE"];
+
+    const WRITES: &[&str] = &[
+        "INSERT", "UPDATE", "DELETE", "MERGE", "TRUNCATE", "INTO", "COPY", "CALL", "DO", "LOCK",
+        "NOTIFY", "CREATE", "ALTER", "DROP", "GRANT", "REVOKE", "NEXTVAL", "SETVAL",
+    ];
+
+    let words = match words(sql) {
+        Some(words) => words,
+        None => return false,
+    };
+
+    match words.first() {
+        Some(first) if READS.iter().any(|read| first.eq_ignore_ascii_case(read)) => {}
+        _ => return false,
+    }
+
+    !words
+        .iter()
+        .any(|word| WRITES.iter().any(|write| word.eq_ignore_ascii_case(write)))
+}
+
+// The words of `sql` outside of strings, quoted identifiers and comments; `None` if it has more
+// than one statement or a dollar-quoted string, which is not looked into
+fn words(sql: &str) -> Option<Vec<&str>> {
+    let bytes = sql.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ b'\'' | quote @ b'"' => {
+                i += 1;
+
+                // a doubled quote is read as two strings next to each other
+                while i < bytes.len() && bytes[i] != quote {
+                    i += 1;
+                }
+
+                i += 1;
+            }
+
+            b'-' if sql[i..].starts_with("--") => {
+                i += sql[i..].find('\n').unwrap_or(bytes.len() - i);
+            }
+
+            b'/' if sql[i..].starts_with("/*") => {
+                i += sql[i..].find("*/").map_or(bytes.len() - i, |end| end + 2);
+            }
+
+            // `$1` is a bind parameter
+            b'$' if bytes.get(i + 1).map_or(false, u8::is_ascii_digit) => i += 1,
+
+            b'$' => return None,
+
+            // a trailing `;` is fine
+            b';' if !sql[i + 1..].trim().is_empty() => return None,
+
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                let start = i;
+
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+
+                words.push(&sql[start..i]);
+            }
+
+            _ => i += 1,
+        }
+    }
+
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_read_only;
+
+    #[test]
+    fn it_explains_only_queries_that_read() {
+        assert!(is_read_only("SELECT * FROM users WHERE id = $1"));
+        assert!(is_read_only("  -- comment\nselect 1;"));
+        assert!(is_read_only(
+            "WITH recent AS (SELECT * FROM orders) SELECT count(*) FROM recent"
+        ));
+        assert!(is_read_only("VALUES (1), (2)"));
+        assert!(is_read_only(
+            "SELECT 'DELETE FROM users', \"update\" FROM t /* INSERT */"
+        ));
+
+        assert!(!is_read_only("UPDATE users SET name = $1"));
+        assert!(!is_read_only(
+            "WITH gone AS (DELETE FROM users RETURNING id) SELECT count(*) FROM gone"
+        ));
+        assert!(!is_read_only("SELECT * INTO copy FROM users"));
+        assert!(!is_read_only("SELECT * FROM users FOR UPDATE"));
+        assert!(!is_read_only("SELECT nextval('users_id_seq')"));
+        assert!(!is_read_only("SELECT 1; DELETE FROM users"));
+        assert!(!is_read_only("SELECT $$it's$$"));
+        assert!(!is_read_only("EXPLAIN SELECT 1"));
+        assert!(!is_read_only(""));
+    }
+}
@@ -1,19 +1,34 @@
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::mem;
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "auto-explain")]
+use std::time::Duration;
 
 use futures_core::future::BoxFuture;
 use futures_util::TryFutureExt;
 
-use crate::connection::{Connect, Connection};
+use crate::connection::{Connect, Connection, StatementCacheStats, TransactionStatus};
+use crate::database::Database;
+use crate::describe::{self, TableColumns};
 use crate::executor::Executor;
+use crate::notice::{HandleNotices, Notice};
+use crate::pool::{Pool, PoolConnection};
 use crate::postgres::protocol::{
     Authentication, AuthenticationMd5, AuthenticationSasl, BackendKeyData, Message,
-    PasswordMessage, StartupMessage, StatementId, Terminate, TypeFormat,
+    NegotiateProtocolVersion, PasswordMessage, StartupMessage, StatementId, Terminate, TypeFormat,
+    TypeId,
 };
+use crate::postgres::sasl::ChannelBinding;
 use crate::postgres::stream::PgStream;
+use crate::postgres::types::SharedStr;
 use crate::postgres::{sasl, tls};
+use crate::postgres::{PgArguments, PgLargeObject, PgLargeObjectMode, PgRow, Postgres};
+use crate::query::query;
+use crate::quote;
+use crate::row::Row;
+use crate::types::Type;
 use crate::url::Url;
 
 /// An asynchronous connection to a [Postgres][super::Postgres] database.
@@ -76,19 +91,116 @@ use crate::url::Url;
 /// `sslmode=verify-ca`, the hostname in the connection string will be verified
 /// against the hostname in the server certificate, so they must be the same for the TLS
 /// upgrade to succeed.
+///
+/// ### Type Checking
+/// [`Row::try_get`] accepts a column of any type in the same class as the requested Rust type;
+/// for instance, an `INT8` column can be decoded into an `i32` and returns an error if the
+/// value does not fit, and `VARCHAR` or `BPCHAR` columns can be decoded into a `String`.
+///
+/// Add `strict_types=true` to your connection string to only accept the exact SQL type of the
+/// Rust type. `XML` is always read into a `String`, as Postgres sends it as text.
+///
+/// ### Buffer Sizes
+/// The connection starts with an 8 KiB buffer for incoming messages and a 1 KiB buffer for
+/// outgoing messages, which grow as needed to fit a whole message. Add `read_buffer_size=<bytes>`
+/// or `write_buffer_size=<bytes>` to your connection string to change their initial sizes; for
+/// instance, a larger read buffer avoids growing it repeatedly when most rows are large while
+/// smaller buffers save memory across a big pool of connections that only run small queries.
+///
+/// A message from the server longer than 64 MiB, such as a row with a very large value, fails
+/// with [`Error::Protocol`](crate::Error::Protocol) before any memory is set aside for it, and the
+/// connection can't be used afterwards. Add `max_message_size=<bytes>` to your connection string
+/// to read larger rows.
+///
+/// ### Schemas
+/// Add `search_path=<schema>[,<schema>...]` to your connection string to look up unqualified
+/// names in those schemas, in order. It is sent when connecting, so it costs no round trip.
+/// [`Pool::acquire_with_search_path`](crate::pool::Pool::acquire_with_search_path) overrides it
+/// for a single use of a pooled connection.
+///
+/// ### Notices
+/// Notices and warnings of the server, such as those raised with `RAISE NOTICE`, are dropped
+/// unless a handler is set with [`HandleNotices::set_notice_handler`]. Add
+/// `client_min_messages=<level>` to your connection string to choose the least serious ones the
+/// server sends, from `debug5` to `warning`; it defaults to `notice`.
+///
+/// ### Extension Types
+/// The object identifiers of types created by extensions, such as `hstore`, or by
+/// `CREATE TYPE` differ between databases. Rust types for them give their name instead, and
+/// [`register_type`](PgConnection::register_type) looks up the object identifier for that name
+/// so values of the type are bound without a cast and checked against the Rust type when they
+/// are decoded. Parameters of a type that is not registered are sent without a type and left
+/// for the server to infer, so add a cast (`$1::hstore`) where it can't.
+///
+/// ### PostGIS (requires `geo` feature)
+/// The `geometry` type from PostGIS is registered when connecting. Add `geometry_oid=<oid>` to
+/// your connection string to skip the lookup.
+///
+/// ### Slow Queries (requires `auto-explain` feature)
+/// Add `auto_explain=<milliseconds>` to your connection string to log the plan of each query that
+/// takes at least that long, as a `WARN` record with the `sqlx::auto_explain` target. When the
+/// query is done, it is run again with `EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON)` and the same
+/// bind parameters, in a `READ ONLY` transaction (or a savepoint of the current transaction)
+/// that is rolled back.
+///
+/// Running the query again doubles its cost, and it is only done for a single statement that
+/// reads: one that starts with `SELECT`, `WITH`, `VALUES` or `TABLE` and has no keyword of a
+/// statement that writes anywhere, such as `INSERT`, `FOR UPDATE` or `nextval`. A function it
+/// calls may still have side effects outside of the transaction. Queries that are not read to
+/// the end, as with `fetch_one`, are not explained.
+///
+/// ### Replication
+/// The streaming replication protocol is not supported, so connecting with `replication=<mode>`
+/// in the connection string returns an error rather than a connection that can't run queries.
+/// Changes can still be read from a logical replication slot with
+/// [`logical_peek`](PgConnection::logical_peek) and [`logical_get`](PgConnection::logical_get).
 pub struct PgConnection {
     pub(super) stream: PgStream,
     pub(super) next_statement_id: u32,
     pub(super) is_ready: bool,
 
+    // Only accept the exact SQL type of the Rust type when decoding values
+    pub(super) strict_types: bool,
+
+    // Names of the types with an object identifier that is not fixed, such as the PostGIS
+    // `geometry` type, by the object identifier they have in this database
+    pub(super) registered_types: Arc<HashMap<TypeId, SharedStr>>,
+
     pub(super) cache_statement: HashMap<Box<str>, StatementId>,
     pub(super) cache_statement_columns: HashMap<StatementId, Arc<HashMap<Box<str>, usize>>>,
     pub(super) cache_statement_formats: HashMap<StatementId, Arc<[TypeFormat]>>,
+    pub(super) cache_statement_types: HashMap<StatementId, Arc<[TypeId]>>,
+
+    // Hits and misses of [cache_statement]; its size is filled in when they are read
+    pub(super) cache_statement_stats: StatementCacheStats,
+
+    // The query of the last [Parse] that was sent; it is taken out of the statement cache again
+    // if the server did not confirm it
+    pub(super) unconfirmed_statement: Option<Box<str>>,
 
     // Work buffer for the value ranges of the current row
     // This is used as the backing memory for each Row's value indexes
     pub(super) current_row_values: Vec<Option<Range<u32>>>,
 
+    // The arguments of the last query, cleared, to be handed out again by `take_arguments`
+    pub(super) spare_arguments: PgArguments,
+
+    // Settings to restore before the next query, with the values they had before a call to
+    // `with_setting` that was dropped before it could restore them itself
+    pub(super) pending_settings: PendingSettings,
+
+    // Whether the server was built with `--disable-integer-datetimes`, which makes the binary
+    // format of date and time values a float8 number of seconds instead of an int8 number of
+    // microseconds; results are then requested in the text format
+    pub(super) float_datetimes: bool,
+
+    // Read-only queries that take at least this long are run again to log their plan
+    #[cfg(feature = "auto-explain")]
+    pub(super) auto_explain: Option<Duration>,
+
+    #[cfg(feature = "auto-explain")]
+    pub(super) timed_query: Option<super::auto_explain::TimedQuery>,
+
     // TODO: Find a use for these values. Perhaps in a debug impl of PgConnection?
     #[allow(dead_code)]
     process_id: u32,
@@ -97,15 +209,35 @@ pub struct PgConnection {
     secret_key: u32,
 }
 
+// The minor version of protocol 3 that we request, and the protocol options (`_pq_.<option>`)
+// that we ask for with it; a server that doesn't support them all replies with
+// [NegotiateProtocolVersion] and carries on without them
+const PROTOCOL_MINOR_VERSION: u16 = 0;
+const PROTOCOL_OPTIONS: &[(&str, &str)] = &[];
+
 // https://www.postgresql.org/docs/12/protocol-flow.html#id-1.10.5.7.3
 async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyData> {
     // Defaults to postgres@.../postgres
     let username = url.username().unwrap_or("postgres");
     let database = url.database().unwrap_or("postgres");
 
+    // a walsender only speaks the replication protocol, or runs simple queries
+    if url.param("replication").is_some() {
+        return Err(protocol_err!(
+            "the replication protocol is not supported; connect without `replication` and use \
+             the logical decoding functions instead"
+        )
+        .into());
+    }
+
+    let search_path = match url.param("search_path") {
+        Some(schemas) => Some(search_path(schemas.split(',').map(str::trim))?),
+        None => None,
+    };
+
     // See this doc for more runtime parameters
     // https://www.postgresql.org/docs/12/runtime-config-client.html
-    let params = &[
+    let mut params = vec![
         ("user", username),
         ("database", database),
         // Sets the display format for date and time values,
@@ -122,7 +254,23 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
         ("client_encoding", "UTF-8"),
     ];
 
-    stream.write(StartupMessage { params });
+    if let Some(search_path) = &search_path {
+        params.push(("search_path", search_path));
+    }
+
+    // The least serious notices the server sends, for a notice handler
+    let client_min_messages = url.param("client_min_messages");
+
+    if let Some(level) = &client_min_messages {
+        params.push(("client_min_messages", level));
+    }
+
+    params.extend_from_slice(PROTOCOL_OPTIONS);
+
+    stream.write(StartupMessage {
+        minor_version: PROTOCOL_MINOR_VERSION,
+        params: &params,
+    });
     stream.flush().await?;
 
     let mut key_data = BackendKeyData {
@@ -183,10 +331,21 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
                         }
                     }
 
+                    // the server only offers SCRAM-SHA-256-PLUS over TLS
+                    let binding = match stream.stream.tls_server_end_point() {
+                        Some(hash) if has_sasl_plus => ChannelBinding::TlsServerEndPoint(hash),
+                        _ if stream.stream.is_tls() && !has_sasl_plus => ChannelBinding::NotOffered,
+                        _ => ChannelBinding::Unsupported,
+                    };
+
                     if has_sasl || has_sasl_plus {
-                        // TODO: Handle -PLUS differently if we're in a TLS stream
-                        sasl::authenticate(stream, username, &url.password().unwrap_or_default())
-                            .await?;
+                        sasl::authenticate(
+                            stream,
+                            username,
+                            &url.password().unwrap_or_default(),
+                            binding,
+                        )
+                        .await?;
                     } else {
                         return Err(protocol_err!(
                             "unsupported SASL auth mechanisms: {:?}",
@@ -208,8 +367,17 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
                 key_data = BackendKeyData::read(stream.buffer())?;
             }
 
-            Message::ParameterStatus => {
-                // do nothing. we do not care about the server values here.
+            Message::NegotiateProtocolVersion => {
+                let negotiated = NegotiateProtocolVersion::read(stream.buffer())?;
+
+                // we speak every minor version up to the one we requested, so there is nothing
+                // to do but go on without the options the server did not recognize
+                log::info!(
+                    "server supports protocol 3.{} (requested 3.{}); unrecognized options: {:?}",
+                    negotiated.minor_version,
+                    PROTOCOL_MINOR_VERSION,
+                    negotiated.unrecognized_options
+                );
             }
 
             Message::ReadyForQuery => {
@@ -244,20 +412,391 @@ impl PgConnection {
         tls::request_if_needed(&mut stream, &url).await?;
         let key_data = startup(&mut stream, &url).await?;
 
-        Ok(Self {
+        let strict_types = url.param("strict_types").as_deref() == Some("true");
+        let float_datetimes = stream
+            .parameters
+            .get("integer_datetimes")
+            .map_or(false, |value| &**value == "off");
+
+        // only mutated to register the PostGIS `geometry` type
+        #[cfg_attr(not(feature = "geo"), allow(unused_mut))]
+        let mut conn = Self {
             stream,
             current_row_values: Vec::with_capacity(10),
+            spare_arguments: PgArguments::default(),
+            pending_settings: Arc::default(),
             next_statement_id: 1,
             is_ready: true,
             cache_statement: HashMap::new(),
             cache_statement_columns: HashMap::new(),
             cache_statement_formats: HashMap::new(),
+            cache_statement_types: HashMap::new(),
+            cache_statement_stats: StatementCacheStats::default(),
+            unconfirmed_statement: None,
+            strict_types,
+            float_datetimes,
+            registered_types: Arc::default(),
+            #[cfg(feature = "auto-explain")]
+            auto_explain: match url.param_or("auto_explain", 0) {
+                0 => None,
+                millis => Some(Duration::from_millis(millis)),
+            },
+            #[cfg(feature = "auto-explain")]
+            timed_query: None,
             process_id: key_data.process_id,
             secret_key: key_data.secret_key,
+        };
+
+        #[cfg(feature = "geo")]
+        {
+            let geometry_oid = match url.param_or("geometry_oid", 0) {
+                0 => query("SELECT oid::int8 FROM pg_type WHERE typname = 'geometry' LIMIT 1")
+                    .try_map(|row: PgRow| row.try_get::<i64, _>(0))
+                    .fetch_optional(&mut conn)
+                    .await?
+                    .map(|oid| TypeId(oid as u32)),
+
+                oid => Some(TypeId(oid)),
+            };
+
+            if let Some(id) = geometry_oid {
+                Arc::make_mut(&mut conn.registered_types).insert(id, "GEOMETRY".into());
+            }
+        }
+
+        Ok(conn)
+    }
+
+    /// Returns the current value of a run-time parameter that the server reports to the
+    /// client, such as `server_version`, `server_encoding`, `TimeZone` or
+    /// `integer_datetimes`.
+    ///
+    /// The server sends these when the connection is established and again whenever one of
+    /// them changes, e.g. after `SET TimeZone = ..`; other parameters are `None` and can be
+    /// read with `SHOW` or `current_setting`. See [the Postgres documentation][docs] for the
+    /// list.
+    ///
+    /// [docs]: https://www.postgresql.org/docs/12/protocol-flow.html#PROTOCOL-ASYNC
+    pub fn server_parameter(&self, name: &str) -> Option<&str> {
+        self.stream.parameters.get(name).map(|value| &**value)
+    }
+
+    /// Looks up the object identifier of the SQL type of `T` in this database by its name, so
+    /// that `T` is bound as that type and values of that type are checked to be `T` when they
+    /// are decoded.
+    ///
+    /// This is needed for types that don't have a fixed object identifier, such as those
+    /// created by extensions or by `CREATE TYPE`, and is done once per connection:
+    ///
+    /// ```rust,ignore
+    /// conn.register_type::<Hstore>().await?;
+    /// ```
+    ///
+    /// The name is looked up like the name in a cast, so it may be qualified with a schema; if
+    /// there is no such type, the error from the database is returned.
+    ///
+    /// Statements that were prepared before the type was registered keep sending its
+    /// parameters without a type.
+    pub async fn register_type<T>(&mut self) -> crate::Result<()>
+    where
+        T: Type<Postgres> + ?Sized,
+    {
+        let type_info = T::type_info();
+        let name = type_info.type_name();
+
+        // fails with `undefined_object` if there is no such type
+        let oid: i64 = query("SELECT $1::regtype::oid::int8")
+            .bind(name)
+            .try_map(|row: PgRow| row.try_get(0))
+            .fetch_one(&mut *self)
+            .await?;
+
+        Arc::make_mut(&mut self.registered_types)
+            .insert(TypeId(oid as u32), name.to_owned().into());
+
+        Ok(())
+    }
+
+    // The object identifier registered for the type called `name`
+    pub(super) fn registered_type(&self, name: &str) -> Option<TypeId> {
+        self.registered_types
+            .iter()
+            .find(|(_, registered)| registered.eq_ignore_ascii_case(name))
+            .map(|(&id, _)| id)
+    }
+
+    /// Runs `f` with the session setting `name` temporarily set to `value`.
+    ///
+    /// The previous value of the setting is restored once `f` completes, whether or not it
+    /// returned an error, so the setting does not leak into later uses of this connection
+    /// (for example, after it is returned to a pool). If the returned future is dropped
+    /// before then, the setting is restored before the next query on this connection instead.
+    ///
+    /// A [`PoolConnection`] or a [`Transaction`](crate::transaction::Transaction) of a
+    /// `PgConnection` has this method as well, through `Deref`.
+    ///
+    /// ```rust,ignore
+    /// let count = conn
+    ///     .with_setting("role", "readonly", |conn| {
+    ///         Box::pin(async move { conn.execute("SELECT * FROM reports").await })
+    ///     })
+    ///     .await?;
+    /// ```
+    ///
+    /// Both `name` and `value` are sent as bind parameters to `set_config()`. When called
+    /// inside a transaction the setting follows the transaction; if `f` leaves the
+    /// transaction in an aborted state the restore fails and the error from `f` is returned.
+    pub async fn with_setting<F, T>(&mut self, name: &str, value: &str, f: F) -> crate::Result<T>
+    where
+        F: FnOnce(&mut PgConnection) -> BoxFuture<'_, crate::Result<T>>,
+    {
+        // `NULL` if the setting is a custom one that has not been defined yet; passing that
+        // back to `set_config` resets it
+        let previous: Option<String> = query("SELECT current_setting($1, true)")
+            .bind(name)
+            .try_map(|row: PgRow| row.try_get(0))
+            .fetch_one(&mut *self)
+            .await?;
+
+        // armed before the setting is changed, so a future dropped from here on leaves the
+        // restore to the next query
+        let mut restore = RestoreSetting {
+            pending: Arc::clone(&self.pending_settings),
+            setting: Some((name.to_owned(), previous.clone())),
+        };
+
+        set_config(self, name, Some(value)).await?;
+
+        let result = f(self).await;
+        let restored = set_config(self, name, previous.as_deref()).await;
+
+        restore.setting = None;
+
+        match (result, restored) {
+            (Err(error), _) | (Ok(_), Err(error)) => Err(error),
+            (Ok(value), Ok(())) => Ok(value),
+        }
+    }
+
+    /// Creates an empty large object and returns its object identifier.
+    pub async fn create_lo(&mut self) -> crate::Result<u32> {
+        let oid: i64 = query("SELECT lo_create(0)::int8")
+            .try_map(|row: PgRow| row.try_get(0))
+            .fetch_one(&mut *self)
+            .await?;
+
+        Ok(oid as u32)
+    }
+
+    /// Opens the large object `oid`, which can then be read, written and seeked in like a
+    /// file.
+    ///
+    /// ```rust,ignore
+    /// let mut tx = conn.begin().await?;
+    ///
+    /// let oid = tx.create_lo().await?;
+    /// tx.open_lo(oid, PgLargeObjectMode::Write).await?.write_all(&data).await?;
+    ///
+    /// tx.commit().await?;
+    /// ```
+    ///
+    /// Postgres closes large objects at the end of the transaction they were opened in, so
+    /// this returns an error outside of a transaction.
+    pub async fn open_lo(
+        &mut self,
+        oid: u32,
+        mode: PgLargeObjectMode,
+    ) -> crate::Result<PgLargeObject<'_>> {
+        if self.transaction_status() == TransactionStatus::Idle {
+            return Err(
+                protocol_err!("large objects can only be opened inside a transaction").into(),
+            );
+        }
+
+        let fd: i32 = query("SELECT lo_open($1::int8::oid, $2)")
+            .bind(oid as i64)
+            .bind(mode.flags())
+            .try_map(|row: PgRow| row.try_get(0))
+            .fetch_one(&mut *self)
+            .await?;
+
+        Ok(PgLargeObject::new(self, oid, fd))
+    }
+
+    /// Deletes the large object `oid`.
+    pub async fn unlink_lo(&mut self, oid: u32) -> crate::Result<()> {
+        query("SELECT lo_unlink($1::int8::oid)")
+            .bind(oid as i64)
+            .execute(&mut *self)
+            .await?;
+
+        Ok(())
+    }
+}
+
+// The name of each setting to restore and the value to restore it to, if it had one
+type PendingSettings = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+// Leaves the restore of a setting of `with_setting` to the next query if it is dropped before
+// `with_setting` could restore it
+struct RestoreSetting {
+    pending: PendingSettings,
+    setting: Option<(String, Option<String>)>,
+}
+
+impl Drop for RestoreSetting {
+    fn drop(&mut self) {
+        if let Some(setting) = self.setting.take() {
+            self.pending
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .push(setting);
+        }
+    }
+}
+
+impl PgConnection {
+    // Restores the settings of the calls to `with_setting` that were dropped, in the order
+    // they were dropped in; boxed, as the queries that restore them call back into it
+    pub(super) fn restore_settings(&mut self) -> BoxFuture<'_, crate::Result<()>> {
+        Box::pin(async move {
+            let pending = mem::take(
+                &mut *self
+                    .pending_settings
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner()),
+            );
+
+            for (name, previous) in pending {
+                set_config(self, &name, previous.as_deref()).await?;
+            }
+
+            Ok(())
         })
     }
 }
 
+async fn set_config(conn: &mut PgConnection, name: &str, value: Option<&str>) -> crate::Result<()> {
+    query("SELECT set_config($1, $2, false)")
+        .bind(name)
+        .bind(value)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+async fn table_id(conn: &mut PgConnection, name: &str) -> crate::Result<Option<u32>> {
+    let (schema, name) = match quote::split_qualified(name, &[('"', '"')], str::to_ascii_lowercase)
+    {
+        Some(parts) => parts,
+        None => return Ok(None),
+    };
+
+    // tables, views, materialized views, foreign tables and partitioned tables; an unqualified
+    // name is only found in the first schema of the `search_path` that has a relation named so,
+    // and `pg_temp` stands for the temporary schema of the session
+    let oid = query(
+        "SELECT c.oid::int8 FROM pg_catalog.pg_class c \
+         JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+         WHERE c.relname = $1 AND c.relkind IN ('r', 'v', 'm', 'f', 'p') \
+         AND CASE WHEN $2::text IS NULL THEN pg_catalog.pg_table_is_visible(c.oid) \
+         WHEN $2 = 'pg_temp' THEN n.oid = pg_catalog.pg_my_temp_schema() \
+         ELSE n.nspname = $2 END",
+    )
+    .bind(name)
+    .bind(schema)
+    .try_map(|row: PgRow| row.try_get::<i64, _>(0))
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(oid.map(|oid| oid as u32))
+}
+
+async fn describe_table(
+    conn: &mut PgConnection,
+    name: &str,
+) -> crate::Result<TableColumns<Postgres>> {
+    let oid = match table_id(conn, name).await? {
+        Some(oid) => oid as i64,
+        None => return Ok(None),
+    };
+
+    // quoted, and qualified unless the table is found on the `search_path`
+    let table: String = query("SELECT $1::int8::oid::regclass::text")
+        .bind(oid)
+        .try_map(|row: PgRow| row.try_get(0))
+        .fetch_one(&mut *conn)
+        .await?;
+
+    // an identity column has no default in `pg_attrdef`, only a sequence of its own
+    let columns = query(
+        "SELECT a.attname::text, NOT a.attnotnull, \
+         a.atthasdef OR pg_catalog.pg_get_serial_sequence($2, a.attname) IS NOT NULL, \
+         EXISTS (SELECT 1 FROM pg_catalog.pg_index i WHERE i.indrelid = a.attrelid \
+         AND i.indisprimary AND a.attnum = ANY (i.indkey)) \
+         FROM pg_catalog.pg_attribute a \
+         WHERE a.attrelid = $1::int8::oid AND a.attnum > 0 AND NOT a.attisdropped \
+         ORDER BY a.attnum",
+    )
+    .bind(oid)
+    .bind(&*table)
+    .try_map(|row: PgRow| {
+        Ok((
+            row.try_get(0)?,
+            row.try_get(1)?,
+            row.try_get(2)?,
+            row.try_get(3)?,
+        ))
+    })
+    .fetch_all(&mut *conn)
+    .await?;
+
+    describe::table_columns(conn, &table, columns)
+        .await
+        .map(Some)
+}
+
+// The value of `search_path` that looks up names in `schemas`, in order
+fn search_path<'a>(schemas: impl IntoIterator<Item = &'a str>) -> crate::Result<String> {
+    let mut quoted = Vec::new();
+
+    for schema in schemas {
+        quoted.push(
+            Postgres::quote_identifier(schema).map_err(|err| crate::Error::Encode(err.into()))?,
+        );
+    }
+
+    Ok(quoted.join(", "))
+}
+
+impl Pool<PgConnection> {
+    /// Retrieves a connection from the pool with `search_path` set to `schemas` instead of the
+    /// schemas given in the connection string, for instance to use the tables of one tenant
+    /// among many that each have their own schema:
+    ///
+    /// ```rust,ignore
+    /// let mut conn = pool.acquire_with_search_path(&["tenant_1", "public"]).await?;
+    /// ```
+    ///
+    /// The `search_path` is reset once the connection is returned to the pool, after rolling
+    /// back a transaction left open on it; if that fails, the connection is closed instead.
+    pub async fn acquire_with_search_path(
+        &self,
+        schemas: &[&str],
+    ) -> crate::Result<PoolConnection<PgConnection>> {
+        let search_path = search_path(schemas.iter().copied())?;
+        let mut conn = self.acquire().await?;
+
+        // set before the query is sent, in case the query fails half-way
+        conn.reset = Some("RESET search_path");
+
+        set_config(&mut conn, "search_path", Some(&search_path)).await?;
+
+        Ok(conn)
+    }
+}
+
 impl Connect for PgConnection {
     fn connect<T>(url: T) -> BoxFuture<'static, crate::Result<PgConnection>>
     where
@@ -276,4 +815,116 @@ impl Connection for PgConnection {
     fn ping(&mut self) -> BoxFuture<crate::Result<()>> {
         Box::pin(Executor::execute(self, "SELECT 1").map_ok(|_| ()))
     }
+
+    fn is_broken(&self) -> bool {
+        self.stream.is_broken
+    }
+
+    fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
+    fn take_arguments(&mut self) -> PgArguments {
+        mem::take(&mut self.spare_arguments)
+    }
+
+    fn transaction_status(&self) -> TransactionStatus {
+        self.stream.transaction_status
+    }
+
+    fn table_id<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, crate::Result<Option<u32>>> {
+        Box::pin(table_id(self, name))
+    }
+
+    fn describe_table<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<TableColumns<Postgres>>> {
+        Box::pin(describe_table(self, name))
+    }
+
+    fn statement_cache_stats(&self) -> StatementCacheStats {
+        StatementCacheStats {
+            size: self.cache_statement.len(),
+            ..self.cache_statement_stats
+        }
+    }
+
+    fn take_transaction_error(&mut self) -> Option<crate::Error> {
+        let error = self.stream.transaction_error.take()?;
+
+        Some(crate::Error::Database(Box::new(error)))
+    }
+}
+
+impl HandleNotices for PgConnection {
+    fn set_notice_handler<F>(&mut self, handler: F)
+    where
+        F: FnMut(Notice) + Send + Sync + 'static,
+    {
+        self.stream.notice_handler = Some(Box::new(handler));
+    }
+
+    fn remove_notice_handler(&mut self) {
+        self.stream.notice_handler = None;
+    }
+}
+
+#[cfg(all(test, feature = "tls"))]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::startup;
+    use crate::io::fixture::block_on;
+    use crate::io::MaybeTlsStream;
+    use crate::postgres::stream::PgStream;
+    use crate::postgres::tls;
+    use crate::url::Url;
+
+    #[test]
+    fn it_carries_on_without_tls_when_the_server_refuses_it() {
+        let url: Url = "postgres://postgres@localhost/postgres".try_into().unwrap();
+        let mut stream = PgStream::with_stream(
+            MaybeTlsStream::fixture(include_str!("../../fixtures/postgres/tls_refused.fixture")),
+            &url,
+        );
+
+        let key_data = block_on(async {
+            tls::request_if_needed(&mut stream, &url).await?;
+            startup(&mut stream, &url).await
+        })
+        .unwrap();
+
+        assert_eq!(key_data.process_id, 4242);
+        assert_eq!(key_data.secret_key, 0x5EC7E7);
+        assert_eq!(
+            stream.parameters.get("server_version").map(|v| &**v),
+            Some("12.2")
+        );
+        assert!(!stream.stream.is_tls());
+
+        stream.stream.assert_fixture_done();
+    }
+
+    #[test]
+    fn it_refuses_a_message_longer_than_the_maximum_size() {
+        let url: Url = "postgres://postgres@localhost/postgres?max_message_size=1024"
+            .try_into()
+            .unwrap();
+
+        // a DataRow of 1025 bytes, after its length field
+        let mut stream = PgStream::with_stream(MaybeTlsStream::fixture("< 4400000405"), &url);
+
+        match block_on(stream.read()) {
+            Err(crate::Error::Protocol(message)) => assert_eq!(
+                &*message,
+                "DataRow message of 1025 bytes exceeds the maximum message size of 1024 bytes"
+            ),
+
+            other => panic!("expected a protocol error, got {:?}", other),
+        }
+
+        assert!(stream.check_broken().is_err());
+        stream.stream.assert_fixture_done();
+    }
 }
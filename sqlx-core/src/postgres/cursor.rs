@@ -6,17 +6,30 @@ use futures_core::future::BoxFuture;
 use crate::connection::ConnectionSource;
 use crate::cursor::Cursor;
 use crate::executor::Execute;
+#[cfg(feature = "tracing")]
+use crate::instrument::QuerySpan;
 use crate::pool::Pool;
 use crate::postgres::protocol::{
-    DataRow, Message, ReadyForQuery, RowDescription, StatementId, TypeFormat,
+    CommandComplete, DataRow, Message, ReadyForQuery, RowDescription, StatementId, TypeFormat,
+    TypeId,
 };
 use crate::postgres::{PgArguments, PgConnection, PgRow, Postgres};
 
 pub struct PgCursor<'c, 'q> {
     source: ConnectionSource<'c, PgConnection>,
     query: Option<(&'q str, Option<PgArguments>)>,
+    first_row_only: bool,
     columns: Arc<HashMap<Box<str>, usize>>,
     formats: Arc<[TypeFormat]>,
+    types: Arc<[TypeId]>,
+
+    // Counted from each `CommandComplete`; see [Cursor::rows_affected]
+    affected_rows: u64,
+    returned_rows: bool,
+    done: bool,
+
+    #[cfg(feature = "tracing")]
+    span: QuerySpan,
 }
 
 impl<'c, 'q> Cursor<'c, 'q> for PgCursor<'c, 'q> {
@@ -28,11 +41,21 @@ impl<'c, 'q> Cursor<'c, 'q> for PgCursor<'c, 'q> {
         Self: Sized,
         E: Execute<'q, Postgres>,
     {
+        let first_row_only = query.first_row_only();
+        let query = query.into_parts();
+
         Self {
+            #[cfg(feature = "tracing")]
+            span: QuerySpan::new("postgresql", query.0),
             source: ConnectionSource::Pool(pool.clone()),
             columns: Arc::default(),
             formats: Arc::new([] as [TypeFormat; 0]),
-            query: Some(query.into_parts()),
+            types: Arc::new([] as [TypeId; 0]),
+            first_row_only,
+            query: Some(query),
+            affected_rows: 0,
+            returned_rows: false,
+            done: false,
         }
     }
 
@@ -42,25 +65,53 @@ impl<'c, 'q> Cursor<'c, 'q> for PgCursor<'c, 'q> {
         Self: Sized,
         E: Execute<'q, Postgres>,
     {
+        let first_row_only = query.first_row_only();
+        let query = query.into_parts();
+
         Self {
+            #[cfg(feature = "tracing")]
+            span: QuerySpan::new("postgresql", query.0),
             source: ConnectionSource::ConnectionRef(conn),
             columns: Arc::default(),
             formats: Arc::new([] as [TypeFormat; 0]),
-            query: Some(query.into_parts()),
+            types: Arc::new([] as [TypeId; 0]),
+            first_row_only,
+            query: Some(query),
+            affected_rows: 0,
+            returned_rows: false,
+            done: false,
         }
     }
 
+    #[cfg(not(feature = "tracing"))]
     fn next(&mut self) -> BoxFuture<crate::Result<Option<PgRow<'_>>>> {
         Box::pin(next(self))
     }
+
+    #[cfg(feature = "tracing")]
+    fn next(&mut self) -> BoxFuture<crate::Result<Option<PgRow<'_>>>> {
+        Box::pin(self.span.clone().next(next(self)))
+    }
+
+    fn rows_affected(&self) -> Option<u64> {
+        if self.done {
+            Some(self.affected_rows)
+        } else {
+            None
+        }
+    }
 }
 
-fn parse_row_description(rd: RowDescription) -> (HashMap<Box<str>, usize>, Vec<TypeFormat>) {
+type ColumnDescription = (HashMap<Box<str>, usize>, Vec<TypeFormat>, Vec<TypeId>);
+
+fn parse_row_description(rd: RowDescription) -> ColumnDescription {
     let mut columns = HashMap::new();
     let mut formats = Vec::new();
+    let mut types = Vec::new();
 
     columns.reserve(rd.fields.len());
     formats.reserve(rd.fields.len());
+    types.reserve(rd.fields.len());
 
     for (index, field) in rd.fields.iter().enumerate() {
         if let Some(name) = &field.name {
@@ -68,16 +119,15 @@ fn parse_row_description(rd: RowDescription) -> (HashMap<Box<str>, usize>, Vec<T
         }
 
         formats.push(field.type_format);
+        types.push(field.type_id);
     }
 
-    (columns, formats)
+    (columns, formats, types)
 }
 
 // Used to describe the incoming results
 // We store the column map in an Arc and share it among all rows
-async fn expect_desc(
-    conn: &mut PgConnection,
-) -> crate::Result<(HashMap<Box<str>, usize>, Vec<TypeFormat>)> {
+async fn expect_desc(conn: &mut PgConnection) -> crate::Result<ColumnDescription> {
     let description: Option<_> = loop {
         match conn.stream.receive().await? {
             Message::ParseComplete | Message::BindComplete => {}
@@ -105,22 +155,31 @@ async fn expect_desc(
 async fn get_or_describe(
     conn: &mut PgConnection,
     statement: StatementId,
-) -> crate::Result<(Arc<HashMap<Box<str>, usize>>, Arc<[TypeFormat]>)> {
+) -> crate::Result<(
+    Arc<HashMap<Box<str>, usize>>,
+    Arc<[TypeFormat]>,
+    Arc<[TypeId]>,
+)> {
     if !conn.cache_statement_columns.contains_key(&statement)
         || !conn.cache_statement_formats.contains_key(&statement)
+        || !conn.cache_statement_types.contains_key(&statement)
     {
-        let (columns, formats) = expect_desc(conn).await?;
+        let (columns, formats, types) = expect_desc(conn).await?;
 
         conn.cache_statement_columns
             .insert(statement, Arc::new(columns));
 
         conn.cache_statement_formats
             .insert(statement, Arc::from(formats));
+
+        conn.cache_statement_types
+            .insert(statement, Arc::from(types));
     }
 
     Ok((
         Arc::clone(&conn.cache_statement_columns[&statement]),
         Arc::clone(&conn.cache_statement_formats[&statement]),
+        Arc::clone(&conn.cache_statement_types[&statement]),
     ))
 }
 
@@ -133,16 +192,17 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
     // contained query. We guard against this happening on _all_ next calls
     // by using [Option::take] which replaces the potential value in the Option with `None
     if let Some((query, arguments)) = cursor.query.take() {
-        let statement = conn.run(query, arguments).await?;
+        let statement = conn.run(query, arguments, cursor.first_row_only).await?;
 
         // If there is a statement ID, this is a non-simple or prepared query
         if let Some(statement) = statement {
             // A prepared statement will re-use the previous column map if
             // this query has been executed before
-            let (columns, formats) = get_or_describe(&mut *conn, statement).await?;
+            let (columns, formats, types) = get_or_describe(&mut *conn, statement).await?;
 
             cursor.columns = columns;
             cursor.formats = formats;
+            cursor.types = types;
         }
 
         // A non-prepared query must be described each time
@@ -156,7 +216,23 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
             Message::ParseComplete | Message::BindComplete => {}
 
             // Indicates that _a_ query has finished executing
-            Message::CommandComplete => {}
+            Message::CommandComplete => {
+                let complete = CommandComplete::read(conn.stream.buffer())?;
+
+                // the count of a `SELECT` is of the rows it returned
+                if !(complete.selected && cursor.returned_rows) {
+                    cursor.affected_rows += complete.affected_rows;
+                }
+
+                cursor.returned_rows = false;
+            }
+
+            // Sent instead of [CommandComplete] for a query that is empty or only has comments
+            Message::EmptyQueryResponse => {}
+
+            // The row limit of [Execute] was reached and the portal was closed after it; see
+            // [PgConnection::run]
+            Message::PortalSuspended | Message::CloseComplete => {}
 
             // Indicates that all queries have finished executing
             Message::ReadyForQuery => {
@@ -164,23 +240,37 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                 let _ready = ReadyForQuery::read(conn.stream.buffer())?;
 
                 conn.is_ready = true;
+                cursor.done = true;
+
+                #[cfg(feature = "auto-explain")]
+                conn.explain_if_slow().await;
+
                 break;
             }
 
             Message::RowDescription => {
                 let rd = RowDescription::read(conn.stream.buffer())?;
-                let (columns, formats) = parse_row_description(rd);
+                let (columns, formats, types) = parse_row_description(rd);
 
                 cursor.columns = Arc::new(columns);
                 cursor.formats = Arc::from(formats);
+                cursor.types = Arc::from(types);
             }
 
             Message::DataRow => {
                 let data = DataRow::read(conn.stream.buffer(), &mut conn.current_row_values)?;
 
+                cursor.returned_rows = true;
+
+                #[cfg(feature = "bench-internals")]
+                conn.stream.timer.row_returned();
+
                 return Ok(Some(PgRow {
                     columns: Arc::clone(&cursor.columns),
                     formats: Arc::clone(&cursor.formats),
+                    types: Arc::clone(&cursor.types),
+                    strict_types: conn.strict_types,
+                    registered_types: Arc::clone(&conn.registered_types),
                     data,
                 }));
             }
@@ -1,4 +1,4 @@
-use crate::database::{Database, HasCursor, HasRawValue, HasRow};
+use crate::database::{Database, HasCursor, HasRawValue, HasRow, ParameterStyle};
 use crate::postgres::row::PgValue;
 
 /// **Postgres** database driver.
@@ -14,6 +14,25 @@ impl Database for Postgres {
     type TableId = u32;
 
     type RawBuffer = Vec<u8>;
+
+    const PARAMETER_STYLE: ParameterStyle = ParameterStyle::Dollar;
+
+    // The count is an `Int16` in [Parse] and [Bind], which the server reads as unsigned
+    const PARAMETER_LIMIT: usize = 65535;
+
+    fn call_statement(name: &str, params: usize, function: bool) -> String {
+        let params = (1..=params)
+            .map(|index| format!("${}", index))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // `SELECT *` expands the OUT parameters of a function into columns
+        if function {
+            format!("SELECT * FROM {}({})", name, params)
+        } else {
+            format!("CALL {}({})", name, params)
+        }
+    }
 }
 
 impl<'a> HasRow<'a> for Postgres {
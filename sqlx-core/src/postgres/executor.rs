@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
@@ -6,13 +7,15 @@ use futures_util::{stream, StreamExt, TryStreamExt};
 
 use crate::arguments::Arguments;
 use crate::cursor::Cursor;
-use crate::describe::{Column, Describe};
+use crate::database::Database;
+use crate::describe::{Column, ColumnFlags, Describe};
 use crate::executor::{Execute, Executor, RefExecutor};
+#[cfg(feature = "tracing")]
+use crate::instrument::QuerySpan;
 use crate::postgres::protocol::{
     self, CommandComplete, Field, Message, ParameterDescription, ReadyForQuery, RowDescription,
     StatementId, TypeFormat, TypeId,
 };
-use crate::postgres::types::SharedStr;
 use crate::postgres::{PgArguments, PgConnection, PgCursor, PgRow, PgTypeInfo, Postgres};
 use crate::row::Row;
 
@@ -23,19 +26,39 @@ impl PgConnection {
 
     pub(crate) fn write_prepare(&mut self, query: &str, args: &PgArguments) -> StatementId {
         if let Some(&id) = self.cache_statement.get(query) {
+            self.cache_statement_stats.hits += 1;
+
             id
         } else {
+            self.cache_statement_stats.misses += 1;
+
             let id = StatementId(self.next_statement_id);
 
             self.next_statement_id += 1;
 
+            let param_types = if args.named_types.is_empty() {
+                Cow::Borrowed(&*args.types)
+            } else {
+                // send the OIDs of registered types; the others are left for the server to infer
+                let mut types = args.types.clone();
+
+                for (index, name) in &args.named_types {
+                    if let Some(id) = self.registered_type(name) {
+                        types[*index] = id.0;
+                    }
+                }
+
+                Cow::Owned(types)
+            };
+
             self.stream.write(protocol::Parse {
                 statement: id,
                 query,
-                param_types: &*args.types,
+                param_types: &param_types,
             });
 
             self.cache_statement.insert(query.into(), id);
+            self.unconfirmed_statement = Some(query.into());
 
             id
         }
@@ -52,7 +75,11 @@ impl PgConnection {
             formats: &[TypeFormat::Binary],
             values_len: args.types.len() as i16,
             values: &*args.values,
-            result_formats: &[TypeFormat::Binary],
+            result_formats: if self.float_datetimes {
+                &[TypeFormat::Text]
+            } else {
+                &[TypeFormat::Binary]
+            },
         });
     }
 
@@ -65,37 +92,82 @@ impl PgConnection {
     }
 
     async fn wait_until_ready(&mut self) -> crate::Result<()> {
+        self.stream.check_broken()?;
+
         // depending on how the previous query finished we may need to continue
-        // pulling messages from the stream until we receive a [ReadyForQuery] message
+        // pulling messages from the stream until we receive a [ReadyForQuery] message;
+        // for instance, if the future reading its results was dropped
 
         // postgres sends the [ReadyForQuery] message when it's fully complete with processing
         // the previous query
 
         if !self.is_ready {
             loop {
-                if let Message::ReadyForQuery = self.stream.receive().await? {
-                    // we are now ready to go
-                    self.is_ready = true;
-                    break;
+                match self.stream.receive().await {
+                    Ok(Message::ReadyForQuery) => {
+                        // we are now ready to go
+                        self.is_ready = true;
+                        break;
+                    }
+
+                    // an error from the previous query is of no interest to the next one
+                    Ok(_) | Err(crate::Error::Database(_)) => {}
+
+                    Err(error) => return Err(error),
                 }
             }
         }
 
+        // If the [Parse] of the previous query failed, the statement does not exist on the
+        // server; it must be parsed again the next time instead of being bound
+        if let Some(query) = self.unconfirmed_statement.take() {
+            if !self.stream.parse_complete {
+                self.cache_statement.remove(&query);
+            }
+        }
+
         Ok(())
     }
 
-    // Write out the query to the connection stream, ensure that we are synchronized at the
-    // most recent [ReadyForQuery] and flush our buffer to postgres.
+    // Ensure that we are synchronized at the most recent [ReadyForQuery], write out the query
+    // to the connection stream and flush our buffer to postgres.
     //
     // It is safe to call this method repeatedly (but all data from postgres would be lost) but
     // it is assumed that a call to [PgConnection::affected_rows] or [PgCursor::next] would
     // immediately follow.
+    //
+    // With `first_row_only`, a prepared query returns at most one row; the rest of the results
+    // are never sent. A query without arguments is sent as a simple query, which has no row
+    // limit and may hold several statements, so all of its results are still read.
     pub(crate) async fn run(
         &mut self,
         query: &str,
         arguments: Option<PgArguments>,
+        first_row_only: bool,
     ) -> crate::Result<Option<StatementId>> {
-        let statement = if let Some(arguments) = arguments {
+        // nothing may be buffered before this returns; if the future is dropped while we are
+        // waiting, the query would otherwise be sent along with the next one
+        self.wait_until_ready().await?;
+        self.restore_settings().await?;
+
+        #[cfg(feature = "auto-explain")]
+        self.time_query(query, arguments.as_ref());
+
+        #[cfg(feature = "bench-internals")]
+        self.stream.timer.start();
+
+        let statement = if let Some(mut arguments) = arguments {
+            if let Some(error) = arguments.error.take() {
+                return Err(error);
+            }
+
+            if arguments.types.len() > Postgres::PARAMETER_LIMIT {
+                return Err(crate::Error::ParameterLimitExceeded {
+                    limit: Postgres::PARAMETER_LIMIT,
+                    got: arguments.types.len(),
+                });
+            }
+
             // Check the statement cache for a statement ID that matches the given query
             // If it doesn't exist, we generate a new statement ID and write out [Parse] to the
             // connection command buffer
@@ -104,6 +176,10 @@ impl PgConnection {
             // Next, [Bind] attaches the arguments to the statement and creates a named portal
             self.write_bind("", statement, &arguments);
 
+            // The values are in the write buffer now; keep the arguments for the next query
+            arguments.clear();
+            self.spare_arguments = arguments;
+
             // Next, [Describe] will return the expected result columns and types
             // Conditionally run [Describe] only if the results have not been cached
             if !self.cache_statement_columns.contains_key(&statement) {
@@ -111,7 +187,15 @@ impl PgConnection {
             }
 
             // Next, [Execute] then executes the named portal
-            self.write_execute("", 0);
+            if first_row_only {
+                self.write_execute("", 1);
+
+                // The portal is suspended after the first row; [Close] drops it so it doesn't
+                // hold on to its resources if this is within a transaction
+                self.stream.write(protocol::Close::Portal(""));
+            } else {
+                self.write_execute("", 0);
+            }
 
             // Finally, [Sync] asks postgres to process the messages that we sent and respond with
             // a [ReadyForQuery] message when it's completely done. Theoretically, we could send
@@ -128,10 +212,8 @@ impl PgConnection {
             None
         };
 
-        self.wait_until_ready().await?;
-
-        self.stream.flush().await?;
         self.is_ready = false;
+        self.stream.flush().await?;
 
         Ok(statement)
     }
@@ -140,6 +222,8 @@ impl PgConnection {
         &'e mut self,
         query: &'q str,
     ) -> crate::Result<Describe<Postgres>> {
+        self.wait_until_ready().await?;
+        self.restore_settings().await?;
         self.is_ready = false;
 
         let statement = self.write_prepare(query, &Default::default());
@@ -199,7 +283,7 @@ impl PgConnection {
             param_types: params
                 .ids
                 .iter()
-                .map(|id| PgTypeInfo::new(*id, &type_names[&id.0]))
+                .map(|id| type_names[&id.0].clone())
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
             result_columns: self
@@ -209,10 +293,12 @@ impl PgConnection {
         })
     }
 
+    // Looks up the types of the parameters and columns of a statement; a domain is described
+    // along with the type it is defined over, which may be a domain itself
     async fn get_type_names(
         &mut self,
         ids: impl IntoIterator<Item = TypeId>,
-    ) -> crate::Result<HashMap<u32, SharedStr>> {
+    ) -> crate::Result<HashMap<u32, PgTypeInfo>> {
         let type_ids: HashSet<u32> = ids.into_iter().map(|id| id.0).collect::<HashSet<u32>>();
 
         if type_ids.is_empty() {
@@ -220,7 +306,9 @@ impl PgConnection {
         }
 
         // uppercase type names are easier to visually identify
-        let mut query = "select types.type_id, UPPER(pg_type.typname) from (VALUES ".to_string();
+        let mut query = "with recursive types(idx, type_id, base_id) as (\
+                         select idx, type_id::oid, type_id::oid from (VALUES "
+            .to_string();
         let mut args = PgArguments::default();
         let mut pushed = false;
 
@@ -238,17 +326,34 @@ impl PgConnection {
             args.add(type_id as i32);
         }
 
-        query += ") as types(idx, type_id) \
-                  inner join pg_catalog.pg_type on pg_type.oid = type_id \
+        query += ") as given(idx, type_id) \
+                  union all \
+                  select types.idx, types.type_id, pg_type.typbasetype from types \
+                  inner join pg_catalog.pg_type on pg_type.oid = types.base_id \
+                  where pg_type.typtype = 'd') \
+                  select types.type_id::int4, UPPER(pg_type.typname), \
+                  types.base_id::int4, UPPER(base.typname) from types \
+                  inner join pg_catalog.pg_type on pg_type.oid = types.type_id \
+                  inner join pg_catalog.pg_type base on base.oid = types.base_id \
+                  where base.typtype <> 'd' \
                   order by types.idx";
 
         crate::query::query(&query)
             .bind_all(args)
-            .try_map(|row: PgRow| -> crate::Result<(u32, SharedStr)> {
-                Ok((
-                    row.try_get::<i32, _>(0)? as u32,
-                    row.try_get::<String, _>(1)?.into(),
-                ))
+            .try_map(|row: PgRow| -> crate::Result<(u32, PgTypeInfo)> {
+                let id = TypeId(row.try_get::<i32, _>(0)? as u32);
+                let name = row.try_get::<String, _>(1)?;
+                let base_id = TypeId(row.try_get::<i32, _>(2)? as u32);
+
+                let type_info = if base_id == id {
+                    PgTypeInfo::new(id, name)
+                } else {
+                    let base = PgTypeInfo::new(base_id, row.try_get::<String, _>(3)?);
+
+                    PgTypeInfo::domain(id, name, base)
+                };
+
+                Ok((id.0, type_info))
             })
             .fetch(self)
             .try_collect()
@@ -258,7 +363,7 @@ impl PgConnection {
     async fn map_result_columns(
         &mut self,
         fields: Box<[Field]>,
-        type_names: HashMap<u32, SharedStr>,
+        type_names: HashMap<u32, PgTypeInfo>,
     ) -> crate::Result<Vec<Column<Postgres>>> {
         if fields.is_empty() {
             return Ok(vec![]);
@@ -315,8 +420,9 @@ impl PgConnection {
                 Ok(Column {
                     name: field.name,
                     table_id: field.table_id,
-                    type_info: PgTypeInfo::new(field.type_id, &type_names[&field.type_id.0]),
+                    type_info: type_names[&field.type_id.0].clone(),
                     non_null,
+                    flags: ColumnFlags::empty(),
                 })
             })
             .try_collect()
@@ -325,7 +431,7 @@ impl PgConnection {
 
     // Poll messages from Postgres, counting the rows affected, until we finish the query
     // This must be called directly after a call to [PgConnection::execute]
-    async fn affected_rows(&mut self) -> crate::Result<u64> {
+    pub(super) async fn affected_rows(&mut self) -> crate::Result<u64> {
         let mut rows = 0;
 
         loop {
@@ -375,12 +481,22 @@ impl Executor for super::PgConnection {
     where
         E: Execute<'q, Self::Database>,
     {
-        Box::pin(async move {
-            let (query, arguments) = query.into_parts();
+        let (query, arguments) = query.into_parts();
 
-            self.run(query, arguments).await?;
-            self.affected_rows().await
-        })
+        let execute = async move {
+            self.run(query, arguments, false).await?;
+            let rows = self.affected_rows().await?;
+
+            #[cfg(feature = "auto-explain")]
+            self.explain_if_slow().await;
+
+            Ok(rows)
+        };
+
+        #[cfg(feature = "tracing")]
+        let execute = QuerySpan::new("postgresql", query).execute(execute);
+
+        Box::pin(execute)
     }
 
     fn fetch<'q, E>(&mut self, query: E) -> PgCursor<'_, 'q>
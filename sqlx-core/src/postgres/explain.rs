@@ -0,0 +1,125 @@
+use serde_json::Value;
+
+use crate::explain::{parse_json, Explain, ExplainNode, ExplainPlan};
+use crate::postgres::Postgres;
+
+impl Explain for Postgres {
+    fn explain_statement(query: &str, analyze: bool) -> crate::Result<String> {
+        let options = if analyze {
+            "FORMAT JSON, ANALYZE"
+        } else {
+            "FORMAT JSON"
+        };
+
+        Ok(format!("EXPLAIN ({}) {}", options, query))
+    }
+
+    fn parse_plan(rows: Vec<Vec<String>>) -> crate::Result<ExplainPlan> {
+        // [{"Plan": {"Node Type": "Seq Scan", .., "Plans": [..]}, "Planning Time": ..}]
+        let raw = parse_json(&rows)?;
+
+        let plan = match &raw[0]["Plan"] {
+            Value::Object(_) => node(&raw[0]["Plan"]),
+            _ => return Err(protocol_err!("unexpected query plan: {}", raw).into()),
+        };
+
+        Ok(ExplainPlan {
+            nodes: vec![plan],
+            raw,
+        })
+    }
+}
+
+fn node(plan: &Value) -> ExplainNode {
+    ExplainNode {
+        node_type: plan["Node Type"].as_str().unwrap_or_default().to_owned(),
+        relation: plan["Relation Name"].as_str().map(ToOwned::to_owned),
+        cost: plan["Total Cost"].as_f64(),
+        rows: plan["Plan Rows"].as_f64(),
+        actual_rows: plan["Actual Rows"].as_f64(),
+        children: plan["Plans"]
+            .as_array()
+            .map(|plans| plans.iter().map(node).collect())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = r#"[
+  {
+    "Plan": {
+      "Node Type": "Nested Loop",
+      "Parallel Aware": false,
+      "Join Type": "Inner",
+      "Startup Cost": 0.15,
+      "Total Cost": 16.43,
+      "Plan Rows": 3,
+      "Plan Width": 36,
+      "Plans": [
+        {
+          "Node Type": "Seq Scan",
+          "Parent Relationship": "Outer",
+          "Relation Name": "users",
+          "Alias": "users",
+          "Startup Cost": 0.00,
+          "Total Cost": 1.03,
+          "Plan Rows": 3,
+          "Plan Width": 36
+        },
+        {
+          "Node Type": "Index Only Scan",
+          "Parent Relationship": "Inner",
+          "Index Name": "posts_pkey",
+          "Relation Name": "posts",
+          "Alias": "posts",
+          "Startup Cost": 0.15,
+          "Total Cost": 5.12,
+          "Plan Rows": 1,
+          "Plan Width": 4
+        }
+      ]
+    }
+  }
+]"#;
+
+    #[test]
+    fn it_parses_a_plan() -> crate::Result<()> {
+        let plan = Postgres::parse_plan(vec![vec![PLAN.to_owned()]])?;
+
+        assert_eq!(
+            plan.nodes,
+            vec![ExplainNode {
+                node_type: "Nested Loop".into(),
+                relation: None,
+                cost: Some(16.43),
+                rows: Some(3.0),
+                actual_rows: None,
+                children: vec![
+                    ExplainNode {
+                        node_type: "Seq Scan".into(),
+                        relation: Some("users".into()),
+                        cost: Some(1.03),
+                        rows: Some(3.0),
+                        actual_rows: None,
+                        children: vec![],
+                    },
+                    ExplainNode {
+                        node_type: "Index Only Scan".into(),
+                        relation: Some("posts".into()),
+                        cost: Some(5.12),
+                        rows: Some(1.0),
+                        actual_rows: None,
+                        children: vec![],
+                    },
+                ],
+            }]
+        );
+
+        assert_eq!(plan.raw[0]["Plan"]["Join Type"], "Inner");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,355 @@
+use std::io::{self, SeekFrom};
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use futures_util::{ready, FutureExt};
+
+use crate::postgres::{PgConnection, PgRow};
+use crate::query::query;
+use crate::row::Row;
+use crate::runtime::{AsyncRead, AsyncSeek, AsyncWrite};
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/include/libpq/libpq-fs.h
+const INV_WRITE: i32 = 0x0002_0000;
+const INV_READ: i32 = 0x0004_0000;
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/include/storage/large_object.h
+const SEEK_SET: i32 = 0;
+const SEEK_CUR: i32 = 1;
+const SEEK_END: i32 = 2;
+
+// The most that is asked of `loread` at once, so a large buffer does not mean a large response
+const MAX_READ: usize = 1 << 20;
+
+/// How a large object is opened by [`PgConnection::open_lo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgLargeObjectMode {
+    /// Reads see the large object as of the start of the transaction, like a query would.
+    Read,
+
+    /// Writes are allowed, and reads see the writes made so far.
+    Write,
+
+    ReadWrite,
+}
+
+impl PgLargeObjectMode {
+    pub(super) fn flags(self) -> i32 {
+        match self {
+            PgLargeObjectMode::Read => INV_READ,
+            PgLargeObjectMode::Write => INV_WRITE,
+            PgLargeObjectMode::ReadWrite => INV_READ | INV_WRITE,
+        }
+    }
+}
+
+/// A large object opened with [`PgConnection::open_lo`].
+///
+/// Reading, writing and seeking each call a server-side function (`loread`, `lowrite` and
+/// `lo_lseek64`) with the connection, which stays borrowed until the large object is dropped.
+/// The large object is closed by Postgres at the end of the transaction it was opened in,
+/// or earlier by closing it as an [`AsyncWrite`].
+///
+/// A write that returned `Poll::Pending` must be retried with the same buffer.
+pub struct PgLargeObject<'c> {
+    oid: u32,
+    fd: i32,
+    state: State<'c>,
+
+    // What `loread` returned that was not yet copied to a buffer of the caller, including all
+    // of a read the caller stopped waiting for; the position of the large object on the
+    // server is after it
+    read_buf: Vec<u8>,
+    read_pos: usize,
+}
+
+enum State<'c> {
+    Idle(&'c mut PgConnection),
+    Busy(BoxFuture<'c, (&'c mut PgConnection, crate::Result<Output>)>),
+
+    // The connection is taken while an operation is started; it is left here if that panics
+    Empty,
+}
+
+enum Output {
+    // `poll_state` moves the data to `read_buf`; none is read at the end of the large object
+    Read(Vec<u8>),
+    Written(usize),
+    Position(u64),
+    Closed,
+}
+
+impl<'c> PgLargeObject<'c> {
+    pub(super) fn new(conn: &'c mut PgConnection, oid: u32, fd: i32) -> Self {
+        Self {
+            oid,
+            fd,
+            state: State::Idle(conn),
+            read_buf: Vec::new(),
+            read_pos: 0,
+        }
+    }
+
+    /// The object identifier of the large object.
+    pub fn oid(&self) -> u32 {
+        self.oid
+    }
+
+    // Waits for the operation in progress, if any, and returns its output
+    fn poll_state(&mut self, cx: &mut Context) -> Poll<io::Result<Option<Output>>> {
+        let (conn, output) = match &mut self.state {
+            State::Idle(_) => return Poll::Ready(Ok(None)),
+            State::Busy(future) => ready!(future.poll_unpin(cx)),
+            State::Empty => return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+        };
+
+        self.state = State::Idle(conn);
+
+        match output.map_err(into_io_error)? {
+            // a read completes even if the caller stopped waiting for it and does something
+            // else; keeping what it read keeps the position of the caller known
+            Output::Read(data) => {
+                self.read_buf = data;
+                self.read_pos = 0;
+
+                Poll::Ready(Ok(Some(Output::Read(Vec::new()))))
+            }
+
+            output => Poll::Ready(Ok(Some(output))),
+        }
+    }
+
+    fn start(
+        &mut self,
+        op: impl FnOnce(
+            &'c mut PgConnection,
+            i32,
+        ) -> BoxFuture<'c, (&'c mut PgConnection, crate::Result<Output>)>,
+    ) {
+        if let State::Idle(conn) = mem::replace(&mut self.state, State::Empty) {
+            self.state = State::Busy(op(conn, self.fd));
+        }
+    }
+
+    // The bytes read from the server but not yet returned by `poll_read`; the server is
+    // ahead of the position seen by the caller by this many bytes
+    fn unread(&mut self) -> i64 {
+        let unread = self.read_buf.len() - self.read_pos;
+
+        self.read_buf.clear();
+        self.read_pos = 0;
+
+        unread as i64
+    }
+}
+
+impl AsyncRead for PgLargeObject<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.read_pos < this.read_buf.len() {
+                let read = &this.read_buf[this.read_pos..];
+                let len = read.len().min(buf.len());
+
+                buf[..len].copy_from_slice(&read[..len]);
+                this.read_pos += len;
+
+                return Poll::Ready(Ok(len));
+            }
+
+            match ready!(this.poll_state(cx))? {
+                Some(Output::Read(_)) => {
+                    if this.read_buf.is_empty() {
+                        return Poll::Ready(Ok(0));
+                    }
+                }
+
+                _ => {
+                    let len = buf.len().min(MAX_READ) as i32;
+
+                    this.start(move |conn, fd| Box::pin(read(conn, fd, len)));
+                }
+            }
+        }
+    }
+}
+
+impl AsyncWrite for PgLargeObject<'_> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            match ready!(this.poll_state(cx))? {
+                Some(Output::Written(len)) => return Poll::Ready(Ok(len)),
+
+                _ => {
+                    let rewind = this.unread();
+                    let data = buf.to_vec();
+
+                    this.start(move |conn, fd| Box::pin(write(conn, fd, rewind, data)));
+                }
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().poll_state(cx).map_ok(|_| ())
+    }
+
+    #[cfg(feature = "runtime-async-std")]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().poll_lo_close(cx)
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().poll_lo_close(cx)
+    }
+}
+
+impl PgLargeObject<'_> {
+    fn poll_lo_close(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        loop {
+            match ready!(self.poll_state(cx))? {
+                Some(Output::Closed) => return Poll::Ready(Ok(())),
+
+                _ => {
+                    self.unread();
+                    self.start(|conn, fd| Box::pin(close(conn, fd)));
+                }
+            }
+        }
+    }
+
+    fn poll_lseek(&mut self, cx: &mut Context, pos: SeekFrom) -> Poll<io::Result<u64>> {
+        loop {
+            match ready!(self.poll_state(cx))? {
+                Some(Output::Position(position)) => return Poll::Ready(Ok(position)),
+                _ => self.start_lseek(pos),
+            }
+        }
+    }
+
+    fn start_lseek(&mut self, pos: SeekFrom) {
+        let unread = self.unread();
+
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (offset as i64, SEEK_SET),
+            SeekFrom::End(offset) => (offset, SEEK_END),
+            SeekFrom::Current(offset) => (offset - unread, SEEK_CUR),
+        };
+
+        self.start(move |conn, fd| Box::pin(seek(conn, fd, offset, whence)));
+    }
+}
+
+#[cfg(feature = "runtime-async-std")]
+impl AsyncSeek for PgLargeObject<'_> {
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context, pos: SeekFrom) -> Poll<io::Result<u64>> {
+        self.get_mut().poll_lseek(cx, pos)
+    }
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl AsyncSeek for PgLargeObject<'_> {
+    fn start_seek(self: Pin<&mut Self>, cx: &mut Context, pos: SeekFrom) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        ready!(this.poll_state(cx))?;
+        this.start_lseek(pos);
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<u64>> {
+        // without a seek in progress, this returns the current position
+        self.get_mut().poll_lseek(cx, SeekFrom::Current(0))
+    }
+}
+
+async fn read(
+    conn: &mut PgConnection,
+    fd: i32,
+    len: i32,
+) -> (&mut PgConnection, crate::Result<Output>) {
+    let result = query("SELECT loread($1, $2)")
+        .bind(fd)
+        .bind(len)
+        .try_map(|row: PgRow| row.try_get(0))
+        .fetch_one(&mut *conn)
+        .await;
+
+    (conn, result.map(Output::Read))
+}
+
+async fn write(
+    conn: &mut PgConnection,
+    fd: i32,
+    rewind: i64,
+    data: Vec<u8>,
+) -> (&mut PgConnection, crate::Result<Output>) {
+    let result = async {
+        if rewind != 0 {
+            lseek(&mut *conn, fd, -rewind, SEEK_CUR).await?;
+        }
+
+        let written: i32 = query("SELECT lowrite($1, $2)")
+            .bind(fd)
+            .bind(data)
+            .try_map(|row: PgRow| row.try_get(0))
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(Output::Written(written as usize))
+    }
+    .await;
+
+    (conn, result)
+}
+
+async fn seek(
+    conn: &mut PgConnection,
+    fd: i32,
+    offset: i64,
+    whence: i32,
+) -> (&mut PgConnection, crate::Result<Output>) {
+    let result = lseek(&mut *conn, fd, offset, whence).await;
+
+    (
+        conn,
+        result.map(|position| Output::Position(position as u64)),
+    )
+}
+
+async fn close(conn: &mut PgConnection, fd: i32) -> (&mut PgConnection, crate::Result<Output>) {
+    let result = query("SELECT lo_close($1)")
+        .bind(fd)
+        .execute(&mut *conn)
+        .await;
+
+    (conn, result.map(|_| Output::Closed))
+}
+
+async fn lseek(conn: &mut PgConnection, fd: i32, offset: i64, whence: i32) -> crate::Result<i64> {
+    query("SELECT lo_lseek64($1, $2, $3)")
+        .bind(fd)
+        .bind(offset)
+        .bind(whence)
+        .try_map(|row: PgRow| row.try_get(0))
+        .fetch_one(conn)
+        .await
+}
+
+fn into_io_error(error: crate::Error) -> io::Error {
+    match error {
+        crate::Error::Io(error) => error,
+        error => io::Error::new(io::ErrorKind::Other, error),
+    }
+}
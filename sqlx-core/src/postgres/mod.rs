@@ -5,18 +5,29 @@ pub use connection::PgConnection;
 pub use cursor::PgCursor;
 pub use database::Postgres;
 pub use error::PgError;
+pub use large_object::{PgLargeObject, PgLargeObjectMode};
 pub use listen::{PgListener, PgNotification};
+pub use replication::PgLogicalChange;
 pub use row::{PgRow, PgValue};
-pub use types::PgTypeInfo;
+pub use types::{PgArrayElement, PgLsn, PgRange, PgTid, PgTsQuery, PgTsVector, PgTypeInfo};
+
+#[cfg(feature = "geo")]
+pub use types::PgGeometry;
 
 mod arguments;
+#[cfg(feature = "auto-explain")]
+mod auto_explain;
 mod connection;
 mod cursor;
 mod database;
 mod error;
 mod executor;
+#[cfg(feature = "json")]
+mod explain;
+mod large_object;
 mod listen;
 mod protocol;
+mod replication;
 mod row;
 mod sasl;
 mod stream;
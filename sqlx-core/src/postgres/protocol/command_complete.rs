@@ -3,6 +3,9 @@ use crate::io::Buf;
 #[derive(Debug)]
 pub(crate) struct CommandComplete {
     pub(crate) affected_rows: u64,
+
+    // The tag is `SELECT` or `FETCH`, whose count is of the rows returned
+    pub(crate) selected: bool,
 }
 
 impl CommandComplete {
@@ -11,8 +14,9 @@ impl CommandComplete {
         // If it can't be parsed, the tag is probably "CREATE TABLE" or something
         // and we should return 0 rows
 
-        let rows = buf
-            .get_str_nul()?
+        let tag = buf.get_str_nul()?;
+
+        let rows = tag
             .rsplit(' ')
             .next()
             .and_then(|s| s.parse().ok())
@@ -20,6 +24,7 @@ impl CommandComplete {
 
         Ok(Self {
             affected_rows: rows,
+            selected: tag.starts_with("SELECT ") || tag.starts_with("FETCH "),
         })
     }
 }
@@ -32,12 +37,22 @@ mod tests {
     const COMMAND_COMPLETE_UPDATE: &[u8] = b"UPDATE 512\0";
     const COMMAND_COMPLETE_CREATE_TABLE: &[u8] = b"CREATE TABLE\0";
     const COMMAND_COMPLETE_BEGIN: &[u8] = b"BEGIN\0";
+    const COMMAND_COMPLETE_SELECT: &[u8] = b"SELECT 42\0";
 
     #[test]
     fn it_reads_command_complete_for_insert() {
         let message = CommandComplete::read(COMMAND_COMPLETE_INSERT).unwrap();
 
         assert_eq!(message.affected_rows, 1);
+        assert!(!message.selected);
+    }
+
+    #[test]
+    fn it_reads_command_complete_for_select() {
+        let message = CommandComplete::read(COMMAND_COMPLETE_SELECT).unwrap();
+
+        assert_eq!(message.affected_rows, 42);
+        assert!(message.selected);
     }
 
     #[test]
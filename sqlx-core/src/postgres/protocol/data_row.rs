@@ -1,11 +1,12 @@
 use crate::io::Buf;
 use byteorder::NetworkEndian;
+use std::borrow::Cow;
 use std::ops::Range;
 
 pub(crate) struct DataRow<'c> {
     len: u16,
-    buffer: &'c [u8],
-    values: &'c [Option<Range<u32>>],
+    buffer: Cow<'c, [u8]>,
+    values: Cow<'c, [Option<Range<u32>>]>,
 }
 
 impl<'c> DataRow<'c> {
@@ -13,11 +14,20 @@ impl<'c> DataRow<'c> {
         self.len as usize
     }
 
-    pub(crate) fn get(&self, index: usize) -> Option<&'c [u8]> {
+    pub(crate) fn get(&self, index: usize) -> Option<&[u8]> {
         let range = self.values[index].as_ref()?;
 
         Some(&self.buffer[(range.start as usize)..(range.end as usize)])
     }
+
+    // Copies the message out of the read buffer of the connection
+    pub(crate) fn into_owned(self) -> DataRow<'static> {
+        DataRow {
+            len: self.len,
+            buffer: Cow::Owned(self.buffer.into_owned()),
+            values: Cow::Owned(self.values.into_owned()),
+        }
+    }
 }
 
 impl<'c> DataRow<'c> {
@@ -54,10 +64,12 @@ impl<'c> DataRow<'c> {
             }
         }
 
+        // `buffer` goes on past the end of this message; `index` is 4 bytes past the end of the
+        // last value
         Ok(Self {
             len,
-            buffer,
-            values,
+            buffer: Cow::Borrowed(&buffer[..(index - 4) as usize]),
+            values: Cow::Borrowed(values),
         })
     }
 }
@@ -11,6 +11,7 @@ pub enum Message {
     DataRow,
     EmptyQueryResponse,
     ErrorResponse,
+    NegotiateProtocolVersion,
     NoData,
     NoticeResponse,
     NotificationResponse,
@@ -45,6 +46,7 @@ impl TryFrom<u8> for Message {
             b't' => Message::ParameterDescription,
             b'T' => Message::RowDescription,
             b'I' => Message::EmptyQueryResponse,
+            b'v' => Message::NegotiateProtocolVersion,
 
             id => {
                 return Err(protocol_err!("unknown message: {:?}", id).into());
@@ -10,6 +10,7 @@ pub use type_id::TypeId;
 
 // REQUESTS
 mod bind;
+mod close;
 mod describe;
 mod execute;
 mod parse;
@@ -24,6 +25,7 @@ mod sync;
 mod terminate;
 
 pub(crate) use bind::Bind;
+pub(crate) use close::Close;
 pub(crate) use describe::Describe;
 pub(crate) use execute::Execute;
 pub(crate) use parse::Parse;
@@ -42,8 +44,10 @@ mod authentication;
 mod backend_key_data;
 mod command_complete;
 mod data_row;
+mod negotiate_protocol_version;
 mod notification_response;
 mod parameter_description;
+mod parameter_status;
 mod ready_for_query;
 mod response;
 mod row_description;
@@ -57,8 +61,10 @@ pub(crate) use backend_key_data::BackendKeyData;
 pub(crate) use command_complete::CommandComplete;
 pub(crate) use data_row::DataRow;
 pub(crate) use message::Message;
+pub(crate) use negotiate_protocol_version::NegotiateProtocolVersion;
 pub(crate) use notification_response::NotificationResponse;
 pub(crate) use parameter_description::ParameterDescription;
+pub(crate) use parameter_status::ParameterStatus;
 pub(crate) use ready_for_query::ReadyForQuery;
 pub(crate) use response::Response;
 pub(crate) use row_description::{Field, RowDescription};
@@ -0,0 +1,58 @@
+use crate::io::Buf;
+use byteorder::NetworkEndian;
+
+/// Sent during startup if the server does not support the minor protocol version or the
+/// protocol options (those starting with `_pq_.`) requested by the client.
+#[derive(Debug)]
+pub struct NegotiateProtocolVersion {
+    /// The newest minor version of the requested major version that the server supports.
+    pub minor_version: u32,
+
+    /// The protocol options the server did not recognize.
+    pub unrecognized_options: Box<[Box<str>]>,
+}
+
+impl NegotiateProtocolVersion {
+    pub(crate) fn read(mut buf: &[u8]) -> crate::Result<Self> {
+        let minor_version = buf.get_u32::<NetworkEndian>()?;
+        let len = buf.get_u32::<NetworkEndian>()?;
+
+        let mut unrecognized_options = Vec::with_capacity(len as usize);
+
+        for _ in 0..len {
+            unrecognized_options.push(buf.get_str_nul()?.into());
+        }
+
+        Ok(Self {
+            minor_version,
+            unrecognized_options: unrecognized_options.into_boxed_slice(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NegotiateProtocolVersion;
+
+    const NEGOTIATE_PROTOCOL_VERSION: &[u8] =
+        b"\0\0\0\0\0\0\0\x02_pq_.compression\0_pq_.report_parameters\0";
+
+    #[test]
+    fn it_decodes_negotiate_protocol_version() {
+        let message = NegotiateProtocolVersion::read(NEGOTIATE_PROTOCOL_VERSION).unwrap();
+
+        assert_eq!(message.minor_version, 0);
+        assert_eq!(
+            &*message.unrecognized_options,
+            &["_pq_.compression".into(), "_pq_.report_parameters".into()]
+        );
+    }
+
+    #[test]
+    fn it_decodes_negotiate_protocol_version_without_options() {
+        let message = NegotiateProtocolVersion::read(b"\0\0\0\x01\0\0\0\0").unwrap();
+
+        assert_eq!(message.minor_version, 1);
+        assert!(message.unrecognized_options.is_empty());
+    }
+}
@@ -1,20 +1,9 @@
-#[derive(Debug)]
-#[repr(u8)]
-pub enum TransactionStatus {
-    /// Not in a transaction block.
-    Idle = b'I',
-
-    /// In a transaction block.
-    Transaction = b'T',
-
-    /// In a _failed_ transaction block. Queries will be rejected until block is ended.
-    Error = b'E',
-}
+use crate::connection::TransactionStatus;
 
 /// `ReadyForQuery` is sent whenever the database is ready for a new query cycle.
 #[derive(Debug)]
 pub struct ReadyForQuery {
-    status: TransactionStatus,
+    pub(crate) status: TransactionStatus,
 }
 
 impl ReadyForQuery {
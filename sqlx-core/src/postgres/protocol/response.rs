@@ -1,4 +1,5 @@
 use crate::io::Buf;
+use crate::notice::{Notice, NoticeSeverity};
 use std::str::{self, FromStr};
 
 #[derive(Debug, Copy, Clone)]
@@ -22,6 +23,19 @@ impl Severity {
     }
 }
 
+impl From<Severity> for NoticeSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Panic | Severity::Fatal | Severity::Error => NoticeSeverity::Error,
+            Severity::Warning => NoticeSeverity::Warning,
+            Severity::Notice => NoticeSeverity::Notice,
+            Severity::Debug => NoticeSeverity::Debug,
+            Severity::Info => NoticeSeverity::Info,
+            Severity::Log => NoticeSeverity::Log,
+        }
+    }
+}
+
 impl FromStr for Severity {
     type Err = crate::Error;
 
@@ -43,7 +57,7 @@ impl FromStr for Severity {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Response {
     pub(crate) severity: Severity,
     pub(crate) code: Box<str>,
@@ -65,6 +79,14 @@ pub(crate) struct Response {
 }
 
 impl Response {
+    pub(crate) fn into_notice(self) -> Notice {
+        Notice {
+            severity: self.severity.into(),
+            code: self.code,
+            message: self.message,
+        }
+    }
+
     pub(crate) fn read(mut buf: &[u8]) -> crate::Result<Self> {
         let mut code = None::<Box<str>>;
         let mut message = None::<Box<str>>;
@@ -5,16 +5,19 @@ use byteorder::NetworkEndian;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 
-pub(crate) struct SaslInitialResponse<'a>(pub(crate) &'a str);
+pub(crate) struct SaslInitialResponse<'a> {
+    pub(crate) mechanism: &'a str,
+    pub(crate) response: &'a str,
+}
 
 impl<'a> Write for SaslInitialResponse<'a> {
     fn write(&self, buf: &mut Vec<u8>) {
-        let len = self.0.as_bytes().len() as u32;
+        let len = self.response.len() as u32;
         buf.push(b'p');
-        buf.put_u32::<NetworkEndian>(4u32 + len + 14u32 + 4u32);
-        buf.put_str_nul("SCRAM-SHA-256");
+        buf.put_u32::<NetworkEndian>(4u32 + self.mechanism.len() as u32 + 1 + 4u32 + len);
+        buf.put_str_nul(self.mechanism);
         buf.put_u32::<NetworkEndian>(len);
-        buf.extend_from_slice(self.0.as_bytes());
+        buf.extend_from_slice(self.response.as_bytes());
     }
 }
 
@@ -49,3 +52,35 @@ pub(crate) fn hi<'a>(s: &'a str, salt: &'a [u8], iter_count: u32) -> Result<[u8;
 
     Ok(hi.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SaslInitialResponse, Write};
+
+    const SCRAM_SHA_256: &[u8] = b"p\0\0\0\x1fSCRAM-SHA-256\0\0\0\0\tn,,n=,r=x";
+    const SCRAM_SHA_256_PLUS: &[u8] =
+        b"p\0\0\09SCRAM-SHA-256-PLUS\0\0\0\0\x1ep=tls-server-end-point,,n=,r=x";
+
+    #[test]
+    fn it_writes_sasl_initial_response() {
+        let mut buf = Vec::new();
+        let m = SaslInitialResponse {
+            mechanism: "SCRAM-SHA-256",
+            response: "n,,n=,r=x",
+        };
+
+        m.write(&mut buf);
+
+        assert_eq!(buf, SCRAM_SHA_256);
+
+        let mut buf = Vec::new();
+        let m = SaslInitialResponse {
+            mechanism: "SCRAM-SHA-256-PLUS",
+            response: "p=tls-server-end-point,,n=,r=x",
+        };
+
+        m.write(&mut buf);
+
+        assert_eq!(buf, SCRAM_SHA_256_PLUS);
+    }
+}
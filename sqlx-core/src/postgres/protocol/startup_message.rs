@@ -3,6 +3,10 @@ use crate::postgres::protocol::Write;
 use byteorder::{BigEndian, ByteOrder, NetworkEndian};
 
 pub struct StartupMessage<'a> {
+    /// The minor version of protocol 3 to request.
+    pub minor_version: u16,
+
+    /// Runtime parameters, followed by any protocol options (named `_pq_.<option>`).
     pub params: &'a [(&'a str, &'a str)],
 }
 
@@ -11,8 +15,9 @@ impl Write for StartupMessage<'_> {
         let pos = buf.len();
         buf.put_i32::<NetworkEndian>(0); // skip over len
 
-        // protocol version number (3.0)
-        buf.put_i32::<NetworkEndian>(196_608);
+        // protocol version number; the major version (3) in the high 16 bits
+        buf.put_u16::<NetworkEndian>(3);
+        buf.put_u16::<NetworkEndian>(self.minor_version);
 
         for (name, value) in self.params {
             buf.put_str_nul(name);
@@ -37,6 +42,7 @@ mod tests {
     fn it_encodes_startup_message() {
         let mut buf = Vec::new();
         let m = StartupMessage {
+            minor_version: 0,
             params: &[("user", "postgres"), ("database", "postgres")],
         };
 
@@ -44,4 +50,18 @@ mod tests {
 
         assert_eq!(buf, STARTUP_MESSAGE);
     }
+
+    #[test]
+    fn it_encodes_the_minor_version_and_protocol_options() {
+        let mut buf = Vec::new();
+        let m = StartupMessage {
+            minor_version: 2,
+            params: &[("user", "postgres"), ("_pq_.option", "on")],
+        };
+
+        m.write(&mut buf);
+
+        assert_eq!(&buf[4..8], b"\0\x03\0\x02");
+        assert_eq!(&buf[8..], &b"user\0postgres\0_pq_.option\0on\0\0"[..]);
+    }
 }
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TypeId(pub(crate) u32);
 
 #[allow(dead_code)]
@@ -14,7 +14,14 @@ impl TypeId {
     pub(crate) const FLOAT4: TypeId = TypeId(700);
     pub(crate) const FLOAT8: TypeId = TypeId(701);
 
+    pub(crate) const NUMERIC: TypeId = TypeId(1700);
+
     pub(crate) const TEXT: TypeId = TypeId(25);
+    pub(crate) const VARCHAR: TypeId = TypeId(1043);
+    pub(crate) const BPCHAR: TypeId = TypeId(1042);
+    pub(crate) const NAME: TypeId = TypeId(19);
+
+    pub(crate) const XML: TypeId = TypeId(142);
 
     pub(crate) const DATE: TypeId = TypeId(1082);
     pub(crate) const TIME: TypeId = TypeId(1083);
@@ -25,6 +32,24 @@ impl TypeId {
 
     pub(crate) const UUID: TypeId = TypeId(2950);
 
+    pub(crate) const TSVECTOR: TypeId = TypeId(3614);
+    pub(crate) const TSQUERY: TypeId = TypeId(3615);
+
+    pub(crate) const PG_LSN: TypeId = TypeId(3220);
+
+    pub(crate) const TID: TypeId = TypeId(27);
+
+    // Composites; the type of an anonymous `ROW(..)`
+    pub(crate) const RECORD: TypeId = TypeId(2249);
+
+    // Ranges
+
+    pub(crate) const INT4RANGE: TypeId = TypeId(3904);
+    pub(crate) const INT8RANGE: TypeId = TypeId(3926);
+    pub(crate) const DATERANGE: TypeId = TypeId(3912);
+    pub(crate) const TSRANGE: TypeId = TypeId(3908);
+    pub(crate) const TSTZRANGE: TypeId = TypeId(3910);
+
     // Arrays
 
     pub(crate) const ARRAY_BOOL: TypeId = TypeId(1000);
@@ -36,7 +61,14 @@ impl TypeId {
     pub(crate) const ARRAY_FLOAT4: TypeId = TypeId(1021);
     pub(crate) const ARRAY_FLOAT8: TypeId = TypeId(1022);
 
+    pub(crate) const ARRAY_NUMERIC: TypeId = TypeId(1231);
+
     pub(crate) const ARRAY_TEXT: TypeId = TypeId(1009);
+    pub(crate) const ARRAY_VARCHAR: TypeId = TypeId(1015);
+    pub(crate) const ARRAY_BPCHAR: TypeId = TypeId(1014);
+    pub(crate) const ARRAY_NAME: TypeId = TypeId(1003);
+
+    pub(crate) const ARRAY_XML: TypeId = TypeId(143);
 
     pub(crate) const ARRAY_DATE: TypeId = TypeId(1182);
     pub(crate) const ARRAY_TIME: TypeId = TypeId(1183);
@@ -46,4 +78,13 @@ impl TypeId {
     pub(crate) const ARRAY_BYTEA: TypeId = TypeId(1001);
 
     pub(crate) const ARRAY_UUID: TypeId = TypeId(2951);
+
+    pub(crate) const ARRAY_TSVECTOR: TypeId = TypeId(3643);
+    pub(crate) const ARRAY_TSQUERY: TypeId = TypeId(3645);
+
+    pub(crate) const ARRAY_PG_LSN: TypeId = TypeId(3221);
+
+    pub(crate) const ARRAY_TID: TypeId = TypeId(1010);
+
+    pub(crate) const ARRAY_RECORD: TypeId = TypeId(2287);
 }
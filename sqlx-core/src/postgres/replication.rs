@@ -0,0 +1,106 @@
+//! Logical decoding through the SQL functions of Postgres, which work on an ordinary connection.
+//!
+//! The streaming replication protocol itself is not supported; see [`PgConnection::connect`].
+//!
+//! https://www.postgresql.org/docs/12/logicaldecoding-example.html
+
+use std::convert::TryFrom;
+
+use crate::postgres::{PgConnection, PgLsn, PgRow};
+use crate::query::query;
+use crate::row::Row;
+
+/// A change read from a logical replication slot, as the output plugin of the slot wrote it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgLogicalChange {
+    /// The position of the change in the write-ahead log.
+    pub lsn: PgLsn,
+
+    /// The transaction that made the change.
+    pub xid: u32,
+
+    /// The change, such as `table public.users: INSERT: id[integer]:1` for `test_decoding`.
+    pub data: String,
+}
+
+impl PgConnection {
+    /// Creates a logical replication slot that decodes changes with the output plugin
+    /// `plugin`, such as `test_decoding` or `wal2json`, and returns the position from which it
+    /// has them.
+    ///
+    /// The slot keeps the write-ahead log it has not been read up to until it is dropped, even
+    /// if nothing reads from it; see [`drop_replication_slot`](Self::drop_replication_slot).
+    /// This needs the `REPLICATION` attribute and `wal_level` to be `logical`.
+    pub async fn create_logical_replication_slot(
+        &mut self,
+        name: &str,
+        plugin: &str,
+    ) -> crate::Result<PgLsn> {
+        query("SELECT lsn FROM pg_create_logical_replication_slot($1::name, $2::name)")
+            .bind(name)
+            .bind(plugin)
+            .try_map(|row: PgRow| row.try_get(0))
+            .fetch_one(&mut *self)
+            .await
+    }
+
+    /// Drops the replication slot `name`, logical or physical.
+    pub async fn drop_replication_slot(&mut self, name: &str) -> crate::Result<()> {
+        query("SELECT pg_drop_replication_slot($1::name)")
+            .bind(name)
+            .execute(&mut *self)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns up to `limit` of the changes waiting in the logical replication slot `name`,
+    /// oldest first, without consuming them; they are returned again by the next call.
+    ///
+    /// Only whole transactions are returned, so there can be more than `limit` changes.
+    pub async fn logical_peek(
+        &mut self,
+        name: &str,
+        limit: u32,
+    ) -> crate::Result<Vec<PgLogicalChange>> {
+        self.logical_changes("pg_logical_slot_peek_changes", name, limit)
+            .await
+    }
+
+    /// Like [`logical_peek`](Self::logical_peek), but consumes the changes it returns, so the
+    /// slot no longer holds on to them.
+    pub async fn logical_get(
+        &mut self,
+        name: &str,
+        limit: u32,
+    ) -> crate::Result<Vec<PgLogicalChange>> {
+        self.logical_changes("pg_logical_slot_get_changes", name, limit)
+            .await
+    }
+
+    async fn logical_changes(
+        &mut self,
+        function: &str,
+        name: &str,
+        limit: u32,
+    ) -> crate::Result<Vec<PgLogicalChange>> {
+        // an `xid` has no binary format of its own to decode
+        let statement = format!(
+            "SELECT lsn, xid::text::int8, data FROM {}($1::name, NULL, $2)",
+            function
+        );
+
+        query(&statement)
+            .bind(name)
+            .bind(i32::try_from(limit).unwrap_or(i32::max_value()))
+            .try_map(|row: PgRow| {
+                Ok(PgLogicalChange {
+                    lsn: row.try_get(0)?,
+                    xid: row.try_get::<i64, _>(1)? as u32,
+                    data: row.try_get(2)?,
+                })
+            })
+            .fetch_all(&mut *self)
+            .await
+    }
+}
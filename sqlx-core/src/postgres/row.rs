@@ -4,10 +4,13 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
+use crate::decode::Decode;
 use crate::error::UnexpectedNullError;
-use crate::postgres::protocol::{DataRow, TypeFormat};
-use crate::postgres::Postgres;
-use crate::row::{ColumnIndex, Row};
+use crate::postgres::protocol::{DataRow, TypeFormat, TypeId};
+use crate::postgres::types::SharedStr;
+use crate::postgres::{PgTypeInfo, Postgres};
+use crate::row::{self, ColumnIndex, Row};
+use crate::types::Type;
 
 /// A value from Postgres. This may be in a BINARY or TEXT format depending
 /// on the data type and if the query was prepared or not.
@@ -32,6 +35,9 @@ pub struct PgRow<'c> {
     pub(super) data: DataRow<'c>,
     pub(super) columns: Arc<HashMap<Box<str>, usize>>,
     pub(super) formats: Arc<[TypeFormat]>,
+    pub(super) types: Arc<[TypeId]>,
+    pub(super) strict_types: bool,
+    pub(super) registered_types: Arc<HashMap<TypeId, SharedStr>>,
 }
 
 impl<'c> Row<'c> for PgRow<'c> {
@@ -41,6 +47,19 @@ impl<'c> Row<'c> for PgRow<'c> {
         self.data.len()
     }
 
+    fn try_get<'r, T, I>(&'r self, index: I) -> crate::Result<T>
+    where
+        'c: 'r,
+        T: Type<Self::Database>,
+        I: ColumnIndex<Self::Database>,
+        T: Decode<'r, Self::Database>,
+    {
+        let index = index.resolve(self)?;
+
+        self.decode::<T>(index)
+            .map_err(|err| row::column_error::<T, _>(err, || self.column_name(index)))
+    }
+
     fn try_get_raw<'r, I>(&'r self, index: I) -> crate::Result<Option<PgValue<'r>>>
     where
         'c: 'r,
@@ -57,4 +76,59 @@ impl<'c> Row<'c> for PgRow<'c> {
             .transpose()
             .map_err(|err: Utf8Error| crate::Error::Decode(Box::new(err)))
     }
+
+    fn into_owned(self) -> PgRow<'static> {
+        PgRow {
+            data: self.data.into_owned(),
+            columns: self.columns,
+            formats: self.formats,
+            types: self.types,
+            strict_types: self.strict_types,
+            registered_types: self.registered_types,
+        }
+    }
+}
+
+impl<'c> PgRow<'c> {
+    fn decode<'r, T>(&'r self, index: usize) -> crate::Result<T>
+    where
+        'c: 'r,
+        T: Type<Postgres>,
+        T: Decode<'r, Postgres>,
+    {
+        let value = self.try_get_raw(index)?;
+
+        // a value in the text format is parsed, which rejects anything that is not valid for
+        // `T`, while the bytes of a binary value would be misread
+        let check = match value {
+            Some(PgValue::Binary(_)) => true,
+            Some(PgValue::Text(_)) => self.strict_types,
+            None => false,
+        };
+
+        if check {
+            if let Some(&id) = self.types.get(index) {
+                let type_info = match self.registered_types.get(&id) {
+                    Some(name) => PgTypeInfo::new(id, name),
+                    None => PgTypeInfo::from_column_type(id),
+                };
+
+                // types that are neither builtin nor registered, such as an enum or one from an
+                // extension, can't be told apart from the types of `T` and are let through
+                if type_info.name.is_some() {
+                    row::check_type::<Postgres, T>(&type_info, self.strict_types)?;
+                }
+            }
+        }
+
+        Decode::decode(value)
+    }
+
+    // The name of the column at `index`, or the index if it has none
+    fn column_name(&self, index: usize) -> Box<str> {
+        self.columns
+            .iter()
+            .find(|&(_, &i)| i == index)
+            .map_or_else(|| index.to_string().into(), |(name, _)| name.clone())
+    }
 }
@@ -7,11 +7,56 @@ use crate::postgres::protocol::{
 };
 use crate::postgres::stream::PgStream;
 
-static GS2_HEADER: &'static str = "n,,";
 static CHANNEL_ATTR: &'static str = "c";
 static USERNAME_ATTR: &'static str = "n";
 static CLIENT_PROOF_ATTR: &'static str = "p";
 static NONCE_ATTR: &'static str = "r";
+static VERIFIER_ATTR: &'static str = "v";
+static ERROR_ATTR: &'static str = "e";
+
+// How the authentication is bound to the TLS session it happens over, if any
+// https://tools.ietf.org/html/rfc5802#section-6
+pub(super) enum ChannelBinding {
+    // The connection does not use TLS
+    Unsupported,
+
+    // The connection uses TLS but the server does not offer SCRAM-SHA-256-PLUS
+    NotOffered,
+
+    // SCRAM-SHA-256-PLUS, bound to the hash of the certificate of the server
+    // https://tools.ietf.org/html/rfc5929#section-4
+    TlsServerEndPoint(Vec<u8>),
+}
+
+impl ChannelBinding {
+    fn mechanism(&self) -> &'static str {
+        match self {
+            ChannelBinding::TlsServerEndPoint(_) => "SCRAM-SHA-256-PLUS",
+            _ => "SCRAM-SHA-256",
+        }
+    }
+
+    // gs2-header = gs2-cbind-flag "," [ authzid ] ","
+    fn gs2_header(&self) -> &'static str {
+        match self {
+            ChannelBinding::Unsupported => "n,,",
+            ChannelBinding::NotOffered => "y,,",
+            ChannelBinding::TlsServerEndPoint(_) => "p=tls-server-end-point,,",
+        }
+    }
+
+    // channel-binding = "c=" base64 ;; base64 encoding of cbind-input
+    // cbind-input = gs2-header [ cbind-data ]
+    fn attr(&self) -> String {
+        let mut input = self.gs2_header().as_bytes().to_vec();
+
+        if let ChannelBinding::TlsServerEndPoint(hash) = self {
+            input.extend_from_slice(hash);
+        }
+
+        format!("{}={}", CHANNEL_ATTR, base64::encode(&input))
+    }
+}
 
 // Nonce generator
 // Nonce is a sequence of random printable bytes
@@ -37,7 +82,7 @@ fn nonce() -> String {
         .collect();
 
     rng.gen_range(32, 128);
-    format!("{}={}", NONCE_ATTR, nonce)
+    nonce
 }
 
 // Performs authenticiton using Simple Authentication Security Layer (SASL) which is what
@@ -46,23 +91,31 @@ pub(super) async fn authenticate<T: AsRef<str>>(
     stream: &mut PgStream,
     username: T,
     password: T,
+    binding: ChannelBinding,
 ) -> crate::Result<()> {
     // channel-binding = "c=" base64
-    let channel_binding = format!("{}={}", CHANNEL_ATTR, base64::encode(GS2_HEADER));
+    let channel_binding = binding.attr();
     // "n=" saslname ;; Usernames are prepared using SASLprep.
     let username = format!("{}={}", USERNAME_ATTR, username.as_ref());
     // nonce = "r=" c-nonce [s-nonce] ;; Second part provided by server.
-    let nonce = nonce();
-    let client_first_message_bare =
-        format!("{username},{nonce}", username = username, nonce = nonce);
+    let client_nonce = nonce();
+    let client_first_message_bare = format!(
+        "{username},{nonce_attr}={nonce}",
+        username = username,
+        nonce_attr = NONCE_ATTR,
+        nonce = client_nonce
+    );
     // client-first-message-bare = [reserved-mext ","] username "," nonce ["," extensions]
     let client_first_message = format!(
         "{gs2_header}{client_first_message_bare}",
-        gs2_header = GS2_HEADER,
+        gs2_header = binding.gs2_header(),
         client_first_message_bare = client_first_message_bare
     );
 
-    stream.write(SaslInitialResponse(&client_first_message));
+    stream.write(SaslInitialResponse {
+        mechanism: binding.mechanism(),
+        response: &client_first_message,
+    });
     stream.flush().await?;
 
     let server_first_message = stream.receive().await?;
@@ -76,6 +129,13 @@ pub(super) async fn authenticate<T: AsRef<str>>(
 
             let server_first_message = sasl.data;
 
+            // the nonce of the server starts with ours, so the exchange can't be replayed
+            if !sasl.nonce.starts_with(client_nonce.as_bytes()) {
+                return Err(
+                    protocol_err!("SASL: server nonce does not start with client nonce").into(),
+                );
+            }
+
             // SaltedPassword := Hi(Normalize(password), salt, i)
             let salted_password = hi(password.as_ref(), &sasl.salt, sasl.iter_count)?;
 
@@ -127,7 +187,7 @@ pub(super) async fn authenticate<T: AsRef<str>>(
             let mut mac =
                 Hmac::<Sha256>::new_varkey(&server_key).expect("HMAC can take key of any size");
             mac.input(&auth_message.as_bytes());
-            let _server_signature = mac.result().code();
+            let server_signature = mac.result().code();
 
             // client-final-message = client-final-message-without-proof "," proof
             let client_final_message = format!(
@@ -140,10 +200,38 @@ pub(super) async fn authenticate<T: AsRef<str>>(
             stream.write(SaslResponse(&client_final_message));
             stream.flush().await?;
 
-            let _server_final_response = stream.receive().await?;
-            // todo: assert that this was SaslFinal?
+            // server-final-message = (server-error / verifier) ["," extensions]
+            if let Message::Authentication = stream.receive().await? {
+                if let Authentication::SaslFinal = Authentication::read(stream.buffer())? {
+                    let attrs = &stream.buffer()[4..];
+                    let first = attrs.split(|&b| b == b',').next().unwrap_or_default();
+
+                    if first.starts_with(format!("{}=", VERIFIER_ATTR).as_bytes()) {
+                        let verifier = base64::decode(&first[2..]).map_err(|_| {
+                            protocol_err!("SASL: server signature was not base64 encoded")
+                        })?;
+
+                        // the server proves that it knows the password too
+                        if verifier[..] != server_signature[..] {
+                            return Err(
+                                protocol_err!("SASL: server signature does not match").into()
+                            );
+                        }
+
+                        return Ok(());
+                    }
+
+                    if first.starts_with(format!("{}=", ERROR_ATTR).as_bytes()) {
+                        return Err(protocol_err!(
+                            "SASL: server error: {}",
+                            String::from_utf8_lossy(&first[2..])
+                        )
+                        .into());
+                    }
+                }
+            }
 
-            Ok(())
+            Err(protocol_err!("SASL: expected the final message of the server").into())
         } else {
             Err(protocol_err!(
                 "Expected Authentication::SaslContinue, but received {:?}",
@@ -1,12 +1,24 @@
+use std::collections::HashMap;
 use std::convert::TryInto;
+use std::io;
 use std::net::Shutdown;
 
 use byteorder::NetworkEndian;
 use futures_channel::mpsc::UnboundedSender;
 
-use crate::io::{Buf, BufStream, MaybeTlsStream};
-use crate::postgres::protocol::{Message, NotificationResponse, Response, Write};
+use crate::connection::TransactionStatus;
+use crate::io::{
+    Buf, BufStream, MaybeTlsStream, DEFAULT_MAX_MESSAGE_SIZE, DEFAULT_READ_BUFFER_SIZE,
+    DEFAULT_WRITE_BUFFER_SIZE,
+};
+use crate::notice::NoticeHandler;
+use crate::postgres::protocol::{
+    Message, NotificationResponse, ParameterStatus, ReadyForQuery, Response, Write,
+};
 use crate::postgres::PgError;
+
+#[cfg(feature = "protocol-trace")]
+use crate::io::trace::{self, Direction};
 use crate::url::Url;
 use futures_util::SinkExt;
 
@@ -18,17 +30,61 @@ pub struct PgStream {
     // Is referenced by our buffered stream
     // Is initialized to ReadyForQuery/0 at the start
     pub(super) message: (Message, u32),
+
+    // Longest message that is read; see [DEFAULT_MAX_MESSAGE_SIZE]
+    max_message_size: usize,
+
+    // Set while a flush is in progress; if that future is dropped part way through, the server
+    // may have received an incomplete message and we can't continue the conversation
+    pub(super) is_broken: bool,
+
+    // Transaction status from the most recent [ReadyForQuery]
+    pub(super) transaction_status: TransactionStatus,
+
+    // The error that caused the current transaction to fail; later statements in it fail with
+    // an error of their own that says nothing about the cause
+    pub(super) transaction_error: Option<PgError>,
+
+    // Whether a [ParseComplete] was received since the last flush
+    pub(super) parse_complete: bool,
+
+    // The run-time parameters reported by the server with [ParameterStatus], at startup and
+    // whenever one of them changes
+    pub(super) parameters: HashMap<Box<str>, Box<str>>,
+
+    // Receives the notices and warnings of the server; they are dropped without one
+    pub(super) notice_handler: Option<NoticeHandler>,
+
+    #[cfg(feature = "bench-internals")]
+    pub(super) timer: crate::bench::PhaseTimer,
 }
 
 impl PgStream {
     pub(super) async fn new(url: &Url) -> crate::Result<Self> {
         let stream = MaybeTlsStream::connect(&url, 5432).await?;
 
-        Ok(Self {
+        Ok(Self::with_stream(stream, url))
+    }
+
+    // Wraps a stream that is connected to the server, before the startup
+    pub(super) fn with_stream(stream: MaybeTlsStream, url: &Url) -> Self {
+        let read_buffer_size = url.param_or("read_buffer_size", DEFAULT_READ_BUFFER_SIZE);
+        let write_buffer_size = url.param_or("write_buffer_size", DEFAULT_WRITE_BUFFER_SIZE);
+
+        Self {
             notifications: None,
-            stream: BufStream::new(stream),
+            stream: BufStream::with_capacity(stream, read_buffer_size, write_buffer_size),
             message: (Message::ReadyForQuery, 0),
-        })
+            max_message_size: url.param_or("max_message_size", DEFAULT_MAX_MESSAGE_SIZE),
+            is_broken: false,
+            transaction_status: TransactionStatus::Idle,
+            transaction_error: None,
+            parse_complete: false,
+            parameters: HashMap::new(),
+            notice_handler: None,
+            #[cfg(feature = "bench-internals")]
+            timer: Default::default(),
+        }
     }
 
     pub(super) fn shutdown(&self) -> crate::Result<()> {
@@ -40,12 +96,41 @@ impl PgStream {
     where
         M: Write,
     {
+        #[cfg(feature = "protocol-trace")]
+        let start = self.stream.buffer_mut().len();
+
         message.write(self.stream.buffer_mut());
+
+        #[cfg(feature = "protocol-trace")]
+        {
+            if trace::enabled() {
+                let frame = &self.stream.buffer_mut()[start..];
+                let (name, sensitive) = frontend_message_name(frame);
+
+                trace::frame(Direction::Send, name, frame, sensitive);
+            }
+        }
     }
 
-    #[inline]
     pub(super) async fn flush(&mut self) -> crate::Result<()> {
-        Ok(self.stream.flush().await?)
+        self.is_broken = true;
+        self.parse_complete = false;
+        self.stream.flush().await?;
+        self.is_broken = false;
+
+        Ok(())
+    }
+
+    pub(super) fn check_broken(&self) -> crate::Result<()> {
+        if self.is_broken {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "connection was left in an unknown state by a dropped query and must be closed",
+            )
+            .into());
+        }
+
+        Ok(())
     }
 
     pub(super) async fn read(&mut self) -> crate::Result<Message> {
@@ -64,7 +149,25 @@ impl PgStream {
         let mut header = self.stream.peek(4 + 1).await?;
 
         let type_ = header.get_u8()?.try_into()?;
-        let length = header.get_u32::<NetworkEndian>()? - 4;
+        let length = header
+            .get_u32::<NetworkEndian>()?
+            .checked_sub(4)
+            .ok_or(protocol_err!(
+                "message length shorter than its own length field"
+            ))?;
+
+        if length as usize > self.max_message_size {
+            // the rest of the message is left unread, so the connection can't go on
+            self.is_broken = true;
+
+            return Err(protocol_err!(
+                "{:?} message of {} bytes exceeds the maximum message size of {} bytes",
+                type_,
+                length,
+                self.max_message_size
+            )
+            .into());
+        }
 
         self.message = (type_, length);
         self.stream.consume(4 + 1);
@@ -73,6 +176,15 @@ impl PgStream {
         // inspecting the data. This is then looked at later through the [buffer] function
         let _ = self.stream.peek(length as usize).await?;
 
+        #[cfg(feature = "protocol-trace")]
+        {
+            if trace::enabled() {
+                let name = format!("{:?}", type_);
+
+                trace::frame(Direction::Receive, &name, self.buffer(), false);
+            }
+        }
+
         Ok(type_)
     }
 
@@ -80,17 +192,57 @@ impl PgStream {
         loop {
             let type_ = self.read().await?;
 
+            #[cfg(feature = "bench-internals")]
+            {
+                use crate::bench::Phase;
+
+                match type_ {
+                    Message::ParseComplete => self.timer.finish(Phase::Prepare),
+                    Message::BindComplete => self.timer.finish(Phase::Bind),
+                    Message::DataRow => self.timer.row_received(),
+                    Message::ReadyForQuery => self.timer.done(),
+                    _ => {}
+                }
+            }
+
             match type_ {
                 Message::ErrorResponse | Message::NoticeResponse => {
                     let response = Response::read(self.stream.buffer())?;
 
                     if response.severity.is_error() {
+                        if self.transaction_error.is_none() {
+                            self.transaction_error = Some(PgError(response.clone()));
+                        }
+
                         // This is an error, bubble up as one immediately
                         return Err(crate::Error::Database(Box::new(PgError(response))));
                     }
 
-                    // TODO: Provide some way of receiving these non-critical
-                    //       notices from postgres
+                    if let Some(handler) = &mut self.notice_handler {
+                        handler(response.into_notice());
+                    }
+
+                    continue;
+                }
+
+                Message::ReadyForQuery => {
+                    self.transaction_status = ReadyForQuery::read(self.buffer())?.status;
+
+                    if self.transaction_status != TransactionStatus::Error {
+                        self.transaction_error = None;
+                    }
+                }
+
+                Message::ParseComplete => {
+                    self.parse_complete = true;
+                }
+
+                Message::ParameterStatus => {
+                    let status = ParameterStatus::read(self.buffer())?;
+                    let (name, value) = (status.name.into(), status.value.into());
+
+                    self.parameters.insert(name, value);
+
                     continue;
                 }
 
@@ -119,3 +271,36 @@ impl PgStream {
         &self.stream.buffer()[..(self.message.1 as usize)]
     }
 }
+
+// Names a message written by the client from its first bytes; password messages are sensitive
+// https://www.postgresql.org/docs/12/protocol-message-formats.html
+#[cfg(feature = "protocol-trace")]
+fn frontend_message_name(frame: &[u8]) -> (&'static str, bool) {
+    let name = match frame.first() {
+        // the messages sent before startup have no type, only a length
+        Some(0) if frame.len() == 8 => "SSLRequest",
+        Some(0) if frame.len() == 16 => "CancelRequest",
+        Some(0) => "StartupMessage",
+
+        // also SASLInitialResponse and SASLResponse, which answer a password challenge
+        Some(b'p') => return ("PasswordMessage", true),
+
+        Some(b'Q') => "Query",
+        Some(b'P') => "Parse",
+        Some(b'B') => "Bind",
+        Some(b'D') => "Describe",
+        Some(b'E') => "Execute",
+        Some(b'S') => "Sync",
+        Some(b'H') => "Flush",
+        Some(b'C') => "Close",
+        Some(b'X') => "Terminate",
+        Some(b'F') => "FunctionCall",
+        Some(b'd') => "CopyData",
+        Some(b'c') => "CopyDone",
+        Some(b'f') => "CopyFail",
+
+        _ => "Unknown",
+    };
+
+    (name, false)
+}
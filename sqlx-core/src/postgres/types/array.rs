@@ -0,0 +1,538 @@
+//! One and two dimensional arrays as `Vec<T>` and `Vec<Vec<T>>`.
+//!
+//! https://www.postgresql.org/docs/12/arrays.html
+
+use std::convert::TryInto;
+
+use byteorder::{ByteOrder, NetworkEndian, ReadBytesExt};
+
+use crate::decode::{Decode, DecodeOwned};
+use crate::encode::{Encode, IsNull};
+use crate::io::BufMut;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::{Lossy, Type, TypeInfo};
+use crate::Error;
+
+/// Types that can be an element of a Postgres array.
+///
+/// `[T]` must implement [`Type`] with the type information of the array type.
+///
+/// The elements of an array in the binary format are checked to be of a type that `T` accepts,
+/// naming both types when they are not; only builtin types are known, so the elements of a type
+/// created with `CREATE TYPE` or `CREATE DOMAIN`, such as a named composite, are decoded as `T`
+/// unchecked.
+pub trait PgArrayElement: Type<Postgres> {}
+
+impl PgArrayElement for bool {}
+impl PgArrayElement for i16 {}
+impl PgArrayElement for i32 {}
+impl PgArrayElement for i64 {}
+impl PgArrayElement for f32 {}
+impl PgArrayElement for f64 {}
+impl PgArrayElement for Lossy<f64> {}
+impl PgArrayElement for String {}
+impl PgArrayElement for &'_ str {}
+impl PgArrayElement for &'_ [u8] {}
+impl PgArrayElement for super::PgLsn {}
+impl PgArrayElement for super::PgTid {}
+impl PgArrayElement for std::time::SystemTime {}
+
+#[cfg(feature = "uuid")]
+impl PgArrayElement for uuid::Uuid {}
+
+#[cfg(feature = "chrono")]
+impl PgArrayElement for chrono::NaiveTime {}
+
+#[cfg(feature = "chrono")]
+impl PgArrayElement for chrono::NaiveDate {}
+
+#[cfg(feature = "chrono")]
+impl PgArrayElement for chrono::NaiveDateTime {}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> PgArrayElement for chrono::DateTime<Tz> where
+    chrono::DateTime<Tz>: Type<Postgres>
+{
+}
+
+// NULL elements
+impl<T: PgArrayElement> PgArrayElement for Option<T> {}
+
+impl<T> Type<Postgres> for [Option<T>]
+where
+    [T]: Type<Postgres>,
+{
+    fn type_info() -> PgTypeInfo {
+        <[T] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Type<Postgres> for [Lossy<f64>] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_NUMERIC, "NUMERIC[]")
+    }
+}
+
+impl<T> Type<Postgres> for Vec<T>
+where
+    T: PgArrayElement,
+    [T]: Type<Postgres>,
+{
+    fn type_info() -> PgTypeInfo {
+        <[T] as Type<Postgres>>::type_info()
+    }
+}
+
+// multi-dimensional arrays have the same type as their one dimensional counterpart
+impl<T> Type<Postgres> for Vec<Vec<T>>
+where
+    T: PgArrayElement,
+    [T]: Type<Postgres>,
+{
+    fn type_info() -> PgTypeInfo {
+        <[T] as Type<Postgres>>::type_info()
+    }
+}
+
+impl<T> Encode<Postgres> for Vec<T>
+where
+    T: PgArrayElement + Encode<Postgres>,
+    [T]: Type<Postgres>,
+{
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = self.try_encode(buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        encode_one_dimension(buf, self)
+    }
+
+    fn size_hint(&self) -> usize {
+        one_dimension_size_hint(self)
+    }
+}
+
+impl<T> Encode<Postgres> for Vec<Vec<T>>
+where
+    T: PgArrayElement + Encode<Postgres>,
+    [T]: Type<Postgres>,
+{
+    /// Postgres arrays are rectangular; panics if the inner `Vec`s differ in length.
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = self.try_encode(buf) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Postgres arrays are rectangular; fails if the inner `Vec`s differ in length.
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        let inner_len = self.first().map_or(0, Vec::len);
+
+        if self.iter().any(|inner| inner.len() != inner_len) {
+            return Err(Error::Encode(
+                "arrays must be rectangular; inner Vecs differ in length".into(),
+            ));
+        }
+
+        encode_header::<T>(buf, &[self.len(), inner_len]);
+
+        for element in self.iter().flatten() {
+            encode_element(buf, element)?;
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        28 + self
+            .iter()
+            .flatten()
+            .map(|el| 4 + el.size_hint())
+            .sum::<usize>()
+    }
+}
+
+impl<'de, T> Decode<'de, Postgres> for Vec<T>
+where
+    T: PgArrayElement + DecodeOwned<Postgres>,
+    [T]: Type<Postgres>,
+{
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        decode_one_dimension(value)
+    }
+}
+
+impl<'de, T> Decode<'de, Postgres> for Vec<Vec<T>>
+where
+    T: PgArrayElement + DecodeOwned<Postgres>,
+    [T]: Type<Postgres>,
+{
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(buf) => {
+                let (dims, mut elements) = decode_binary(buf)?;
+
+                match *dims {
+                    [] => Ok(Vec::new()),
+
+                    [rows, cols] => {
+                        let mut out = Vec::with_capacity(rows);
+
+                        for _ in 0..rows {
+                            let rest = elements.split_off(cols);
+                            out.push(elements);
+                            elements = rest;
+                        }
+
+                        Ok(out)
+                    }
+
+                    _ => Err(dimension_err(2, dims.len())),
+                }
+            }
+
+            PgValue::Text(s) => match TextParser::new(s).parse()? {
+                TextNode::List(rows) => rows
+                    .into_iter()
+                    .map(|row| match row {
+                        TextNode::List(items) => items.into_iter().map(TextNode::decode).collect(),
+                        TextNode::Item(_) => Err(dimension_err(2, 1)),
+                    })
+                    .collect(),
+
+                TextNode::Item(_) => Err(dimension_err(2, 0)),
+            },
+        }
+    }
+}
+
+// `Vec<u8>` is a `BYTEA` and not a `PgArrayElement`, as older compilers can't tell the impls
+// for `Vec<T>` and `Vec<Vec<T>>` apart when it is; `BYTEA[]` has impls of its own.
+impl Type<Postgres> for Vec<Vec<u8>> {
+    fn type_info() -> PgTypeInfo {
+        <[Vec<u8>] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for Vec<Vec<u8>> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = self.try_encode(buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        encode_one_dimension(buf, self)
+    }
+
+    fn size_hint(&self) -> usize {
+        one_dimension_size_hint(self)
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for Vec<Vec<u8>> {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        decode_one_dimension(value)
+    }
+}
+
+fn encode_one_dimension<T>(buf: &mut Vec<u8>, elements: &[T]) -> crate::Result<IsNull>
+where
+    T: Type<Postgres> + Encode<Postgres>,
+{
+    encode_header::<T>(buf, &[elements.len()]);
+
+    for element in elements {
+        encode_element(buf, element)?;
+    }
+
+    Ok(IsNull::No)
+}
+
+fn one_dimension_size_hint<T: Encode<Postgres>>(elements: &[T]) -> usize {
+    20 + elements.iter().map(|el| 4 + el.size_hint()).sum::<usize>()
+}
+
+fn decode_one_dimension<T>(value: Option<PgValue<'_>>) -> crate::Result<Vec<T>>
+where
+    T: Type<Postgres> + DecodeOwned<Postgres>,
+{
+    match value.try_into()? {
+        PgValue::Binary(buf) => {
+            let (dims, elements) = decode_binary(buf)?;
+
+            match dims.len() {
+                0 | 1 => Ok(elements),
+                ndim => Err(dimension_err(1, ndim)),
+            }
+        }
+
+        PgValue::Text(s) => match TextParser::new(s).parse()? {
+            TextNode::List(items) => items.into_iter().map(TextNode::decode).collect(),
+            TextNode::Item(_) => Err(dimension_err(1, 0)),
+        },
+    }
+}
+
+fn dimension_err(expected: usize, actual: usize) -> Error {
+    Error::Decode(
+        format!(
+            "expected an array with {} dimension(s) but received one with {}",
+            expected, actual
+        )
+        .into(),
+    )
+}
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/arrayfuncs.c#L1548-L1592
+fn encode_header<T: Type<Postgres>>(buf: &mut Vec<u8>, dims: &[usize]) {
+    // an array without elements has no dimensions
+    let ndim = if dims.contains(&0) { 0 } else { dims.len() };
+
+    buf.put_i32::<NetworkEndian>(ndim as i32);
+
+    // flags; the server works out if there are any NULL elements itself
+    buf.put_i32::<NetworkEndian>(0);
+
+    buf.put_u32::<NetworkEndian>(T::type_info().id.0);
+
+    for &len in &dims[..ndim] {
+        buf.put_i32::<NetworkEndian>(len as i32);
+
+        // lower bound
+        buf.put_i32::<NetworkEndian>(1);
+    }
+}
+
+fn encode_element<T: Encode<Postgres>>(buf: &mut Vec<u8>, element: &T) -> crate::Result<()> {
+    let pos = buf.len();
+
+    buf.put_i32::<NetworkEndian>(0);
+
+    let len = if let IsNull::No = element.try_encode(buf)? {
+        (buf.len() - pos - 4) as i32
+    } else {
+        -1
+    };
+
+    NetworkEndian::write_i32(&mut buf[pos..], len);
+
+    Ok(())
+}
+
+// Returns the length of each dimension and all elements in row-major order. The lower bound
+// of each dimension is ignored as `Vec` is always indexed from zero.
+fn decode_binary<'de, T>(mut buf: &'de [u8]) -> crate::Result<(Vec<usize>, Vec<T>)>
+where
+    T: Type<Postgres> + Decode<'de, Postgres>,
+{
+    let ndim = buf.read_i32::<NetworkEndian>().map_err(Error::decode)?;
+    let _flags = buf.read_i32::<NetworkEndian>().map_err(Error::decode)?;
+    let element_type = buf.read_u32::<NetworkEndian>().map_err(Error::decode)?;
+
+    check_element_type::<T>(TypeId(element_type))?;
+
+    let mut dims = Vec::with_capacity(ndim.max(0) as usize);
+
+    for _ in 0..ndim {
+        let len = buf.read_i32::<NetworkEndian>().map_err(Error::decode)?;
+        let _lower_bound = buf.read_i32::<NetworkEndian>().map_err(Error::decode)?;
+
+        dims.push(len.max(0) as usize);
+    }
+
+    let count = if dims.is_empty() {
+        0
+    } else {
+        dims.iter().product()
+    };
+
+    let mut elements = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let len = buf.read_i32::<NetworkEndian>().map_err(Error::decode)?;
+
+        let value = if len < 0 {
+            None
+        } else {
+            let len = len as usize;
+
+            if buf.len() < len {
+                return Err(
+                    protocol_err!("array element extends past the end of the value").into(),
+                );
+            }
+
+            let (value, rest) = buf.split_at(len);
+            buf = rest;
+
+            Some(PgValue::Binary(value))
+        };
+
+        elements.push(T::decode(value)?);
+    }
+
+    Ok((dims, elements))
+}
+
+// The elements of a binary array are decoded as `T` without going through the type check of
+// the row, so a composite or any other builtin type that `T` does not accept would be misread.
+// Types that are not builtin, such as a domain or a named composite, can't be told apart from
+// the types of `T` without looking them up and are let through.
+fn check_element_type<T: Type<Postgres>>(element_type: TypeId) -> crate::Result<()> {
+    let expected = T::type_info();
+    let actual = PgTypeInfo::from_column_type(element_type);
+
+    if expected.id.0 == 0 || actual.name.is_none() || expected.compatible(&actual) {
+        return Ok(());
+    }
+
+    let element = if element_type == TypeId::RECORD {
+        "composite `record`".to_owned()
+    } else {
+        format!("`{}`", actual)
+    };
+
+    Err(Error::Decode(
+        format!(
+            "unsupported: {}[] (array of {}) decoded as an array of `{}`",
+            actual, element, expected
+        )
+        .into(),
+    ))
+}
+
+// The text format is `{1,2,3}` or `{{1,2},{3,4}}`, optionally prefixed with the bounds of each
+// dimension (`[0:1][1:2]={{1,2},{3,4}}`) when any lower bound is not 1
+enum TextNode {
+    List(Vec<TextNode>),
+    Item(Option<String>),
+}
+
+impl TextNode {
+    fn decode<T>(self) -> crate::Result<T>
+    where
+        T: DecodeOwned<Postgres>,
+    {
+        match self {
+            TextNode::Item(Some(s)) => T::decode(Some(PgValue::Text(&s))),
+            TextNode::Item(None) => T::decode(None),
+            TextNode::List(_) => Err(Error::Decode(
+                "expected an array element but received a nested array".into(),
+            )),
+        }
+    }
+}
+
+struct TextParser<'a> {
+    s: &'a str,
+}
+
+impl<'a> TextParser<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { s }
+    }
+
+    fn parse(mut self) -> crate::Result<TextNode> {
+        if self.s.starts_with('[') {
+            let start = self.s.find('=').ok_or_else(|| text_err(self.s))?;
+            self.s = &self.s[start + 1..];
+        }
+
+        let node = self.parse_list()?;
+
+        if !self.s.is_empty() {
+            return Err(text_err(self.s));
+        }
+
+        Ok(node)
+    }
+
+    fn parse_list(&mut self) -> crate::Result<TextNode> {
+        self.expect('{')?;
+
+        let mut items = Vec::new();
+
+        if self.s.starts_with('}') {
+            self.s = &self.s[1..];
+
+            return Ok(TextNode::List(items));
+        }
+
+        loop {
+            let item = if self.s.starts_with('{') {
+                self.parse_list()?
+            } else {
+                self.parse_item()?
+            };
+
+            items.push(item);
+
+            if self.s.starts_with(',') {
+                self.s = &self.s[1..];
+            } else {
+                self.expect('}')?;
+
+                return Ok(TextNode::List(items));
+            }
+        }
+    }
+
+    fn parse_item(&mut self) -> crate::Result<TextNode> {
+        if self.s.starts_with('"') {
+            let mut item = String::new();
+            let mut chars = self.s[1..].char_indices();
+
+            loop {
+                match chars.next() {
+                    Some((i, '"')) => {
+                        self.s = &self.s[i + 2..];
+
+                        return Ok(TextNode::Item(Some(item)));
+                    }
+
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, c)) => item.push(c),
+                        None => return Err(text_err(self.s)),
+                    },
+
+                    Some((_, c)) => item.push(c),
+
+                    None => return Err(text_err(self.s)),
+                }
+            }
+        }
+
+        let end = self
+            .s
+            .find(&[',', '}'][..])
+            .ok_or_else(|| text_err(self.s))?;
+
+        let item = self.s[..end].trim();
+        self.s = &self.s[end..];
+
+        if item.eq_ignore_ascii_case("NULL") {
+            Ok(TextNode::Item(None))
+        } else {
+            Ok(TextNode::Item(Some(item.to_owned())))
+        }
+    }
+
+    fn expect(&mut self, c: char) -> crate::Result<()> {
+        if self.s.starts_with(c) {
+            self.s = &self.s[1..];
+
+            Ok(())
+        } else {
+            Err(text_err(self.s))
+        }
+    }
+}
+
+fn text_err(rest: &str) -> Error {
+    Error::Decode(format!("unexpected input in array: {:?}", rest).into())
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::convert::TryInto;
 
 use crate::decode::Decode;
@@ -5,7 +6,7 @@ use crate::encode::Encode;
 use crate::postgres::protocol::TypeId;
 use crate::postgres::types::PgTypeInfo;
 use crate::postgres::{PgValue, Postgres};
-use crate::types::Type;
+use crate::types::{copy_to_byte_array, Type};
 
 impl Type<Postgres> for [u8] {
     fn type_info() -> PgTypeInfo {
@@ -19,12 +20,24 @@ impl Type<Postgres> for [&'_ [u8]] {
     }
 }
 
+impl Type<Postgres> for [Vec<u8>] {
+    fn type_info() -> PgTypeInfo {
+        <[&[u8]] as Type<Postgres>>::type_info()
+    }
+}
+
 impl Type<Postgres> for Vec<u8> {
     fn type_info() -> PgTypeInfo {
         <[u8] as Type<Postgres>>::type_info()
     }
 }
 
+impl Type<Postgres> for Cow<'_, [u8]> {
+    fn type_info() -> PgTypeInfo {
+        <[u8] as Type<Postgres>>::type_info()
+    }
+}
+
 impl Encode<Postgres> for [u8] {
     fn encode(&self, buf: &mut Vec<u8>) {
         buf.extend_from_slice(self);
@@ -37,6 +50,12 @@ impl Encode<Postgres> for Vec<u8> {
     }
 }
 
+impl Encode<Postgres> for Cow<'_, [u8]> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        <[u8] as Encode<Postgres>>::encode(self, buf);
+    }
+}
+
 impl<'de> Decode<'de, Postgres> for Vec<u8> {
     fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
         match value.try_into()? {
@@ -61,3 +80,44 @@ impl<'de> Decode<'de, Postgres> for &'de [u8] {
         }
     }
 }
+
+// Borrows from the row in the binary format; the text format is hex-decoded into an owned buffer
+impl<'de> Decode<'de, Postgres> for Cow<'de, [u8]> {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(buf) => Ok(Cow::Borrowed(buf)),
+            PgValue::Text(s) => hex::decode(&s[2..])
+                .map(Cow::Owned)
+                .map_err(crate::Error::decode),
+        }
+    }
+}
+
+macro_rules! impl_byte_array {
+    ([$($generics:tt)*] $len:tt) => {
+        impl<$($generics)*> Type<Postgres> for [u8; $len] {
+            fn type_info() -> PgTypeInfo {
+                <[u8] as Type<Postgres>>::type_info()
+            }
+        }
+
+        impl<$($generics)*> Encode<Postgres> for [u8; $len] {
+            fn encode(&self, buf: &mut Vec<u8>) {
+                <[u8] as Encode<Postgres>>::encode(self, buf);
+            }
+        }
+
+        impl<'de, $($generics)*> Decode<'de, Postgres> for [u8; $len] {
+            fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+                let bytes = <Cow<'de, [u8]> as Decode<Postgres>>::decode(value)?;
+                let mut array = [0; $len];
+
+                copy_to_byte_array(&mut array, &bytes)?;
+
+                Ok(array)
+            }
+        }
+    };
+}
+
+impl_for_byte_arrays!(impl_byte_array);
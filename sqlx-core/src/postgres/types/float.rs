@@ -11,6 +11,22 @@ use crate::postgres::types::PgTypeInfo;
 use crate::postgres::{PgValue, Postgres};
 use crate::types::Type;
 
+// Postgres spells the special values as `NaN`, `Infinity` and `-Infinity` in text mode
+// which `FromStr` does not accept on every toolchain we support
+pub(super) fn parse_text<T>(s: &str) -> crate::Result<T>
+where
+    T: FromStr + From<f32>,
+    <T as FromStr>::Err: std::error::Error + Send + Sync + 'static,
+{
+    match s {
+        "NaN" => Ok(T::from(std::f32::NAN)),
+        "Infinity" => Ok(T::from(std::f32::INFINITY)),
+        "-Infinity" => Ok(T::from(std::f32::NEG_INFINITY)),
+
+        _ => T::from_str(s).map_err(Error::decode),
+    }
+}
+
 impl Type<Postgres> for f32 {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::new(TypeId::FLOAT4, "FLOAT4")
@@ -37,7 +53,7 @@ impl<'de> Decode<'de, Postgres> for f32 {
                 .map_err(Error::decode)
                 .map(|value| f32::from_bits(value as u32)),
 
-            PgValue::Text(s) => f32::from_str(s).map_err(Error::decode),
+            PgValue::Text(s) => parse_text(s),
         }
     }
 }
@@ -68,7 +84,7 @@ impl<'de> Decode<'de, Postgres> for f64 {
                 .map_err(Error::decode)
                 .map(|value| f64::from_bits(value as u64)),
 
-            PgValue::Text(s) => f64::from_str(s).map_err(Error::decode),
+            PgValue::Text(s) => parse_text(s),
         }
     }
 }
@@ -0,0 +1,358 @@
+//! PostGIS `GEOMETRY` values in the extended well-known binary format (EWKB)
+//!
+//! <https://github.com/postgis/postgis/blob/master/doc/ZMSgeoms.txt>
+
+use std::convert::TryInto;
+
+use byteorder::{BigEndian, LittleEndian};
+use geo_types::{Coordinate, LineString, Point, Polygon};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::{Buf, BufMut};
+use crate::postgres::row::PgValue;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::Postgres;
+use crate::types::Type;
+
+// Flags in the high bits of the geometry type
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// A PostGIS `GEOMETRY` along with its spatial reference system identifier (SRID).
+///
+/// `Point`, `LineString` and `Polygon` from `geo-types` can also be bound and decoded on their
+/// own; they are sent without an SRID (which PostGIS reads as `0`, unknown) and the SRID of
+/// values read into them is ignored. Wrap them in `PgGeometry` to send or read the SRID, e.g.
+/// for a column declared as `GEOMETRY(Point, 4326)`.
+///
+/// Geometries with Z or M coordinates are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgGeometry<G> {
+    pub srid: u32,
+    pub geometry: G,
+}
+
+impl<G> PgGeometry<G> {
+    pub fn new(srid: u32, geometry: G) -> Self {
+        Self { srid, geometry }
+    }
+}
+
+pub(crate) fn type_info() -> PgTypeInfo {
+    // the OID of an extension type is not fixed; it is left for the server to infer when
+    // binding and matched against the OID resolved by the connection when decoding
+    PgTypeInfo::with_name("GEOMETRY")
+}
+
+mod private {
+    use super::Ewkb;
+
+    // Only the geometries below can be wrapped in `PgGeometry`
+    pub trait Geometry: Sized + 'static {
+        const KIND: u32;
+
+        const NAME: &str;
+
+        fn write(&self, buf: &mut Vec<u8>);
+
+        fn read(buf: &mut Ewkb<'_>) -> crate::Result<Self>;
+    }
+}
+
+use private::Geometry;
+
+impl Geometry for Point<f64> {
+    const KIND: u32 = 1;
+    const NAME: &str = "POINT";
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        write_coordinate(buf, self.0);
+    }
+
+    fn read(buf: &mut Ewkb<'_>) -> crate::Result<Self> {
+        buf.coordinate().map(Point)
+    }
+}
+
+impl Geometry for LineString<f64> {
+    const KIND: u32 = 2;
+    const NAME: &str = "LINESTRING";
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.put_u32::<LittleEndian>(self.0.len() as u32);
+
+        for &coordinate in &self.0 {
+            write_coordinate(buf, coordinate);
+        }
+    }
+
+    fn read(buf: &mut Ewkb<'_>) -> crate::Result<Self> {
+        let len = buf.len()?;
+        let mut coordinates = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            coordinates.push(buf.coordinate()?);
+        }
+
+        Ok(LineString(coordinates))
+    }
+}
+
+impl Geometry for Polygon<f64> {
+    const KIND: u32 = 3;
+    const NAME: &str = "POLYGON";
+
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.put_u32::<LittleEndian>(1 + self.interiors().len() as u32);
+
+        self.exterior().write(buf);
+
+        for ring in self.interiors() {
+            ring.write(buf);
+        }
+    }
+
+    fn read(buf: &mut Ewkb<'_>) -> crate::Result<Self> {
+        let len = buf.len()?;
+
+        // an empty polygon has no rings at all
+        if len == 0 {
+            return Ok(Polygon::new(LineString(Vec::new()), Vec::new()));
+        }
+
+        let exterior = LineString::read(buf)?;
+        let mut interiors = Vec::with_capacity(len - 1);
+
+        for _ in 1..len {
+            interiors.push(LineString::read(buf)?);
+        }
+
+        Ok(Polygon::new(exterior, interiors))
+    }
+}
+
+fn write_coordinate(buf: &mut Vec<u8>, coordinate: Coordinate<f64>) {
+    buf.put_u64::<LittleEndian>(coordinate.x.to_bits());
+    buf.put_u64::<LittleEndian>(coordinate.y.to_bits());
+}
+
+fn encode<G: Geometry>(geometry: &G, srid: Option<u32>, buf: &mut Vec<u8>) {
+    // little-endian
+    buf.push(1);
+
+    if let Some(srid) = srid {
+        buf.put_u32::<LittleEndian>(G::KIND | EWKB_SRID);
+        buf.put_u32::<LittleEndian>(srid);
+    } else {
+        buf.put_u32::<LittleEndian>(G::KIND);
+    }
+
+    geometry.write(buf);
+}
+
+fn decode<G: Geometry>(value: Option<PgValue<'_>>) -> crate::Result<PgGeometry<G>> {
+    // the text format of a geometry is its EWKB in hex
+    let hex;
+    let buf = match value.try_into()? {
+        PgValue::Binary(buf) => buf,
+        PgValue::Text(s) => {
+            hex = hex::decode(s).map_err(crate::Error::decode)?;
+            &hex[..]
+        }
+    };
+
+    let mut buf = Ewkb {
+        buf,
+        little_endian: true,
+    };
+
+    buf.little_endian = match buf.buf.get_u8()? {
+        0 => false,
+        1 => true,
+        order => {
+            return Err(crate::Error::Decode(
+                format!("invalid byte order {} in EWKB", order).into(),
+            ))
+        }
+    };
+
+    let kind = buf.u32()?;
+
+    if kind & (EWKB_Z | EWKB_M) != 0 {
+        return Err(crate::Error::Decode(
+            "geometries with Z or M coordinates are not supported".into(),
+        ));
+    }
+
+    let srid = if kind & EWKB_SRID != 0 { buf.u32()? } else { 0 };
+    let kind = kind & !EWKB_SRID;
+
+    if kind != G::KIND {
+        return Err(crate::Error::Decode(
+            format!("expected a {} but received geometry type {}", G::NAME, kind).into(),
+        ));
+    }
+
+    let geometry = G::read(&mut buf)?;
+
+    if !buf.buf.is_empty() {
+        return Err(crate::Error::Decode(
+            format!("{} trailing bytes after the {}", buf.buf.len(), G::NAME).into(),
+        ));
+    }
+
+    Ok(PgGeometry { srid, geometry })
+}
+
+pub struct Ewkb<'a> {
+    buf: &'a [u8],
+    little_endian: bool,
+}
+
+impl Ewkb<'_> {
+    fn u32(&mut self) -> crate::Result<u32> {
+        Ok(if self.little_endian {
+            self.buf.get_u32::<LittleEndian>()?
+        } else {
+            self.buf.get_u32::<BigEndian>()?
+        })
+    }
+
+    fn f64(&mut self) -> crate::Result<f64> {
+        let bits = if self.little_endian {
+            self.buf.get_u64::<LittleEndian>()?
+        } else {
+            self.buf.get_u64::<BigEndian>()?
+        };
+
+        Ok(f64::from_bits(bits))
+    }
+
+    fn coordinate(&mut self) -> crate::Result<Coordinate<f64>> {
+        Ok(Coordinate {
+            x: self.f64()?,
+            y: self.f64()?,
+        })
+    }
+
+    // Reads a count of points or rings; each takes at least 4 bytes so this can't be used to
+    // allocate more than the size of the value
+    fn len(&mut self) -> crate::Result<usize> {
+        let len = self.u32()? as usize;
+
+        if len > self.buf.len() / 4 {
+            return Err(crate::Error::Decode(
+                format!("count of {} overruns the EWKB", len).into(),
+            ));
+        }
+
+        Ok(len)
+    }
+}
+
+impl<G: Geometry> Type<Postgres> for PgGeometry<G> {
+    fn type_info() -> PgTypeInfo {
+        type_info()
+    }
+}
+
+impl<G: Geometry> Encode<Postgres> for PgGeometry<G> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        encode(&self.geometry, Some(self.srid), buf);
+    }
+}
+
+impl<'de, G: Geometry> Decode<'de, Postgres> for PgGeometry<G> {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        decode(value)
+    }
+}
+
+macro_rules! impl_geometry {
+    ($($ty:ty),*) => {
+        $(
+            impl Type<Postgres> for $ty {
+                fn type_info() -> PgTypeInfo {
+                    type_info()
+                }
+            }
+
+            impl Encode<Postgres> for $ty {
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    encode(self, None, buf);
+                }
+            }
+
+            impl<'de> Decode<'de, Postgres> for $ty {
+                fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+                    decode(value).map(|value: PgGeometry<$ty>| value.geometry)
+                }
+            }
+        )*
+    };
+}
+
+impl_geometry!(Point<f64>, LineString<f64>, Polygon<f64>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SELECT 'SRID=4326;POINT(1 2)'::geometry
+    const POINT_4326: &str = "0101000020E6100000000000000000F03F0000000000000040";
+
+    #[test]
+    fn it_encodes_a_point_with_its_srid() {
+        let mut buf = Vec::new();
+        PgGeometry::new(4326, Point::new(1.0, 2.0)).encode(&mut buf);
+
+        assert_eq!(hex::encode_upper(&buf), POINT_4326);
+    }
+
+    #[test]
+    fn it_decodes_text_and_big_endian_values() {
+        let point: PgGeometry<Point<f64>> =
+            Decode::decode(Some(PgValue::Text(POINT_4326))).unwrap();
+
+        assert_eq!(point, PgGeometry::new(4326, Point::new(1.0, 2.0)));
+
+        // SELECT ST_AsBinary('POINT(1 2)'::geometry, 'XDR')
+        let buf = hex::decode("00000000013FF00000000000004000000000000000").unwrap();
+        let point: Point<f64> = Decode::decode(Some(PgValue::Binary(&buf))).unwrap();
+
+        assert_eq!(point, Point::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn it_round_trips_a_polygon_with_a_hole() {
+        let polygon = Polygon::new(
+            LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.), (0., 0.)]),
+            vec![LineString::from(vec![
+                (2., 2.),
+                (4., 2.),
+                (4., 4.),
+                (2., 2.),
+            ])],
+        );
+
+        let mut buf = Vec::new();
+        PgGeometry::new(3857, polygon.clone()).encode(&mut buf);
+
+        let decoded: PgGeometry<Polygon<f64>> =
+            Decode::decode(Some(PgValue::Binary(&buf))).unwrap();
+
+        assert_eq!(decoded, PgGeometry::new(3857, polygon));
+    }
+
+    #[test]
+    fn it_rejects_other_geometry_types() {
+        let mut buf = Vec::new();
+        Point::new(1.0, 2.0).encode(&mut buf);
+
+        let result: crate::Result<LineString<f64>> = Decode::decode(Some(PgValue::Binary(&buf)));
+
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,5 @@
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error as StdError;
 use std::str::FromStr;
 
 use byteorder::{NetworkEndian, ReadBytesExt};
@@ -31,10 +32,7 @@ impl Encode<Postgres> for i16 {
 
 impl<'de> Decode<'de, Postgres> for i16 {
     fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            PgValue::Binary(mut buf) => buf.read_i16::<NetworkEndian>().map_err(Error::decode),
-            PgValue::Text(s) => i16::from_str(s).map_err(Error::decode),
-        }
+        decode_int(value)
     }
 }
 
@@ -58,10 +56,7 @@ impl Encode<Postgres> for i32 {
 
 impl<'de> Decode<'de, Postgres> for i32 {
     fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            PgValue::Binary(mut buf) => buf.read_i32::<NetworkEndian>().map_err(Error::decode),
-            PgValue::Text(s) => i32::from_str(s).map_err(Error::decode),
-        }
+        decode_int(value)
     }
 }
 
@@ -85,9 +80,27 @@ impl Encode<Postgres> for i64 {
 
 impl<'de> Decode<'de, Postgres> for i64 {
     fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
-        match value.try_into()? {
-            PgValue::Binary(mut buf) => buf.read_i64::<NetworkEndian>().map_err(Error::decode),
-            PgValue::Text(s) => i64::from_str(s).map_err(Error::decode),
-        }
+        decode_int(value)
     }
 }
+
+// Integers of any width are accepted so an `INT8` column can be decoded into an `i32`; the
+// value is checked to fit
+fn decode_int<T>(value: Option<PgValue<'_>>) -> crate::Result<T>
+where
+    T: TryFrom<i64>,
+    T::Error: StdError + Send + Sync + 'static,
+{
+    let value = match value.try_into()? {
+        PgValue::Binary(mut buf) => match buf.len() {
+            2 => buf.read_i16::<NetworkEndian>().map(i64::from),
+            4 => buf.read_i32::<NetworkEndian>().map(i64::from),
+            _ => buf.read_i64::<NetworkEndian>(),
+        }
+        .map_err(Error::decode)?,
+
+        PgValue::Text(s) => i64::from_str(s).map_err(Error::decode)?,
+    };
+
+    T::try_from(value).map_err(Error::decode)
+}
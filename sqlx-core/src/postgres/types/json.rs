@@ -0,0 +1,20 @@
+use std::convert::TryInto;
+
+use crate::decode::Decode;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::JsonText;
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/jsonb.c#L130
+const JSONB_VERSION: u8 = 1;
+
+impl<'de> Decode<'de, Postgres> for JsonText<'de> {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            // `JSONB` is prefixed with a version number in the binary format; a `JSON` document
+            // cannot start with it
+            PgValue::Binary(buf) if buf.first() == Some(&JSONB_VERSION) => Ok(JsonText(&buf[1..])),
+            PgValue::Binary(buf) => Ok(JsonText(buf)),
+            PgValue::Text(s) => Ok(JsonText(s.as_bytes())),
+        }
+    }
+}
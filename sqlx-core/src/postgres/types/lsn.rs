@@ -0,0 +1,126 @@
+//! `PG_LSN`, a position in the write-ahead log.
+//!
+//! https://www.postgresql.org/docs/12/datatype-pg-lsn.html
+
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::BufMut;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::Type;
+use crate::Error;
+
+/// A `PG_LSN`; the byte offset of a position in the write-ahead log.
+///
+/// It is written as two hexadecimal numbers of up to 32 bits separated by a slash, the high
+/// half first, such as `16/B374D848`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(pub u64);
+
+impl From<u64> for PgLsn {
+    fn from(lsn: u64) -> Self {
+        PgLsn(lsn)
+    }
+}
+
+impl From<PgLsn> for u64 {
+    fn from(lsn: PgLsn) -> Self {
+        lsn.0
+    }
+}
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 as u32)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let invalid = || Error::Decode(format!("invalid PG_LSN {:?}", s).into());
+
+        let mut parts = s.splitn(2, '/');
+        let mut half = || {
+            parts
+                .next()
+                .filter(|part| !part.is_empty() && part.len() <= 8)
+                .and_then(|part| u32::from_str_radix(part, 16).ok())
+                .ok_or_else(invalid)
+        };
+
+        let high = half()?;
+        let low = half()?;
+
+        Ok(PgLsn((u64::from(high) << 32) | u64::from(low)))
+    }
+}
+
+impl Type<Postgres> for PgLsn {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::PG_LSN, "PG_LSN")
+    }
+}
+
+impl Type<Postgres> for [PgLsn] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_PG_LSN, "PG_LSN[]")
+    }
+}
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/pg_lsn.c#L100-L109
+impl Encode<Postgres> for PgLsn {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.put_u64::<NetworkEndian>(self.0);
+    }
+
+    fn size_hint(&self) -> usize {
+        8
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgLsn {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(mut buf) => buf
+                .read_u64::<NetworkEndian>()
+                .map(PgLsn)
+                .map_err(Error::decode),
+
+            PgValue::Text(s) => s.parse(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PgLsn;
+
+    #[test]
+    fn it_formats_the_halves_in_hex() {
+        assert_eq!(PgLsn(0x16_B374_D848).to_string(), "16/B374D848");
+        assert_eq!(PgLsn(0).to_string(), "0/0");
+        assert_eq!(PgLsn(u64::max_value()).to_string(), "FFFFFFFF/FFFFFFFF");
+    }
+
+    #[test]
+    fn it_parses_the_text_format() {
+        assert_eq!(
+            "16/B374D848".parse::<PgLsn>().unwrap(),
+            PgLsn(0x16_B374_D848)
+        );
+        assert_eq!("0/1a".parse::<PgLsn>().unwrap(), PgLsn(0x1A));
+
+        for invalid in &["", "16", "/1", "1/", "1/2/3", "G/0", "100000000/0"] {
+            assert!(invalid.parse::<PgLsn>().is_err(), "{:?}", invalid);
+        }
+    }
+}
@@ -7,11 +7,18 @@ use crate::postgres::protocol::TypeId;
 use crate::postgres::{PgValue, Postgres};
 use crate::types::TypeInfo;
 
+mod array;
 mod bool;
 mod bytes;
 mod float;
 mod int;
+mod lsn;
+mod numeric;
+mod range;
 mod str;
+mod system_time;
+mod tid;
+mod tsearch;
 
 #[cfg(feature = "chrono")]
 mod chrono;
@@ -19,10 +26,31 @@ mod chrono;
 #[cfg(feature = "uuid")]
 mod uuid;
 
+#[cfg(feature = "geo")]
+mod geo;
+
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(feature = "messagepack")]
+mod serialized;
+
+pub use array::PgArrayElement;
+pub use lsn::PgLsn;
+pub use range::PgRange;
+pub use tid::PgTid;
+pub use tsearch::{PgTsQuery, PgTsVector};
+
+#[cfg(feature = "geo")]
+pub use geo::PgGeometry;
+
 #[derive(Debug, Clone)]
 pub struct PgTypeInfo {
     pub(crate) id: TypeId,
     pub(crate) name: Option<SharedStr>,
+
+    // The type a domain is defined over, when this is a domain described by the server
+    pub(crate) base: Option<Box<PgTypeInfo>>,
 }
 
 impl PgTypeInfo {
@@ -30,6 +58,16 @@ impl PgTypeInfo {
         Self {
             id,
             name: Some(name.into()),
+            base: None,
+        }
+    }
+
+    // A domain over `base`, e.g. a parameter inserted into a column whose type is `email`, a
+    // domain over `TEXT`; it is compatible with the Rust types of its base
+    pub(crate) fn domain(id: TypeId, name: impl Into<SharedStr>, base: PgTypeInfo) -> Self {
+        Self {
+            base: Some(Box::new(base)),
+            ..Self::new(id, name)
         }
     }
 
@@ -41,9 +79,82 @@ impl PgTypeInfo {
         Self {
             id: TypeId(oid),
             name: None,
+            base: None,
         }
     }
 
+    /// Create a `PgTypeInfo` for a type whose object identifier differs between databases,
+    /// such as one created by an extension or by `CREATE TYPE`.
+    ///
+    /// Values of the type are matched by name; see
+    /// [`PgConnection::register_type`](crate::postgres::PgConnection::register_type).
+    pub fn with_name(name: &'static str) -> Self {
+        Self::new(TypeId(0), name)
+    }
+
+    // Type information for a column in a result set; builtin types are given their name so
+    // they can be shown in error messages
+    pub(crate) fn from_column_type(id: TypeId) -> Self {
+        let name = match id {
+            TypeId::BOOL => "BOOL",
+            TypeId::INT2 => "INT2",
+            TypeId::INT4 => "INT4",
+            TypeId::INT8 => "INT8",
+            TypeId::FLOAT4 => "FLOAT4",
+            TypeId::FLOAT8 => "FLOAT8",
+            TypeId::NUMERIC => "NUMERIC",
+            TypeId::TEXT => "TEXT",
+            TypeId::VARCHAR => "VARCHAR",
+            TypeId::BPCHAR => "BPCHAR",
+            TypeId::NAME => "NAME",
+            TypeId::XML => "XML",
+            TypeId::DATE => "DATE",
+            TypeId::TIME => "TIME",
+            TypeId::TIMESTAMP => "TIMESTAMP",
+            TypeId::TIMESTAMPTZ => "TIMESTAMPTZ",
+            TypeId::BYTEA => "BYTEA",
+            TypeId::UUID => "UUID",
+            TypeId::TSVECTOR => "TSVECTOR",
+            TypeId::TSQUERY => "TSQUERY",
+            TypeId::PG_LSN => "PG_LSN",
+            TypeId::TID => "TID",
+            TypeId::INT4RANGE => "INT4RANGE",
+            TypeId::INT8RANGE => "INT8RANGE",
+            TypeId::DATERANGE => "DATERANGE",
+            TypeId::TSRANGE => "TSRANGE",
+            TypeId::TSTZRANGE => "TSTZRANGE",
+            TypeId::RECORD => "RECORD",
+
+            TypeId::ARRAY_BOOL => "BOOL[]",
+            TypeId::ARRAY_INT2 => "INT2[]",
+            TypeId::ARRAY_INT4 => "INT4[]",
+            TypeId::ARRAY_INT8 => "INT8[]",
+            TypeId::ARRAY_FLOAT4 => "FLOAT4[]",
+            TypeId::ARRAY_FLOAT8 => "FLOAT8[]",
+            TypeId::ARRAY_NUMERIC => "NUMERIC[]",
+            TypeId::ARRAY_TEXT => "TEXT[]",
+            TypeId::ARRAY_VARCHAR => "VARCHAR[]",
+            TypeId::ARRAY_BPCHAR => "BPCHAR[]",
+            TypeId::ARRAY_NAME => "NAME[]",
+            TypeId::ARRAY_XML => "XML[]",
+            TypeId::ARRAY_DATE => "DATE[]",
+            TypeId::ARRAY_TIME => "TIME[]",
+            TypeId::ARRAY_TIMESTAMP => "TIMESTAMP[]",
+            TypeId::ARRAY_TIMESTAMPTZ => "TIMESTAMPTZ[]",
+            TypeId::ARRAY_BYTEA => "BYTEA[]",
+            TypeId::ARRAY_UUID => "UUID[]",
+            TypeId::ARRAY_TSVECTOR => "TSVECTOR[]",
+            TypeId::ARRAY_TSQUERY => "TSQUERY[]",
+            TypeId::ARRAY_PG_LSN => "PG_LSN[]",
+            TypeId::ARRAY_TID => "TID[]",
+            TypeId::ARRAY_RECORD => "RECORD[]",
+
+            _ => return Self::with_oid(id.0),
+        };
+
+        Self::new(id, name)
+    }
+
     #[doc(hidden)]
     pub fn type_name(&self) -> &str {
         self.name.as_deref().unwrap_or("<UNKNOWN>")
@@ -51,8 +162,18 @@ impl PgTypeInfo {
 
     #[doc(hidden)]
     pub fn type_feature_gate(&self) -> Option<&'static str> {
+        if let Some(base) = &self.base {
+            return base.type_feature_gate();
+        }
+
         match self.id {
-            TypeId::DATE | TypeId::TIME | TypeId::TIMESTAMP | TypeId::TIMESTAMPTZ => Some("chrono"),
+            TypeId::DATE
+            | TypeId::TIME
+            | TypeId::TIMESTAMP
+            | TypeId::TIMESTAMPTZ
+            | TypeId::DATERANGE
+            | TypeId::TSRANGE
+            | TypeId::TSTZRANGE => Some("chrono"),
             TypeId::UUID => Some("uuid"),
             _ => None,
         }
@@ -71,8 +192,109 @@ impl Display for PgTypeInfo {
 
 impl TypeInfo for PgTypeInfo {
     fn compatible(&self, other: &Self) -> bool {
+        if self.id == other.id {
+            return true;
+        }
+
+        if let Some(compatible) = self.compatible_by_domain(other, Self::compatible) {
+            return compatible;
+        }
+
+        if let Some(compatible) = self.compatible_by_name(other) {
+            return compatible;
+        }
+
+        // a `Uuid` is also read from its hyphenated form in a textual column
+        if self.id == TypeId::UUID && TypeClass::of(other.id) == Some(TypeClass::Textual) {
+            return true;
+        }
+
+        match (TypeClass::of(self.id), TypeClass::of(other.id)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    fn compatible_strict(&self, other: &Self) -> bool {
         // TODO: 99% of postgres types are direct equality for [compatible]; when we add something that isn't (e.g, JSON/JSONB), fix this here
+        if let Some(compatible) = self.compatible_by_domain(other, Self::compatible_strict) {
+            return self.id.0 == other.id.0 || compatible;
+        }
+
         self.id.0 == other.id.0
+            || self.compatible_by_name(other) == Some(true)
+            || (self.is_text_or_xml() && other.is_text_or_xml())
+    }
+}
+
+impl PgTypeInfo {
+    // XML is sent as text in both formats, so a `String` stands for it
+    fn is_text_or_xml(&self) -> bool {
+        self.id == TypeId::TEXT || self.id == TypeId::XML
+    }
+
+    // A domain is compatible with what its base type is compatible with, unless it was
+    // registered itself and is matched by name
+    fn compatible_by_domain(
+        &self,
+        other: &Self,
+        compatible: fn(&Self, &Self) -> bool,
+    ) -> Option<bool> {
+        if self.compatible_by_name(other) == Some(true) {
+            return None;
+        }
+
+        match (&self.base, &other.base) {
+            (_, Some(base)) => Some(compatible(self, base)),
+            (Some(base), None) => Some(compatible(base, other)),
+            (None, None) => None,
+        }
+    }
+
+    // Types with an OID that is not known ahead of time, such as those from extensions, are
+    // given an OID of 0 and matched by name instead
+    fn compatible_by_name(&self, other: &Self) -> Option<bool> {
+        if self.id.0 != 0 && other.id.0 != 0 {
+            return None;
+        }
+
+        match (&self.name, &other.name) {
+            (Some(a), Some(b)) => Some(a.eq_ignore_ascii_case(b)),
+            _ => Some(false),
+        }
+    }
+}
+
+// Groups of types that share a binary format, or whose binary formats differ only in width
+#[derive(PartialEq, Clone, Copy)]
+enum TypeClass {
+    Integral,
+    Textual,
+    IntegralArray,
+    TextualArray,
+}
+
+impl TypeClass {
+    fn of(id: TypeId) -> Option<Self> {
+        Some(match id {
+            TypeId::INT2 | TypeId::INT4 | TypeId::INT8 => TypeClass::Integral,
+
+            TypeId::TEXT | TypeId::VARCHAR | TypeId::BPCHAR | TypeId::NAME | TypeId::XML => {
+                TypeClass::Textual
+            }
+
+            TypeId::ARRAY_INT2 | TypeId::ARRAY_INT4 | TypeId::ARRAY_INT8 => {
+                TypeClass::IntegralArray
+            }
+
+            TypeId::ARRAY_TEXT
+            | TypeId::ARRAY_VARCHAR
+            | TypeId::ARRAY_BPCHAR
+            | TypeId::ARRAY_NAME
+            | TypeId::ARRAY_XML => TypeClass::TextualArray,
+
+            _ => return None,
+        })
     }
 }
 
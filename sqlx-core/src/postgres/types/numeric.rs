@@ -0,0 +1,195 @@
+use std::convert::TryInto;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::error::Error;
+use crate::io::BufMut;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::{Lossy, Type};
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/numeric.c#L167-L170
+const SIGN_POS: u16 = 0x0000;
+const SIGN_NEG: u16 = 0x4000;
+const SIGN_NAN: u16 = 0xC000;
+const SIGN_PINF: u16 = 0xD000;
+const SIGN_NINF: u16 = 0xF000;
+
+/// `Lossy<f64>` is accepted for `NUMERIC` columns.
+///
+/// Decoding rounds the value to the nearest `f64`. Encoding sends the shortest decimal
+/// representation that round-trips to the same `f64`.
+impl Type<Postgres> for Lossy<f64> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::NUMERIC, "NUMERIC")
+    }
+}
+
+impl Encode<Postgres> for Lossy<f64> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let value = self.0;
+
+        let special = if value.is_nan() {
+            Some(SIGN_NAN)
+        } else if value.is_infinite() {
+            Some(if value > 0.0 { SIGN_PINF } else { SIGN_NINF })
+        } else {
+            None
+        };
+
+        if let Some(sign) = special {
+            buf.put_i16::<NetworkEndian>(0);
+            buf.put_i16::<NetworkEndian>(0);
+            buf.put_u16::<NetworkEndian>(sign);
+            buf.put_u16::<NetworkEndian>(0);
+
+            return;
+        }
+
+        // the `Display` impl of `f64` never uses an exponent
+        let text = value.abs().to_string();
+        let mut parts = text.splitn(2, '.');
+        let int_part = parts.next().unwrap_or_default();
+        let frac_part = parts.next().unwrap_or_default();
+
+        // left-pad the integer part and right-pad the fraction so both split into
+        // base-10000 digits
+        let int_pad = (4 - int_part.len() % 4) % 4;
+        let frac_pad = (4 - frac_part.len() % 4) % 4;
+
+        let mut decimal = String::with_capacity(int_pad + text.len() + frac_pad);
+        decimal.extend((0..int_pad).map(|_| '0'));
+        decimal.push_str(int_part);
+        decimal.push_str(frac_part);
+        decimal.extend((0..frac_pad).map(|_| '0'));
+
+        let mut digits: Vec<i16> = decimal
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0_i16, |acc, &b| acc * 10 + i16::from(b - b'0'))
+            })
+            .collect();
+
+        let mut weight = ((int_pad + int_part.len()) / 4) as i16 - 1;
+
+        let leading = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(..leading);
+        weight -= leading as i16;
+
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        if digits.is_empty() {
+            weight = 0;
+        }
+
+        buf.put_i16::<NetworkEndian>(digits.len() as i16);
+        buf.put_i16::<NetworkEndian>(weight);
+        buf.put_u16::<NetworkEndian>(if value.is_sign_negative() && !digits.is_empty() {
+            SIGN_NEG
+        } else {
+            SIGN_POS
+        });
+        buf.put_u16::<NetworkEndian>(frac_part.len() as u16);
+
+        for digit in digits {
+            buf.put_i16::<NetworkEndian>(digit);
+        }
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for Lossy<f64> {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(mut buf) => {
+                let num_digits = buf.read_i16::<NetworkEndian>().map_err(Error::decode)?;
+                let weight = buf.read_i16::<NetworkEndian>().map_err(Error::decode)?;
+                let sign = buf.read_u16::<NetworkEndian>().map_err(Error::decode)?;
+                let _scale = buf.read_u16::<NetworkEndian>().map_err(Error::decode)?;
+
+                match sign {
+                    SIGN_NAN => return Ok(Lossy(std::f64::NAN)),
+                    SIGN_PINF => return Ok(Lossy(std::f64::INFINITY)),
+                    SIGN_NINF => return Ok(Lossy(std::f64::NEG_INFINITY)),
+                    SIGN_POS | SIGN_NEG => {}
+
+                    _ => {
+                        return Err(Error::Decode(
+                            format!("unknown NUMERIC sign: {:#06X}", sign).into(),
+                        ))
+                    }
+                }
+
+                let digits = (0..num_digits)
+                    .map(|_| buf.read_i16::<NetworkEndian>())
+                    .collect::<std::io::Result<Vec<i16>>>()
+                    .map_err(Error::decode)?;
+
+                // rebuild the decimal string and let the float parser do the rounding
+                let mut text = String::with_capacity(4 * digits.len() + 8);
+
+                if sign == SIGN_NEG {
+                    text.push('-');
+                }
+
+                if weight < 0 {
+                    text.push('0');
+                } else {
+                    for i in 0..=(weight as usize) {
+                        let digit = digits.get(i).copied().unwrap_or(0);
+
+                        if i == 0 {
+                            let _ = write!(text, "{}", digit);
+                        } else {
+                            let _ = write!(text, "{:04}", digit);
+                        }
+                    }
+                }
+
+                text.push('.');
+
+                for _ in 0..(-1 - i32::from(weight)).max(0) {
+                    text.push_str("0000");
+                }
+
+                let frac_start = (i32::from(weight) + 1).max(0) as usize;
+
+                for digit in digits.iter().skip(frac_start) {
+                    let _ = write!(text, "{:04}", digit);
+                }
+
+                text.push('0');
+
+                f64::from_str(&text).map_err(Error::decode).and_then(finite)
+            }
+
+            PgValue::Text(s) => match s {
+                "NaN" | "Infinity" | "-Infinity" => super::float::parse_text(s).map(Lossy),
+                _ => f64::from_str(s).map_err(Error::decode).and_then(finite),
+            },
+        }
+    }
+}
+
+// A `NUMERIC` may have up to 131072 digits before the decimal point, which is far out of the
+// range of an `f64`; the float parser makes an infinity of such a value
+fn finite(value: f64) -> crate::Result<Lossy<f64>> {
+    if value.is_infinite() {
+        return Err(Error::Decode(
+            "NUMERIC value is out of the range of `Lossy<f64>`, beyond ±1.8e308; cast it to \
+             TEXT to read it"
+                .into(),
+        ));
+    }
+
+    Ok(Lossy(value))
+}
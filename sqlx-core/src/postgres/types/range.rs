@@ -0,0 +1,469 @@
+//! Range types: `INT4RANGE`, `INT8RANGE` and, with `chrono`, `DATERANGE`, `TSRANGE` and
+//! `TSTZRANGE`.
+//!
+//! https://www.postgresql.org/docs/12/rangetypes.html
+
+use std::convert::TryInto;
+use std::ops::{Bound, Range, RangeFrom, RangeInclusive, RangeTo, RangeToInclusive};
+
+use byteorder::{ByteOrder, NetworkEndian, ReadBytesExt};
+
+use crate::decode::{Decode, DecodeOwned};
+use crate::encode::{Encode, IsNull};
+use crate::io::BufMut;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::Type;
+use crate::Error;
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/include/utils/rangetypes.h#L35-L45
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+/// A value of a Postgres range type, such as `INT4RANGE` for `PgRange<i32>` or `TSTZRANGE` for
+/// `PgRange<DateTime<Utc>>`.
+///
+/// Postgres gives the ranges of discrete types (`INT4RANGE`, `INT8RANGE` and `DATERANGE`) in a
+/// canonical form, with an inclusive start and an exclusive end; `[1,3]` is read back as
+/// `[1,4)`. Other ranges are read back with the bounds they were written with.
+///
+/// ```rust,ignore
+/// let period: PgRange<NaiveDate> = (start..end).into();
+///
+/// sqlx::query("INSERT INTO bookings (room, period) VALUES ($1, $2)")
+///     .bind(room)
+///     .bind(period)
+///     .execute(&mut conn)
+///     .await?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgRange<T> {
+    /// The range that contains no values.
+    Empty,
+
+    /// The values between a start and an end bound, either of which can be unbounded.
+    Bounds(Bound<T>, Bound<T>),
+}
+
+impl<T> PgRange<T> {
+    /// Creates a range between two bounds.
+    pub fn new(start: Bound<T>, end: Bound<T>) -> Self {
+        PgRange::Bounds(start, end)
+    }
+
+    /// The start bound of the range, or `None` if the range is empty.
+    pub fn start(&self) -> Option<Bound<&T>> {
+        match self {
+            PgRange::Empty => None,
+            PgRange::Bounds(start, _) => Some(bound_ref(start)),
+        }
+    }
+
+    /// The end bound of the range, or `None` if the range is empty.
+    pub fn end(&self) -> Option<Bound<&T>> {
+        match self {
+            PgRange::Empty => None,
+            PgRange::Bounds(_, end) => Some(bound_ref(end)),
+        }
+    }
+
+    /// Whether `value` is in the range.
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialOrd,
+    {
+        let (start, end) = match self {
+            PgRange::Empty => return false,
+            PgRange::Bounds(start, end) => (start, end),
+        };
+
+        let after_start = match start {
+            Bound::Included(start) => start <= value,
+            Bound::Excluded(start) => start < value,
+            Bound::Unbounded => true,
+        };
+
+        let before_end = match end {
+            Bound::Included(end) => value <= end,
+            Bound::Excluded(end) => value < end,
+            Bound::Unbounded => true,
+        };
+
+        after_start && before_end
+    }
+}
+
+fn bound_ref<T>(bound: &Bound<T>) -> Bound<&T> {
+    match bound {
+        Bound::Included(value) => Bound::Included(value),
+        Bound::Excluded(value) => Bound::Excluded(value),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl<T> From<(Bound<T>, Bound<T>)> for PgRange<T> {
+    fn from((start, end): (Bound<T>, Bound<T>)) -> Self {
+        PgRange::Bounds(start, end)
+    }
+}
+
+impl<T> From<Range<T>> for PgRange<T> {
+    fn from(range: Range<T>) -> Self {
+        PgRange::Bounds(Bound::Included(range.start), Bound::Excluded(range.end))
+    }
+}
+
+impl<T> From<RangeInclusive<T>> for PgRange<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let (start, end) = range.into_inner();
+
+        PgRange::Bounds(Bound::Included(start), Bound::Included(end))
+    }
+}
+
+impl<T> From<RangeFrom<T>> for PgRange<T> {
+    fn from(range: RangeFrom<T>) -> Self {
+        PgRange::Bounds(Bound::Included(range.start), Bound::Unbounded)
+    }
+}
+
+impl<T> From<RangeTo<T>> for PgRange<T> {
+    fn from(range: RangeTo<T>) -> Self {
+        PgRange::Bounds(Bound::Unbounded, Bound::Excluded(range.end))
+    }
+}
+
+impl<T> From<RangeToInclusive<T>> for PgRange<T> {
+    fn from(range: RangeToInclusive<T>) -> Self {
+        PgRange::Bounds(Bound::Unbounded, Bound::Included(range.end))
+    }
+}
+
+impl Type<Postgres> for PgRange<i32> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::INT4RANGE, "INT4RANGE")
+    }
+}
+
+impl Type<Postgres> for PgRange<i64> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::INT8RANGE, "INT8RANGE")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type<Postgres> for PgRange<chrono::NaiveDate> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::DATERANGE, "DATERANGE")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type<Postgres> for PgRange<chrono::NaiveDateTime> {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TSRANGE, "TSRANGE")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> Type<Postgres> for PgRange<chrono::DateTime<Tz>>
+where
+    Tz: chrono::TimeZone,
+{
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TSTZRANGE, "TSTZRANGE")
+    }
+}
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/rangetypes.c#L244-L298
+impl<T> Encode<Postgres> for PgRange<T>
+where
+    T: Encode<Postgres>,
+    PgRange<T>: Type<Postgres>,
+{
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = self.try_encode(buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        let (start, end) = match self {
+            PgRange::Empty => {
+                buf.push(RANGE_EMPTY);
+
+                return Ok(IsNull::No);
+            }
+
+            PgRange::Bounds(start, end) => (start, end),
+        };
+
+        let mut flags = 0;
+
+        flags |= match start {
+            Bound::Included(_) => RANGE_LB_INC,
+            Bound::Excluded(_) => 0,
+            Bound::Unbounded => RANGE_LB_INF,
+        };
+
+        flags |= match end {
+            Bound::Included(_) => RANGE_UB_INC,
+            Bound::Excluded(_) => 0,
+            Bound::Unbounded => RANGE_UB_INF,
+        };
+
+        buf.push(flags);
+
+        for bound in &[start, end] {
+            match bound {
+                Bound::Included(value) | Bound::Excluded(value) => encode_bound(buf, value)?,
+                Bound::Unbounded => {}
+            }
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        let bound = |bound: &Bound<T>| match bound {
+            Bound::Included(value) | Bound::Excluded(value) => 4 + value.size_hint(),
+            Bound::Unbounded => 0,
+        };
+
+        match self {
+            PgRange::Empty => 1,
+            PgRange::Bounds(start, end) => 1 + bound(start) + bound(end),
+        }
+    }
+}
+
+fn encode_bound<T: Encode<Postgres>>(buf: &mut Vec<u8>, value: &T) -> crate::Result<()> {
+    let pos = buf.len();
+
+    buf.put_i32::<NetworkEndian>(0);
+
+    if let IsNull::Yes = value.try_encode(buf)? {
+        return Err(Error::Encode("the bound of a range can't be NULL".into()));
+    }
+
+    let len = (buf.len() - pos - 4) as i32;
+    NetworkEndian::write_i32(&mut buf[pos..], len);
+
+    Ok(())
+}
+
+impl<'de, T> Decode<'de, Postgres> for PgRange<T>
+where
+    T: DecodeOwned<Postgres>,
+    PgRange<T>: Type<Postgres>,
+{
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(buf) => decode_binary(buf),
+            PgValue::Text(s) => decode_text(s),
+        }
+    }
+}
+
+fn decode_binary<T>(mut buf: &[u8]) -> crate::Result<PgRange<T>>
+where
+    T: DecodeOwned<Postgres>,
+{
+    let flags = buf.read_u8().map_err(Error::decode)?;
+
+    if flags & RANGE_EMPTY != 0 {
+        return Ok(PgRange::Empty);
+    }
+
+    // the bounds are in the binary format of the element type
+    let mut bound = |inclusive: u8, infinite: u8| -> crate::Result<Bound<T>> {
+        if flags & infinite != 0 {
+            return Ok(Bound::Unbounded);
+        }
+
+        let len = buf.read_i32::<NetworkEndian>().map_err(Error::decode)?;
+
+        if len < 0 || buf.len() < len as usize {
+            return Err(protocol_err!("range bound extends past the end of the value").into());
+        }
+
+        let (value, rest) = buf.split_at(len as usize);
+        buf = rest;
+
+        let value = T::decode(Some(PgValue::Binary(value)))?;
+
+        Ok(if flags & inclusive != 0 {
+            Bound::Included(value)
+        } else {
+            Bound::Excluded(value)
+        })
+    };
+
+    let start = bound(RANGE_LB_INC, RANGE_LB_INF)?;
+    let end = bound(RANGE_UB_INC, RANGE_UB_INF)?;
+
+    Ok(PgRange::Bounds(start, end))
+}
+
+// The text format is `empty` or `[start,end)`, where either bound can be left out for an
+// unbounded range and is double-quoted if it has special characters
+fn decode_text<T>(s: &str) -> crate::Result<PgRange<T>>
+where
+    T: DecodeOwned<Postgres>,
+{
+    if s.eq_ignore_ascii_case("empty") {
+        return Ok(PgRange::Empty);
+    }
+
+    let text_err = || Error::Decode(format!("invalid range: {:?}", s).into());
+
+    let start_inclusive = match s.chars().next() {
+        Some('[') => true,
+        Some('(') => false,
+        _ => return Err(text_err()),
+    };
+
+    let end_inclusive = match s.chars().last() {
+        Some(']') => true,
+        Some(')') => false,
+        _ => return Err(text_err()),
+    };
+
+    let mut rest = &s[1..s.len() - 1];
+
+    let start = parse_text_bound(&mut rest, ',').ok_or_else(text_err)?;
+    let end = parse_text_bound(&mut rest, '\0').ok_or_else(text_err)?;
+
+    let bound = |value: Option<String>, inclusive: bool| -> crate::Result<Bound<T>> {
+        Ok(match value {
+            None => Bound::Unbounded,
+            Some(value) => {
+                let value = T::decode(Some(PgValue::Text(&value)))?;
+
+                if inclusive {
+                    Bound::Included(value)
+                } else {
+                    Bound::Excluded(value)
+                }
+            }
+        })
+    };
+
+    Ok(PgRange::Bounds(
+        bound(start, start_inclusive)?,
+        bound(end, end_inclusive)?,
+    ))
+}
+
+// Reads a bound up to `delimiter` (or the end of the input for `\0`) and consumes the
+// delimiter; returns `Some(None)` for an unbounded side
+fn parse_text_bound(s: &mut &str, delimiter: char) -> Option<Option<String>> {
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut any = false;
+    let mut chars = s.char_indices();
+
+    let end = loop {
+        match chars.next() {
+            None if delimiter == '\0' && !quoted => break s.len(),
+            None => return None,
+
+            Some((i, c)) if c == delimiter && !quoted => break i + c.len_utf8(),
+
+            Some((_, '"')) => {
+                any = true;
+
+                // a doubled quote inside quotes is a literal quote
+                if quoted && chars.as_str().starts_with('"') {
+                    value.push('"');
+                    chars.next();
+                } else {
+                    quoted = !quoted;
+                }
+            }
+
+            Some((_, '\\')) => {
+                value.push(chars.next()?.1);
+                any = true;
+            }
+
+            Some((_, c)) => {
+                value.push(c);
+                any = true;
+            }
+        }
+    };
+
+    *s = &s[end..];
+
+    Some(if any { Some(value) } else { None })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Bound;
+
+    use super::{decode_binary, decode_text, PgRange};
+    use crate::encode::Encode;
+
+    #[test]
+    fn it_encodes_and_decodes_binary() {
+        let ranges: Vec<PgRange<i32>> = vec![
+            (1..5).into(),
+            (1..=5).into(),
+            (3..).into(),
+            (..3).into(),
+            PgRange::new(Bound::Excluded(1), Bound::Unbounded),
+            PgRange::Empty,
+        ];
+
+        for range in ranges {
+            let mut buf = Vec::new();
+            range.encode(&mut buf);
+
+            assert_eq!(buf.len(), range.size_hint());
+            assert_eq!(decode_binary::<i32>(&buf).unwrap(), range);
+        }
+    }
+
+    #[test]
+    fn it_encodes_unbounded_ranges() {
+        let mut buf = Vec::new();
+        PgRange::<i32>::from(3..).encode(&mut buf);
+
+        assert_eq!(buf, [0x02 | 0x10, 0, 0, 0, 4, 0, 0, 0, 3]);
+    }
+
+    #[test]
+    fn it_decodes_text() {
+        assert_eq!(decode_text::<i32>("empty").unwrap(), PgRange::Empty);
+        assert_eq!(decode_text::<i32>("[1,5)").unwrap(), (1..5).into());
+        assert_eq!(decode_text::<i32>("[3,)").unwrap(), (3..).into());
+        assert_eq!(
+            decode_text::<i32>("(,3]").unwrap(),
+            PgRange::new(Bound::Unbounded, Bound::Included(3))
+        );
+        assert_eq!(
+            decode_text::<String>(r#"["a\"b","c,d")"#).unwrap(),
+            PgRange::from("a\"b".to_owned().."c,d".to_owned())
+        );
+
+        assert!(decode_text::<i32>("1,5").is_err());
+        assert!(decode_text::<i32>("[1;5)").is_err());
+    }
+
+    #[test]
+    fn it_checks_containment() {
+        let range = PgRange::from(1..5);
+
+        assert!(range.contains(&1));
+        assert!(range.contains(&4));
+        assert!(!range.contains(&5));
+        assert!(!PgRange::Empty.contains(&1));
+        assert!(PgRange::from(..=5).contains(&5));
+    }
+}
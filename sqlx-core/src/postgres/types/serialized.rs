@@ -0,0 +1,46 @@
+use std::borrow::Cow;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::serialized::{self, MsgPack};
+use crate::types::Type;
+
+// Serialized values are `BYTEA`, written straight into the arguments
+macro_rules! impl_serialized {
+    ($($wrapper:ident),*) => {
+        $(
+            impl<T> Type<Postgres> for $wrapper<T> {
+                fn type_info() -> PgTypeInfo {
+                    <[u8] as Type<Postgres>>::type_info()
+                }
+            }
+
+            impl<T: Serialize> Encode<Postgres> for $wrapper<T> {
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    if let Err(error) = Encode::<Postgres>::try_encode(self, buf) {
+                        panic!("{}", error);
+                    }
+                }
+
+                fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+                    serialized::encode(self, buf)?;
+
+                    Ok(IsNull::No)
+                }
+            }
+
+            impl<'de, T: DeserializeOwned + 'de> Decode<'de, Postgres> for $wrapper<T> {
+                fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+                    serialized::decode(&<Cow<'de, [u8]> as Decode<Postgres>>::decode(value)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialized!(MsgPack);
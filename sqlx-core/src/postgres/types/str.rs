@@ -1,8 +1,10 @@
 use std::convert::TryInto;
+use std::path::{Path, PathBuf};
 use std::str::from_utf8;
 
 use crate::decode::Decode;
-use crate::encode::Encode;
+use crate::encode::{Encode, IsNull};
+use crate::path::path_to_str;
 use crate::postgres::protocol::TypeId;
 use crate::postgres::row::PgValue;
 use crate::postgres::types::PgTypeInfo;
@@ -22,6 +24,12 @@ impl Type<Postgres> for [&'_ str] {
     }
 }
 
+impl Type<Postgres> for [String] {
+    fn type_info() -> PgTypeInfo {
+        <[&str] as Type<Postgres>>::type_info()
+    }
+}
+
 impl Type<Postgres> for String {
     fn type_info() -> PgTypeInfo {
         <str as Type<Postgres>>::type_info()
@@ -62,3 +70,51 @@ impl<'de> Decode<'de, Postgres> for &'de str {
         }
     }
 }
+
+impl Type<Postgres> for Path {
+    fn type_info() -> PgTypeInfo {
+        <str as Type<Postgres>>::type_info()
+    }
+}
+
+impl Type<Postgres> for PathBuf {
+    fn type_info() -> PgTypeInfo {
+        <str as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for Path {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = Encode::<Postgres>::try_encode(self, buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        Encode::<Postgres>::try_encode(path_to_str(self)?, buf)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.as_os_str().len()
+    }
+}
+
+impl Encode<Postgres> for PathBuf {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        <Path as Encode<Postgres>>::encode(self.as_path(), buf)
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        <Path as Encode<Postgres>>::try_encode(self.as_path(), buf)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.as_os_str().len()
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PathBuf {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        <&'de str as Decode<Postgres>>::decode(value).map(PathBuf::from)
+    }
+}
@@ -0,0 +1,81 @@
+use std::convert::TryInto;
+use std::mem;
+use std::time::SystemTime;
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::postgres::protocol::TypeId;
+use crate::postgres::row::PgValue;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::Postgres;
+use crate::system_time::{from_unix_micros, to_unix_micros, DateTime};
+use crate::types::Type;
+use crate::Error;
+
+// Microseconds from the Unix epoch to the Postgres epoch, 2000-01-01 00:00:00 UTC
+const POSTGRES_EPOCH_MICROS: i64 = 946_684_800_000_000;
+
+impl Type<Postgres> for SystemTime {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TIMESTAMPTZ, "TIMESTAMPTZ")
+    }
+}
+
+impl Type<Postgres> for [SystemTime] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_TIMESTAMPTZ, "TIMESTAMPTZ[]")
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for SystemTime {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        let micros = match value.try_into()? {
+            PgValue::Binary(mut buf) => {
+                let micros = buf.read_i64::<NetworkEndian>().map_err(Error::decode)?;
+
+                // `infinity` and `-infinity`
+                if micros == std::i64::MAX || micros == std::i64::MIN {
+                    return Err(Error::Decode(
+                        "infinite timestamp can't be decoded into SystemTime".into(),
+                    ));
+                }
+
+                micros.checked_add(POSTGRES_EPOCH_MICROS)
+            }
+
+            PgValue::Text(s) => Some(DateTime::parse(s).ok_or_else(|| {
+                Error::Decode(format!("invalid timestamp for SystemTime: {:?}", s).into())
+            })?),
+        };
+
+        micros
+            .and_then(from_unix_micros)
+            .ok_or_else(|| Error::Decode("Postgres timestamp out of range for SystemTime".into()))
+    }
+}
+
+impl Encode<Postgres> for SystemTime {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = Encode::<Postgres>::try_encode(self, buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        let micros = to_unix_micros(*self)
+            .and_then(|micros| micros.checked_sub(POSTGRES_EPOCH_MICROS))
+            .ok_or_else(|| {
+                Error::Encode(format!("SystemTime out of range for Postgres: {:?}", self).into())
+            })?;
+
+        Encode::<Postgres>::encode(&micros, buf);
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        mem::size_of::<i64>()
+    }
+}
@@ -0,0 +1,147 @@
+//! `TID`, the physical location of a row version in its table.
+//!
+//! https://www.postgresql.org/docs/12/datatype-oid.html
+
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::BufMut;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::Type;
+use crate::Error;
+
+/// A `TID`, such as the `ctid` system column of every table: the block of the table a row
+/// version is stored in, and its index within the block.
+///
+/// It is written as `(block,offset)`, such as `(0,1)`. A row gets a new `ctid` whenever it is
+/// updated, and `VACUUM FULL` moves rows around, so it is only good for finding a row again
+/// shortly after reading it:
+///
+/// ```rust,ignore
+/// let (ctid,): (PgTid,) = sqlx::query_as("SELECT ctid FROM items WHERE ...")
+///     .fetch_one(&mut conn)
+///     .await?;
+///
+/// sqlx::query("UPDATE items SET seen = true WHERE ctid = $1")
+///     .bind(ctid)
+///     .execute(&mut conn)
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgTid {
+    /// The block number within the table.
+    pub block: u32,
+
+    /// The index of the row version within the block, from 1.
+    pub offset: u16,
+}
+
+impl Display for PgTid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.block, self.offset)
+    }
+}
+
+impl FromStr for PgTid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let invalid = || Error::Decode(format!("invalid TID {:?}", s).into());
+
+        if s.len() < 2 || !s.starts_with('(') || !s.ends_with(')') {
+            return Err(invalid());
+        }
+
+        let mut parts = s[1..s.len() - 1].splitn(2, ',');
+
+        let block = parts.next().and_then(|part| part.parse().ok());
+        let offset = parts.next().and_then(|part| part.parse().ok());
+
+        match (block, offset) {
+            (Some(block), Some(offset)) => Ok(PgTid { block, offset }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Type<Postgres> for PgTid {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TID, "TID")
+    }
+}
+
+impl Type<Postgres> for [PgTid] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_TID, "TID[]")
+    }
+}
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/tid.c#L116-L158
+impl Encode<Postgres> for PgTid {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.put_u32::<NetworkEndian>(self.block);
+        buf.put_u16::<NetworkEndian>(self.offset);
+    }
+
+    fn size_hint(&self) -> usize {
+        6
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgTid {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(mut buf) => {
+                let block = buf.read_u32::<NetworkEndian>().map_err(Error::decode)?;
+                let offset = buf.read_u16::<NetworkEndian>().map_err(Error::decode)?;
+
+                Ok(PgTid { block, offset })
+            }
+
+            PgValue::Text(s) => s.parse(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PgTid;
+
+    #[test]
+    fn it_round_trips_the_text_format() {
+        let tid = PgTid {
+            block: 4_294_967_295,
+            offset: 65535,
+        };
+
+        assert_eq!(tid.to_string(), "(4294967295,65535)");
+        assert_eq!("(4294967295,65535)".parse::<PgTid>().unwrap(), tid);
+        assert_eq!(
+            "(0,1)".parse::<PgTid>().unwrap(),
+            PgTid {
+                block: 0,
+                offset: 1
+            }
+        );
+
+        for invalid in &[
+            "",
+            "()",
+            "(1)",
+            "0,1",
+            "(0,1",
+            "(-1,1)",
+            "(0,65536)",
+            "((0,1))",
+        ] {
+            assert!(invalid.parse::<PgTid>().is_err(), "{:?}", invalid);
+        }
+    }
+}
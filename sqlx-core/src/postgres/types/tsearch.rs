@@ -0,0 +1,612 @@
+//! Full-text search types: `TSVECTOR` and `TSQUERY`, in their text form.
+//!
+//! https://www.postgresql.org/docs/12/datatype-textsearch.html
+
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::iter::Peekable;
+use std::str::Chars;
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::io::{Buf, BufMut};
+use crate::postgres::protocol::TypeId;
+use crate::postgres::types::PgTypeInfo;
+use crate::postgres::{PgValue, Postgres};
+use crate::types::Type;
+use crate::Error;
+
+/// A `TSVECTOR` in its text form, as Postgres prints it, e.g. `'a':1A 'cat':2,3 'fat'`.
+///
+/// This is only decoded; a vector is built in SQL, with `to_tsvector` or a `'..'::tsvector`
+/// literal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgTsVector(pub String);
+
+impl Display for PgTsVector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Type<Postgres> for PgTsVector {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TSVECTOR, "TSVECTOR")
+    }
+}
+
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/tsvector.c#L403-L437
+impl<'de> Decode<'de, Postgres> for PgTsVector {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(mut buf) => {
+                let len = buf.read_i32::<NetworkEndian>().map_err(Error::decode)?;
+                let mut text = String::new();
+
+                for i in 0..len {
+                    if i > 0 {
+                        text.push(' ');
+                    }
+
+                    write_quoted(&mut text, buf.get_str_nul().map_err(Error::decode)?);
+
+                    let num_positions = buf.read_u16::<NetworkEndian>().map_err(Error::decode)?;
+
+                    for j in 0..num_positions {
+                        let position = buf.read_u16::<NetworkEndian>().map_err(Error::decode)?;
+
+                        text.push(if j == 0 { ':' } else { ',' });
+
+                        // the weight is in the top 2 bits; `D`, the default, is not printed
+                        let _ = write!(text, "{}", position & 0x3FFF);
+
+                        match position >> 14 {
+                            3 => text.push('A'),
+                            2 => text.push('B'),
+                            1 => text.push('C'),
+                            _ => {}
+                        }
+                    }
+                }
+
+                Ok(PgTsVector(text))
+            }
+
+            PgValue::Text(s) => Ok(PgTsVector(s.to_owned())),
+        }
+    }
+}
+
+/// A `TSQUERY` in its text form, e.g. `'fat' & ( 'rat' | 'cat' )`.
+///
+/// It is bound and decoded as a `TSQUERY`, so `WHERE fts @@ $1` needs no cast. Words are
+/// matched as-is, as in a `'..'::tsquery` literal; to have them normalized by a text search
+/// configuration, bind a `&str` to `to_tsquery($1)` or `websearch_to_tsquery($1)` instead.
+/// Text that is not a valid query fails when it is bound.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgTsQuery(pub String);
+
+impl Display for PgTsQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Type<Postgres> for PgTsQuery {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TSQUERY, "TSQUERY")
+    }
+}
+
+impl Encode<Postgres> for PgTsQuery {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        if let Err(error) = self.try_encode(buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<u8>) -> crate::Result<IsNull> {
+        match QueryParser::new(&self.0).parse()? {
+            Some(root) => {
+                buf.put_i32::<NetworkEndian>(root.len() as i32);
+                root.encode(buf);
+            }
+
+            // an empty query is valid and matches nothing
+            None => buf.put_i32::<NetworkEndian>(0),
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        4 + self.0.len()
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgTsQuery {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        match value.try_into()? {
+            PgValue::Binary(mut buf) => {
+                let mut text = String::new();
+
+                if buf.read_i32::<NetworkEndian>().map_err(Error::decode)? > 0 {
+                    QueryNode::decode(&mut buf)?.write_infix(&mut text, 0, false);
+                }
+
+                Ok(PgTsQuery(text))
+            }
+
+            PgValue::Text(s) => Ok(PgTsQuery(s.to_owned())),
+        }
+    }
+}
+
+// The binary format is the query tree in prefix order, with the right operand of a binary
+// operator before its left operand
+// https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/tsquery.c#L1080-L1120
+
+const QI_VAL: u8 = 1;
+const QI_OPR: u8 = 2;
+
+const OP_NOT: u8 = 1;
+const OP_AND: u8 = 2;
+const OP_OR: u8 = 3;
+const OP_PHRASE: u8 = 4;
+
+enum QueryNode {
+    Operand {
+        word: String,
+        weight: u8,
+        prefix: bool,
+    },
+    Not(Box<QueryNode>),
+    Binary {
+        op: u8,
+        distance: i16,
+        left: Box<QueryNode>,
+        right: Box<QueryNode>,
+    },
+}
+
+impl QueryNode {
+    fn len(&self) -> usize {
+        match self {
+            QueryNode::Operand { .. } => 1,
+            QueryNode::Not(operand) => 1 + operand.len(),
+            QueryNode::Binary { left, right, .. } => 1 + left.len() + right.len(),
+        }
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            QueryNode::Operand {
+                word,
+                weight,
+                prefix,
+            } => {
+                buf.put_u8(QI_VAL);
+                buf.put_u8(*weight);
+                buf.put_u8(*prefix as u8);
+                buf.put_str_nul(word);
+            }
+
+            QueryNode::Not(operand) => {
+                buf.put_u8(QI_OPR);
+                buf.put_u8(OP_NOT);
+                operand.encode(buf);
+            }
+
+            QueryNode::Binary {
+                op,
+                distance,
+                left,
+                right,
+            } => {
+                buf.put_u8(QI_OPR);
+                buf.put_u8(*op);
+
+                if *op == OP_PHRASE {
+                    buf.put_i16::<NetworkEndian>(*distance);
+                }
+
+                right.encode(buf);
+                left.encode(buf);
+            }
+        }
+    }
+
+    fn decode(buf: &mut &[u8]) -> crate::Result<Self> {
+        let item = buf.read_u8().map_err(Error::decode)?;
+
+        if item == QI_VAL {
+            let weight = buf.read_u8().map_err(Error::decode)?;
+            let prefix = buf.read_u8().map_err(Error::decode)? != 0;
+            let word = buf.get_str_nul().map_err(Error::decode)?.to_owned();
+
+            return Ok(QueryNode::Operand {
+                word,
+                weight,
+                prefix,
+            });
+        }
+
+        match buf.read_u8().map_err(Error::decode)? {
+            OP_NOT if item == QI_OPR => Ok(QueryNode::Not(Box::new(QueryNode::decode(buf)?))),
+
+            op @ OP_AND | op @ OP_OR | op @ OP_PHRASE if item == QI_OPR => {
+                let distance = if op == OP_PHRASE {
+                    buf.read_i16::<NetworkEndian>().map_err(Error::decode)?
+                } else {
+                    0
+                };
+
+                let right = Box::new(QueryNode::decode(buf)?);
+                let left = Box::new(QueryNode::decode(buf)?);
+
+                Ok(QueryNode::Binary {
+                    op,
+                    distance,
+                    left,
+                    right,
+                })
+            }
+
+            op => Err(Error::Decode(
+                format!("unknown TSQUERY item: {} {}", item, op).into(),
+            )),
+        }
+    }
+
+    fn priority(&self) -> u8 {
+        match self {
+            QueryNode::Operand { .. } => 5,
+            QueryNode::Not(_) => 4,
+            QueryNode::Binary { op: OP_PHRASE, .. } => 3,
+            QueryNode::Binary { op: OP_AND, .. } => 2,
+            QueryNode::Binary { .. } => 1,
+        }
+    }
+
+    // Mirrors `infix` in tsquery.c, so a value reads the same in both formats
+    fn write_infix(&self, out: &mut String, parent_priority: u8, right_phrase_op: bool) {
+        let priority = self.priority();
+
+        let parens = match self {
+            QueryNode::Operand { .. } => false,
+            QueryNode::Not(_) => priority < parent_priority,
+            QueryNode::Binary { op, .. } => {
+                priority < parent_priority || (*op == OP_PHRASE && right_phrase_op)
+            }
+        };
+
+        if parens {
+            out.push_str("( ");
+        }
+
+        match self {
+            QueryNode::Operand {
+                word,
+                weight,
+                prefix,
+            } => {
+                write_quoted(out, word);
+
+                if *prefix || *weight != 0 {
+                    out.push(':');
+
+                    if *prefix {
+                        out.push('*');
+                    }
+
+                    for &(bit, c) in &[(8, 'A'), (4, 'B'), (2, 'C'), (1, 'D')] {
+                        if weight & bit != 0 {
+                            out.push(c);
+                        }
+                    }
+                }
+            }
+
+            QueryNode::Not(operand) => {
+                out.push('!');
+                operand.write_infix(out, priority, false);
+            }
+
+            QueryNode::Binary {
+                op,
+                distance,
+                left,
+                right,
+            } => {
+                left.write_infix(out, priority, false);
+
+                match *op {
+                    OP_AND => out.push_str(" & "),
+                    OP_OR => out.push_str(" | "),
+                    _ if *distance == 1 => out.push_str(" <-> "),
+                    _ => {
+                        let _ = write!(out, " <{}> ", distance);
+                    }
+                }
+
+                right.write_infix(out, priority, *op == OP_PHRASE);
+            }
+        }
+
+        if parens {
+            out.push_str(" )");
+        }
+    }
+}
+
+// Recursive descent parser for the `TSQUERY` input syntax, where `!` binds tightest, then
+// `<->` and `<N>`, then `&`, then `|`
+struct QueryParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> QueryParser<'a> {
+    fn new(query: &'a str) -> Self {
+        Self {
+            chars: query.chars().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> crate::Result<Option<QueryNode>> {
+        if self.peek().is_none() {
+            return Ok(None);
+        }
+
+        let root = self.parse_binary(OP_OR)?;
+
+        match self.peek() {
+            None => Ok(Some(root)),
+            Some(c) => Err(query_err(format_args!("unexpected {:?}", c))),
+        }
+    }
+
+    // The next character that is not whitespace
+    fn peek(&mut self) -> Option<char> {
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                return Some(c);
+            }
+
+            self.chars.next();
+        }
+
+        None
+    }
+
+    // Parses operands joined by `op`, each of which is joined by the operator that binds
+    // tighter; all of them associate to the left
+    fn parse_binary(&mut self, op: u8) -> crate::Result<QueryNode> {
+        let (symbol, operand) = match op {
+            OP_OR => ('|', OP_AND),
+            OP_AND => ('&', OP_PHRASE),
+            _ => ('<', OP_NOT),
+        };
+
+        let mut node = self.parse_operand(operand)?;
+
+        while self.peek() == Some(symbol) {
+            self.chars.next();
+
+            let distance = if op == OP_PHRASE {
+                self.parse_distance()?
+            } else {
+                0
+            };
+
+            node = QueryNode::Binary {
+                op,
+                distance,
+                left: Box::new(node),
+                right: Box::new(self.parse_operand(operand)?),
+            };
+        }
+
+        Ok(node)
+    }
+
+    fn parse_operand(&mut self, op: u8) -> crate::Result<QueryNode> {
+        if op != OP_NOT {
+            return self.parse_binary(op);
+        }
+
+        match self.peek() {
+            Some('!') => {
+                self.chars.next();
+
+                Ok(QueryNode::Not(Box::new(self.parse_operand(OP_NOT)?)))
+            }
+
+            Some('(') => {
+                self.chars.next();
+
+                let node = self.parse_binary(OP_OR)?;
+
+                if self.peek() != Some(')') {
+                    return Err(query_err("expected `)`"));
+                }
+
+                self.chars.next();
+
+                Ok(node)
+            }
+
+            Some(_) => self.parse_word(),
+
+            None => Err(query_err("unexpected end of query")),
+        }
+    }
+
+    // The rest of `<->` or `<N>`, after the `<`
+    fn parse_distance(&mut self) -> crate::Result<i16> {
+        let mut inner = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some('>') => break,
+                Some(c) => inner.push(c),
+                None => return Err(query_err("unterminated phrase operator")),
+            }
+        }
+
+        if inner == "-" {
+            return Ok(1);
+        }
+
+        match inner.trim().parse::<i16>() {
+            Ok(distance) if (0..=16384).contains(&distance) => Ok(distance),
+            _ => Err(query_err(format_args!(
+                "invalid phrase operator <{}>",
+                inner
+            ))),
+        }
+    }
+
+    // A single-quoted word, with `''` or `\` escapes, or a bare word, followed by an optional
+    // `:` with `*` for a prefix match and the weights to match
+    fn parse_word(&mut self) -> crate::Result<QueryNode> {
+        let mut word = String::new();
+
+        if self.chars.peek() == Some(&'\'') {
+            self.chars.next();
+
+            loop {
+                match self.chars.next() {
+                    Some('\'') if self.chars.peek() == Some(&'\'') => {
+                        self.chars.next();
+                        word.push('\'');
+                    }
+
+                    Some('\'') => break,
+
+                    Some('\\') => match self.chars.next() {
+                        Some(c) => word.push(c),
+                        None => return Err(query_err("unterminated quoted word")),
+                    },
+
+                    Some(c) => word.push(c),
+
+                    None => return Err(query_err("unterminated quoted word")),
+                }
+            }
+        } else {
+            while let Some(&c) = self.chars.peek() {
+                if c.is_whitespace() || "':&|!()<".contains(c) {
+                    break;
+                }
+
+                word.push(c);
+                self.chars.next();
+            }
+        }
+
+        if word.is_empty() {
+            return Err(query_err("expected a word"));
+        }
+
+        let mut weight = 0;
+        let mut prefix = false;
+
+        if self.chars.peek() == Some(&':') {
+            self.chars.next();
+
+            while let Some(&c) = self.chars.peek() {
+                match c.to_ascii_uppercase() {
+                    '*' => prefix = true,
+                    'A' => weight |= 8,
+                    'B' => weight |= 4,
+                    'C' => weight |= 2,
+                    'D' => weight |= 1,
+                    _ => break,
+                }
+
+                self.chars.next();
+            }
+        }
+
+        Ok(QueryNode::Operand {
+            word,
+            weight,
+            prefix,
+        })
+    }
+}
+
+fn query_err(message: impl Display) -> Error {
+    Error::Encode(format!("invalid TSQUERY: {}", message).into())
+}
+
+// Quotes a lexeme the way `tsvectorout` does, doubling quotes and backslashes
+fn write_quoted(out: &mut String, word: &str) {
+    out.push('\'');
+
+    for c in word.chars() {
+        if c == '\'' || c == '\\' {
+            out.push(c);
+        }
+
+        out.push(c);
+    }
+
+    out.push('\'');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PgTsQuery;
+    use crate::decode::Decode;
+    use crate::encode::Encode;
+    use crate::postgres::{PgValue, Postgres};
+
+    fn round_trip(query: &str) -> crate::Result<String> {
+        let mut buf = Vec::new();
+        Encode::<Postgres>::try_encode(&PgTsQuery(query.into()), &mut buf)?;
+
+        let query: PgTsQuery = Decode::<Postgres>::decode(Some(PgValue::Binary(&buf)))?;
+
+        Ok(query.0)
+    }
+
+    #[test]
+    fn it_encodes_a_query_in_prefix_order() {
+        let mut buf = Vec::new();
+        Encode::<Postgres>::encode(&PgTsQuery("a & b:*A".into()), &mut buf);
+
+        assert_eq!(
+            buf,
+            [0, 0, 0, 3, 2, 2, 1, 8, 1, b'b', 0, 1, 0, 0, b'a', 0].to_vec()
+        );
+    }
+
+    #[test]
+    fn it_round_trips_into_the_text_postgres_prints() {
+        for &(query, text) in &[
+            ("", ""),
+            ("fat & (rat | cat)", "'fat' & ( 'rat' | 'cat' )"),
+            ("fat & rat | cat", "'fat' & 'rat' | 'cat'"),
+            ("!!a", "!!'a'"),
+            ("!(a | b) & c", "!( 'a' | 'b' ) & 'c'"),
+            ("a <-> b <2> c", "'a' <-> 'b' <2> 'c'"),
+            ("a <-> (b <-> c)", "'a' <-> ( 'b' <-> 'c' )"),
+            ("'it''s':*ab | 'a\\\\b':d", "'it''s':*AB | 'a\\\\b':D"),
+        ] {
+            assert_eq!(round_trip(query).unwrap(), text, "{:?}", query);
+
+            // what Postgres prints reads back the same
+            assert_eq!(round_trip(text).unwrap(), text, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn it_rejects_text_that_is_not_a_query() {
+        for &query in &["(a", "a)", "a &", "& a", "a b", "a <x> b", "a <-", "'a"] {
+            assert!(round_trip(query).is_err(), "{:?}", query);
+        }
+    }
+}
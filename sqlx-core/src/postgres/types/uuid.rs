@@ -1,6 +1,7 @@
 use std::convert::TryInto;
 use std::str::FromStr;
 
+use uuid::adapter::Hyphenated;
 use uuid::Uuid;
 
 use crate::decode::Decode;
@@ -29,11 +30,45 @@ impl Encode<Postgres> for Uuid {
     }
 }
 
+// A `Uuid` is also decoded from its hyphenated form in a textual column; the binary format of
+// UUID is always 16 bytes long, which text that can be parsed as a UUID never is
 impl<'de> Decode<'de, Postgres> for Uuid {
     fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
         match value.try_into()? {
-            PgValue::Binary(buf) => Uuid::from_slice(buf).map_err(|err| crate::Error::decode(err)),
-            PgValue::Text(s) => Uuid::from_str(s).map_err(|err| crate::Error::decode(err)),
+            PgValue::Binary(buf) if buf.len() == 16 => {
+                Uuid::from_slice(buf).map_err(crate::Error::decode)
+            }
+
+            value => {
+                let s = <&'de str as Decode<Postgres>>::decode(Some(value))?;
+
+                Uuid::from_str(s).map_err(crate::Error::decode)
+            }
         }
     }
 }
+
+// The hyphenated form of a UUID, for a UUID stored in a TEXT or VARCHAR column
+impl Type<Postgres> for Hyphenated {
+    fn type_info() -> PgTypeInfo {
+        <str as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for Hyphenated {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let mut s = [0; Hyphenated::LENGTH];
+
+        buf.extend_from_slice(self.encode_lower(&mut s).as_bytes());
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for Hyphenated {
+    fn decode(value: Option<PgValue<'de>>) -> crate::Result<Self> {
+        let s = <&'de str as Decode<Postgres>>::decode(value)?;
+
+        Uuid::from_str(s)
+            .map(Uuid::to_hyphenated)
+            .map_err(crate::Error::decode)
+    }
+}
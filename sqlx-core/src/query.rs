@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::marker::PhantomData;
 
 use async_stream::try_stream;
@@ -6,10 +7,19 @@ use futures_util::future::ready;
 use futures_util::TryFutureExt;
 
 use crate::arguments::Arguments;
-use crate::cursor::Cursor;
+use crate::cursor::{reserve_row, Cursor};
 use crate::database::{Database, HasCursor, HasRow};
+#[cfg(feature = "json")]
+use crate::decode::DecodeOwned;
 use crate::encode::Encode;
-use crate::executor::{Execute, Executor, RefExecutor};
+use crate::executor::{Execute, Executor, FirstRow, RefExecutor};
+#[cfg(feature = "json")]
+use crate::explain::{Explain, ExplainPlan};
+use crate::named;
+use crate::quote::Identifier;
+use crate::row::FromRow;
+#[cfg(feature = "json")]
+use crate::row::{ColumnIndex, Row};
 use crate::types::Type;
 
 /// Raw SQL query with bind parameters. Returned by [`query`][crate::query::query].
@@ -19,6 +29,8 @@ where
 {
     pub(crate) query: &'q str,
     pub(crate) arguments: DB::Arguments,
+    // whether values were bound by position, which can't be mixed with named parameters
+    positional: bool,
     database: PhantomData<DB>,
 }
 
@@ -68,19 +80,64 @@ where
         T: Encode<DB>,
     {
         self.arguments.add(value);
+        self.positional = true;
         self
     }
 
-    #[doc(hidden)]
+    /// Bind all the arguments of this SQL query at once, replacing any that were bound before.
+    ///
+    /// Arguments taken from [`Connection::take_arguments`] keep the buffers of the last query
+    /// run on that connection; refilling them for each query of a loop avoids allocating new
+    /// ones every time.
+    ///
+    /// [`Connection::take_arguments`]: crate::connection::Connection::take_arguments
     pub fn bind_all(self, arguments: DB::Arguments) -> Query<'q, DB> {
         Query {
             query: self.query,
             arguments,
+            positional: true,
             database: PhantomData,
         }
     }
 }
 
+impl<'q, DB> Query<'q, DB>
+where
+    DB: Database,
+{
+    /// Bind a value to the named parameter `:name` of this SQL query, making it a
+    /// [`NamedQuery`].
+    ///
+    /// ```rust,ignore
+    /// let rows = sqlx::query("SELECT * FROM users WHERE id = :id OR parent_id = :id")
+    ///     .bind_named("id", 10)
+    ///     .fetch_all::<(i32, String), _>(&mut conn)
+    ///     .await?;
+    /// ```
+    ///
+    /// Before the query is sent, each `:name` is replaced by a bind parameter of the database
+    /// (`$1` for Postgres, `?` for MySQL). A `:` in a string, a quoted identifier or a comment,
+    /// or in a cast (`::type`), is left alone; write an array slice as `[lo : hi]`. Named and
+    /// positional parameters can't be mixed in a query: if values were bound with
+    /// [`bind`](#method.bind) or [`bind_all`](#method.bind_all) before, running the query
+    /// fails with [`Error::Encode`] without sending it.
+    ///
+    /// [`Error::Encode`]: crate::Error::Encode
+    pub fn bind_named<T>(self, name: &'q str, value: T) -> NamedQuery<'q, DB>
+    where
+        T: Type<DB>,
+        T: Encode<DB>,
+        T: Send + 'q,
+    {
+        NamedQuery {
+            query: self.query,
+            positional: self.positional,
+            values: Vec::new(),
+        }
+        .bind_named(name, value)
+    }
+}
+
 impl<'q, DB> Query<'q, DB>
 where
     DB: Database,
@@ -131,6 +188,93 @@ where
     {
         executor.fetch_by_ref(self)
     }
+
+    /// Folds the rows of the query into `init` with `fold`, which only borrows each row, and
+    /// returns the result; the first error of `fold` stops the query.
+    ///
+    /// Only the row being folded is held in memory, so this can aggregate any number of rows
+    /// without mapping each of them to an owned value first:
+    ///
+    /// ```rust,ignore
+    /// let total: i64 = sqlx::query("SELECT amount FROM payments")
+    ///     .fetch_scan(&mut conn, 0, |total, row: &PgRow| {
+    ///         *total += row.try_get::<i64, _>(0)?;
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn fetch_scan<'e, E, S, F>(
+        self,
+        executor: E,
+        init: S,
+        mut fold: F,
+    ) -> crate::Result<S>
+    where
+        E: RefExecutor<'e, Database = DB>,
+        F: for<'r> FnMut(&mut S, &<DB as HasRow<'r>>::Row) -> crate::Result<()>,
+    {
+        let mut cursor = executor.fetch_by_ref(self);
+        let mut state = init;
+
+        while let Some(row) = cursor.next().await? {
+            fold(&mut state, &row)?;
+        }
+
+        Ok(state)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'q, DB> Query<'q, DB>
+where
+    DB: Explain,
+    String: DecodeOwned<DB>,
+    usize: ColumnIndex<DB>,
+{
+    /// Returns the plan the database would use to run the query with the arguments bound so
+    /// far, which can change it. The query itself is not run.
+    ///
+    /// The plan is requested with `EXPLAIN (FORMAT JSON)` from Postgres, `EXPLAIN FORMAT=JSON`
+    /// from MySQL and `EXPLAIN QUERY PLAN` from SQLite.
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn explain<'e, E>(self, executor: E) -> crate::Result<ExplainPlan>
+    where
+        E: RefExecutor<'e, Database = DB>,
+    {
+        self.fetch_plan(executor, false).await
+    }
+
+    /// Runs the query and returns its plan along with the number of rows each step actually
+    /// produced, with `EXPLAIN (FORMAT JSON, ANALYZE)`.
+    ///
+    /// As the query is run, so are its side effects; wrap it in a transaction that is rolled
+    /// back to explain a statement that changes data. Only supported by Postgres.
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn explain_analyze<'e, E>(self, executor: E) -> crate::Result<ExplainPlan>
+    where
+        E: RefExecutor<'e, Database = DB>,
+    {
+        self.fetch_plan(executor, true).await
+    }
+
+    async fn fetch_plan<'e, E>(self, executor: E, analyze: bool) -> crate::Result<ExplainPlan>
+    where
+        E: RefExecutor<'e, Database = DB>,
+    {
+        let statement = DB::explain_statement(self.query, analyze)?;
+        let mut cursor = executor.fetch_by_ref(query::<DB>(&statement).bind_all(self.arguments));
+        let mut rows = Vec::new();
+
+        while let Some(row) = cursor.next().await? {
+            let columns = (0..row.len())
+                .map(|index| row.try_get_unchecked(index))
+                .collect::<crate::Result<Vec<String>>>()?;
+
+            rows.push(columns);
+        }
+
+        DB::parse_plan(rows)
+    }
 }
 
 impl<'q, DB, F> Map<'q, DB, F>
@@ -140,6 +284,9 @@ where
     F: TryMapRow<DB>,
 {
     /// Execute the query and get a [Stream] of the results, returning our mapped type.
+    ///
+    /// Each row is mapped before the next one is read, so the values of the stream don't
+    /// borrow the connection and can be kept across `.await`s while it goes on.
     pub fn fetch<'e: 'q, E>(
         mut self,
         executor: E,
@@ -166,7 +313,7 @@ where
         'q: 'e,
     {
         // could be implemented in terms of `fetch()` but this avoids overhead from `try_stream!`
-        let mut cursor = executor.fetch_by_ref(self.query);
+        let mut cursor = executor.fetch_by_ref(FirstRow(self.query));
         let mut mapper = self.mapper;
         let val = cursor.next().await?;
         val.map(|row| mapper.try_map_row(row)).transpose()
@@ -185,20 +332,67 @@ where
             .await
     }
 
-    pub async fn fetch_all<'e, E>(mut self, executor: E) -> crate::Result<Vec<F::Output>>
+    pub async fn fetch_all<'e, E>(self, executor: E) -> crate::Result<Vec<F::Output>>
+    where
+        E: RefExecutor<'e, Database = DB>,
+        'q: 'e,
+    {
+        self.fetch_all_with_capacity(executor, 0).await
+    }
+
+    /// Like [`fetch_all`](#method.fetch_all), with room for `capacity` rows set aside up front
+    /// for a caller that knows about how many rows the query returns.
+    ///
+    /// None of the databases says how many rows are coming before it sends them, so
+    /// `fetch_all` starts with room for 64 rows and doubles it whenever it runs out.
+    pub async fn fetch_all_with_capacity<'e, E>(
+        mut self,
+        executor: E,
+        capacity: usize,
+    ) -> crate::Result<Vec<F::Output>>
     where
         E: RefExecutor<'e, Database = DB>,
         'q: 'e,
     {
         let mut cursor = executor.fetch_by_ref(self.query);
-        let mut out = vec![];
+        let mut out = Vec::with_capacity(capacity);
 
         while let Some(row) = cursor.next().await? {
-            out.push(self.mapper.try_map_row(row)?);
+            let row = self.mapper.try_map_row(row)?;
+
+            reserve_row(&mut out);
+            out.push(row);
         }
 
         Ok(out)
     }
+
+    /// Like [`fetch_all`](#method.fetch_all), along with the number of rows the query
+    /// inserted, updated or deleted, as given by
+    /// [`Cursor::rows_affected`](crate::cursor::Cursor::rows_affected).
+    ///
+    /// The count is not the length of the rows: a `SELECT` affects none, and a MySQL procedure
+    /// or a query of several statements may change rows it does not return.
+    pub async fn fetch_all_with_count<'e, E>(
+        mut self,
+        executor: E,
+    ) -> crate::Result<(Vec<F::Output>, u64)>
+    where
+        E: RefExecutor<'e, Database = DB>,
+        'q: 'e,
+    {
+        let mut cursor = executor.fetch_by_ref(self.query);
+        let mut out = Vec::new();
+
+        while let Some(row) = cursor.next().await? {
+            let row = self.mapper.try_map_row(row)?;
+
+            reserve_row(&mut out);
+            out.push(row);
+        }
+
+        Ok((out, cursor.rows_affected().unwrap_or(0)))
+    }
 }
 
 // A (hopefully) temporary workaround for an internal compiler error (ICE) involving higher-ranked
@@ -243,6 +437,413 @@ where
     Query {
         database: PhantomData,
         arguments: Default::default(),
+        positional: false,
         query: sql,
     }
 }
+
+/// Raw SQL query with named bind parameters (`:name`). Returned by [`Query::bind_named`].
+pub struct NamedQuery<'q, DB>
+where
+    DB: Database,
+{
+    query: &'q str,
+
+    // values were bound by position to the query before
+    positional: bool,
+
+    values: Vec<(&'q str, AddValue<'q, DB>)>,
+}
+
+// Adds a named value to the arguments; once for every time it is used, with MySQL
+type AddValue<'q, DB> = Box<dyn Fn(&mut <DB as Database>::Arguments) + Send + 'q>;
+
+impl<'q, DB> NamedQuery<'q, DB>
+where
+    DB: Database,
+{
+    /// Bind a value to the named parameter `:name`, replacing any bound to it before. The
+    /// leading `:` of `name` is optional.
+    pub fn bind_named<T>(mut self, name: &'q str, value: T) -> Self
+    where
+        T: Type<DB>,
+        T: Encode<DB>,
+        T: Send + 'q,
+    {
+        let name = name.trim_start_matches(':');
+
+        self.values.retain(|(bound, _)| *bound != name);
+        self.values
+            .push((name, Box::new(move |arguments| arguments.add(&value))));
+
+        self
+    }
+
+    fn into_statement(self) -> crate::Result<(String, DB::Arguments)> {
+        if self.positional {
+            return Err(crate::Error::Encode(
+                "named parameters can't be bound to a query with positional parameters".into(),
+            ));
+        }
+
+        let (statement, names) = named::rewrite(self.query, DB::PARAMETER_STYLE);
+        let mut arguments = DB::Arguments::default();
+
+        for name in names {
+            let add = self
+                .values
+                .iter()
+                .find(|(bound, _)| *bound == name)
+                .map(|(_, add)| add)
+                .ok_or_else(|| {
+                    crate::Error::Encode(
+                        format!("no value bound to the named parameter :{}", name).into(),
+                    )
+                })?;
+
+            add(&mut arguments);
+        }
+
+        Ok((statement, arguments))
+    }
+
+    /// Execute the query and return the number of affected rows.
+    pub async fn execute<E>(self, mut executor: E) -> crate::Result<u64>
+    where
+        E: Executor<Database = DB>,
+    {
+        let (statement, arguments) = self.into_statement()?;
+
+        executor
+            .execute(query::<DB>(&statement).bind_all(arguments))
+            .await
+    }
+
+    /// Get the first row in the result, returning [`Error::RowNotFound`] if there is none.
+    ///
+    /// [`Error::RowNotFound`]: crate::Error::RowNotFound
+    pub async fn fetch_one<'e, O, E>(self, executor: E) -> crate::Result<O>
+    where
+        E: RefExecutor<'e, Database = DB>,
+        O: for<'c> FromRow<'c, <DB as HasRow<'c>>::Row>,
+    {
+        self.fetch_optional(executor)
+            .await?
+            .ok_or(crate::Error::RowNotFound)
+    }
+
+    /// Get the first row in the result, if any.
+    pub async fn fetch_optional<'e, O, E>(self, executor: E) -> crate::Result<Option<O>>
+    where
+        E: RefExecutor<'e, Database = DB>,
+        O: for<'c> FromRow<'c, <DB as HasRow<'c>>::Row>,
+    {
+        let (statement, arguments) = self.into_statement()?;
+
+        let mut cursor =
+            executor.fetch_by_ref(FirstRow(query::<DB>(&statement).bind_all(arguments)));
+
+        let row = cursor.next().await?;
+        row.map(O::from_row).transpose()
+    }
+
+    /// Collect every row in the result.
+    pub async fn fetch_all<'e, O, E>(self, executor: E) -> crate::Result<Vec<O>>
+    where
+        E: RefExecutor<'e, Database = DB>,
+        O: for<'c> FromRow<'c, <DB as HasRow<'c>>::Row>,
+    {
+        let (statement, arguments) = self.into_statement()?;
+
+        let mut cursor = executor.fetch_by_ref(query::<DB>(&statement).bind_all(arguments));
+        let mut out = Vec::new();
+
+        while let Some(row) = cursor.next().await? {
+            let row = O::from_row(row)?;
+
+            reserve_row(&mut out);
+            out.push(row);
+        }
+
+        Ok(out)
+    }
+}
+
+/// Call of a stored procedure or function with bind parameters. Returned by [query_call].
+pub struct QueryCall<'n, DB>
+where
+    DB: Database,
+{
+    name: &'n str,
+    function: bool,
+    params: usize,
+    arguments: DB::Arguments,
+}
+
+impl<'n, DB> QueryCall<'n, DB>
+where
+    DB: Database,
+{
+    /// Bind a value for the next parameter of the procedure.
+    pub fn bind<T>(mut self, value: T) -> Self
+    where
+        T: Type<DB>,
+        T: Encode<DB>,
+    {
+        self.arguments.add(value);
+        self.params += 1;
+        self
+    }
+
+    /// Call a function instead of a stored procedure.
+    ///
+    /// On Postgres, the function is called with `SELECT * FROM` so its `OUT` parameters (or
+    /// the fields of the composite type it returns) are read as separate columns.
+    pub fn function(mut self) -> Self {
+        self.function = true;
+        self
+    }
+
+    fn statement(&self) -> String {
+        DB::call_statement(self.name, self.params, self.function)
+    }
+
+    /// Execute the call and return the number of affected rows.
+    pub async fn execute<E>(self, mut executor: E) -> crate::Result<u64>
+    where
+        E: Executor<Database = DB>,
+    {
+        let statement = self.statement();
+
+        executor
+            .execute(Query {
+                query: &statement,
+                arguments: self.arguments,
+                positional: true,
+                database: PhantomData,
+            })
+            .await
+    }
+
+    /// Execute the call and map the row holding the values of its `OUT` and `INOUT`
+    /// parameters (or the first row returned by a function).
+    ///
+    /// Anything returned after that row, such as the final status of a MySQL `CALL`, is read
+    /// before the connection runs another query.
+    pub async fn fetch_one<'e, O, E>(self, executor: E) -> crate::Result<O>
+    where
+        E: RefExecutor<'e, Database = DB>,
+        O: for<'c> FromRow<'c, <DB as HasRow<'c>>::Row>,
+    {
+        let statement = self.statement();
+
+        let mut cursor = executor.fetch_by_ref(Query {
+            query: &statement,
+            arguments: self.arguments,
+            positional: true,
+            database: PhantomData,
+        });
+
+        let row = cursor.next().await?.ok_or(crate::Error::RowNotFound)?;
+
+        O::from_row(row)
+    }
+}
+
+/// Make a call of the stored procedure (or function) `name`, passing each bound value as the
+/// next parameter.
+///
+/// This generates the statement for the target database: `CALL name($1, $2)` for a Postgres
+/// procedure or `CALL name(?, ?)` for a MySQL one. `name` is included as-is and must not come
+/// from untrusted input.
+///
+/// ```rust,ignore
+/// // CREATE PROCEDURE increment(INOUT counter INT, step INT) ...
+/// let (counter,): (i32,) = sqlx::query_call::<Postgres>("increment")
+///     .bind(1)
+///     .bind(5)
+///     .fetch_one(&mut conn)
+///     .await?;
+/// ```
+///
+/// Postgres returns the values of the `INOUT` parameters of a procedure as a single row. MySQL
+/// only does so when the statement is prepared, which is the case once a value is bound; to
+/// read the `OUT` parameters of a procedure that takes none, pass user variables instead (see
+/// `MySqlConnection::call`).
+pub fn query_call<DB>(name: &str) -> QueryCall<'_, DB>
+where
+    DB: Database,
+{
+    QueryCall {
+        name,
+        function: false,
+        params: 0,
+        arguments: Default::default(),
+    }
+}
+
+/// A text or binary value of a single row, of which only the length or a part is read, so a
+/// large value isn't transferred whole to look at its start. Returned by [large_value].
+pub struct LargeValue<'a, K> {
+    table: Identifier<'a>,
+    column: &'a str,
+    key_column: &'a str,
+    key: K,
+}
+
+impl<'a, K> LargeValue<'a, K> {
+    /// Returns the length of the value, in bytes for a binary value and in characters for a
+    /// text one, or `None` if it is `NULL`.
+    ///
+    /// Fails with [`RowNotFound`][crate::Error::RowNotFound] if no row has the key.
+    pub async fn length<'e, DB, E>(&self, executor: E) -> crate::Result<Option<i64>>
+    where
+        DB: Database,
+        E: RefExecutor<'e, Database = DB>,
+        K: Type<DB> + Encode<DB>,
+        i32: Type<DB> + Encode<DB>,
+        (Option<i64>,): for<'c> FromRow<'c, <DB as HasRow<'c>>::Row>,
+    {
+        let column = quote_identifier::<DB>(self.column)?;
+        let value = format!("{}({})", DB::LENGTH_FUNCTION, column);
+
+        let (length,) = self.fetch_part(executor, &value, (0, 0)).await?;
+
+        Ok(length)
+    }
+
+    /// Returns up to `len` bytes of the value, or characters of a text one as UTF-8, starting
+    /// at `offset`, counted from `0`; or `None` if it is `NULL`. Fewer are returned if the
+    /// value ends before, and none if it ends before `offset`.
+    ///
+    /// Fails with [`RowNotFound`][crate::Error::RowNotFound] if no row has the key, or with
+    /// [`Encode`][crate::Error::Encode] before anything is sent if `offset` or `len` is larger
+    /// than an `INT` can hold.
+    pub async fn substring<'e, DB, E>(
+        &self,
+        executor: E,
+        offset: usize,
+        len: usize,
+    ) -> crate::Result<Option<Vec<u8>>>
+    where
+        DB: Database,
+        E: RefExecutor<'e, Database = DB>,
+        K: Type<DB> + Encode<DB>,
+        i32: Type<DB> + Encode<DB>,
+        (Option<Vec<u8>>,): for<'c> FromRow<'c, <DB as HasRow<'c>>::Row>,
+    {
+        // positions in SQL start at 1
+        let start = offset
+            .checked_add(1)
+            .and_then(|start| i32::try_from(start).ok());
+
+        let range = match (start, i32::try_from(len)) {
+            (Some(start), Ok(len)) => (start, len),
+
+            _ => {
+                return Err(crate::Error::Encode(
+                    format!(
+                        "substring of {} from {} is out of range of an INT",
+                        len, offset
+                    )
+                    .into(),
+                ))
+            }
+        };
+
+        let column = quote_identifier::<DB>(self.column)?;
+        let value = format!("{}({}, :start, :len)", DB::SUBSTRING_FUNCTION, column);
+
+        let (part,) = self.fetch_part(executor, &value, range).await?;
+
+        Ok(part)
+    }
+
+    // Selects `value` from the row with the key; `range` is bound to the named parameters
+    // `:start` and `:len` of `value`, in whichever order the database takes them
+    async fn fetch_part<'e, DB, E, O>(
+        &self,
+        executor: E,
+        value: &str,
+        range: (i32, i32),
+    ) -> crate::Result<O>
+    where
+        DB: Database,
+        E: RefExecutor<'e, Database = DB>,
+        K: Type<DB> + Encode<DB>,
+        i32: Type<DB> + Encode<DB>,
+        O: for<'c> FromRow<'c, <DB as HasRow<'c>>::Row>,
+    {
+        let table = self
+            .table
+            .quote::<DB>()
+            .map_err(|err| crate::Error::Encode(err.into()))?;
+
+        let statement = format!(
+            "SELECT {} FROM {} WHERE {} = :key",
+            value,
+            table,
+            quote_identifier::<DB>(self.key_column)?
+        );
+
+        let (statement, names) = named::rewrite(&statement, DB::PARAMETER_STYLE);
+        let mut arguments = DB::Arguments::default();
+
+        for name in names {
+            match name {
+                "key" => arguments.add(&self.key),
+                "start" => arguments.add(range.0),
+                _ => arguments.add(range.1),
+            }
+        }
+
+        let mut cursor = executor.fetch_by_ref(Query {
+            query: &statement,
+            arguments,
+            positional: true,
+            database: PhantomData,
+        });
+
+        let row = cursor.next().await?.ok_or(crate::Error::RowNotFound)?;
+
+        O::from_row(row)
+    }
+}
+
+fn quote_identifier<DB: Database>(ident: &str) -> crate::Result<String> {
+    DB::quote_identifier(ident).map_err(|err| crate::Error::Encode(err.into()))
+}
+
+/// Reads the value of `column` of the row of `table` whose `key_column` is `key`, in parts;
+/// e.g. to check the size of a large `BYTEA` or `BLOB` before fetching it, or to serve a
+/// range of it.
+///
+/// This generates the statements for the target database, with the identifiers quoted and
+/// the key and range bound as parameters:
+///
+/// ```rust,ignore
+/// let value = sqlx::query::large_value(Identifier::new("files"), "content", "id", file_id);
+///
+/// // SELECT length("content") FROM "files" WHERE "id" = $1
+/// let size = value.length(&mut conn).await?;
+///
+/// // SELECT substr("content", $2, $3) FROM "files" WHERE "id" = $1
+/// let header = value.substring(&mut conn, 0, 512).await?;
+/// ```
+///
+/// MySQL uses `CHAR_LENGTH` and `SUBSTRING` instead. Postgres may still read a whole value
+/// that is compressed in order to take a part of it; storing the column uncompressed with
+/// `ALTER TABLE ... ALTER COLUMN ... SET STORAGE EXTERNAL` lets it read only that part.
+pub fn large_value<'a, K>(
+    table: Identifier<'a>,
+    column: &'a str,
+    key_column: &'a str,
+    key: K,
+) -> LargeValue<'a, K> {
+    LargeValue {
+        table,
+        column,
+        key_column,
+        key,
+    }
+}
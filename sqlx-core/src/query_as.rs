@@ -90,6 +90,28 @@ macro_rules! make_query_as {
                 O: 'e + Send + for<'c> crate::row::FromRow<'c, $row<'c>>,
                 'q: 'e;
 
+            /// Like `fetch_all`, with room for `capacity` rows set aside up front.
+            fn fetch_all_with_capacity<'e, E>(
+                self,
+                executor: E,
+                capacity: usize,
+            ) -> futures_core::future::BoxFuture<'e, crate::Result<Vec<O>>>
+            where
+                E: 'e + Send + crate::executor::RefExecutor<'e, Database = $db>,
+                O: 'e + Send + for<'c> crate::row::FromRow<'c, $row<'c>>,
+                'q: 'e;
+
+            /// Like `fetch_all`, along with the number of rows the query inserted, updated or
+            /// deleted; see [`Cursor::rows_affected`](crate::cursor::Cursor::rows_affected).
+            fn fetch_all_with_count<'e, E>(
+                self,
+                executor: E,
+            ) -> futures_core::future::BoxFuture<'e, crate::Result<(Vec<O>, u64)>>
+            where
+                E: 'e + Send + crate::executor::RefExecutor<'e, Database = $db>,
+                O: 'e + Send + for<'c> crate::row::FromRow<'c, $row<'c>>,
+                'q: 'e;
+
             fn fetch_one<'e, E>(
                 self,
                 executor: E,
@@ -144,7 +166,7 @@ macro_rules! make_query_as {
                 use crate::cursor::Cursor;
 
                 Box::pin(async move {
-                    let mut cursor = executor.fetch_by_ref(self);
+                    let mut cursor = executor.fetch_by_ref(crate::executor::FirstRow(self));
                     let row = cursor.next().await?;
 
                     row.map(O::from_row).transpose()
@@ -177,21 +199,61 @@ macro_rules! make_query_as {
                 O: 'e + Send + for<'c> crate::row::FromRow<'c, $row<'c>>,
                 'q: 'e,
             {
-                use crate::cursor::Cursor;
+                self.fetch_all_with_capacity(executor, 0)
+            }
+
+            fn fetch_all_with_capacity<'e, E>(
+                self,
+                executor: E,
+                capacity: usize,
+            ) -> futures_core::future::BoxFuture<'e, crate::Result<Vec<O>>>
+            where
+                E: 'e + Send + crate::executor::RefExecutor<'e, Database = $db>,
+                O: 'e + Send + for<'c> crate::row::FromRow<'c, $row<'c>>,
+                'q: 'e,
+            {
+                use crate::cursor::{reserve_row, Cursor};
 
                 Box::pin(async move {
                     let mut cursor = executor.fetch_by_ref(self);
-                    let mut out = Vec::new();
+                    let mut out = Vec::with_capacity(capacity);
 
                     while let Some(row) = cursor.next().await? {
                         let obj = O::from_row(row)?;
 
+                        reserve_row(&mut out);
                         out.push(obj);
                     }
 
                     Ok(out)
                 })
             }
+
+            fn fetch_all_with_count<'e, E>(
+                self,
+                executor: E,
+            ) -> futures_core::future::BoxFuture<'e, crate::Result<(Vec<O>, u64)>>
+            where
+                E: 'e + Send + crate::executor::RefExecutor<'e, Database = $db>,
+                O: 'e + Send + for<'c> crate::row::FromRow<'c, $row<'c>>,
+                'q: 'e,
+            {
+                use crate::cursor::{reserve_row, Cursor};
+
+                Box::pin(async move {
+                    let mut cursor = executor.fetch_by_ref(self);
+                    let mut out = Vec::new();
+
+                    while let Some(row) = cursor.next().await? {
+                        let obj = O::from_row(row)?;
+
+                        reserve_row(&mut out);
+                        out.push(obj);
+                    }
+
+                    Ok((out, cursor.rows_affected().unwrap_or(0)))
+                })
+            }
         }
     };
 }
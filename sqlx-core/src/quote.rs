@@ -0,0 +1,292 @@
+//! Quoting of identifiers, which can't be passed as bind parameters, and escaping of the
+//! patterns of `LIKE`, which can.
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+
+use crate::database::Database;
+
+/// The name of a table, column or other object to splice into a query, optionally qualified by
+/// the schema (or database, for MySQL) it belongs to.
+///
+/// ```rust,ignore
+/// let table = Identifier::qualified("audit", user_supplied_name).quote::<Postgres>()?;
+/// let rows = sqlx::query(&format!("SELECT * FROM {} WHERE id = $1", table))
+/// ```
+///
+/// Both parts are quoted as a whole, so a `.` in either of them is part of the name rather
+/// than a separator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Identifier<'a> {
+    schema: Option<&'a str>,
+    name: &'a str,
+}
+
+impl<'a> Identifier<'a> {
+    pub fn new(name: &'a str) -> Self {
+        Self { schema: None, name }
+    }
+
+    pub fn qualified(schema: &'a str, name: &'a str) -> Self {
+        Self {
+            schema: Some(schema),
+            name,
+        }
+    }
+
+    /// Quotes the identifier for `DB`; see [`Database::quote_identifier`].
+    pub fn quote<DB: Database>(&self) -> Result<String, InvalidIdentifier> {
+        let name = DB::quote_identifier(self.name)?;
+
+        Ok(match self.schema {
+            Some(schema) => format!("{}.{}", DB::quote_identifier(schema)?, name),
+            None => name,
+        })
+    }
+}
+
+/// An identifier that can't be used by the database, even when quoted; for instance, one that
+/// is empty or contains a NUL character.
+#[derive(Debug)]
+pub struct InvalidIdentifier {
+    ident: Box<str>,
+    reason: &'static str,
+}
+
+impl InvalidIdentifier {
+    pub(crate) fn new(ident: &str, reason: &'static str) -> Self {
+        Self {
+            ident: ident.into(),
+            reason,
+        }
+    }
+}
+
+impl Display for InvalidIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid identifier {:?}: {}", self.ident, self.reason)
+    }
+}
+
+impl StdError for InvalidIdentifier {}
+
+/// Escapes `input` to be matched literally by `LIKE`: `%`, `_` and `escape` itself are each
+/// preceded by `escape`. The pattern is then bound as usual, with wildcards of its own added
+/// around it as needed, and `escape` given to the `ESCAPE` clause:
+///
+/// ```rust,ignore
+/// let pattern = format!("%{}%", escape_like(user_input, '\\'));
+///
+/// // Postgres
+/// sqlx::query("SELECT * FROM users WHERE name LIKE $1 ESCAPE '\\'").bind(pattern)
+///
+/// // MySQL, where a backslash escapes in string literals too
+/// sqlx::query("SELECT * FROM users WHERE name LIKE ? ESCAPE '\\\\'").bind(pattern)
+///
+/// // SQLite, which has no escape character unless one is given
+/// sqlx::query("SELECT * FROM users WHERE name LIKE ?1 ESCAPE '\\'").bind(pattern)
+/// ```
+///
+/// Without it, input such as `%` matches every row. Any character may be the escape, including
+/// one outside of ASCII, as long as it is the one given to `ESCAPE`.
+pub fn escape_like(input: &str, escape: char) -> String {
+    let mut escaped = String::with_capacity(input.len());
+
+    for ch in input.chars() {
+        if ch == '%' || ch == '_' || ch == escape {
+            escaped.push(escape);
+        }
+
+        escaped.push(ch);
+    }
+
+    escaped
+}
+
+// Wraps `ident` in `quote`, doubling any occurrence of it
+pub(crate) fn quote_with(ident: &str, quote: char) -> Result<String, InvalidIdentifier> {
+    if ident.is_empty() {
+        return Err(InvalidIdentifier::new(ident, "identifier is empty"));
+    }
+
+    if ident.contains('\0') {
+        return Err(InvalidIdentifier::new(ident, "identifier contains a NUL"));
+    }
+
+    let mut quoted = String::with_capacity(ident.len() + 2);
+    quoted.push(quote);
+
+    for ch in ident.chars() {
+        if ch == quote {
+            quoted.push(quote);
+        }
+
+        quoted.push(ch);
+    }
+
+    quoted.push(quote);
+
+    Ok(quoted)
+}
+
+// Splits a table name as written in SQL, such as `public.users` or `"My Schema"."Users"`, into
+// its schema, if it is qualified, and its name. A part wrapped in one of the pairs of `quotes`
+// is taken as written, with doubled closing quotes undone; `fold` is applied to a part that is
+// not quoted. Returns `None` for a name that could not be written in SQL.
+#[allow(dead_code)]
+pub(crate) fn split_qualified(
+    name: &str,
+    quotes: &[(char, char)],
+    fold: fn(&str) -> String,
+) -> Option<(Option<String>, String)> {
+    let mut parts = Vec::new();
+    let mut chars = name.chars().peekable();
+
+    loop {
+        let close = chars
+            .peek()
+            .and_then(|&ch| quotes.iter().find(|&&(open, _)| open == ch))
+            .map(|&(_, close)| close);
+
+        let part = if let Some(close) = close {
+            chars.next();
+
+            let mut part = String::new();
+
+            loop {
+                match chars.next()? {
+                    ch if ch == close => {
+                        if chars.peek() == Some(&close) {
+                            chars.next();
+                            part.push(close);
+                        } else {
+                            break;
+                        }
+                    }
+
+                    ch => part.push(ch),
+                }
+            }
+
+            part
+        } else {
+            let mut part = String::new();
+
+            while let Some(&ch) = chars.peek() {
+                if ch == '.' {
+                    break;
+                }
+
+                if ch.is_whitespace() || quotes.iter().any(|&(open, _)| open == ch) {
+                    return None;
+                }
+
+                part.push(ch);
+                chars.next();
+            }
+
+            fold(&part)
+        };
+
+        if part.is_empty() {
+            return None;
+        }
+
+        parts.push(part);
+
+        match chars.next() {
+            Some('.') => {}
+            Some(_) => return None,
+            None => break,
+        }
+    }
+
+    let name = parts.pop()?;
+
+    match parts.len() {
+        0 => Some((None, name)),
+        1 => Some((parts.pop(), name)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_qualified_names() {
+        let pg = |name| split_qualified(name, &[('"', '"')], str::to_ascii_lowercase);
+
+        assert_eq!(pg("Users"), Some((None, "users".to_owned())));
+        assert_eq!(
+            pg("Public.Users"),
+            Some((Some("public".to_owned()), "users".to_owned()))
+        );
+        assert_eq!(
+            pg(r#""My Schema"."Us""ers""#),
+            Some((Some("My Schema".to_owned()), "Us\"ers".to_owned()))
+        );
+        assert_eq!(pg(r#""a.b""#), Some((None, "a.b".to_owned())));
+
+        let sqlite =
+            |name| split_qualified(name, &[('"', '"'), ('`', '`'), ('[', ']')], str::to_owned);
+
+        assert_eq!(
+            sqlite("[main].`Users`"),
+            Some((Some("main".to_owned()), "Users".to_owned()))
+        );
+    }
+
+    #[test]
+    fn it_rejects_names_that_could_not_be_written() {
+        let pg = |name| split_qualified(name, &[('"', '"')], str::to_ascii_lowercase);
+
+        assert_eq!(pg(""), None);
+        assert_eq!(pg("a..b"), None);
+        assert_eq!(pg("a.b.c"), None);
+        assert_eq!(pg("a b"), None);
+        assert_eq!(pg(r#""unterminated"#), None);
+        assert_eq!(pg(r#""a"b"#), None);
+        assert_eq!(pg(r#""""#), None);
+    }
+
+    #[test]
+    fn it_doubles_the_quote() {
+        assert_eq!(quote_with("users", '"').unwrap(), r#""users""#);
+        assert_eq!(
+            quote_with(r#"a"; DROP TABLE users; --"#, '"').unwrap(),
+            r#""a""; DROP TABLE users; --""#
+        );
+        assert_eq!(quote_with(r#"""#, '"').unwrap(), r#""""""#);
+        assert_eq!(quote_with("a`b", '`').unwrap(), "`a``b`");
+        assert_eq!(quote_with("a\"b", '`').unwrap(), "`a\"b`");
+        assert_eq!(quote_with("tábla 表", '"').unwrap(), "\"tábla 表\"");
+    }
+
+    #[test]
+    fn it_escapes_like_patterns() {
+        assert_eq!(escape_like("john", '\\'), "john");
+        assert_eq!(escape_like("100%", '\\'), "100\\%");
+        assert_eq!(escape_like("%%__", '\\'), "\\%\\%\\_\\_");
+        assert_eq!(escape_like("a\\%b", '\\'), "a\\\\\\%b");
+        assert_eq!(escape_like("\\\\", '\\'), "\\\\\\\\");
+        assert_eq!(escape_like("", '\\'), "");
+    }
+
+    #[test]
+    fn it_escapes_like_patterns_with_any_character() {
+        assert_eq!(escape_like("50% off!", '!'), "50!% off!!");
+        assert_eq!(escape_like("ünï_cødé%", '§'), "ünï§_cødé§%");
+        assert_eq!(escape_like("a§b", '§'), "a§§b");
+
+        // a backslash is only escaped when it is the escape character
+        assert_eq!(escape_like("C:\\_", '!'), "C:\\!_");
+    }
+
+    #[test]
+    fn it_rejects_empty_names_and_nul() {
+        assert!(quote_with("", '"').is_err());
+        assert!(quote_with("a\0b", '"').is_err());
+    }
+}
@@ -1,8 +1,24 @@
 //! Contains the Row and FromRow traits.
 
+#[cfg(feature = "json")]
+use std::fmt::Display;
+
 use crate::database::{Database, HasRawValue, HasRow};
 use crate::decode::Decode;
+#[cfg(any(
+    feature = "postgres",
+    feature = "mysql",
+    feature = "sqlite",
+    feature = "json"
+))]
+use crate::error::ColumnDecodeError;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+use crate::error::MismatchedTypeError;
+#[cfg(feature = "json")]
+use crate::types::JsonText;
 use crate::types::Type;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+use crate::types::TypeInfo;
 
 pub trait ColumnIndex<DB>
 where
@@ -24,6 +40,12 @@ pub trait Row<'c>: Unpin + Send {
     /// Returns the number of values in the row.
     fn len(&self) -> usize;
 
+    /// Decodes the value at `index` as `T`; see [`try_get`](Row::try_get).
+    ///
+    /// # Panics
+    ///
+    /// Panics with the message of the error `try_get` would return, which names the column,
+    /// if the value can not be decoded; for instance if it is `NULL` and `T` is not an `Option`.
     fn get<'r, T, I>(&'r self, index: I) -> T
     where
         'c: 'r,
@@ -31,9 +53,26 @@ pub trait Row<'c>: Unpin + Send {
         I: ColumnIndex<Self::Database>,
         T: Decode<'r, Self::Database>,
     {
-        self.try_get::<T, I>(index).unwrap()
+        self.try_get::<T, I>(index)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
+    /// Decodes the value at `index` as `T`.
+    ///
+    /// A value that can not be decoded is returned as an [`Error::Decode`] holding a
+    /// [`ColumnDecodeError`] that names the column and `T`. This is the same for every database:
+    /// a `NULL` decoded into a type that is not an `Option` is a `ColumnDecodeError` whose
+    /// source is an [`UnexpectedNullError`] (see [`Error::is_unexpected_null`]), while an
+    /// `Option<T>` decodes it as `None`.
+    ///
+    /// The error of the value is the [`source`](std::error::Error::source) of the
+    /// `ColumnDecodeError` rather than the error in `Error::Decode` itself, as it was in SQLx
+    /// 0.2.6 and earlier; downcast the source to inspect it.
+    ///
+    /// [`Error::Decode`]: crate::Error::Decode
+    /// [`ColumnDecodeError`]: crate::error::ColumnDecodeError
+    /// [`UnexpectedNullError`]: crate::error::UnexpectedNullError
+    /// [`Error::is_unexpected_null`]: crate::Error::is_unexpected_null
     fn try_get<'r, T, I>(&'r self, index: I) -> crate::Result<T>
     where
         'c: 'r,
@@ -44,6 +83,85 @@ pub trait Row<'c>: Unpin + Send {
         Ok(Decode::decode(self.try_get_raw(index)?)?)
     }
 
+    /// Like [`try_get`](Row::try_get), but returns `default` if the value at `index` is `NULL`
+    /// rather than failing; a shorthand for `try_get::<Option<T>, _>(index)?.unwrap_or(default)`.
+    ///
+    /// ```rust,ignore
+    /// // SELECT region, SUM(amount) AS total FROM sales GROUP BY ROLLUP (region)
+    /// let region: String = row.try_get_or("region", "(all regions)".to_owned())?;
+    /// let total: i64 = row.try_get_or("total", 0)?;
+    /// ```
+    fn try_get_or<'r, T, I>(&'r self, index: I, default: T) -> crate::Result<T>
+    where
+        'c: 'r,
+        T: Type<Self::Database>,
+        I: ColumnIndex<Self::Database>,
+        Option<T>: Decode<'r, Self::Database>,
+    {
+        Ok(self.try_get::<Option<T>, I>(index)?.unwrap_or(default))
+    }
+
+    /// Like [`get`](Row::get), but without checking that the SQL type of the column is
+    /// compatible with `T`; see [`try_get_unchecked`](Row::try_get_unchecked).
+    fn get_unchecked<'r, T, I>(&'r self, index: I) -> T
+    where
+        'c: 'r,
+        I: ColumnIndex<Self::Database>,
+        T: Decode<'r, Self::Database>,
+    {
+        self.try_get_unchecked::<T, I>(index)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Decodes the value at `index` as `T` without checking that the SQL type of the column is
+    /// compatible with `T` first.
+    ///
+    /// This saves the comparison of type information for each value, which can add up when
+    /// reading many columns of many rows whose types are already known to match. If they do not,
+    /// the result is whatever `T` makes of the bytes of the value: an [`Error::Decode`] at
+    /// best, or a wrong value when the sizes happen to line up (an `INT8` read as an `f64`, say).
+    ///
+    /// [`Error::Decode`]: crate::Error::Decode
+    fn try_get_unchecked<'r, T, I>(&'r self, index: I) -> crate::Result<T>
+    where
+        'c: 'r,
+        I: ColumnIndex<Self::Database>,
+        T: Decode<'r, Self::Database>,
+    {
+        Decode::decode(self.try_get_raw(index)?)
+    }
+
+    /// Deserializes the JSON value at `index` into `T`.
+    ///
+    /// The column may be of any type holding the text of a JSON document: `JSON` or `JSONB` in
+    /// Postgres, `JSON` or a string in MySQL and `TEXT` in SQLite. A `NULL` is deserialized
+    /// from JSON `null`, so it can be read into an `Option<T>`.
+    ///
+    /// A document that does not match `T` is returned as an [`Error::Decode`] holding a
+    /// [`ColumnDecodeError`], with the `serde_json::Error` as its source.
+    ///
+    /// [`Error::Decode`]: crate::Error::Decode
+    /// [`ColumnDecodeError`]: crate::error::ColumnDecodeError
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    fn try_get_json<'r, T, I>(&'r self, index: I) -> crate::Result<T>
+    where
+        'c: 'r,
+        T: serde::de::DeserializeOwned,
+        I: ColumnIndex<Self::Database> + Display + Copy,
+        Option<JsonText<'r>>: Decode<'r, Self::Database>,
+    {
+        let text: Option<JsonText> = Decode::decode(self.try_get_raw(index)?)?;
+        let text = text.as_ref().map_or(&b"null"[..], |text| text.0);
+
+        serde_json::from_slice(text).map_err(|err| {
+            crate::Error::decode(ColumnDecodeError::new::<T>(
+                index.to_string().into(),
+                Box::new(err),
+            ))
+        })
+    }
+
     fn try_get_raw<'r, I>(
         &'r self,
         index: I,
@@ -51,6 +169,56 @@ pub trait Row<'c>: Unpin + Send {
     where
         'c: 'r,
         I: ColumnIndex<Self::Database>;
+
+    /// Copies the values of the row into a row of its own, which no longer borrows the
+    /// connection; see [`Cursor::next_owned`](crate::cursor::Cursor::next_owned).
+    fn into_owned(self) -> OwnedRow<Self::Database>;
+}
+
+/// A row that owns its values, so it can be kept while its cursor moves on or the connection is
+/// used for another query; e.g. `PgRow<'static>`. See [`Row::into_owned`].
+pub type OwnedRow<DB> = <DB as HasRow<'static>>::Row;
+
+// Checks that a value of `sql_type` can be decoded as `T`, returning an error naming both
+// types if it cannot; SQLite has no column types to check
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub(crate) fn check_type<DB, T>(sql_type: &DB::TypeInfo, strict: bool) -> crate::Result<()>
+where
+    DB: Database,
+    T: Type<DB> + ?Sized,
+{
+    let expected = T::type_info();
+
+    let compatible = if strict {
+        expected.compatible_strict(sql_type)
+    } else {
+        expected.compatible(sql_type)
+    };
+
+    if compatible {
+        Ok(())
+    } else {
+        Err(crate::Error::decode(MismatchedTypeError::new::<DB, T>(
+            sql_type,
+        )))
+    }
+}
+
+// Names the column and the Rust type in an error decoding the value of a column; `column`
+// is only called when there is an error to name it in
+#[cfg(any(feature = "postgres", feature = "mysql", feature = "sqlite"))]
+pub(crate) fn column_error<T, F>(error: crate::Error, column: F) -> crate::Error
+where
+    T: ?Sized,
+    F: FnOnce() -> Box<str>,
+{
+    match error {
+        crate::Error::Decode(source) if !source.is::<ColumnDecodeError>() => {
+            crate::Error::decode(ColumnDecodeError::new::<T>(column(), source))
+        }
+
+        error => error,
+    }
 }
 
 /// A **record** that can be built from a row returned from by the database.
@@ -71,7 +239,7 @@ macro_rules! impl_from_row_for_tuple {
         impl<'c, $($T,)+> crate::row::FromRow<'c, $r<'c>> for ($($T,)+)
         where
             $($T: crate::types::Type<$db>,)+
-            $($T: for<'r> crate::decode::Decode<'r, $db>,)+
+            $($T: crate::decode::DecodeOwned<$db>,)+
         {
             #[inline]
             fn from_row(row: $r<'c>) -> crate::Result<Self> {
@@ -86,6 +254,14 @@ macro_rules! impl_from_row_for_tuple {
 #[allow(unused_macros)]
 macro_rules! impl_from_row_for_tuples {
     ($db:ident, $r:ident) => {
+        // a row without any columns, or whose columns are of no interest
+        impl<'c> crate::row::FromRow<'c, $r<'c>> for () {
+            #[inline]
+            fn from_row(_: $r<'c>) -> crate::Result<Self> {
+                Ok(())
+            }
+        }
+
         impl_from_row_for_tuple!($db, $r;
             (0) -> T1;
         );
@@ -3,7 +3,7 @@ pub use async_std::{
     fs,
     future::timeout,
     io::prelude::{ReadExt as AsyncReadExt, WriteExt as AsyncWriteExt},
-    io::{Read as AsyncRead, Write as AsyncWrite},
+    io::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
     net::TcpStream,
     task::sleep,
     task::spawn,
@@ -13,7 +13,7 @@ pub use async_std::{
 #[cfg(feature = "runtime-tokio")]
 pub use tokio::{
     fs,
-    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncWrite, AsyncWriteExt},
     net::TcpStream,
     task::spawn,
     task::yield_now,
@@ -36,9 +36,16 @@ pub enum SqliteArgumentValue {
 pub struct SqliteArguments {
     index: usize,
     values: Vec<SqliteArgumentValue>,
+
+    // The first value that failed to encode; the query fails with it instead of being run
+    pub(super) error: Option<crate::Error>,
 }
 
 impl SqliteArguments {
+    pub(super) fn len(&self) -> usize {
+        self.values.len()
+    }
+
     pub(crate) fn next(&mut self) -> Option<SqliteArgumentValue> {
         if self.index >= self.values.len() {
             return None;
@@ -59,11 +66,21 @@ impl Arguments for SqliteArguments {
         self.values.reserve(len);
     }
 
+    fn clear(&mut self) {
+        self.index = 0;
+        self.values.clear();
+        self.error = None;
+    }
+
     fn add<T>(&mut self, value: T)
     where
         T: Encode<Self::Database> + Type<Self::Database>,
     {
-        value.encode(&mut self.values);
+        if let Err(error) = value.try_encode(&mut self.values) {
+            // keep the following values at their index
+            self.values.push(SqliteArgumentValue::Null);
+            self.error.get_or_insert(error);
+        }
     }
 }
 
@@ -1,21 +1,36 @@
-use core::ptr::{null, null_mut, NonNull};
+use core::ptr::{self, null, null_mut, NonNull};
 
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::ffi::CString;
+use std::mem;
+use std::os::raw::c_uint;
+use std::slice;
+use std::sync::{Arc, Mutex};
 
 use futures_core::future::BoxFuture;
 use futures_util::future;
 use libsqlite3_sys::{
-    sqlite3, sqlite3_close, sqlite3_extended_result_codes, sqlite3_open_v2, SQLITE_OK,
-    SQLITE_OPEN_CREATE, SQLITE_OPEN_NOMUTEX, SQLITE_OPEN_READWRITE
+    sqlite3, sqlite3_close, sqlite3_deserialize, sqlite3_extended_result_codes, sqlite3_free,
+    sqlite3_get_autocommit, sqlite3_interrupt, sqlite3_malloc64, sqlite3_open_v2,
+    sqlite3_serialize, SQLITE_DESERIALIZE_FREEONCLOSE, SQLITE_DESERIALIZE_READONLY,
+    SQLITE_DESERIALIZE_RESIZEABLE, SQLITE_ERROR, SQLITE_NOMEM, SQLITE_OK, SQLITE_OPEN_CREATE,
+    SQLITE_OPEN_NOMUTEX, SQLITE_OPEN_READWRITE,
 };
 
-use crate::connection::{Connect, Connection};
+use crate::connection::{Connect, Connection, StatementCacheStats, TransactionStatus};
+use crate::database::Database;
+use crate::describe::TableColumns;
 use crate::executor::Executor;
-use crate::sqlite::statement::Statement;
+use crate::query::query;
+use crate::quote;
+use crate::row::Row;
+use crate::sqlite::executor;
+use crate::sqlite::statement::{Statement, StatementKey};
 use crate::sqlite::worker::Worker;
+use crate::sqlite::SqliteArguments;
 use crate::sqlite::SqliteError;
+use crate::sqlite::{Sqlite, SqliteRow};
 use crate::url::Url;
 
 /// Thin wrapper around [sqlite3] to impl `Send`.
@@ -31,6 +46,46 @@ pub struct SqliteConnection {
     // Storage of persistent statements
     pub(super) statements: Vec<Statement>,
     pub(super) statement_by_query: HashMap<String, usize>,
+
+    // Hits and misses of [statement_by_query]; its size is filled in when they are read
+    pub(super) statement_cache_stats: StatementCacheStats,
+    // The statement that is being stepped through and has not yet been run to completion
+    pub(super) busy: Option<StatementKey>,
+    // The arguments of the last query, cleared, to be handed out again by `take_arguments`
+    pub(super) spare_arguments: SqliteArguments,
+    // Shared with each [SqliteInterruptHandle]; emptied before the connection is closed
+    interrupt: Arc<Mutex<Option<SqliteConnectionHandle>>>,
+}
+
+/// A handle to interrupt the query running on a [`SqliteConnection`] from another task or
+/// thread, such as one that has run for too long; see [`SqliteConnection::interrupt_handle`].
+///
+/// It is cheap to clone, and does nothing once the connection is closed.
+#[derive(Clone)]
+pub struct SqliteInterruptHandle {
+    handle: Arc<Mutex<Option<SqliteConnectionHandle>>>,
+}
+
+impl SqliteInterruptHandle {
+    /// Stops the query that is running on the connection, if any, at the next opportunity.
+    ///
+    /// The query then fails with an error for which [`Error::is_interrupted`] returns `true`;
+    /// a transaction it ran in may have been rolled back. The connection can be used again
+    /// afterwards.
+    ///
+    /// [`Error::is_interrupted`]: crate::Error::is_interrupted
+    pub fn interrupt(&self) {
+        // the lock is held so the connection can't be closed meanwhile
+        let handle = self.handle.lock().unwrap_or_else(|err| err.into_inner());
+
+        if let Some(handle) = &*handle {
+            // https://www.sqlite.org/c3ref/interrupt.html
+            #[allow(unsafe_code)]
+            unsafe {
+                sqlite3_interrupt(handle.0.as_ptr());
+            }
+        }
+    }
 }
 
 // A SQLite3 handle is safe to send between threads, provided not more than
@@ -64,9 +119,7 @@ async fn establish(url: crate::Result<Url>) -> crate::Result<SqliteConnection> {
 
             // [SQLITE_OPEN_NOMUTEX] will instruct [sqlite3_open_v2] to return an error if it
             // cannot satisfy our wish for a thread-safe, lock-free connection object
-            let flags = SQLITE_OPEN_READWRITE
-                | SQLITE_OPEN_CREATE
-                | SQLITE_OPEN_NOMUTEX;
+            let flags = SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE | SQLITE_OPEN_NOMUTEX;
 
             // <https://www.sqlite.org/c3ref/open.html>
             #[allow(unsafe_code)]
@@ -105,6 +158,10 @@ async fn establish(url: crate::Result<Url>) -> crate::Result<SqliteConnection> {
         statement: None,
         statements: Vec::with_capacity(10),
         statement_by_query: HashMap::with_capacity(10),
+        statement_cache_stats: StatementCacheStats::default(),
+        busy: None,
+        spare_arguments: SqliteArguments::default(),
+        interrupt: Arc::new(Mutex::new(Some(handle))),
     })
 }
 
@@ -113,6 +170,157 @@ impl SqliteConnection {
     pub(super) fn handle(&mut self) -> *mut sqlite3 {
         self.handle.0.as_ptr()
     }
+
+    /// Returns a handle to interrupt the queries of this connection from elsewhere, which stays
+    /// safe to use after the connection is closed.
+    ///
+    /// ```rust,ignore
+    /// let interrupt = conn.interrupt_handle();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(5));
+    ///     interrupt.interrupt();
+    /// });
+    ///
+    /// // fails after 5 seconds, unless it is done first
+    /// let rows = sqlx::query("SELECT * FROM huge WHERE body LIKE '%needle%'")
+    ///     .fetch_all(&mut conn)
+    ///     .await;
+    /// ```
+    pub fn interrupt_handle(&self) -> SqliteInterruptHandle {
+        SqliteInterruptHandle {
+            handle: Arc::clone(&self.interrupt),
+        }
+    }
+
+    /// Loads `data`, the contents of a database file, as the database `schema` of this
+    /// connection, which is attached first unless it is `main` or attached already. What the
+    /// database held before is discarded.
+    ///
+    /// ```rust,ignore
+    /// static CITIES: &[u8] = include_bytes!("cities.db");
+    ///
+    /// conn.deserialize("cities", CITIES.to_vec(), true).await?;
+    ///
+    /// let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cities.city")
+    ///     .fetch_one(&mut conn)
+    ///     .await?;
+    /// ```
+    ///
+    /// The database is kept in memory, in a copy of `data` that SQLite frees once the database
+    /// is detached or the connection is closed. With `read_only`, writing to it fails; otherwise
+    /// it grows as needed. A file in WAL mode can't be loaded; switch it to another journal mode
+    /// before reading it into `data`.
+    pub async fn deserialize(
+        &mut self,
+        schema: &str,
+        data: Vec<u8>,
+        read_only: bool,
+    ) -> crate::Result<()> {
+        if !has_database(self, schema).await? {
+            let quoted =
+                Sqlite::quote_identifier(schema).map_err(|err| crate::Error::Encode(err.into()))?;
+
+            let sql = format!("ATTACH DATABASE ':memory:' AS {}", quoted);
+
+            self.execute(&*sql).await?;
+        }
+
+        let schema = CString::new(schema).map_err(|err| crate::Error::Encode(err.into()))?;
+        let handle = self.handle;
+
+        self.worker
+            .run(move || -> crate::Result<()> {
+                let handle = handle.0.as_ptr();
+
+                // SQLite frees the database itself, so the copy is allocated by SQLite; the
+                // buffer can't be empty, as `sqlite3_malloc64(0)` returns NULL
+                #[allow(unsafe_code)]
+                let buf = unsafe { sqlite3_malloc64(data.len().max(1) as u64) } as *mut u8;
+
+                if buf.is_null() {
+                    return Err(SqliteError::new(SQLITE_NOMEM, "out of memory".to_owned()).into());
+                }
+
+                let mut flags = SQLITE_DESERIALIZE_FREEONCLOSE;
+
+                flags |= if read_only {
+                    SQLITE_DESERIALIZE_READONLY
+                } else {
+                    SQLITE_DESERIALIZE_RESIZEABLE
+                };
+
+                // `buf` is owned by SQLite from here on, even when this fails
+                // https://www.sqlite.org/c3ref/deserialize.html
+                #[allow(unsafe_code)]
+                let status = unsafe {
+                    ptr::copy_nonoverlapping(data.as_ptr(), buf, data.len());
+
+                    sqlite3_deserialize(
+                        handle,
+                        schema.as_ptr(),
+                        buf,
+                        data.len() as i64,
+                        data.len().max(1) as i64,
+                        flags as c_uint,
+                    )
+                };
+
+                if status != SQLITE_OK {
+                    return Err(SqliteError::from_connection(handle).into());
+                }
+
+                Ok(())
+            })
+            .await
+    }
+
+    /// Returns the contents of the database `schema` of this connection, such as `main` or an
+    /// attached database, as they would be written to a file; they can be loaded again with
+    /// [`deserialize`](SqliteConnection::deserialize).
+    pub async fn serialize(&mut self, schema: &str) -> crate::Result<Vec<u8>> {
+        if !has_database(self, schema).await? {
+            return Err(
+                SqliteError::new(SQLITE_ERROR, format!("no such database: {}", schema)).into(),
+            );
+        }
+
+        let schema = CString::new(schema).map_err(|err| crate::Error::Encode(err.into()))?;
+        let handle = self.handle;
+
+        self.worker
+            .run(move || {
+                let mut size = 0;
+
+                // https://www.sqlite.org/c3ref/serialize.html
+                #[allow(unsafe_code)]
+                let data =
+                    unsafe { sqlite3_serialize(handle.0.as_ptr(), schema.as_ptr(), &mut size, 0) };
+
+                if data.is_null() {
+                    if size > 0 {
+                        panic!("SQLite is unable to allocate memory to hold the database");
+                    }
+
+                    // an empty database; `size` is negative if it could not be read
+                    return if size == 0 {
+                        Ok(Vec::new())
+                    } else {
+                        Err(SqliteError::from_connection(handle.0.as_ptr()).into())
+                    };
+                }
+
+                #[allow(unsafe_code)]
+                unsafe {
+                    let copy = slice::from_raw_parts(data, size as usize).to_vec();
+
+                    sqlite3_free(data as *mut _);
+
+                    Ok(copy)
+                }
+            })
+            .await
+    }
 }
 
 impl Connect for SqliteConnection {
@@ -152,6 +360,130 @@ impl Connection for SqliteConnection {
         // For SQLite connections, PING does effectively nothing
         Box::pin(future::ok(()))
     }
+
+    fn is_ready(&self) -> bool {
+        self.busy.is_none()
+    }
+
+    fn take_arguments(&mut self) -> SqliteArguments {
+        mem::take(&mut self.spare_arguments)
+    }
+
+    fn transaction_status(&self) -> TransactionStatus {
+        // https://www.sqlite.org/c3ref/get_autocommit.html
+        #[allow(unsafe_code)]
+        let autocommit = unsafe { sqlite3_get_autocommit(self.handle.0.as_ptr()) };
+
+        if autocommit == 0 {
+            TransactionStatus::Transaction
+        } else {
+            TransactionStatus::Idle
+        }
+    }
+
+    fn table_id<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, crate::Result<Option<String>>> {
+        Box::pin(table_id(self, name))
+    }
+
+    fn describe_table<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<TableColumns<Sqlite>>> {
+        Box::pin(describe_table(self, name))
+    }
+
+    fn statement_cache_stats(&self) -> StatementCacheStats {
+        StatementCacheStats {
+            size: self.statement_by_query.len(),
+            ..self.statement_cache_stats
+        }
+    }
+}
+
+async fn table_id(conn: &mut SqliteConnection, name: &str) -> crate::Result<Option<String>> {
+    Ok(find_table(conn, name).await?.map(|(_, name)| name))
+}
+
+async fn describe_table(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> crate::Result<TableColumns<Sqlite>> {
+    match find_table(conn, name).await? {
+        Some((schema, name)) => executor::describe_table(conn, &schema, &name).await,
+        None => Ok(None),
+    }
+}
+
+// The database and the name of the table (or view) `name`
+async fn find_table(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> crate::Result<Option<(String, String)>> {
+    let quotes = &[('"', '"'), ('`', '`'), ('[', ']')];
+
+    let (schema, name) = match quote::split_qualified(name, quotes, str::to_owned) {
+        Some(parts) => parts,
+        None => return Ok(None),
+    };
+
+    // an unqualified name is looked up in `temp` first, as in a query; databases attached
+    // with `ATTACH` are only searched when named
+    let schemas = match schema {
+        Some(schema) => {
+            // `temp` is only listed once something was created in it
+            match databases(conn)
+                .await?
+                .into_iter()
+                .chain(Some("temp".to_owned()))
+                .find(|database| database.eq_ignore_ascii_case(&schema))
+            {
+                Some(database) => vec![database],
+                None => return Ok(None),
+            }
+        }
+
+        None => vec!["temp".to_owned(), "main".to_owned()],
+    };
+
+    for schema in schemas {
+        let quoted =
+            Sqlite::quote_identifier(&schema).map_err(|err| crate::Error::Encode(err.into()))?;
+
+        let sql = format!(
+            "SELECT name FROM {}.sqlite_master WHERE type IN ('table', 'view') \
+             AND name = ?1 COLLATE NOCASE",
+            quoted
+        );
+
+        let found: Option<String> = query(&sql)
+            .bind(&*name)
+            .try_map(|row: SqliteRow| row.try_get(0))
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        if let Some(found) = found {
+            return Ok(Some((schema, found)));
+        }
+    }
+
+    Ok(None)
+}
+
+// The names of the databases of the connection: `main`, `temp` once something was created in
+// it, and those attached
+async fn databases(conn: &mut SqliteConnection) -> crate::Result<Vec<String>> {
+    query("PRAGMA database_list")
+        .try_map(|row: SqliteRow| row.try_get(1))
+        .fetch_all(conn)
+        .await
+}
+
+// Whether the connection has the database `schema`, whose name SQLite matches ignoring case
+async fn has_database(conn: &mut SqliteConnection, schema: &str) -> crate::Result<bool> {
+    Ok(databases(conn)
+        .await?
+        .iter()
+        .any(|database| database.eq_ignore_ascii_case(schema)))
 }
 
 impl Drop for SqliteConnection {
@@ -159,11 +491,16 @@ impl Drop for SqliteConnection {
         // Drop all statements first
         self.statements.clear();
 
+        // The handles to interrupt must not see the connection once it is closed
+        let mut interrupt = self.interrupt.lock().unwrap_or_else(|err| err.into_inner());
+
+        interrupt.take();
+
         // Next close the statement
         // https://sqlite.org/c3ref/close.html
         #[allow(unsafe_code)]
         unsafe {
-            let _ = sqlite3_close(self.handle());
+            let _ = sqlite3_close(self.handle.0.as_ptr());
         }
     }
 }
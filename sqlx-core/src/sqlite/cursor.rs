@@ -3,15 +3,24 @@ use futures_core::future::BoxFuture;
 use crate::connection::ConnectionSource;
 use crate::cursor::Cursor;
 use crate::executor::Execute;
+#[cfg(feature = "tracing")]
+use crate::instrument::QuerySpan;
 use crate::pool::Pool;
-use crate::sqlite::statement::Step;
+use crate::sqlite::statement::{StatementKey, Step};
 use crate::sqlite::{Sqlite, SqliteArguments, SqliteConnection, SqliteRow};
 
 pub struct SqliteCursor<'c, 'q> {
     pub(super) source: ConnectionSource<'c, SqliteConnection>,
     query: &'q str,
     arguments: Option<SqliteArguments>,
-    pub(super) statement: Option<Option<usize>>,
+    pub(super) statement: Option<StatementKey>,
+
+    // Added up after each statement; see [Cursor::rows_affected]
+    affected_rows: u64,
+    done: bool,
+
+    #[cfg(feature = "tracing")]
+    span: QuerySpan,
 }
 
 impl<'c, 'q> Cursor<'c, 'q> for SqliteCursor<'c, 'q> {
@@ -30,6 +39,10 @@ impl<'c, 'q> Cursor<'c, 'q> for SqliteCursor<'c, 'q> {
             statement: None,
             query,
             arguments,
+            affected_rows: 0,
+            done: false,
+            #[cfg(feature = "tracing")]
+            span: QuerySpan::new("sqlite", query),
         }
     }
 
@@ -46,12 +59,30 @@ impl<'c, 'q> Cursor<'c, 'q> for SqliteCursor<'c, 'q> {
             statement: None,
             query,
             arguments,
+            affected_rows: 0,
+            done: false,
+            #[cfg(feature = "tracing")]
+            span: QuerySpan::new("sqlite", query),
         }
     }
 
+    #[cfg(not(feature = "tracing"))]
     fn next(&mut self) -> BoxFuture<crate::Result<Option<SqliteRow<'_>>>> {
         Box::pin(next(self))
     }
+
+    #[cfg(feature = "tracing")]
+    fn next(&mut self) -> BoxFuture<crate::Result<Option<SqliteRow<'_>>>> {
+        Box::pin(self.span.clone().next(next(self)))
+    }
+
+    fn rows_affected(&self) -> Option<u64> {
+        if self.done {
+            Some(self.affected_rows)
+        } else {
+            None
+        }
+    }
 }
 
 async fn next<'a, 'c: 'a, 'q: 'a>(
@@ -61,34 +92,62 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
 
     loop {
         if cursor.statement.is_none() {
-            let key = conn.prepare(&mut cursor.query, cursor.arguments.is_some())?;
+            if let Some(arguments) = &cursor.arguments {
+                conn.check_parameter_limit(arguments)?;
+            }
+
+            let key = match conn.prepare(&mut cursor.query, cursor.arguments.is_some())? {
+                Some(key) => key,
+
+                // the rest of the query is only whitespace, `;` or comments
+                None => {
+                    if let Some(arguments) = cursor.arguments.take() {
+                        conn.keep_arguments(arguments);
+                    }
+
+                    cursor.done = true;
+
+                    return Ok(None);
+                }
+            };
 
             if let Some(arguments) = &mut cursor.arguments {
                 conn.statement_mut(key).bind(arguments)?;
             }
 
+            if cursor.query.is_empty() {
+                if let Some(arguments) = cursor.arguments.take() {
+                    conn.keep_arguments(arguments);
+                }
+            }
+
             cursor.statement = Some(key);
         }
 
         let key = cursor.statement.unwrap();
-        let statement = conn.statement_mut(key);
 
-        let step = statement.step().await?;
+        // Only one row is read at a time; its values are borrowed from the statement
+        // until the cursor is advanced again
+        let step = conn.step(key).await?;
 
         match step {
             Step::Row => {
-                return Ok(Some(SqliteRow {
-                    values: statement.data_count(),
-                    statement: key,
-                    connection: conn,
-                }));
-            }
+                let values = conn.statement_mut(key).data_count();
 
-            Step::Done if cursor.query.is_empty() => {
-                return Ok(None);
+                return Ok(Some(SqliteRow::new(values, key, conn)));
             }
 
             Step::Done => {
+                if !conn.statement(key).readonly() {
+                    cursor.affected_rows += conn.changes();
+                }
+
+                if cursor.query.is_empty() {
+                    cursor.done = true;
+
+                    return Ok(None);
+                }
+
                 cursor.statement = None;
                 // continue
             }
@@ -1,4 +1,4 @@
-use crate::database::{Database, HasCursor, HasRawValue, HasRow};
+use crate::database::{Database, HasCursor, HasRawValue, HasRow, ParameterStyle};
 
 /// **Sqlite** database driver.
 pub struct Sqlite;
@@ -13,6 +13,12 @@ impl Database for Sqlite {
     type TableId = String;
 
     type RawBuffer = Vec<super::SqliteArgumentValue>;
+
+    const PARAMETER_STYLE: ParameterStyle = ParameterStyle::NumberedQuestion;
+
+    // `SQLITE_MAX_VARIABLE_NUMBER` since SQLite 3.32, and of the bundled SQLite; a build can
+    // allow more, which a connection checks against instead
+    const PARAMETER_LIMIT: usize = 32766;
 }
 
 impl<'c> HasRow<'c> for Sqlite {
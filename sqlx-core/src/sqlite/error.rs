@@ -32,6 +32,14 @@ impl SqliteError {
             message: message.to_owned(),
         }
     }
+
+    // An error that SQLite would report for the same mistake, found before calling into it
+    pub(super) fn new(code: c_int, message: String) -> Self {
+        Self {
+            code: code.to_string(),
+            message,
+        }
+    }
 }
 
 impl Display for SqliteError {
@@ -48,4 +56,17 @@ impl DatabaseError for SqliteError {
     fn code(&self) -> Option<&str> {
         Some(&self.code)
     }
+
+    // the code is an (extended) SQLite result code, not a SQLSTATE
+    fn code_class(&self) -> Option<&str> {
+        None
+    }
+
+    fn is_retryable(&self) -> bool {
+        // the primary result code is the low byte of an extended one; SQLITE_BUSY, SQLITE_LOCKED
+        match self.code.parse::<c_int>().map(|code| code & 0xff) {
+            Ok(5) | Ok(6) => true,
+            _ => false,
+        }
+    }
 }
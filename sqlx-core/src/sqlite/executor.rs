@@ -1,61 +1,112 @@
+use std::collections::HashMap;
+
 use futures_core::future::BoxFuture;
 
-use libsqlite3_sys::sqlite3_changes;
+use libsqlite3_sys::{sqlite3_changes, sqlite3_limit, SQLITE_LIMIT_VARIABLE_NUMBER};
 
+use crate::arguments::Arguments;
 use crate::cursor::Cursor;
-use crate::describe::{Column, Describe};
+use crate::database::Database;
+use crate::describe::{self, CatalogColumn, Column, ColumnFlags, Describe, TableColumns};
 use crate::executor::{Execute, Executor, RefExecutor};
+#[cfg(feature = "tracing")]
+use crate::instrument::QuerySpan;
+use crate::query::query;
+use crate::quote::Identifier;
+use crate::row::Row;
 use crate::sqlite::cursor::SqliteCursor;
-use crate::sqlite::statement::{Statement, Step};
-use crate::sqlite::types::SqliteType;
-use crate::sqlite::{Sqlite, SqliteConnection, SqliteTypeInfo};
+use crate::sqlite::statement::{Statement, StatementKey, Step};
+use crate::sqlite::types::{SqliteType, SqliteTypeAffinity};
+use crate::sqlite::{Sqlite, SqliteArguments, SqliteConnection, SqliteRow, SqliteTypeInfo};
 
 impl SqliteConnection {
+    // Prepares the first statement of `query` and advances `query` past it, returning the key
+    // of the statement; see [SqliteConnection::statement]. Returns `None` if `query` has no
+    // statement left, only whitespace, `;` or comments.
     pub(super) fn prepare(
         &mut self,
         query: &mut &str,
         persistent: bool,
-    ) -> crate::Result<Option<usize>> {
+    ) -> crate::Result<Option<StatementKey>> {
         // TODO: Revisit statement caching and allow cache expiration by using a
         //       generational index
 
+        // A cursor that was dropped before reaching the end of its results leaves its statement
+        // active, which holds on to its read transaction and locks the tables it reads from
+        if let Some(key) = self.busy.take() {
+            if let Some(statement) = self.statement_opt_mut(key) {
+                statement.reset();
+            }
+        }
+
         if !persistent {
             // A non-persistent query will be immediately prepared and returned,
             // regardless of the current state of the cache
-            self.statement = Some(Statement::new(self, query, false)?);
-            return Ok(None);
+            self.statement = match Statement::new(self, query, false)? {
+                Some(statement) => Some(statement),
+                None => return Ok(None),
+            };
+
+            return Ok(Some(StatementKey::Temporary));
         }
 
         if let Some(key) = self.statement_by_query.get(&**query) {
+            self.statement_cache_stats.hits += 1;
+
             let statement = &mut self.statements[*key];
 
             // Adjust the passed in query string as if [string3_prepare]
             // did the tail parsing
-            *query = &query[statement.tail..];
+            *query = query[statement.tail..].trim();
 
             // As this statement has very likely been used before, we reset
             // it to clear the bindings and its program state
             statement.reset();
 
-            return Ok(Some(*key));
+            return Ok(Some(StatementKey::Persistent(*key)));
         }
 
         // Prepare a new statement object; ensuring to tell SQLite that this will be stored
         // for a "long" time and re-used multiple times
 
         let query_key = query.to_owned();
-        let statement = Statement::new(self, query, true)?;
+        let statement = match Statement::new(self, query, true)? {
+            Some(statement) => statement,
+            None => return Ok(None),
+        };
 
         let key = self.statements.len();
 
+        self.statement_cache_stats.misses += 1;
         self.statement_by_query.insert(query_key, key);
         self.statements.push(statement);
 
-        Ok(Some(key))
+        Ok(Some(StatementKey::Persistent(key)))
+    }
+
+    // Fails if there are more arguments than any statement of this connection can have. Without
+    // this, SQLite would fail to prepare the statement with an error that gives no count.
+    pub(super) fn check_parameter_limit(
+        &mut self,
+        arguments: &SqliteArguments,
+    ) -> crate::Result<()> {
+        // https://www.sqlite.org/c3ref/limit.html
+        #[allow(unsafe_code)]
+        let limit = unsafe { sqlite3_limit(self.handle(), SQLITE_LIMIT_VARIABLE_NUMBER, -1) };
+        let limit = limit as usize;
+
+        if arguments.len() > limit {
+            return Err(crate::Error::ParameterLimitExceeded {
+                limit,
+                got: arguments.len(),
+            });
+        }
+
+        Ok(())
     }
 
     // This is used for [affected_rows] in the public API.
-    fn changes(&mut self) -> u64 {
+    pub(super) fn changes(&mut self) -> u64 {
         // Returns the number of rows modified, inserted or deleted by the most recently
         // completed INSERT, UPDATE or DELETE statement.
 
@@ -66,18 +117,45 @@ impl SqliteConnection {
     }
 
     #[inline]
-    pub(super) fn statement(&self, key: Option<usize>) -> &Statement {
+    pub(super) fn statement(&self, key: StatementKey) -> &Statement {
+        match key {
+            StatementKey::Persistent(key) => &self.statements[key],
+            StatementKey::Temporary => self.statement.as_ref().unwrap(),
+        }
+    }
+
+    #[inline]
+    fn statement_opt_mut(&mut self, key: StatementKey) -> Option<&mut Statement> {
         match key {
-            Some(key) => &self.statements[key],
-            None => self.statement.as_ref().unwrap(),
+            StatementKey::Persistent(key) => self.statements.get_mut(key),
+            StatementKey::Temporary => self.statement.as_mut(),
         }
     }
 
+    // Steps the statement to its next row, tracking whether it is left active
+    pub(super) async fn step(&mut self, key: StatementKey) -> crate::Result<Step> {
+        self.busy = Some(key);
+
+        let step = self.statement_mut(key).step().await?;
+
+        if let Step::Done = step {
+            self.busy = None;
+        }
+
+        Ok(step)
+    }
+
+    // Keeps the buffer of arguments that were bound to every statement of their query
+    pub(super) fn keep_arguments(&mut self, mut arguments: SqliteArguments) {
+        arguments.clear();
+        self.spare_arguments = arguments;
+    }
+
     #[inline]
-    pub(super) fn statement_mut(&mut self, key: Option<usize>) -> &mut Statement {
+    pub(super) fn statement_mut(&mut self, key: StatementKey) -> &mut Statement {
         match key {
-            Some(key) => &mut self.statements[key],
-            None => self.statement.as_mut().unwrap(),
+            StatementKey::Persistent(key) => &mut self.statements[key],
+            StatementKey::Temporary => self.statement.as_mut().unwrap(),
         }
     }
 }
@@ -94,26 +172,44 @@ impl Executor for SqliteConnection {
     {
         let (mut query, mut arguments) = query.into_parts();
 
-        Box::pin(async move {
-            loop {
-                let key = self.prepare(&mut query, arguments.is_some())?;
-                let statement = self.statement_mut(key);
+        #[cfg(feature = "tracing")]
+        let span = QuerySpan::new("sqlite", query);
 
+        let execute = async move {
+            if let Some(arguments) = &arguments {
+                self.check_parameter_limit(arguments)?;
+            }
+
+            let mut executed = false;
+
+            while let Some(key) = self.prepare(&mut query, arguments.is_some())? {
                 if let Some(arguments) = &mut arguments {
-                    statement.bind(arguments)?;
+                    self.statement_mut(key).bind(arguments)?;
                 }
 
-                while let Step::Row = statement.step().await? {
+                while let Step::Row = self.step(key).await? {
                     // We only care about the rows modified; ignore
                 }
 
+                executed = true;
+
                 if query.is_empty() {
                     break;
                 }
             }
 
-            Ok(self.changes())
-        })
+            if let Some(arguments) = arguments {
+                self.keep_arguments(arguments);
+            }
+
+            // a query without any statement changes nothing
+            Ok(if executed { self.changes() } else { 0 })
+        };
+
+        #[cfg(feature = "tracing")]
+        let execute = span.execute(execute);
+
+        Box::pin(execute)
     }
 
     fn fetch<'q, E>(&mut self, query: E) -> SqliteCursor<'_, 'q>
@@ -132,7 +228,18 @@ impl Executor for SqliteConnection {
     {
         Box::pin(async move {
             let (mut query, _) = query.into_parts();
-            let key = self.prepare(&mut query, false)?;
+
+            let key = match self.prepare(&mut query, false)? {
+                Some(key) => key,
+
+                None => {
+                    return Ok(Describe {
+                        param_types: Box::new([]),
+                        result_columns: Box::new([]),
+                    })
+                }
+            };
+
             let statement = self.statement_mut(key);
 
             // First let's attempt to describe what we can about parameter types
@@ -147,35 +254,51 @@ impl Executor for SqliteConnection {
             ]
             .into_boxed_slice();
 
-            // Next, collect (return) column types and names
+            // Next, collect (return) column types and names, and the table columns they are read
+            // from; the statement is replaced by the queries that look up those tables
             let num_columns = statement.column_count();
-            let mut columns = Vec::with_capacity(num_columns);
+            let mut described = Vec::with_capacity(num_columns);
             for i in 0..num_columns {
                 let name = statement.column_name(i).to_owned();
-                let decl = statement.column_decltype(i);
-
-                let r#type = match decl {
-                    None => SqliteType::Null,
-                    Some(decl) => match &*decl.to_ascii_lowercase() {
-                        "bool" | "boolean" => SqliteType::Boolean,
-                        "clob" | "text" => SqliteType::Text,
-                        "blob" => SqliteType::Blob,
-                        "real" | "double" | "double precision" | "float" => SqliteType::Float,
-                        _ if decl.contains("int") => SqliteType::Integer,
-                        _ if decl.contains("char") => SqliteType::Text,
-                        _ => SqliteType::Null,
-                    },
-                };
+                let r#type = statement
+                    .column_decltype(i)
+                    .map_or(SqliteType::Null, decl_type);
+
+                let origin = statement.column_origin(i).map(|(database, table, column)| {
+                    (database.to_owned(), table.to_owned(), column.to_owned())
+                });
+
+                described.push((name, r#type, origin));
+            }
 
-                columns.push(Column {
+            let mut tables = HashMap::new();
+            let mut columns = Vec::with_capacity(num_columns);
+            for (name, r#type, origin) in described {
+                let mut column = Column {
                     name: Some(name.into()),
                     non_null: None,
                     table_id: None,
+                    flags: ColumnFlags::empty(),
                     type_info: SqliteTypeInfo {
                         r#type,
                         affinity: None,
                     },
-                })
+                };
+
+                if let Some((database, table, name)) = origin {
+                    let key = (database, table);
+
+                    if !tables.contains_key(&key) {
+                        let info = TableInfo::fetch(self, &key.0, &key.1).await?;
+                        tables.insert(key.clone(), info);
+                    }
+
+                    if let Some(info) = &tables[&key] {
+                        info.describe(&name, &mut column);
+                    }
+                }
+
+                columns.push(column);
             }
 
             Ok(Describe {
@@ -196,3 +319,332 @@ impl<'e> RefExecutor<'e> for &'e mut SqliteConnection {
         SqliteCursor::from_connection(self, query)
     }
 }
+
+// The type of a column declared as `decl`, going by its name as SQLite does to find its affinity
+fn decl_type(decl: &str) -> SqliteType {
+    let decl = decl.to_ascii_lowercase();
+
+    match &*decl {
+        "bool" | "boolean" => SqliteType::Boolean,
+        "clob" | "text" => SqliteType::Text,
+        "blob" => SqliteType::Blob,
+        "real" | "double" | "double precision" | "float" => SqliteType::Float,
+        _ if decl.contains("int") => SqliteType::Integer,
+        _ if decl.contains("char") => SqliteType::Text,
+        _ => SqliteType::Null,
+    }
+}
+
+// What `PRAGMA table_xinfo` and the `CREATE TABLE` statement tell about the columns of a table,
+// beyond the declared types the statement reports
+struct TableInfo {
+    name: String,
+    columns: Vec<ColumnInfo>,
+    strict: bool,
+    without_rowid: bool,
+}
+
+struct ColumnInfo {
+    name: String,
+    declared: String,
+    not_null: bool,
+    // whether it has a default other than `NULL`
+    default: bool,
+    primary_key: bool,
+    // 0 for a normal column, 1 for a hidden column of a virtual table, and 2 or 3 for a
+    // generated column
+    hidden: i64,
+}
+
+impl TableInfo {
+    // `None` if the table is not in `sqlite_master`, as for the tables of the schema itself. A
+    // view is fetched as a table without options.
+    async fn fetch(
+        conn: &mut SqliteConnection,
+        database: &str,
+        table: &str,
+    ) -> crate::Result<Option<Self>> {
+        let schema =
+            Sqlite::quote_identifier(database).map_err(|err| crate::Error::Encode(err.into()))?;
+
+        let sql = format!(
+            "SELECT type, sql FROM {}.sqlite_master WHERE type IN ('table', 'view') \
+             AND name = ?1",
+            schema
+        );
+
+        let found: Option<(String, String)> = query(&sql)
+            .bind(table)
+            .try_map(|row: SqliteRow| Ok((row.try_get(0)?, row.try_get(1)?)))
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        let options = match found {
+            Some((r#type, sql)) if r#type == "table" => table_options(&sql),
+            Some(_) => Vec::new(),
+            None => return Ok(None),
+        };
+
+        // `pragma_table_xinfo()` is a virtual table, which statements are prepared without
+        let name =
+            Sqlite::quote_identifier(table).map_err(|err| crate::Error::Encode(err.into()))?;
+
+        // cid, name, type, notnull, dflt_value, pk, hidden
+        let sql = format!("PRAGMA {}.table_xinfo({})", schema, name);
+        let columns = query(&sql)
+            .try_map(|row: SqliteRow| {
+                Ok(ColumnInfo {
+                    name: row.try_get(1)?,
+                    declared: row.try_get(2)?,
+                    not_null: row.try_get(3)?,
+                    default: row
+                        .try_get::<Option<String>, _>(4)?
+                        .map_or(false, |default| !default.eq_ignore_ascii_case("NULL")),
+                    primary_key: row.try_get::<i64, _>(5)? > 0,
+                    hidden: row.try_get(6)?,
+                })
+            })
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok(Some(TableInfo {
+            name: table.to_owned(),
+            columns,
+            strict: options.iter().any(|option| option == "STRICT"),
+            without_rowid: options
+                .windows(2)
+                .any(|words| words == ["WITHOUT", "ROWID"]),
+        }))
+    }
+
+    // Whether `column` is an alias of the rowid: the only column of the primary key of a rowid
+    // table, declared as `INTEGER` exactly
+    fn is_rowid_alias(&self, column: &ColumnInfo) -> bool {
+        !self.without_rowid
+            && column.primary_key
+            && column.declared.eq_ignore_ascii_case("INTEGER")
+            && self
+                .columns
+                .iter()
+                .filter(|column| column.primary_key)
+                .count()
+                == 1
+    }
+
+    // The primary key of a WITHOUT ROWID table is NOT NULL even when it is not declared so
+    fn is_not_null(&self, column: &ColumnInfo) -> bool {
+        column.not_null || self.is_rowid_alias(column) || (column.primary_key && self.without_rowid)
+    }
+
+    // The columns of the table as the catalog describes them, leaving out the hidden columns of
+    // a virtual table. A row inserted without a rowid alias is given the next rowid.
+    fn catalog_columns(&self) -> Vec<CatalogColumn> {
+        self.columns
+            .iter()
+            .filter(|column| column.hidden != 1)
+            .map(|column| {
+                (
+                    column.name.clone(),
+                    !self.is_not_null(column),
+                    column.default || self.is_rowid_alias(column) || column.hidden > 1,
+                    column.primary_key,
+                )
+            })
+            .collect()
+    }
+
+    // Fills in what is known of the result column read from the table column `name`. Values of
+    // a column of a STRICT table have its declared type; other columns of a table only have an
+    // affinity, so their types are left for overrides.
+    fn describe(&self, name: &str, column: &mut Column<Sqlite>) {
+        let table_column = match self.columns.iter().find(|column| column.name == name) {
+            Some(table_column) => table_column,
+            None => return,
+        };
+
+        let rowid_alias = self.is_rowid_alias(table_column);
+
+        let declared = if rowid_alias {
+            Some(SqliteType::Integer)
+        } else if self.strict {
+            match &*table_column.declared.to_ascii_uppercase() {
+                "INT" | "INTEGER" => Some(SqliteType::Integer),
+                "REAL" => Some(SqliteType::Float),
+                "TEXT" => Some(SqliteType::Text),
+                "BLOB" => Some(SqliteType::Blob),
+
+                // `ANY` holds values of any type
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        if let Some(r#type) = declared {
+            column.type_info = SqliteTypeInfo {
+                r#type,
+                affinity: Some(match r#type {
+                    SqliteType::Integer => SqliteTypeAffinity::Integer,
+                    SqliteType::Float => SqliteTypeAffinity::Real,
+                    SqliteType::Text => SqliteTypeAffinity::Text,
+                    _ => SqliteTypeAffinity::Blob,
+                }),
+            };
+        }
+
+        column.non_null = Some(self.is_not_null(table_column));
+
+        column.table_id = Some(self.name.clone());
+
+        if table_column.primary_key {
+            column.flags |= ColumnFlags::PRIMARY_KEY;
+        }
+
+        // a row inserted without it is given the next rowid
+        if rowid_alias {
+            column.flags |= ColumnFlags::AUTO_INCREMENT;
+        }
+
+        match table_column.hidden {
+            1 => column.flags |= ColumnFlags::INVISIBLE,
+            2 | 3 => column.flags |= ColumnFlags::GENERATED,
+            _ => {}
+        }
+    }
+}
+
+pub(super) async fn describe_table(
+    conn: &mut SqliteConnection,
+    database: &str,
+    table: &str,
+) -> crate::Result<TableColumns<Sqlite>> {
+    let info = match TableInfo::fetch(conn, database, table).await? {
+        Some(info) => info,
+        None => return Ok(None),
+    };
+
+    let table = Identifier::qualified(database, table)
+        .quote::<Sqlite>()
+        .map_err(|err| crate::Error::Encode(err.into()))?;
+
+    describe::table_columns(conn, &table, info.catalog_columns())
+        .await
+        .map(Some)
+}
+
+// The upper-cased words of the options after the column definitions of `CREATE TABLE`, such as
+// `STRICT` or `WITHOUT ROWID`
+fn table_options(sql: &str) -> Vec<String> {
+    let options = sql.rfind(')').map_or("", |end| &sql[end + 1..]);
+
+    options
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_column(name: &str, declared: &str, not_null: bool, primary_key: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_owned(),
+            declared: declared.to_owned(),
+            not_null,
+            default: false,
+            primary_key,
+            hidden: 0,
+        }
+    }
+
+    fn describe(table: &TableInfo, name: &str) -> Column<Sqlite> {
+        let mut column = Column {
+            name: Some(name.into()),
+            non_null: None,
+            table_id: None,
+            flags: ColumnFlags::empty(),
+            type_info: SqliteTypeInfo {
+                r#type: SqliteType::Null,
+                affinity: None,
+            },
+        };
+
+        table.describe(name, &mut column);
+        column
+    }
+
+    #[test]
+    fn it_reads_the_options_of_tables() {
+        assert_eq!(
+            table_options(
+                "CREATE TABLE t (a INT, b TEXT CHECK (b <> '')) strict,\n without  rowid"
+            ),
+            ["STRICT", "WITHOUT", "ROWID"]
+        );
+
+        assert!(table_options("CREATE TABLE t (a)").is_empty());
+    }
+
+    #[test]
+    fn it_describes_columns_of_strict_tables() {
+        let table = TableInfo {
+            name: "t".to_owned(),
+            columns: vec![
+                table_column("id", "INTEGER", false, true),
+                table_column("weight", "REAL", true, false),
+                table_column("anything", "ANY", false, false),
+            ],
+            strict: true,
+            without_rowid: false,
+        };
+
+        let id = describe(&table, "id");
+
+        assert_eq!(id.type_info.affinity, Some(SqliteTypeAffinity::Integer));
+        assert_eq!(id.non_null, Some(true));
+        assert_eq!(
+            id.flags,
+            ColumnFlags::PRIMARY_KEY | ColumnFlags::AUTO_INCREMENT
+        );
+
+        let weight = describe(&table, "weight");
+
+        assert_eq!(weight.type_info.affinity, Some(SqliteTypeAffinity::Real));
+        assert_eq!(weight.non_null, Some(true));
+
+        let anything = describe(&table, "anything");
+
+        assert_eq!(anything.type_info.affinity, None);
+        assert_eq!(anything.non_null, Some(false));
+    }
+
+    #[test]
+    fn it_finds_aliases_of_the_rowid() {
+        let mut table = TableInfo {
+            name: "t".to_owned(),
+            columns: vec![table_column("id", "integer", false, true)],
+            strict: false,
+            without_rowid: false,
+        };
+
+        assert!(table.is_rowid_alias(&table.columns[0]));
+
+        // only `INTEGER` exactly makes an alias
+        table.columns[0].declared = "INT".to_owned();
+        assert!(!table.is_rowid_alias(&table.columns[0]));
+
+        table.columns[0].declared = "INTEGER".to_owned();
+        table.without_rowid = true;
+        assert!(!table.is_rowid_alias(&table.columns[0]));
+        assert_eq!(describe(&table, "id").non_null, Some(true));
+
+        table.without_rowid = false;
+        table
+            .columns
+            .push(table_column("other", "INTEGER", false, true));
+        assert!(!table.is_rowid_alias(&table.columns[0]));
+        assert_eq!(describe(&table, "id").non_null, Some(false));
+    }
+}
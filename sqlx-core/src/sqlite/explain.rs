@@ -0,0 +1,113 @@
+use serde_json::{json, Value};
+
+use crate::explain::{Explain, ExplainNode, ExplainPlan};
+use crate::sqlite::Sqlite;
+
+impl Explain for Sqlite {
+    fn explain_statement(query: &str, analyze: bool) -> crate::Result<String> {
+        if analyze {
+            return Err(protocol_err!("EXPLAIN ANALYZE is not supported for SQLite").into());
+        }
+
+        Ok(format!("EXPLAIN QUERY PLAN {}", query))
+    }
+
+    fn parse_plan(rows: Vec<Vec<String>>) -> crate::Result<ExplainPlan> {
+        // https://www.sqlite.org/eqp.html
+        // each row is a step: its id, the id of the step it is nested in, and its description
+        let mut steps = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            match &*row {
+                [id, parent, _, detail] => steps.push((
+                    id.parse().map_err(crate::Error::decode)?,
+                    parent.parse().map_err(crate::Error::decode)?,
+                    detail.clone(),
+                )),
+
+                _ => {
+                    return Err(protocol_err!(
+                        "expected 4 columns in the query plan but received {}",
+                        row.len()
+                    )
+                    .into())
+                }
+            }
+        }
+
+        let raw = steps
+            .iter()
+            .map(|(id, parent, detail)| json!({ "id": id, "parent": parent, "detail": detail }))
+            .collect();
+
+        Ok(ExplainPlan {
+            nodes: nested_in(&steps, 0),
+            raw: Value::Array(raw),
+        })
+    }
+}
+
+fn nested_in(steps: &[(i64, i64, String)], parent: i64) -> Vec<ExplainNode> {
+    steps
+        .iter()
+        .filter(|step| step.1 == parent && step.0 != parent)
+        .map(|(id, _, detail)| ExplainNode {
+            node_type: detail.clone(),
+            children: nested_in(steps, *id),
+            ..ExplainNode::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: i64, parent: i64, detail: &str) -> Vec<String> {
+        vec![
+            id.to_string(),
+            parent.to_string(),
+            "0".into(),
+            detail.into(),
+        ]
+    }
+
+    fn step(detail: &str, children: Vec<ExplainNode>) -> ExplainNode {
+        ExplainNode {
+            node_type: detail.into(),
+            children,
+            ..ExplainNode::default()
+        }
+    }
+
+    #[test]
+    fn it_parses_a_plan() -> crate::Result<()> {
+        let plan = Sqlite::parse_plan(vec![
+            row(3, 0, "SCAN TABLE users"),
+            row(9, 0, "CORRELATED SCALAR SUBQUERY 1"),
+            row(
+                14,
+                9,
+                "SEARCH TABLE posts USING INDEX posts_user_id (user_id=?)",
+            ),
+        ])?;
+
+        assert_eq!(
+            plan.nodes,
+            vec![
+                step("SCAN TABLE users", vec![]),
+                step(
+                    "CORRELATED SCALAR SUBQUERY 1",
+                    vec![step(
+                        "SEARCH TABLE posts USING INDEX posts_user_id (user_id=?)",
+                        vec![]
+                    )]
+                ),
+            ]
+        );
+
+        assert_eq!(plan.raw[2]["parent"], 9);
+
+        Ok(())
+    }
+}
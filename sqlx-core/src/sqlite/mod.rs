@@ -4,6 +4,8 @@ mod cursor;
 mod database;
 mod error;
 mod executor;
+#[cfg(feature = "json")]
+mod explain;
 mod row;
 mod statement;
 mod types;
@@ -11,7 +13,7 @@ mod value;
 mod worker;
 
 pub use arguments::{SqliteArgumentValue, SqliteArguments};
-pub use connection::SqliteConnection;
+pub use connection::{SqliteConnection, SqliteInterruptHandle};
 pub use cursor::SqliteCursor;
 pub use database::Sqlite;
 pub use error::SqliteError;
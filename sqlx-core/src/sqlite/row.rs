@@ -1,18 +1,58 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use crate::database::HasRow;
-use crate::row::{ColumnIndex, Row};
-use crate::sqlite::statement::Statement;
-use crate::sqlite::value::SqliteValue;
+use crate::decode::Decode;
+use crate::row::{self, ColumnIndex, Row};
+use crate::sqlite::statement::{Statement, StatementKey};
+use crate::sqlite::value::{OwnedValue, SqliteValue};
 use crate::sqlite::{Sqlite, SqliteConnection};
+use crate::types::Type;
 
 pub struct SqliteRow<'c> {
-    pub(super) values: usize,
-    pub(super) statement: Option<usize>,
-    pub(super) connection: &'c mut SqliteConnection,
+    pub(super) inner: RowInner<'c>,
+}
+
+pub(super) enum RowInner<'c> {
+    // The current row of a statement of the connection
+    Statement {
+        values: usize,
+        statement: StatementKey,
+        connection: &'c mut SqliteConnection,
+    },
+
+    // The values of a row, copied out of the statement by [Row::into_owned]
+    Owned {
+        values: Box<[OwnedValue]>,
+        columns: Arc<HashMap<String, usize>>,
+    },
 }
 
 impl<'c> SqliteRow<'c> {
-    fn statement(&'c self) -> &'c Statement {
-        self.connection.statement(self.statement)
+    pub(super) fn new(
+        values: usize,
+        statement: StatementKey,
+        connection: &'c mut SqliteConnection,
+    ) -> Self {
+        Self {
+            inner: RowInner::Statement {
+                values,
+                statement,
+                connection,
+            },
+        }
+    }
+
+    fn columns(&self) -> &HashMap<String, usize> {
+        match &self.inner {
+            RowInner::Statement {
+                statement,
+                connection,
+                ..
+            } => &connection.statement(*statement).columns,
+
+            RowInner::Owned { columns, .. } => columns,
+        }
     }
 }
 
@@ -21,7 +61,30 @@ impl<'c> Row<'c> for SqliteRow<'c> {
 
     #[inline]
     fn len(&self) -> usize {
-        self.values
+        match &self.inner {
+            RowInner::Statement { values, .. } => *values,
+            RowInner::Owned { values, .. } => values.len(),
+        }
+    }
+
+    fn try_get<'r, T, I>(&'r self, index: I) -> crate::Result<T>
+    where
+        'c: 'r,
+        T: Type<Self::Database>,
+        I: ColumnIndex<Self::Database>,
+        T: Decode<'r, Self::Database>,
+    {
+        let index = index.resolve(self)?;
+
+        Decode::decode(self.try_get_raw(index)?).map_err(|err| {
+            row::column_error::<T, _>(err, || {
+                self.columns()
+                    .iter()
+                    .find(|&(_, &i)| i == index)
+                    .map_or_else(|| index.to_string(), |(name, _)| name.clone())
+                    .into()
+            })
+        })
     }
 
     fn try_get_raw<'r, I>(&'r self, index: I) -> crate::Result<SqliteValue<'r>>
@@ -30,9 +93,43 @@ impl<'c> Row<'c> for SqliteRow<'c> {
         I: ColumnIndex<Self::Database>,
     {
         let index = index.resolve(self)?;
-        let value = SqliteValue::new(self.statement(), index);
 
-        Ok(value)
+        Ok(match &self.inner {
+            RowInner::Statement {
+                statement,
+                connection,
+                ..
+            } => SqliteValue::new(connection.statement(*statement), index),
+
+            RowInner::Owned { values, .. } => SqliteValue::owned(&values[index]),
+        })
+    }
+
+    fn into_owned(self) -> SqliteRow<'static> {
+        let (values, statement, connection) = match self.inner {
+            RowInner::Statement {
+                values,
+                statement,
+                connection,
+            } => (values, statement, connection),
+
+            RowInner::Owned { values, columns } => {
+                return SqliteRow {
+                    inner: RowInner::Owned { values, columns },
+                }
+            }
+        };
+
+        let statement: &Statement = connection.statement(statement);
+
+        SqliteRow {
+            inner: RowInner::Owned {
+                values: (0..values)
+                    .map(|index| OwnedValue::copy(statement, index))
+                    .collect(),
+                columns: Arc::clone(&statement.columns),
+            },
+        }
     }
 }
 
@@ -50,8 +147,7 @@ impl ColumnIndex<Sqlite> for usize {
 
 impl ColumnIndex<Sqlite> for &'_ str {
     fn resolve(self, row: &<Sqlite as HasRow>::Row) -> crate::Result<usize> {
-        row.statement()
-            .columns
+        row.columns()
             .get(self)
             .ok_or_else(|| crate::Error::ColumnNotFound((*self).into()))
             .map(|&index| index as usize)
@@ -2,13 +2,15 @@ use core::ptr::{null, null_mut, NonNull};
 
 use std::collections::HashMap;
 use std::ffi::CStr;
-use std::os::raw::c_int;
+use std::os::raw::{c_char, c_int};
+use std::sync::Arc;
 
 use libsqlite3_sys::{
     sqlite3_bind_parameter_count, sqlite3_clear_bindings, sqlite3_column_count,
-    sqlite3_column_decltype, sqlite3_column_name, sqlite3_data_count, sqlite3_finalize,
-    sqlite3_prepare_v3, sqlite3_reset, sqlite3_step, sqlite3_stmt, SQLITE_DONE, SQLITE_OK,
-    SQLITE_PREPARE_NO_VTAB, SQLITE_PREPARE_PERSISTENT, SQLITE_ROW,
+    sqlite3_column_database_name, sqlite3_column_decltype, sqlite3_column_name,
+    sqlite3_column_origin_name, sqlite3_column_table_name, sqlite3_data_count, sqlite3_finalize,
+    sqlite3_prepare_v3, sqlite3_reset, sqlite3_step, sqlite3_stmt, sqlite3_stmt_readonly,
+    SQLITE_DONE, SQLITE_OK, SQLITE_PREPARE_NO_VTAB, SQLITE_PREPARE_PERSISTENT, SQLITE_ROW,
 };
 
 use crate::sqlite::connection::SqliteConnectionHandle;
@@ -25,6 +27,16 @@ pub(super) enum Step {
     Row,
 }
 
+/// Which statement of a connection is meant; see [SqliteConnection::statement].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum StatementKey {
+    /// The statement at this index of the persistent statements.
+    Persistent(usize),
+
+    /// The most recently prepared, non-persistent statement.
+    Temporary,
+}
+
 /// Thin wrapper around [sqlite3_stmt] to impl `Send`.
 #[derive(Clone, Copy)]
 pub(super) struct SqliteStatementHandle(NonNull<sqlite3_stmt>);
@@ -38,7 +50,8 @@ pub(super) struct Statement {
     pub(super) connection: SqliteConnectionHandle,
     pub(super) worker: Worker,
     pub(super) tail: usize,
-    pub(super) columns: HashMap<String, usize>,
+    // Shared with the rows that are copied out of the statement
+    pub(super) columns: Arc<HashMap<String, usize>>,
 }
 
 // SQLite3 statement objects are safe to send between threads, but *not* safe
@@ -49,17 +62,15 @@ pub(super) struct Statement {
 unsafe impl Send for SqliteStatementHandle {}
 
 impl Statement {
+    // Prepares the first statement of `query` and advances `query` past it. Empty statements,
+    // such as a lone `;` or a comment, are skipped; returns `None` if there is nothing else.
     pub(super) fn new(
         conn: &mut SqliteConnection,
         query: &mut &str,
         persistent: bool,
-    ) -> crate::Result<Self> {
-        // TODO: Error on queries that are too large
-        let query_ptr = query.as_bytes().as_ptr() as *const i8;
-        let query_len = query.len() as i32;
-        let mut statement_handle: *mut sqlite3_stmt = null_mut();
+    ) -> crate::Result<Option<Self>> {
+        let original = *query;
         let mut flags = SQLITE_PREPARE_NO_VTAB;
-        let mut tail: *const i8 = null();
 
         if persistent {
             // SQLITE_PREPARE_PERSISTENT
@@ -69,46 +80,65 @@ impl Statement {
             flags |= SQLITE_PREPARE_PERSISTENT;
         }
 
-        // <https://www.sqlite.org/c3ref/prepare.html>
-        #[allow(unsafe_code)]
-        let status = unsafe {
-            sqlite3_prepare_v3(
-                conn.handle(),
-                query_ptr,
-                query_len,
-                flags as u32,
-                &mut statement_handle,
-                &mut tail,
-            )
-        };
-
-        if status != SQLITE_OK {
-            return Err(SqliteError::from_connection(conn.handle()).into());
-        }
+        let (statement_handle, tail) = loop {
+            // TODO: Error on queries that are too large
+            let query_ptr = query.as_bytes().as_ptr() as *const i8;
+            let query_len = query.len() as i32;
+            let mut statement_handle: *mut sqlite3_stmt = null_mut();
+            let mut tail: *const i8 = null();
+
+            // <https://www.sqlite.org/c3ref/prepare.html>
+            #[allow(unsafe_code)]
+            let status = unsafe {
+                sqlite3_prepare_v3(
+                    conn.handle(),
+                    query_ptr,
+                    query_len,
+                    flags as u32,
+                    &mut statement_handle,
+                    &mut tail,
+                )
+            };
+
+            if status != SQLITE_OK {
+                return Err(SqliteError::from_connection(conn.handle()).into());
+            }
 
-        // If pzTail is not NULL then *pzTail is made to point to the first byte
-        // past the end of the first SQL statement in zSql.
-        let tail = (tail as usize) - (query_ptr as usize);
-        *query = &query[tail..].trim();
+            // If pzTail is not NULL then *pzTail is made to point to the first byte
+            // past the end of the first SQL statement in zSql; the offset is kept from the
+            // start of the whole query, to skip the statement when it is found in the cache
+            let tail = (tail as usize) - (original.as_ptr() as usize);
+            *query = original[tail..].trim();
+
+            // If the input text contains no SQL (if the input is an empty string or a
+            // comment) then *ppStmt is set to NULL
+            match NonNull::new(statement_handle) {
+                Some(handle) => break (handle, tail),
+                None if query.is_empty() => return Ok(None),
+                None => {}
+            }
+        };
 
         let mut self_ = Self {
             worker: conn.worker.clone(),
             connection: conn.handle,
-            handle: SqliteStatementHandle(NonNull::new(statement_handle).unwrap()),
-            columns: HashMap::new(),
+            handle: SqliteStatementHandle(statement_handle),
+            columns: Arc::default(),
             tail,
         };
 
         // Prepare a column hash map for use in pulling values from a column by name
         let count = self_.column_count();
-        self_.columns.reserve(count);
+        let mut columns = HashMap::with_capacity(count);
 
         for i in 0..count {
             let name = self_.column_name(i).to_owned();
-            self_.columns.insert(name, i);
+            columns.insert(name, i);
         }
 
-        Ok(self_)
+        self_.columns = Arc::new(columns);
+
+        Ok(Some(self_))
     }
 
     /// Returns a pointer to the raw C pointer backing this statement.
@@ -168,6 +198,41 @@ impl Statement {
         name.map(|s| s.to_str().unwrap())
     }
 
+    // The database, table and column that the column is read from, if it is a column of a
+    // table rather than an expression
+    pub(super) fn column_origin(&mut self, index: usize) -> Option<(&str, &str, &str)> {
+        // https://sqlite.org/c3ref/column_database_name.html
+        // The names are null for an expression, and live as long as the statement
+        #[allow(unsafe_code)]
+        unsafe {
+            let name = |ptr: *const c_char| -> Option<&str> {
+                if ptr.is_null() {
+                    None
+                } else {
+                    CStr::from_ptr(ptr).to_str().ok()
+                }
+            };
+
+            let handle = self.handle();
+            let index = index as c_int;
+
+            Some((
+                name(sqlite3_column_database_name(handle, index))?,
+                name(sqlite3_column_table_name(handle, index))?,
+                name(sqlite3_column_origin_name(handle, index))?,
+            ))
+        }
+    }
+
+    // Whether the statement leaves the database as it is, so its changes are not the count of
+    // `sqlite3_changes` (which is then of an earlier statement)
+    pub(super) fn readonly(&self) -> bool {
+        // https://sqlite.org/c3ref/stmt_readonly.html
+        #[allow(unsafe_code)]
+        let readonly = unsafe { sqlite3_stmt_readonly(self.handle()) };
+        readonly != 0
+    }
+
     pub(super) fn params(&mut self) -> usize {
         // https://www.hwaci.com/sw/sqlite/c3ref/bind_parameter_count.html
         #[allow(unsafe_code)]
@@ -176,6 +241,10 @@ impl Statement {
     }
 
     pub(super) fn bind(&mut self, arguments: &mut SqliteArguments) -> crate::Result<()> {
+        if let Some(error) = arguments.error.take() {
+            return Err(error);
+        }
+
         for index in 0..self.params() {
             if let Some(value) = arguments.next() {
                 value.bind(self, index + 1)?;
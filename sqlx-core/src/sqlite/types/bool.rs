@@ -16,8 +16,10 @@ impl Encode<Sqlite> for bool {
     }
 }
 
+// SQLite has no boolean type; `TRUE` and `FALSE` are the integers 1 and 0, and any other integer
+// than 0 is true
 impl<'a> Decode<'a, Sqlite> for bool {
     fn decode(value: SqliteValue<'a>) -> crate::Result<bool> {
-        Ok(value.int() != 0)
+        Ok(value.not_null()?.int64() != 0)
     }
 }
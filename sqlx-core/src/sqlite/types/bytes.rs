@@ -2,7 +2,7 @@ use crate::decode::Decode;
 use crate::encode::Encode;
 use crate::sqlite::types::{SqliteType, SqliteTypeAffinity};
 use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValue};
-use crate::types::Type;
+use crate::types::{copy_to_byte_array, Type};
 
 impl Type<Sqlite> for [u8] {
     fn type_info() -> SqliteTypeInfo {
@@ -31,7 +31,7 @@ impl Encode<Sqlite> for Vec<u8> {
 
 impl<'de> Decode<'de, Sqlite> for &'de [u8] {
     fn decode(value: SqliteValue<'de>) -> crate::Result<&'de [u8]> {
-        Ok(value.blob())
+        Ok(value.not_null()?.blob())
     }
 }
 
@@ -40,3 +40,31 @@ impl<'de> Decode<'de, Sqlite> for Vec<u8> {
         <&[u8] as Decode<Sqlite>>::decode(value).map(ToOwned::to_owned)
     }
 }
+
+macro_rules! impl_byte_array {
+    ([$($generics:tt)*] $len:tt) => {
+        impl<$($generics)*> Type<Sqlite> for [u8; $len] {
+            fn type_info() -> SqliteTypeInfo {
+                <[u8] as Type<Sqlite>>::type_info()
+            }
+        }
+
+        impl<$($generics)*> Encode<Sqlite> for [u8; $len] {
+            fn encode(&self, values: &mut Vec<SqliteArgumentValue>) {
+                <[u8] as Encode<Sqlite>>::encode(self, values)
+            }
+        }
+
+        impl<'de, $($generics)*> Decode<'de, Sqlite> for [u8; $len] {
+            fn decode(value: SqliteValue<'de>) -> crate::Result<Self> {
+                let mut array = [0; $len];
+
+                copy_to_byte_array(&mut array, <&[u8] as Decode<Sqlite>>::decode(value)?)?;
+
+                Ok(array)
+            }
+        }
+    };
+}
+
+impl_for_byte_arrays!(impl_byte_array);
@@ -18,7 +18,7 @@ impl Encode<Sqlite> for f32 {
 
 impl<'a> Decode<'a, Sqlite> for f32 {
     fn decode(value: SqliteValue<'a>) -> crate::Result<f32> {
-        Ok(value.double() as f32)
+        Ok(value.not_null()?.double() as f32)
     }
 }
 
@@ -36,6 +36,6 @@ impl Encode<Sqlite> for f64 {
 
 impl<'a> Decode<'a, Sqlite> for f64 {
     fn decode(value: SqliteValue<'a>) -> crate::Result<f64> {
-        Ok(value.double())
+        Ok(value.not_null()?.double())
     }
 }
@@ -18,7 +18,7 @@ impl Encode<Sqlite> for i32 {
 
 impl<'a> Decode<'a, Sqlite> for i32 {
     fn decode(value: SqliteValue<'a>) -> crate::Result<i32> {
-        Ok(value.int())
+        Ok(value.not_null()?.int())
     }
 }
 
@@ -36,6 +36,6 @@ impl Encode<Sqlite> for i64 {
 
 impl<'a> Decode<'a, Sqlite> for i64 {
     fn decode(value: SqliteValue<'a>) -> crate::Result<i64> {
-        Ok(value.int64())
+        Ok(value.not_null()?.int64())
     }
 }
@@ -0,0 +1,9 @@
+use crate::decode::Decode;
+use crate::sqlite::{Sqlite, SqliteValue};
+use crate::types::JsonText;
+
+impl<'de> Decode<'de, Sqlite> for JsonText<'de> {
+    fn decode(value: SqliteValue<'de>) -> crate::Result<Self> {
+        Ok(JsonText(value.not_null()?.text().as_bytes()))
+    }
+}
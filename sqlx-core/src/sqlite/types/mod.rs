@@ -11,6 +11,12 @@ mod float;
 mod int;
 mod str;
 
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(feature = "messagepack")]
+mod serialized;
+
 // https://www.sqlite.org/c3ref/c_blob.html
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub(crate) enum SqliteType {
@@ -66,6 +72,11 @@ impl TypeInfo for SqliteTypeInfo {
     fn compatible(&self, other: &Self) -> bool {
         self.affinity == other.affinity
     }
+
+    // values in SQLite are dynamically typed; the affinity is the closest we have to a type
+    fn compatible_strict(&self, other: &Self) -> bool {
+        self.compatible(other)
+    }
 }
 
 impl<'de, T> Decode<'de, Sqlite> for Option<T>
@@ -0,0 +1,49 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValue};
+use crate::types::serialized::{self, MsgPack};
+use crate::types::Type;
+
+// Serialized values are `BLOB`s; the buffer a value is serialized into is bound as it is
+macro_rules! impl_serialized {
+    ($($wrapper:ident),*) => {
+        $(
+            impl<T> Type<Sqlite> for $wrapper<T> {
+                fn type_info() -> SqliteTypeInfo {
+                    <[u8] as Type<Sqlite>>::type_info()
+                }
+            }
+
+            impl<T: Serialize> Encode<Sqlite> for $wrapper<T> {
+                fn encode(&self, values: &mut Vec<SqliteArgumentValue>) {
+                    if let Err(error) = Encode::<Sqlite>::try_encode(self, values) {
+                        panic!("{}", error);
+                    }
+                }
+
+                fn try_encode(
+                    &self,
+                    values: &mut Vec<SqliteArgumentValue>,
+                ) -> crate::Result<IsNull> {
+                    let mut bytes = Vec::new();
+
+                    serialized::encode(self, &mut bytes)?;
+                    values.push(SqliteArgumentValue::Blob(bytes));
+
+                    Ok(IsNull::No)
+                }
+            }
+
+            impl<'de, T: DeserializeOwned + 'de> Decode<'de, Sqlite> for $wrapper<T> {
+                fn decode(value: SqliteValue<'de>) -> crate::Result<Self> {
+                    serialized::decode(<&'de [u8] as Decode<Sqlite>>::decode(value)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialized!(MsgPack);
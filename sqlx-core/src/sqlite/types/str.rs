@@ -1,5 +1,8 @@
+use std::path::{Path, PathBuf};
+
 use crate::decode::Decode;
-use crate::encode::Encode;
+use crate::encode::{Encode, IsNull};
+use crate::path::path_to_str;
 use crate::sqlite::types::{SqliteType, SqliteTypeAffinity};
 use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValue};
 use crate::types::Type;
@@ -31,7 +34,7 @@ impl Encode<Sqlite> for String {
 
 impl<'de> Decode<'de, Sqlite> for &'de str {
     fn decode(value: SqliteValue<'de>) -> crate::Result<&'de str> {
-        Ok(value.text())
+        Ok(value.not_null()?.text())
     }
 }
 
@@ -40,3 +43,43 @@ impl<'de> Decode<'de, Sqlite> for String {
         <&str as Decode<Sqlite>>::decode(value).map(ToOwned::to_owned)
     }
 }
+
+impl Type<Sqlite> for Path {
+    fn type_info() -> SqliteTypeInfo {
+        <str as Type<Sqlite>>::type_info()
+    }
+}
+
+impl Type<Sqlite> for PathBuf {
+    fn type_info() -> SqliteTypeInfo {
+        <str as Type<Sqlite>>::type_info()
+    }
+}
+
+impl Encode<Sqlite> for Path {
+    fn encode(&self, buf: &mut Vec<SqliteArgumentValue>) {
+        if let Err(error) = Encode::<Sqlite>::try_encode(self, buf) {
+            panic!("{}", error);
+        }
+    }
+
+    fn try_encode(&self, buf: &mut Vec<SqliteArgumentValue>) -> crate::Result<IsNull> {
+        Encode::<Sqlite>::try_encode(path_to_str(self)?, buf)
+    }
+}
+
+impl Encode<Sqlite> for PathBuf {
+    fn encode(&self, buf: &mut Vec<SqliteArgumentValue>) {
+        <Path as Encode<Sqlite>>::encode(self.as_path(), buf)
+    }
+
+    fn try_encode(&self, buf: &mut Vec<SqliteArgumentValue>) -> crate::Result<IsNull> {
+        <Path as Encode<Sqlite>>::try_encode(self.as_path(), buf)
+    }
+}
+
+impl<'de> Decode<'de, Sqlite> for PathBuf {
+    fn decode(value: SqliteValue<'de>) -> crate::Result<Self> {
+        <&'de str as Decode<Sqlite>>::decode(value).map(PathBuf::from)
+    }
+}
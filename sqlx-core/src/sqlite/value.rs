@@ -1,39 +1,102 @@
+use core::ptr::NonNull;
 use core::slice;
 
 use std::ffi::CStr;
+use std::os::raw::{c_int, c_uchar, c_void};
 use std::str::from_utf8_unchecked;
 
 use libsqlite3_sys::{
     sqlite3_column_blob, sqlite3_column_bytes, sqlite3_column_double, sqlite3_column_int,
-    sqlite3_column_int64, sqlite3_column_text, sqlite3_column_type, SQLITE_BLOB, SQLITE_FLOAT,
-    SQLITE_INTEGER, SQLITE_NULL, SQLITE_TEXT,
+    sqlite3_column_int64, sqlite3_column_text, sqlite3_column_type, sqlite3_column_value,
+    sqlite3_value, sqlite3_value_blob, sqlite3_value_bytes, sqlite3_value_double,
+    sqlite3_value_dup, sqlite3_value_free, sqlite3_value_int, sqlite3_value_int64,
+    sqlite3_value_text, sqlite3_value_type, SQLITE_BLOB, SQLITE_FLOAT, SQLITE_INTEGER, SQLITE_NULL,
+    SQLITE_TEXT,
 };
 
+use crate::error::UnexpectedNullError;
 use crate::sqlite::statement::Statement;
 use crate::sqlite::types::SqliteType;
 
 pub struct SqliteValue<'c> {
-    index: usize,
-    statement: &'c Statement,
+    inner: Inner<'c>,
+}
+
+enum Inner<'c> {
+    // A column of the current row of a statement
+    Column {
+        index: usize,
+        statement: &'c Statement,
+    },
+
+    // A value copied out of a row; see [OwnedValue]
+    Owned(&'c OwnedValue),
+}
+
+/// A copy of a column of the current row of a statement, made with `sqlite3_value_dup`, that
+/// outlives the row. It is read with the same conversions as the column itself.
+pub(super) struct OwnedValue(NonNull<sqlite3_value>);
+
+// A copied value belongs to no connection. It is read from one thread at a time, as its text or
+// blob may be converted in place; it is not `Sync`.
+#[allow(unsafe_code)]
+unsafe impl Send for OwnedValue {}
+
+impl OwnedValue {
+    pub(super) fn copy(statement: &Statement, index: usize) -> Self {
+        // https://www.sqlite.org/c3ref/value_dup.html
+        #[allow(unsafe_code)]
+        let value =
+            unsafe { sqlite3_value_dup(sqlite3_column_value(statement.handle(), index as c_int)) };
+
+        // `sqlite3_value_dup` only fails to allocate
+        OwnedValue(NonNull::new(value).expect("out of memory copying a SQLite value"))
+    }
+}
+
+impl Drop for OwnedValue {
+    fn drop(&mut self) {
+        #[allow(unsafe_code)]
+        unsafe {
+            sqlite3_value_free(self.0.as_ptr());
+        }
+    }
 }
 
 impl<'c> SqliteValue<'c> {
     #[inline]
     pub(super) fn new(statement: &'c Statement, index: usize) -> Self {
-        Self { statement, index }
+        Self {
+            inner: Inner::Column { statement, index },
+        }
+    }
+
+    #[inline]
+    pub(super) fn owned(value: &'c OwnedValue) -> Self {
+        Self {
+            inner: Inner::Owned(value),
+        }
     }
 }
 
 // https://www.sqlite.org/c3ref/column_blob.html
-// https://www.sqlite.org/capi3ref.html#sqlite3_column_blob
+// https://www.sqlite.org/c3ref/value_blob.html
 
-// These routines return information about a single column of the current result row of a query.
+// These routines return information about a single column of the current result row of a query,
+// or about a copy of it.
 
 impl<'c> SqliteValue<'c> {
     /// Returns the initial data type of the result column.
     pub(super) fn r#type(&self) -> SqliteType {
         #[allow(unsafe_code)]
-        let type_code = unsafe { sqlite3_column_type(self.statement.handle(), self.index as i32) };
+        let type_code = unsafe {
+            match self.inner {
+                Inner::Column { statement, index } => {
+                    sqlite3_column_type(statement.handle(), index as c_int)
+                }
+                Inner::Owned(value) => sqlite3_value_type(value.0.as_ptr()),
+            }
+        };
 
         match type_code {
             SQLITE_INTEGER => SqliteType::Integer,
@@ -46,11 +109,25 @@ impl<'c> SqliteValue<'c> {
         }
     }
 
+    /// Fails with an [`UnexpectedNullError`] if the value is `NULL`, which the other accessors
+    /// would read as `0`, an empty string or an empty blob.
+    pub(super) fn not_null(self) -> crate::Result<Self> {
+        match self.r#type() {
+            SqliteType::Null => Err(crate::Error::decode(UnexpectedNullError)),
+            _ => Ok(self),
+        }
+    }
+
     /// Returns the 32-bit INTEGER result.
     pub(super) fn int(&self) -> i32 {
         #[allow(unsafe_code)]
         unsafe {
-            sqlite3_column_int(self.statement.handle(), self.index as i32)
+            match self.inner {
+                Inner::Column { statement, index } => {
+                    sqlite3_column_int(statement.handle(), index as c_int)
+                }
+                Inner::Owned(value) => sqlite3_value_int(value.0.as_ptr()),
+            }
         }
     }
 
@@ -58,7 +135,12 @@ impl<'c> SqliteValue<'c> {
     pub(super) fn int64(&self) -> i64 {
         #[allow(unsafe_code)]
         unsafe {
-            sqlite3_column_int64(self.statement.handle(), self.index as i32)
+            match self.inner {
+                Inner::Column { statement, index } => {
+                    sqlite3_column_int64(statement.handle(), index as c_int)
+                }
+                Inner::Owned(value) => sqlite3_value_int64(value.0.as_ptr()),
+            }
         }
     }
 
@@ -66,7 +148,12 @@ impl<'c> SqliteValue<'c> {
     pub(super) fn double(&self) -> f64 {
         #[allow(unsafe_code)]
         unsafe {
-            sqlite3_column_double(self.statement.handle(), self.index as i32)
+            match self.inner {
+                Inner::Column { statement, index } => {
+                    sqlite3_column_double(statement.handle(), index as c_int)
+                }
+                Inner::Owned(value) => sqlite3_value_double(value.0.as_ptr()),
+            }
         }
     }
 
@@ -74,24 +161,45 @@ impl<'c> SqliteValue<'c> {
     pub(super) fn text(&self) -> &'c str {
         #[allow(unsafe_code)]
         unsafe {
-            let ptr = sqlite3_column_text(self.statement.handle(), self.index as i32) as *const i8;
+            let ptr: *const c_uchar = match self.inner {
+                Inner::Column { statement, index } => {
+                    sqlite3_column_text(statement.handle(), index as c_int)
+                }
+                Inner::Owned(value) => sqlite3_value_text(value.0.as_ptr()),
+            };
 
             debug_assert!(!ptr.is_null());
 
-            from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes())
+            from_utf8_unchecked(CStr::from_ptr(ptr as *const i8).to_bytes())
         }
     }
 
     /// Returns the BLOB result.
     pub(super) fn blob(&self) -> &'c [u8] {
-        let index = self.index as i32;
-
+        // Returns the size of the BLOB result in bytes, after the pointer to it as the docs
+        // recommend
         #[allow(unsafe_code)]
-        let ptr = unsafe { sqlite3_column_blob(self.statement.handle(), index) };
-
-        // Returns the size of the BLOB result in bytes.
-        #[allow(unsafe_code)]
-        let len = unsafe { sqlite3_column_bytes(self.statement.handle(), index) };
+        let (ptr, len): (*const c_void, c_int) = unsafe {
+            match self.inner {
+                Inner::Column { statement, index } => {
+                    let index = index as c_int;
+                    let ptr = sqlite3_column_blob(statement.handle(), index);
+
+                    (ptr, sqlite3_column_bytes(statement.handle(), index))
+                }
+
+                Inner::Owned(value) => {
+                    let ptr = sqlite3_value_blob(value.0.as_ptr());
+
+                    (ptr, sqlite3_value_bytes(value.0.as_ptr()))
+                }
+            }
+        };
+
+        // the pointer of an empty BLOB is null
+        if len == 0 {
+            return &[];
+        }
 
         #[allow(unsafe_code)]
         unsafe {
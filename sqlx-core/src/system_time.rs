@@ -0,0 +1,288 @@
+//! Conversions between `SystemTime` and calendar dates and times in UTC, for the drivers that
+//! encode timestamps as one or the other.
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MICROS_PER_SEC: i64 = 1_000_000;
+const SECS_PER_DAY: i64 = 86_400;
+
+/// A date and time in UTC in the proleptic Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DateTime {
+    pub(crate) year: i32,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) micros: u32,
+}
+
+impl DateTime {
+    // only MySQL sends the fields of a date and time
+    #[cfg_attr(not(feature = "mysql"), allow(dead_code))]
+    pub(crate) fn from_unix_micros(micros: i64) -> Self {
+        let secs = micros.div_euclid(MICROS_PER_SEC);
+        let days = secs.div_euclid(SECS_PER_DAY);
+        let time = secs.rem_euclid(SECS_PER_DAY) as u32;
+
+        let (year, month, day) = civil_from_days(days);
+
+        Self {
+            year: year as i32,
+            month,
+            day,
+            hour: time / 3600,
+            minute: time / 60 % 60,
+            second: time % 60,
+            micros: micros.rem_euclid(MICROS_PER_SEC) as u32,
+        }
+    }
+
+    pub(crate) fn unix_micros(&self) -> i64 {
+        let days = days_from_civil(self.year as i64, self.month, self.day);
+        let secs = days * SECS_PER_DAY
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64;
+
+        secs * MICROS_PER_SEC + self.micros as i64
+    }
+
+    /// Parses `YYYY-MM-DD HH:MM:SS[.ffffff][+HH[:MM[:SS]]][ BC]`, the text format of a timestamp
+    /// in Postgres (with `DateStyle` set to `ISO`) and MySQL.
+    ///
+    /// Returns microseconds since the Unix epoch, taking the UTC offset into account.
+    pub(crate) fn parse(s: &str) -> Option<i64> {
+        let bc = s.ends_with(" BC");
+        let s = if bc { &s[..s.len() - 3] } else { s };
+
+        let (date, time) = split_once(s, |ch| ch == ' ' || ch == 'T')?;
+
+        let (year, date) = split_once(date, |ch| ch == '-')?;
+        let (month, day) = split_once(date, |ch| ch == '-')?;
+
+        // the UTC offset follows the seconds directly
+        let (time, offset) = match time.find(&['+', '-'][..]) {
+            Some(index) => (&time[..index], Some(&time[index..])),
+            None => (time, None),
+        };
+
+        let (hour, time) = split_once(time, |ch| ch == ':')?;
+        let (minute, second) = split_once(time, |ch| ch == ':')?;
+
+        let (second, micros) = match split_once(second, |ch| ch == '.') {
+            Some((second, fraction)) => {
+                if fraction.is_empty() || fraction.len() > 6 {
+                    return None;
+                }
+
+                let scale = 10_u32.pow(6 - fraction.len() as u32);
+
+                (second, number(fraction)? * scale)
+            }
+
+            None => (second, 0),
+        };
+
+        let year = number(year)? as i32;
+
+        let date_time = DateTime {
+            // there is no year 0; 1 BC is year 0 of the proleptic Gregorian calendar
+            year: if bc { 1 - year } else { year },
+            month: number(month)?,
+            day: number(day)?,
+            hour: number(hour)?,
+            minute: number(minute)?,
+            second: number(second)?,
+            micros,
+        };
+
+        if !date_time.is_valid() {
+            return None;
+        }
+
+        let offset = match offset {
+            Some(offset) => {
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                let mut secs = 0;
+
+                for (part, scale) in offset[1..].split(':').zip(&[3600, 60, 1]) {
+                    secs += number(part)? as i64 * scale;
+                }
+
+                sign * secs
+            }
+
+            None => 0,
+        };
+
+        Some(date_time.unix_micros() - offset * MICROS_PER_SEC)
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        self.month >= 1
+            && self.month <= 12
+            && self.day >= 1
+            && self.day <= days_in_month(self.year as i64, self.month)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+    }
+}
+
+/// Returns the number of microseconds since the Unix epoch, rounding down; `None` if that does
+/// not fit into an `i64`.
+pub(crate) fn to_unix_micros(time: SystemTime) -> Option<i64> {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => i64::try_from(after.as_micros()).ok(),
+
+        Err(before) => {
+            let before = before.duration();
+
+            // round towards the past
+            let micros = before.as_micros() + (before.subsec_nanos() % 1_000 != 0) as u128;
+
+            i64::try_from(micros).ok().map(|micros| -micros)
+        }
+    }
+}
+
+pub(crate) fn from_unix_micros(micros: i64) -> Option<SystemTime> {
+    if micros >= 0 {
+        UNIX_EPOCH.checked_add(Duration::from_micros(micros as u64))
+    } else {
+        UNIX_EPOCH.checked_sub(Duration::from_micros(micros.wrapping_neg() as u64))
+    }
+}
+
+fn split_once(s: &str, pat: impl Fn(char) -> bool) -> Option<(&str, &str)> {
+    let index = s.find(pat)?;
+
+    Some((&s[..index], &s[index + 1..]))
+}
+
+fn number(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+#[cfg_attr(not(feature = "mysql"), allow(dead_code))]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_to_and_from_dates() {
+        let cases = [
+            (0, "1970-01-01 00:00:00"),
+            (951_782_400_000_000, "2000-02-29 00:00:00"),
+            (1_576_062_065_115_100, "2019-12-11 11:01:05.1151"),
+            (-315_619_200_000_000, "1960-01-01 00:00:00"),
+            (-1, "1969-12-31 23:59:59.999999"),
+            (-62_135_596_800_000_000, "0001-01-01 00:00:00"),
+        ];
+
+        for &(micros, s) in &cases {
+            let date_time = DateTime::from_unix_micros(micros);
+
+            assert_eq!(date_time.unix_micros(), micros);
+            assert_eq!(DateTime::parse(s), Some(micros), "{}", s);
+        }
+
+        assert_eq!(
+            DateTime::from_unix_micros(-1),
+            DateTime {
+                year: 1969,
+                month: 12,
+                day: 31,
+                hour: 23,
+                minute: 59,
+                second: 59,
+                micros: 999_999,
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_offsets_and_eras() {
+        let micros = 1_576_062_065_000_000;
+
+        assert_eq!(DateTime::parse("2019-12-11 11:01:05+00"), Some(micros));
+        assert_eq!(DateTime::parse("2019-12-11 13:01:05+02"), Some(micros));
+        assert_eq!(DateTime::parse("2019-12-11 05:31:05-05:30"), Some(micros));
+        assert_eq!(DateTime::parse("2019-12-11T11:01:05"), Some(micros));
+
+        assert_eq!(
+            DateTime::parse("0001-12-31 00:00:00+00 BC"),
+            Some(-62_135_596_800_000_000 - 86_400_000_000)
+        );
+
+        assert_eq!(DateTime::parse("2019-02-29 00:00:00"), None);
+        assert_eq!(DateTime::parse("2019-12-11"), None);
+        assert_eq!(DateTime::parse("infinity"), None);
+    }
+
+    #[test]
+    fn it_rounds_system_times_towards_the_past() {
+        assert_eq!(
+            to_unix_micros(UNIX_EPOCH + Duration::from_nanos(1_500)),
+            Some(1)
+        );
+        assert_eq!(
+            to_unix_micros(UNIX_EPOCH - Duration::from_nanos(1_500)),
+            Some(-2)
+        );
+        assert_eq!(
+            from_unix_micros(-2),
+            Some(UNIX_EPOCH - Duration::from_micros(2))
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! Utilities for tests of code that uses SQLx.
+
+use std::sync::{Arc, Mutex};
+
+use crate::notice::{HandleNotices, Notice, NoticeSeverity};
+
+/// Records the notices and warnings of the server on a connection, for a test to inspect.
+///
+/// ```rust,ignore
+/// let notices = CaptureNotices::attach(&mut conn);
+///
+/// conn.execute("DO $$ BEGIN RAISE WARNING 'low balance'; END $$").await?;
+///
+/// assert_eq!(notices.take()[0].message(), "low balance");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CaptureNotices {
+    notices: Arc<Mutex<Vec<Notice>>>,
+}
+
+impl CaptureNotices {
+    /// Starts recording the notices of `conn`, replacing its notice handler.
+    pub fn attach<C>(conn: &mut C) -> Self
+    where
+        C: HandleNotices,
+    {
+        let capture = Self::default();
+        let notices = Arc::clone(&capture.notices);
+
+        conn.set_notice_handler(move |notice| notices.lock().unwrap().push(notice));
+
+        capture
+    }
+
+    /// Returns the notices recorded so far, oldest first.
+    pub fn notices(&self) -> Vec<Notice> {
+        self.notices.lock().unwrap().clone()
+    }
+
+    /// Returns the notices recorded so far and forgets them.
+    pub fn take(&self) -> Vec<Notice> {
+        std::mem::take(&mut *self.notices.lock().unwrap())
+    }
+
+    /// Returns the notices recorded so far that are at least as serious as `severity`.
+    pub fn at_least(&self, severity: NoticeSeverity) -> Vec<Notice> {
+        self.notices
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|notice| notice.severity() >= severity)
+            .cloned()
+            .collect()
+    }
+}
@@ -2,10 +2,10 @@ use std::ops::{Deref, DerefMut};
 
 use futures_core::future::BoxFuture;
 
-use crate::connection::Connection;
+use crate::connection::{Connection, StatementCacheStats, TransactionStatus};
 use crate::database::Database;
 use crate::database::HasCursor;
-use crate::describe::Describe;
+use crate::describe::{Describe, TableColumns};
 use crate::executor::{Execute, Executor, RefExecutor};
 use crate::runtime::spawn;
 
@@ -42,7 +42,24 @@ where
         Transaction::new(self.depth, self.inner.take().expect(ERR_FINALIZED)).await
     }
 
+    /// Commits the transaction, or releases the savepoint of a nested transaction.
+    ///
+    /// Returns the error that made the transaction fail if a statement in it failed, as Postgres
+    /// would otherwise answer `COMMIT` by rolling the transaction back. The transaction is then
+    /// rolled back in the background, like when a `Transaction` is dropped.
+    ///
+    /// A failed transaction whose error is not known, e.g. as the statement that failed was
+    /// executed on the connection before the `Transaction` was started, is rolled back all the
+    /// same and returns [`Error::TransactionFailed`](crate::Error::TransactionFailed).
     pub async fn commit(mut self) -> crate::Result<T> {
+        let inner = self.inner.as_mut().expect(ERR_FINALIZED);
+
+        if inner.transaction_status() == TransactionStatus::Error {
+            return Err(inner
+                .take_transaction_error()
+                .unwrap_or(crate::Error::TransactionFailed));
+        }
+
         let mut inner = self.inner.take().expect(ERR_FINALIZED);
         let depth = self.depth;
 
@@ -152,8 +169,15 @@ where
     fn drop(&mut self) {
         if self.depth > 0 {
             if let Some(mut inner) = self.inner.take() {
+                // a nested transaction only undoes its own savepoint
+                let stmt = if self.depth == 1 {
+                    "ROLLBACK".to_owned()
+                } else {
+                    format!("ROLLBACK TO SAVEPOINT _sqlx_savepoint_{}", self.depth - 1)
+                };
+
                 spawn(async move {
-                    let res = inner.execute("ROLLBACK").await;
+                    let res = inner.execute(&*stmt).await;
 
                     // If the rollback failed we need to close the inner connection
                     if res.is_err() {
@@ -166,3 +190,161 @@ where
         }
     }
 }
+
+/// A transaction that is never committed, started by [`Connection::begin_test`].
+///
+/// The transaction is rolled back when the `TestTransaction` is dropped, so a test can run
+/// against a database shared with other tests and leave it as it found it. It dereferences to
+/// the connection, and is a [`Connection`] itself: code under test that begins a transaction
+/// of its own on it gets a savepoint within this one, which it can commit or roll back as usual.
+///
+/// ```rust,ignore
+/// let mut conn = PgConnection::connect(&url).await?.begin_test().await?;
+///
+/// create_user(&mut conn, "alice").await?;
+///
+/// // the user is gone once `conn` is dropped
+/// ```
+pub struct TestTransaction<T>
+where
+    T: Connection,
+{
+    tx: Transaction<T>,
+}
+
+impl<T> TestTransaction<T>
+where
+    T: Connection,
+{
+    pub(crate) async fn new(inner: T) -> crate::Result<Self> {
+        Ok(Self {
+            tx: inner.begin().await?,
+        })
+    }
+
+    /// Rolls back the transaction now and returns the connection.
+    pub async fn rollback(self) -> crate::Result<T> {
+        self.tx.rollback().await
+    }
+}
+
+impl<T> Deref for TestTransaction<T>
+where
+    T: Connection,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
+}
+
+impl<T> DerefMut for TestTransaction<T>
+where
+    T: Connection,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tx
+    }
+}
+
+impl<T> Connection for TestTransaction<T>
+where
+    T: Connection,
+{
+    /// Closes the connection, which discards the transaction.
+    fn close(mut self) -> BoxFuture<'static, crate::Result<()>> {
+        self.tx.inner.take().expect(ERR_FINALIZED).close()
+    }
+
+    fn ping(&mut self) -> BoxFuture<crate::Result<()>> {
+        (**self).ping()
+    }
+
+    fn is_broken(&self) -> bool {
+        (**self).is_broken()
+    }
+
+    fn is_ready(&self) -> bool {
+        (**self).is_ready()
+    }
+
+    fn take_arguments(&mut self) -> <T::Database as Database>::Arguments {
+        (**self).take_arguments()
+    }
+
+    fn transaction_status(&self) -> TransactionStatus {
+        (**self).transaction_status()
+    }
+
+    fn table_id<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<Option<<T::Database as Database>::TableId>>> {
+        (**self).table_id(name)
+    }
+
+    fn describe_table<'e>(
+        &'e mut self,
+        name: &'e str,
+    ) -> BoxFuture<'e, crate::Result<TableColumns<T::Database>>> {
+        (**self).describe_table(name)
+    }
+
+    fn take_transaction_error(&mut self) -> Option<crate::Error> {
+        (**self).take_transaction_error()
+    }
+
+    fn statement_cache_stats(&self) -> StatementCacheStats {
+        (**self).statement_cache_stats()
+    }
+}
+
+impl<T> Executor for TestTransaction<T>
+where
+    T: Connection,
+{
+    type Database = T::Database;
+
+    fn execute<'e, 'q: 'e, 'c: 'e, E: 'e>(
+        &'c mut self,
+        query: E,
+    ) -> BoxFuture<'e, crate::Result<u64>>
+    where
+        E: Execute<'q, Self::Database>,
+    {
+        (**self).execute(query)
+    }
+
+    fn fetch<'e, 'q, E>(&'e mut self, query: E) -> <Self::Database as HasCursor<'e, 'q>>::Cursor
+    where
+        E: Execute<'q, Self::Database>,
+    {
+        (**self).fetch(query)
+    }
+
+    fn describe<'e, 'q, E: 'e>(
+        &'e mut self,
+        query: E,
+    ) -> BoxFuture<'e, crate::Result<Describe<Self::Database>>>
+    where
+        E: Execute<'q, Self::Database>,
+    {
+        (**self).describe(query)
+    }
+}
+
+impl<'e, DB, T> RefExecutor<'e> for &'e mut TestTransaction<T>
+where
+    DB: Database,
+    T: Connection<Database = DB>,
+{
+    type Database = DB;
+
+    fn fetch_by_ref<'q, E>(self, query: E) -> <Self::Database as HasCursor<'e, 'q>>::Cursor
+    where
+        E: Execute<'q, Self::Database>,
+    {
+        (**self).fetch(query)
+    }
+}
@@ -1,6 +1,7 @@
 //! Traits linking Rust types to SQL types.
 
 use std::fmt::{Debug, Display};
+use std::ops::Deref;
 
 use crate::database::Database;
 
@@ -8,16 +9,127 @@ use crate::database::Database;
 #[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
 pub use uuid::Uuid;
 
+/// The hyphenated form of a [`Uuid`], as a string; for a UUID stored in a textual column.
+///
+/// A `Uuid` is decoded from such a column as well, but is encoded as the native UUID type of
+/// Postgres and as 16 bytes for MySQL.
+#[cfg(feature = "uuid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+pub use uuid::adapter::Hyphenated;
+
 #[cfg(feature = "chrono")]
 #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
 pub mod chrono {
     pub use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 }
 
+#[cfg(feature = "geo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+pub mod geo {
+    pub use geo_types::{Coordinate, LineString, Point, Polygon};
+}
+
+#[cfg(feature = "messagepack")]
+pub(crate) mod serialized;
+
+#[cfg(feature = "messagepack")]
+#[cfg_attr(docsrs, doc(cfg(feature = "messagepack")))]
+pub use serialized::MsgPack;
+
+// Calls `$impl!([generics] length)` to implement a trait for byte arrays: once for every length
+// with const generics, or for each length from 0 to 32 on compilers without them
+#[cfg(sqlx_const_generics)]
+macro_rules! impl_for_byte_arrays {
+    ($impl:ident) => {
+        $impl!([const N: usize] N);
+    };
+}
+
+#[cfg(not(sqlx_const_generics))]
+macro_rules! impl_for_byte_arrays {
+    ($impl:ident) => {
+        impl_for_byte_arrays!($impl:
+            0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+            17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+        );
+    };
+
+    ($impl:ident: $($len:literal)*) => {
+        $($impl!([] $len);)*
+    };
+}
+
+// Copies `bytes` into `array`, as the decoding of `[u8; N]`; fails if their lengths differ
+pub(crate) fn copy_to_byte_array(array: &mut [u8], bytes: &[u8]) -> crate::Result<()> {
+    if array.len() != bytes.len() {
+        return Err(crate::Error::Decode(
+            format!(
+                "expected {} bytes but the value has {}",
+                array.len(),
+                bytes.len()
+            )
+            .into(),
+        ));
+    }
+
+    array.copy_from_slice(bytes);
+
+    Ok(())
+}
+
+/// Opt-in wrapper for conversions that may lose precision.
+///
+/// Exact numeric types such as `DECIMAL` or `NUMERIC` are not decoded into floating-point
+/// types by default as that would silently round the value. Wrapping the target type in
+/// `Lossy` (e.g. `Lossy<f64>`) accepts those columns anyway and rounds to the nearest
+/// representable value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Lossy<T>(pub T);
+
+impl<T> Lossy<T> {
+    /// Unwraps the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Lossy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for Lossy<T> {
+    fn from(value: T) -> Self {
+        Lossy(value)
+    }
+}
+
+/// The text of a JSON value, before it is deserialized by
+/// [`Row::try_get_json`](crate::row::Row::try_get_json).
+#[cfg(feature = "json")]
+#[doc(hidden)]
+pub struct JsonText<'r>(pub(crate) &'r [u8]);
+
 pub trait TypeInfo: Debug + Display + Clone {
     /// Compares type information to determine if `other` is compatible at the Rust level
     /// with `self`.
+    ///
+    /// Types in the same class are compatible with each other; for instance, integers of any
+    /// width or the different textual types. Decoding a value of a wider type into a narrower
+    /// one checks that the value fits.
     fn compatible(&self, other: &Self) -> bool;
+
+    /// Like [`compatible`](TypeInfo::compatible) but only accepts `other` if it is the same
+    /// SQL type as `self`.
+    ///
+    /// Defaults to [`compatible`](TypeInfo::compatible), for type information that has no
+    /// looser notion of compatibility.
+    fn compatible_strict(&self, other: &Self) -> bool {
+        self.compatible(other)
+    }
 }
 
 /// Indicates that a SQL type is supported for a database.
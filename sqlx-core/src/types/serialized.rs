@@ -0,0 +1,144 @@
+//! Values stored in binary columns in a serialization format, such as [`MsgPack`].
+//!
+//! A format is added as a wrapper that implements [`SerializedWrapper`]; the `impl_serialized!`
+//! macro of each database then gives it `Type`, `Encode` and `Decode` for its binary type.
+
+use std::error::Error as StdError;
+use std::ops::{Deref, DerefMut};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+type BoxDynError = Box<dyn StdError + Send + Sync>;
+
+// A wrapper of `T` that is stored serialized in a binary column
+pub(crate) trait SerializedWrapper<T>: Sized {
+    // The name of the format, for the errors of encoding and decoding
+    const FORMAT: &'static str;
+
+    fn new(value: T) -> Self;
+
+    fn get(&self) -> &T;
+
+    // Appends `value` to `buf`; this is the whole value of the column
+    fn serialize_into(value: &T, buf: &mut Vec<u8>) -> Result<(), BoxDynError>
+    where
+        T: Serialize;
+
+    fn deserialize(bytes: &[u8]) -> Result<T, BoxDynError>
+    where
+        T: DeserializeOwned;
+}
+
+// Serializes the value of `wrapper` into `buf`, as `Encode::try_encode` does
+pub(crate) fn encode<W, T>(wrapper: &W, buf: &mut Vec<u8>) -> crate::Result<()>
+where
+    W: SerializedWrapper<T>,
+    T: Serialize,
+{
+    W::serialize_into(wrapper.get(), buf).map_err(|err| {
+        crate::Error::Encode(
+            format!("failed to serialize the value as {}: {}", W::FORMAT, err).into(),
+        )
+    })
+}
+
+// Deserializes the value of a column; the error tells a value written by another version of
+// `T`, which is the usual reason for a value that can't be read, apart from one that isn't
+// serialized in this format at all
+pub(crate) fn decode<W, T>(bytes: &[u8]) -> crate::Result<W>
+where
+    W: SerializedWrapper<T>,
+    T: DeserializeOwned,
+{
+    W::deserialize(bytes).map(W::new).map_err(|err| {
+        crate::Error::Decode(
+            format!(
+                "failed to deserialize {} bytes of {} as `{}`; the value may have been written \
+                 by another version of the type, or in another format: {}",
+                bytes.len(),
+                W::FORMAT,
+                std::any::type_name::<T>(),
+                err
+            )
+            .into(),
+        )
+    })
+}
+
+/// A value stored in a binary column (`BYTEA`, `BLOB` or `VARBINARY`) as [MessagePack], which
+/// is smaller and quicker to read than JSON.
+///
+/// Structs are written as maps of their field names, so fields can be reordered, and added
+/// with `#[serde(default)]`, without making the values already stored unreadable. A value that
+/// can't be read fails to decode with an error that names the type it was read as.
+///
+/// ```rust,ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Session { user_id: i64, scopes: Vec<String> }
+///
+/// sqlx::query("INSERT INTO sessions (token, data) VALUES ($1, $2)")
+///     .bind(token)
+///     .bind(MsgPack(&session))
+///     .execute(&mut conn)
+///     .await?;
+///
+/// let MsgPack(session): MsgPack<Session> = row.try_get("data")?;
+/// ```
+///
+/// [MessagePack]: https://msgpack.org
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MsgPack<T>(pub T);
+
+impl<T> MsgPack<T> {
+    /// Unwraps the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for MsgPack<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for MsgPack<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for MsgPack<T> {
+    fn from(value: T) -> Self {
+        MsgPack(value)
+    }
+}
+
+impl<T> SerializedWrapper<T> for MsgPack<T> {
+    const FORMAT: &'static str = "MessagePack";
+
+    fn new(value: T) -> Self {
+        MsgPack(value)
+    }
+
+    fn get(&self) -> &T {
+        &self.0
+    }
+
+    fn serialize_into(value: &T, buf: &mut Vec<u8>) -> Result<(), BoxDynError>
+    where
+        T: Serialize,
+    {
+        rmp_serde::encode::write_named(buf, value).map_err(Into::into)
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<T, BoxDynError>
+    where
+        T: DeserializeOwned,
+    {
+        rmp_serde::from_slice(bytes).map_err(Into::into)
+    }
+}
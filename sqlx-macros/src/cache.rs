@@ -0,0 +1,208 @@
+//! Descriptions of the queries of the query macros kept on disk, so a query that has not
+//! changed since the last build is not described by the database again.
+//!
+//! A description ([QueryData]) is stored under `target/sqlx/`, keyed by a hash of the SQL of the
+//! query (the content of the file for `query_file!()`), the database it was described by and
+//! the version of its schema. The version is read from the database once per macro, which is
+//! much cheaper than describing the query; if `SQLX_MACRO_CACHE_EPOCH` is set, its value is used
+//! instead and a cached description is used without connecting at all, so it must be changed
+//! whenever the schema is. The macro is always expanded from its input, so its errors point
+//! into it whether or not the description was cached.
+//!
+//! Set `SQLX_MACRO_CACHE=off` to always describe queries; `cargo clean` empties the cache.
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use quote::ToTokens;
+use sqlx::connection::Connect;
+use url::Url;
+
+use crate::database::DatabaseExt;
+use crate::query_macros::{QueryData, QueryMacroInput};
+
+pub struct MacroCache {
+    dir: PathBuf,
+    epoch: Option<String>,
+    // has seen everything in the key but the version of the schema
+    hasher: DefaultHasher,
+}
+
+impl MacroCache {
+    /// Returns `None` if the cache is turned off or there is no target directory to keep it in.
+    pub fn new(sql: &str, url: &Url) -> Option<Self> {
+        if dotenv::var("SQLX_MACRO_CACHE").map_or(false, |value| value.eq_ignore_ascii_case("off"))
+        {
+            return None;
+        }
+
+        let dir = target_dir()?.join("sqlx");
+
+        let mut hasher = DefaultHasher::new();
+
+        // the description of the same query changes with the version of the macros and the
+        // types they know about
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        cfg!(feature = "chrono").hash(&mut hasher);
+        cfg!(feature = "uuid").hash(&mut hasher);
+
+        sql.hash(&mut hasher);
+
+        // the credentials don't change what the database is
+        url.scheme().hash(&mut hasher);
+        url.host_str().hash(&mut hasher);
+        url.port().hash(&mut hasher);
+        url.path().hash(&mut hasher);
+
+        Some(Self {
+            dir,
+            epoch: dotenv::var("SQLX_MACRO_CACHE_EPOCH").ok(),
+            hasher,
+        })
+    }
+
+    /// The description cached for the epoch set by the user, if any; found without connecting
+    /// to the database.
+    pub fn load_for_epoch(&self) -> Option<QueryData> {
+        self.load(self.epoch.as_ref()?)
+    }
+
+    fn path(&self, schema_version: &str) -> PathBuf {
+        let mut hasher = self.hasher.clone();
+        schema_version.hash(&mut hasher);
+
+        self.dir.join(format!("{:016x}", hasher.finish()))
+    }
+
+    fn load(&self, schema_version: &str) -> Option<QueryData> {
+        syn::parse_str(&fs::read_to_string(self.path(schema_version)).ok()?).ok()
+    }
+
+    // Failing to store a description only means the query is described again next time
+    fn store(&self, schema_version: &str, data: &QueryData) {
+        let path = self.path(schema_version);
+
+        // several crates may be built at once; another one only ever sees a complete file
+        let tmp = path.with_extension(format!("{}.tmp", process::id()));
+
+        let stored = fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&tmp, data.to_token_stream().to_string()))
+            .and_then(|_| fs::rename(&tmp, &path));
+
+        if stored.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// Describes the query of `input` with a connection to `url`, unless `cache` has its
+/// description for the current schema of the database; a query that fails to be described is
+/// not cached.
+pub async fn describe<DB: DatabaseExt>(
+    cache: Option<MacroCache>,
+    url: &Url,
+    input: &QueryMacroInput,
+) -> crate::Result<QueryData> {
+    if let Some(data) = cache.as_ref().and_then(MacroCache::load_for_epoch) {
+        return Ok(data);
+    }
+
+    let mut conn = DB::Connection::connect(url.as_str())
+        .await
+        .map_err(|e| format!("failed to connect to database: {}", e))?;
+
+    let cache = match cache {
+        Some(cache) => cache,
+        None => return input.describe(&mut conn).await,
+    };
+
+    let schema_version = match &cache.epoch {
+        // not cached yet
+        Some(epoch) => epoch.clone(),
+
+        None => match DB::schema_version(&mut conn).await {
+            Ok(version) => version,
+
+            // for instance, if the user may not read the catalog
+            Err(_) => return input.describe(&mut conn).await,
+        },
+    };
+
+    if cache.epoch.is_none() {
+        if let Some(data) = cache.load(&schema_version) {
+            return Ok(data);
+        }
+    }
+
+    let data = input.describe(&mut conn).await?;
+
+    cache.store(&schema_version, &data);
+
+    Ok(data)
+}
+
+// `CARGO_TARGET_DIR`, or else the closest `target` directory above the crate; that is the one
+// of the workspace, if the crate is in one
+fn target_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("CARGO_TARGET_DIR") {
+        return Some(dir.into());
+    }
+
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR")?;
+
+    Path::new(&manifest_dir)
+        .ancestors()
+        .map(|dir| dir.join("target"))
+        .find(|dir| dir.is_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::fs;
+    use std::process;
+
+    use super::MacroCache;
+
+    fn cache(name: &str) -> MacroCache {
+        MacroCache {
+            dir: std::env::temp_dir().join(format!("sqlx-macros-{}-{}", name, process::id())),
+            epoch: None,
+            hasher: DefaultHasher::new(),
+        }
+    }
+
+    #[test]
+    fn it_loads_what_it_stored() {
+        let cache = cache("store");
+        let data =
+            syn::parse_str("param Ok \"i32\" column Some \"id\" Some true Ok \"i64\"").unwrap();
+
+        assert!(cache.load("1").is_none());
+
+        cache.store("1", &data);
+
+        assert_eq!(cache.load("1"), Some(data));
+
+        // a description is not used with another version of the schema
+        assert!(cache.load("2").is_none());
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn it_ignores_a_corrupt_file() {
+        let cache = cache("corrupt");
+
+        fs::create_dir_all(&cache.dir).unwrap();
+        fs::write(cache.path("1"), "param Ok").unwrap();
+
+        assert!(cache.load("1").is_none());
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+}
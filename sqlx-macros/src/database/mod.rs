@@ -1,4 +1,6 @@
+use futures::future::LocalBoxFuture;
 use sqlx::database::Database;
+use sqlx::describe::Column;
 
 #[derive(PartialEq, Eq)]
 #[allow(dead_code)]
@@ -25,7 +27,17 @@ pub trait DatabaseExt: Database {
 
     fn return_type_for_id(id: &Self::TypeInfo) -> Option<&'static str>;
 
+    /// The Rust type of a result column, which is that of its type unless the database knows
+    /// better from the column.
+    fn return_type_for_column(column: &Column<Self>) -> Option<&'static str> {
+        Self::return_type_for_id(&column.type_info)
+    }
+
     fn get_feature_gate(info: &Self::TypeInfo) -> Option<&'static str>;
+
+    /// A value that changes whenever the schema of the database does, to key the descriptions
+    /// kept by [crate::cache].
+    fn schema_version(conn: &mut Self::Connection) -> LocalBoxFuture<'_, sqlx::Result<String>>;
 }
 
 macro_rules! impl_database_ext {
@@ -35,7 +47,9 @@ macro_rules! impl_database_ext {
         },
         ParamChecking::$param_checking:ident,
         feature-types: $name:ident => $get_gate:expr,
-        row = $row:path
+        row = $row:path,
+        $(column-type: $column:ident => $column_type:expr,)?
+        schema-version = $schema_version:literal
     ) => {
         impl $crate::database::DatabaseExt for $database {
             const DATABASE_PATH: &'static str = stringify!($database);
@@ -47,7 +61,7 @@ macro_rules! impl_database_ext {
                     $(
                         // `if` statements cannot have attributes but these can
                         $(#[$meta])?
-                        _ if sqlx::types::TypeInfo::compatible(&<$ty as sqlx::types::Type<$database>>::type_info(), &info) => Some(input_ty!($ty $(, $input)?)),
+                        _ if sqlx::types::TypeInfo::compatible_strict(&<$ty as sqlx::types::Type<$database>>::type_info(), &info) => Some(input_ty!($ty $(, $input)?)),
                     )*
                     _ => None
                 }
@@ -57,15 +71,35 @@ macro_rules! impl_database_ext {
                 match () {
                     $(
                         $(#[$meta])?
-                        _ if sqlx::types::TypeInfo::compatible(&<$ty as sqlx::types::Type<$database>>::type_info(), &info) => return Some(stringify!($ty)),
+                        _ if sqlx::types::TypeInfo::compatible_strict(&<$ty as sqlx::types::Type<$database>>::type_info(), &info) => return Some(stringify!($ty)),
                     )*
                     _ => None
                 }
             }
 
+            $(
+                fn return_type_for_column(
+                    $column: &sqlx::describe::Column<Self>,
+                ) -> Option<&'static str> {
+                    $column_type
+                }
+            )?
+
             fn get_feature_gate($name: &Self::TypeInfo) -> Option<&'static str> {
                 $get_gate
             }
+
+            fn schema_version(
+                conn: &mut <Self as sqlx::database::Database>::Connection,
+            ) -> futures::future::LocalBoxFuture<'_, sqlx::Result<String>> {
+                use sqlx::row::Row as _;
+
+                Box::pin(
+                    sqlx::query::query($schema_version)
+                        .try_map(|row: $row| row.try_get::<String, _>(0))
+                        .fetch_one(conn),
+                )
+            }
         }
     }
 }
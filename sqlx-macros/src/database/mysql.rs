@@ -11,12 +11,21 @@ impl_database_ext! {
         f32,
         f64,
 
+        // YEAR
+        sqlx::mysql::MySqlYear,
+
+        // DECIMAL; only decoded into a float when asked for explicitly
+        sqlx::types::Lossy<f64>,
+
         // CHAR, VAR_CHAR, TEXT
         String,
 
         // BINARY, VAR_BINARY, BLOB
         Vec<u8>,
 
+        // GEOMETRY, POINT, LINESTRING, POLYGON, ...
+        sqlx::mysql::MySqlGeometry,
+
         #[cfg(feature = "chrono")]
         sqlx::types::chrono::NaiveTime,
 
@@ -31,5 +40,9 @@ impl_database_ext! {
     },
     ParamChecking::Weak,
     feature-types: info => info.type_feature_gate(),
-    row = sqlx::mysql::MySqlRow
+    row = sqlx::mysql::MySqlRow,
+    schema-version = "SELECT CONCAT_WS(',', COUNT(*), \
+        SUM(CRC32(CONCAT_WS(',', TABLE_NAME, COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, \
+        COLUMN_KEY, EXTRA)))) \
+        FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE()"
 }
@@ -8,6 +8,19 @@ impl_database_ext! {
         f32,
         f64,
 
+        // NUMERIC; only decoded into a float when asked for explicitly
+        sqlx::types::Lossy<f64>,
+
+        // TSVECTOR; only decoded, into its text form
+        sqlx::postgres::PgTsVector,
+
+        // TSQUERY
+        sqlx::postgres::PgTsQuery,
+
+        sqlx::postgres::PgLsn,
+
+        sqlx::postgres::PgTid,
+
         // BYTEA
         Vec<u8> | &[u8],
 
@@ -25,8 +38,29 @@ impl_database_ext! {
 
         #[cfg(feature = "chrono")]
         sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc> | sqlx::types::chrono::DateTime<_>,
+
+        // INT4RANGE, INT8RANGE, DATERANGE, TSRANGE, TSTZRANGE
+        sqlx::postgres::PgRange<i32>,
+        sqlx::postgres::PgRange<i64>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::PgRange<sqlx::types::chrono::NaiveDate>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::PgRange<sqlx::types::chrono::NaiveDateTime>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::PgRange<sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>>
+            | sqlx::postgres::PgRange<sqlx::types::chrono::DateTime<_>>,
     },
     ParamChecking::Strong,
     feature-types: info => info.type_feature_gate(),
-    row = sqlx::postgres::PgRow
+    row = sqlx::postgres::PgRow,
+    // a change to a table, a column or a type writes a row to the catalog with a new `xmin`;
+    // dropping a table only deletes rows
+    schema-version = "SELECT concat_ws(',', \
+        (SELECT count(*) FROM pg_catalog.pg_class), \
+        (SELECT max(xmin::text::int8) FROM pg_catalog.pg_class), \
+        (SELECT max(xmin::text::int8) FROM pg_catalog.pg_attribute), \
+        (SELECT max(xmin::text::int8) FROM pg_catalog.pg_type))"
 }
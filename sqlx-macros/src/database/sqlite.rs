@@ -9,5 +9,13 @@ impl_database_ext! {
     },
     ParamChecking::Weak,
     feature-types: _info => None,
-    row = sqlx::sqlite::SqliteRow
+    row = sqlx::sqlite::SqliteRow,
+    // `INTEGER PRIMARY KEY` is an alias of the rowid, which is a 64-bit integer
+    column-type: column => if column.flags.contains(sqlx::describe::ColumnFlags::AUTO_INCREMENT) {
+        Some("i64")
+    } else {
+        Self::return_type_for_id(&column.type_info)
+    },
+    // `pragma_schema_version()` is missing from older builds of SQLite; the schema itself is small
+    schema-version = "SELECT COALESCE(group_concat(sql, ';'), '') FROM sqlite_master"
 }
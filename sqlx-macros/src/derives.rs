@@ -1,5 +1,8 @@
 use quote::quote;
-use syn::{parse_quote, Data, DataStruct, DeriveInput, Fields, FieldsUnnamed};
+use syn::{
+    parse_quote, Attribute, Data, DataEnum, DataStruct, DeriveInput, Fields, FieldsNamed,
+    FieldsUnnamed, Ident, Lit, Meta, NestedMeta,
+};
 
 pub(crate) fn expand_derive_encode(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     match &input.data {
@@ -31,6 +34,9 @@ pub(crate) fn expand_derive_encode(input: DeriveInput) -> syn::Result<proc_macro
                     fn encode_nullable(&self, buf: &mut <DB as sqlx::Database>::RawBuffer) -> sqlx::encode::IsNull {
                         sqlx::encode::Encode::encode_nullable(&self.0, buf)
                     }
+                    fn try_encode(&self, buf: &mut <DB as sqlx::Database>::RawBuffer) -> sqlx::Result<sqlx::encode::IsNull> {
+                        sqlx::encode::Encode::try_encode(&self.0, buf)
+                    }
                     fn size_hint(&self) -> usize {
                         sqlx::encode::Encode::size_hint(&self.0)
                     }
@@ -71,7 +77,7 @@ pub(crate) fn expand_derive_decode(input: DeriveInput) -> syn::Result<proc_macro
 
                 impls.push(quote!(
                     impl #impl_generics sqlx::decode::Decode<'de, sqlx::Postgres> for #ident #ty_generics #where_clause {
-                        fn decode(value: <sqlx::Postgres as sqlx::HasRawValue<'de>>::RawValue) -> sqlx::Result<Self> {
+                        fn decode(value: <sqlx::Postgres as sqlx::database::HasRawValue<'de>>::RawValue) -> sqlx::Result<Self> {
                             <#ty as sqlx::decode::Decode<'de, sqlx::Postgres>>::decode(value).map(Self)
                         }
                     }
@@ -90,7 +96,7 @@ pub(crate) fn expand_derive_decode(input: DeriveInput) -> syn::Result<proc_macro
 
                 impls.push(quote!(
                     impl #impl_generics sqlx::decode::Decode<'de, sqlx::MySql> for #ident #ty_generics #where_clause {
-                        fn decode(value: <sqlx::MySql as sqlx::HasRawValue<'de>>::RawValue) -> sqlx::Result<Self> {
+                        fn decode(value: <sqlx::MySql as sqlx::database::HasRawValue<'de>>::RawValue) -> sqlx::Result<Self> {
                             <#ty as sqlx::decode::Decode<'de, sqlx::MySql>>::decode(value).map(Self)
                         }
                     }
@@ -106,3 +112,496 @@ pub(crate) fn expand_derive_decode(input: DeriveInput) -> syn::Result<proc_macro
         )),
     }
 }
+
+pub(crate) fn expand_derive_type(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let variants = match &input.data {
+        Data::Enum(DataEnum { variants, .. }) => variants,
+        _ => return Err(syn::Error::new_spanned(input, "expected a fieldless enum")),
+    };
+
+    check_sqlx_attrs(&input.attrs, &["rename_all"])?;
+
+    for variant in variants {
+        check_sqlx_attrs(&variant.attrs, &["rename"])?;
+
+        match variant.fields {
+            Fields::Unit => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "expected a fieldless enum",
+                ))
+            }
+        }
+    }
+
+    let ident = &input.ident;
+    let rename_all = parse_rename_all(&input.attrs)?;
+
+    match parse_repr(&input.attrs) {
+        // the integer repr is only used for storage if no names were asked for
+        Some(repr) if rename_all.is_none() => {
+            let idents: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+
+            Ok(quote!(
+                impl<DB> sqlx::types::Type<DB> for #ident
+                where
+                    DB: sqlx::Database,
+                    #repr: sqlx::types::Type<DB>,
+                {
+                    fn type_info() -> DB::TypeInfo {
+                        <#repr as sqlx::types::Type<DB>>::type_info()
+                    }
+                }
+
+                impl<DB> sqlx::encode::Encode<DB> for #ident
+                where
+                    DB: sqlx::Database,
+                    #repr: sqlx::encode::Encode<DB>,
+                {
+                    fn encode(&self, buf: &mut DB::RawBuffer) {
+                        let value = match self {
+                            #(#ident::#idents => #ident::#idents as #repr,)*
+                        };
+
+                        <#repr as sqlx::encode::Encode<DB>>::encode(&value, buf)
+                    }
+
+                    fn size_hint(&self) -> usize {
+                        std::mem::size_of::<#repr>()
+                    }
+                }
+
+                impl<'de, DB> sqlx::decode::Decode<'de, DB> for #ident
+                where
+                    DB: sqlx::Database,
+                    #repr: sqlx::decode::Decode<'de, DB>,
+                {
+                    fn decode(value: <DB as sqlx::database::HasRawValue<'de>>::RawValue) -> sqlx::Result<Self> {
+                        match <#repr as sqlx::decode::Decode<'de, DB>>::decode(value)? {
+                            #(value if value == #ident::#idents as #repr => Ok(#ident::#idents),)*
+
+                            value => Err(sqlx::Error::Decode(
+                                format!("unexpected value {} for enum {}", value, stringify!(#ident)).into(),
+                            )),
+                        }
+                    }
+                }
+            ))
+        }
+
+        _ => {
+            let mut idents = Vec::new();
+            let mut names = Vec::new();
+
+            for variant in variants {
+                idents.push(&variant.ident);
+                names.push(match parse_rename(&variant.attrs)? {
+                    Some(name) => name,
+                    None => rename(&variant.ident.to_string(), rename_all.as_deref()),
+                });
+            }
+
+            Ok(quote!(
+                impl<DB> sqlx::types::Type<DB> for #ident
+                where
+                    DB: sqlx::Database,
+                    str: sqlx::types::Type<DB>,
+                {
+                    fn type_info() -> DB::TypeInfo {
+                        <str as sqlx::types::Type<DB>>::type_info()
+                    }
+                }
+
+                impl<DB> sqlx::encode::Encode<DB> for #ident
+                where
+                    DB: sqlx::Database,
+                    str: sqlx::encode::Encode<DB>,
+                {
+                    fn encode(&self, buf: &mut DB::RawBuffer) {
+                        let value = match self {
+                            #(#ident::#idents => #names,)*
+                        };
+
+                        <str as sqlx::encode::Encode<DB>>::encode(value, buf)
+                    }
+
+                    fn size_hint(&self) -> usize {
+                        match self {
+                            #(#ident::#idents => #names.len(),)*
+                        }
+                    }
+                }
+
+                impl<'de, DB> sqlx::decode::Decode<'de, DB> for #ident
+                where
+                    DB: sqlx::Database,
+                    &'de str: sqlx::decode::Decode<'de, DB>,
+                {
+                    fn decode(value: <DB as sqlx::database::HasRawValue<'de>>::RawValue) -> sqlx::Result<Self> {
+                        match <&'de str as sqlx::decode::Decode<'de, DB>>::decode(value)? {
+                            #(#names => Ok(#ident::#idents),)*
+
+                            value => Err(sqlx::Error::Decode(
+                                format!("unexpected value {:?} for enum {}", value, stringify!(#ident)).into(),
+                            )),
+                        }
+                    }
+                }
+            ))
+        }
+    }
+}
+
+pub(crate) fn expand_derive_from_row(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(FieldsNamed { named, .. }),
+            ..
+        }) => named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "expected a struct with named fields",
+            ))
+        }
+    };
+
+    // the impl is generic over the row, which would have to be merged with the generics of the
+    // struct
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "a generic struct is not supported",
+        ));
+    }
+
+    check_sqlx_attrs(&input.attrs, &[])?;
+
+    let ident = &input.ident;
+
+    let mut predicates = Vec::new();
+    let mut values = Vec::new();
+    let mut any_json = false;
+
+    for field in fields {
+        let name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+
+        check_sqlx_attrs(&field.attrs, &["rename", "json"])?;
+
+        let column = match parse_rename(&field.attrs)? {
+            Some(column) => column,
+            None => name.to_string(),
+        };
+
+        if parse_sqlx_flag(&field.attrs, "json")? {
+            any_json = true;
+
+            let field = format!("{}.{}", ident, name);
+
+            values.push(quote!(
+                #name: row.try_get_json::<#ty, _>(#column).map_err(|err| match err {
+                    sqlx::Error::Decode(err) => sqlx::Error::Decode(Box::new(
+                        sqlx::error::FieldDecodeError::new(#field, err),
+                    )),
+                    err => err,
+                })?
+            ));
+        } else {
+            predicates.push(quote!(
+                #ty: sqlx::types::Type<R::Database> + sqlx::decode::DecodeOwned<R::Database>
+            ));
+
+            values.push(quote!(#name: row.try_get::<#ty, _>(#column)?));
+        }
+    }
+
+    if any_json {
+        // the bound of `Row::try_get_json`
+        predicates.push(quote!(
+            for<'r> Option<sqlx::types::JsonText<'r>>: sqlx::decode::Decode<'r, R::Database>
+        ));
+    }
+
+    Ok(quote!(
+        impl<'c, R> sqlx::row::FromRow<'c, R> for #ident
+        where
+            R: sqlx::row::Row<'c>,
+            &'static str: sqlx::row::ColumnIndex<R::Database>,
+            #(#predicates,)*
+        {
+            fn from_row(row: R) -> sqlx::Result<Self> {
+                use sqlx::row::Row as _;
+
+                Ok(#ident {
+                    #(#values,)*
+                })
+            }
+        }
+    ))
+}
+
+// Returns the integer type named by `#[repr(..)]`, if any
+fn parse_repr(attrs: &[Attribute]) -> Option<Ident> {
+    const INTEGERS: &[&str] = &["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"];
+
+    for attr in attrs {
+        if let Ok(Meta::List(list)) = attr.parse_meta() {
+            if !list.path.is_ident("repr") {
+                continue;
+            }
+
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    if let Some(ident) = path.get_ident() {
+                        if INTEGERS.iter().any(|ty| ident == ty) {
+                            return Some(ident.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// Fails on anything in `#[sqlx(..)]` other than the `allowed` keys, which are those that mean
+// something where the attribute is given
+fn check_sqlx_attrs(attrs: &[Attribute], allowed: &[&str]) -> syn::Result<()> {
+    for attr in attrs {
+        if !attr.path.is_ident("sqlx") {
+            continue;
+        }
+
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected #[sqlx(..)]")),
+        };
+
+        for nested in list.nested {
+            let path = match &nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) => &pair.path,
+                NestedMeta::Meta(Meta::Path(path)) => path,
+                _ => return Err(syn::Error::new_spanned(&nested, "unknown attribute")),
+            };
+
+            if !allowed.iter().any(|key| path.is_ident(key)) {
+                let message = if allowed.is_empty() {
+                    "unknown attribute; expected none here".to_owned()
+                } else {
+                    format!("unknown attribute; expected one of {:?} here", allowed)
+                };
+
+                return Err(syn::Error::new_spanned(nested, message));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Returns the value of `#[sqlx(<key> = "..")]`, if any
+fn parse_sqlx_str(attrs: &[Attribute], key: &str) -> syn::Result<Option<String>> {
+    for attr in attrs {
+        if !attr.path.is_ident("sqlx") {
+            continue;
+        }
+
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::NameValue(pair)) = nested {
+                    if pair.path.is_ident(key) {
+                        return match pair.lit {
+                            Lit::Str(value) => Ok(Some(value.value())),
+                            lit => Err(syn::Error::new_spanned(lit, "expected a string")),
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// Returns whether `#[sqlx(<key>)]` is given
+fn parse_sqlx_flag(attrs: &[Attribute], key: &str) -> syn::Result<bool> {
+    for attr in attrs {
+        if !attr.path.is_ident("sqlx") {
+            continue;
+        }
+
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    if path.is_ident(key) {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+fn parse_rename_all(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let rename_all = parse_sqlx_str(attrs, "rename_all")?;
+
+    if let Some(case) = &rename_all {
+        const CASES: &[&str] = &[
+            "lowercase",
+            "UPPERCASE",
+            "snake_case",
+            "SCREAMING_SNAKE_CASE",
+            "kebab-case",
+            "camelCase",
+            "PascalCase",
+        ];
+
+        if !CASES.contains(&&**case) {
+            let attr = attrs.iter().find(|attr| attr.path.is_ident("sqlx"));
+
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("unknown case {:?}; expected one of {:?}", case, CASES),
+            ));
+        }
+    }
+
+    Ok(rename_all)
+}
+
+fn parse_rename(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    parse_sqlx_str(attrs, "rename")
+}
+
+// Converts the name of a (PascalCase) variant to the given case
+fn rename(name: &str, case: Option<&str>) -> String {
+    let words = || {
+        let mut words: Vec<String> = Vec::new();
+
+        for c in name.chars() {
+            match words.last_mut() {
+                Some(word) if !c.is_uppercase() => word.push(c),
+                _ => words.push(c.to_string()),
+            }
+        }
+
+        words
+    };
+
+    let join = |sep: &str, upper: bool| {
+        words()
+            .iter()
+            .map(|word| {
+                if upper {
+                    word.to_uppercase()
+                } else {
+                    word.to_lowercase()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(sep)
+    };
+
+    match case {
+        Some("lowercase") => name.to_lowercase(),
+        Some("UPPERCASE") => name.to_uppercase(),
+        Some("snake_case") => join("_", false),
+        Some("SCREAMING_SNAKE_CASE") => join("_", true),
+        Some("kebab-case") => join("-", false),
+        Some("camelCase") => {
+            let mut chars = name.chars();
+
+            chars
+                .next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        // for the variants that are not PascalCase already, e.g. `dark_green`
+        Some("PascalCase") => name
+            .split('_')
+            .flat_map(|word| {
+                let mut chars = word.chars();
+
+                chars
+                    .next()
+                    .into_iter()
+                    .flat_map(char::to_uppercase)
+                    .chain(chars)
+            })
+            .collect(),
+        None => name.to_owned(),
+        Some(case) => unreachable!("unknown case {:?}", case),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::{expand_derive_from_row, expand_derive_type};
+
+    #[test]
+    fn it_accepts_attributes_where_they_mean_something() {
+        assert!(expand_derive_type(parse_quote!(
+            #[sqlx(rename_all = "lowercase")]
+            enum Color {
+                #[sqlx(rename = "grey")]
+                Gray,
+            }
+        ))
+        .is_ok());
+
+        assert!(expand_derive_from_row(parse_quote!(
+            struct User {
+                #[sqlx(json, rename = "previous")]
+                previous_prefs: Option<Prefs>,
+            }
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn it_rejects_attributes_where_they_do_not() {
+        let error = expand_derive_type(parse_quote!(
+            #[sqlx(json)]
+            enum Color {
+                Red,
+            }
+        ))
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown attribute; expected one of [\"rename_all\"] here"
+        );
+
+        assert!(expand_derive_type(parse_quote!(
+            enum Color {
+                #[sqlx(json)]
+                Red,
+            }
+        ))
+        .is_err());
+
+        assert!(expand_derive_from_row(parse_quote!(
+            #[sqlx(rename_all = "lowercase")]
+            struct User {
+                name: String,
+            }
+        ))
+        .is_err());
+
+        assert!(expand_derive_from_row(parse_quote!(
+            struct User {
+                #[sqlx(rename_all = "lowercase")]
+                name: String,
+            }
+        ))
+        .is_err());
+    }
+}
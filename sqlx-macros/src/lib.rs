@@ -17,6 +17,8 @@ type Error = Box<dyn std::error::Error>;
 
 type Result<T> = std::result::Result<T, Error>;
 
+mod cache;
+
 mod database;
 
 mod derives;
@@ -52,25 +54,32 @@ fn macro_result(tokens: proc_macro2::TokenStream) -> TokenStream {
 }
 
 macro_rules! async_macro (
-    ($db:ident, $input:ident: $ty:ty => $expr:expr) => {{
+    ($input:ident: $ty:ty => $expand:ident) => {
+        async_macro!($input: $ty => $expand, expand_file = false)
+    };
+    ($input:ident: $ty:ty => $expand:ident, expand_file = $expand_file:literal) => {{
         let $input = match syn::parse::<$ty>($input) {
             Ok(input) => input,
             Err(e) => return macro_result(e.to_compile_error()),
         };
 
         let res: Result<proc_macro2::TokenStream> = block_on(async {
-            use sqlx::connection::Connect;
-
             let db_url = Url::parse(&dotenv::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?)?;
 
+            let $input = if $expand_file {
+                $input.expand_file_src().await?
+            } else {
+                $input
+            };
+
+            let cache = cache::MacroCache::new($input.sql(), &db_url);
+
             match db_url.scheme() {
                 #[cfg(feature = "sqlite")]
                 "sqlite" => {
-                    let $db = sqlx::sqlite::SqliteConnection::connect(db_url.as_str())
-                        .await
-                        .map_err(|e| format!("failed to connect to database: {}", e))?;
+                    let data = cache::describe::<sqlx::sqlite::Sqlite>(cache, &db_url, $input.as_ref()).await?;
 
-                    $expr.await
+                    $expand::<sqlx::sqlite::Sqlite>($input, data)
                 }
                 #[cfg(not(feature = "sqlite"))]
                 "sqlite" => Err(format!(
@@ -80,11 +89,9 @@ macro_rules! async_macro (
                 ).into()),
                 #[cfg(feature = "postgres")]
                 "postgresql" | "postgres" => {
-                    let $db = sqlx::postgres::PgConnection::connect(db_url.as_str())
-                        .await
-                        .map_err(|e| format!("failed to connect to database: {}", e))?;
+                    let data = cache::describe::<sqlx::postgres::Postgres>(cache, &db_url, $input.as_ref()).await?;
 
-                    $expr.await
+                    $expand::<sqlx::postgres::Postgres>($input, data)
                 }
                 #[cfg(not(feature = "postgres"))]
                 "postgresql" | "postgres" => Err(format!(
@@ -94,11 +101,9 @@ macro_rules! async_macro (
                 ).into()),
                 #[cfg(feature = "mysql")]
                 "mysql" | "mariadb" => {
-                    let $db = sqlx::mysql::MySqlConnection::connect(db_url.as_str())
-                            .await
-                            .map_err(|e| format!("failed to connect to database: {}", e))?;
+                    let data = cache::describe::<sqlx::mysql::MySql>(cache, &db_url, $input.as_ref()).await?;
 
-                    $expr.await
+                    $expand::<sqlx::mysql::MySql>($input, data)
                 }
                 #[cfg(not(feature = "mysql"))]
                 "mysql" | "mariadb" => Err(format!(
@@ -128,27 +133,27 @@ macro_rules! async_macro (
 #[allow(unused_variables)]
 pub fn query(input: TokenStream) -> TokenStream {
     #[allow(unused_variables)]
-    async_macro!(db, input: QueryMacroInput => expand_query(input, db))
+    async_macro!(input: QueryMacroInput => expand_query)
 }
 
 #[proc_macro]
 #[allow(unused_variables)]
 pub fn query_file(input: TokenStream) -> TokenStream {
     #[allow(unused_variables)]
-    async_macro!(db, input: QueryMacroInput => expand_query_file(input, db))
+    async_macro!(input: QueryMacroInput => expand_query, expand_file = true)
 }
 
 #[proc_macro]
 #[allow(unused_variables)]
 pub fn query_as(input: TokenStream) -> TokenStream {
     #[allow(unused_variables)]
-    async_macro!(db, input: QueryAsMacroInput => expand_query_as(input, db))
+    async_macro!(input: QueryAsMacroInput => expand_query_as)
 }
 
 #[proc_macro]
 #[allow(unused_variables)]
 pub fn query_file_as(input: TokenStream) -> TokenStream {
-    async_macro!(db, input: QueryAsMacroInput => expand_query_file_as(input, db))
+    async_macro!(input: QueryAsMacroInput => expand_query_as, expand_file = true)
 }
 
 #[proc_macro_derive(Encode)]
@@ -160,6 +165,35 @@ pub fn derive_encode(tokenstream: TokenStream) -> TokenStream {
     }
 }
 
+/// Implements `Type`, `Encode` and `Decode` for a fieldless enum on every database.
+///
+/// An enum with an integer `#[repr(..)]` is stored as its discriminant. Otherwise, or if
+/// `#[sqlx(rename_all = "..")]` is given, it is stored as the name of the variant, which may be
+/// changed with `#[sqlx(rename = "..")]`. Decoding a value that matches no variant fails.
+#[proc_macro_derive(Type, attributes(sqlx))]
+pub fn derive_type(tokenstream: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(tokenstream as syn::DeriveInput);
+    match derives::expand_derive_type(input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Implements `FromRow` for a struct with named fields, reading each field from the column of
+/// the same name (or the one given with `#[sqlx(rename = "..")]`) on every database.
+///
+/// A field marked `#[sqlx(json)]` is deserialized from the JSON document in its column with
+/// `Row::try_get_json`, which needs the `json` feature; an `Option` field reads a `NULL` as
+/// `None`. A document that does not match the field fails with a `FieldDecodeError` naming it.
+#[proc_macro_derive(FromRow, attributes(sqlx))]
+pub fn derive_from_row(tokenstream: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(tokenstream as syn::DeriveInput);
+    match derives::expand_derive_from_row(input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[proc_macro_derive(Decode)]
 pub fn derive_decode(tokenstream: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(tokenstream as syn::DeriveInput);
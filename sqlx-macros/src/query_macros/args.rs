@@ -3,16 +3,15 @@ use syn::spanned::Spanned;
 use syn::Expr;
 
 use quote::{quote, quote_spanned, ToTokens};
-use sqlx::describe::Describe;
 
 use crate::database::{DatabaseExt, ParamChecking};
-use crate::query_macros::QueryMacroInput;
+use crate::query_macros::{QueryData, QueryMacroInput};
 
 /// Returns a tokenstream which typechecks the arguments passed to the macro
 /// and binds them to `DB::Arguments` with the ident `query_args`.
 pub fn quote_args<DB: DatabaseExt>(
     input: &QueryMacroInput,
-    describe: &Describe<DB>,
+    data: &QueryData,
 ) -> crate::Result<TokenStream> {
     let db_path = DB::db_path();
 
@@ -25,32 +24,14 @@ pub fn quote_args<DB: DatabaseExt>(
     let arg_name = &input.arg_names;
 
     let args_check = if DB::PARAM_CHECKING == ParamChecking::Strong {
-        describe
-            .param_types
+        data.params
             .iter()
             .zip(input.arg_names.iter().zip(&input.arg_exprs))
-            .enumerate()
-            .map(|(i, (param_ty, (name, expr)))| -> crate::Result<_>{
-                let param_ty = get_type_override(expr)
-                    .or_else(|| {
-                        Some(
-                            DB::param_type_for_id(param_ty)?
-                                .parse::<proc_macro2::TokenStream>()
-                                .unwrap(),
-                        )
-                    })
-                    .ok_or_else(|| {
-                        if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(&param_ty) {
-                            format!(
-                                "optional feature `{}` required for type {} of param #{}",
-                                feature_gate,
-                                param_ty,
-                                i + 1,
-                            )
-                        } else {
-                            format!("unsupported type {} for param #{}", param_ty, i + 1)
-                        }
-                    })?;
+            .map(|(param_ty, (name, expr))| -> crate::Result<_> {
+                let param_ty = match get_type_override(expr) {
+                    Some(param_ty) => param_ty,
+                    None => param_ty.clone()?.parse::<TokenStream>().unwrap(),
+                };
 
                 Ok(quote_spanned!(expr.span() =>
                     // this shouldn't actually run
@@ -74,7 +55,7 @@ pub fn quote_args<DB: DatabaseExt>(
             })
             .collect::<crate::Result<TokenStream>>()?
     } else {
-        // all we can do is check arity which we did in `QueryMacroInput::describe_validate()`
+        // all we can do is check arity which we did in `QueryMacroInput::validate()`
         TokenStream::new()
     };
 
@@ -94,6 +75,25 @@ pub fn quote_args<DB: DatabaseExt>(
     })
 }
 
+/// The Rust type of the parameter at `i` with the SQL type `param_ty`, or the error to report if
+/// an argument is bound to it without a type of its own.
+pub fn param_type<DB: DatabaseExt>(i: usize, param_ty: &DB::TypeInfo) -> Result<String, String> {
+    DB::param_type_for_id(param_ty)
+        .map(str::to_owned)
+        .ok_or_else(|| {
+            if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(param_ty) {
+                format!(
+                    "optional feature `{}` required for type {} of param #{}",
+                    feature_gate,
+                    param_ty,
+                    i + 1,
+                )
+            } else {
+                format!("unsupported type {} for param #{}", param_ty, i + 1)
+            }
+        })
+}
+
 fn get_type_override(expr: &Expr) -> Option<TokenStream> {
     match expr {
         Expr::Cast(cast) => Some(cast.ty.to_token_stream()),
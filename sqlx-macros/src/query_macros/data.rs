@@ -0,0 +1,181 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitBool, LitStr};
+
+use sqlx::describe::Describe;
+
+use super::{args, output};
+use crate::database::DatabaseExt;
+
+/// What the query macros use of the [`Describe`] of a query: the Rust types of its parameters
+/// and result columns, or the errors to report for them if they are used.
+///
+/// Unlike the expansion, it does not depend on the tokens of the macro input, so it can be kept
+/// in [crate::cache] and expanded again with the spans of a later input.
+#[derive(Debug, PartialEq)]
+pub struct QueryData {
+    pub(super) params: Vec<Result<String, String>>,
+    pub(super) columns: Vec<ColumnData>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ColumnData {
+    pub(super) name: Option<String>,
+    pub(super) non_null: Option<bool>,
+    pub(super) type_: Result<String, String>,
+}
+
+impl QueryData {
+    pub fn from_describe<DB: DatabaseExt>(describe: &Describe<DB>) -> Self {
+        Self {
+            params: describe
+                .param_types
+                .iter()
+                .enumerate()
+                .map(|(i, param_ty)| args::param_type::<DB>(i, param_ty))
+                .collect(),
+
+            columns: describe
+                .result_columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| ColumnData {
+                    name: column.name.as_deref().map(str::to_owned),
+                    non_null: column.non_null,
+                    type_: output::return_type::<DB>(i, column),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Written to the cache as `param Ok "i32" column Some "id" None Ok "i64" ..`
+
+impl ToTokens for QueryData {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for param in &self.params {
+            let param = quote_result(param);
+
+            tokens.extend(quote!(param #param));
+        }
+
+        for column in &self.columns {
+            let name = match &column.name {
+                Some(name) => quote!(Some #name),
+                None => quote!(None),
+            };
+
+            let non_null = match column.non_null {
+                Some(non_null) => quote!(Some #non_null),
+                None => quote!(None),
+            };
+
+            let type_ = quote_result(&column.type_);
+
+            tokens.extend(quote!(column #name #non_null #type_));
+        }
+    }
+}
+
+impl Parse for QueryData {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut params = Vec::new();
+        let mut columns = Vec::new();
+
+        while !input.is_empty() {
+            let item: Ident = input.parse()?;
+
+            if item == "param" {
+                params.push(parse_result(input)?);
+            } else if item == "column" {
+                columns.push(ColumnData {
+                    name: parse_option::<LitStr>(input)?.map(|name| name.value()),
+                    non_null: parse_option::<LitBool>(input)?.map(|non_null| non_null.value),
+                    type_: parse_result(input)?,
+                });
+            } else {
+                return Err(syn::Error::new(item.span(), "expected `param` or `column`"));
+            }
+        }
+
+        Ok(Self { params, columns })
+    }
+}
+
+fn quote_result(result: &Result<String, String>) -> TokenStream {
+    match result {
+        Ok(ty) => quote!(Ok #ty),
+        Err(error) => quote!(Err #error),
+    }
+}
+
+fn parse_result(input: ParseStream) -> syn::Result<Result<String, String>> {
+    let variant: Ident = input.parse()?;
+    let value: LitStr = input.parse()?;
+
+    if variant == "Ok" {
+        Ok(Ok(value.value()))
+    } else if variant == "Err" {
+        Ok(Err(value.value()))
+    } else {
+        Err(syn::Error::new(variant.span(), "expected `Ok` or `Err`"))
+    }
+}
+
+fn parse_option<T: Parse>(input: ParseStream) -> syn::Result<Option<T>> {
+    let variant: Ident = input.parse()?;
+
+    if variant == "Some" {
+        Ok(Some(input.parse()?))
+    } else if variant == "None" {
+        Ok(None)
+    } else {
+        Err(syn::Error::new(variant.span(), "expected `Some` or `None`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+
+    use super::{ColumnData, QueryData};
+
+    #[test]
+    fn it_round_trips_through_a_string() {
+        let data = QueryData {
+            params: vec![
+                Ok("i32".into()),
+                Err("unsupported type CIRCLE for param #2".into()),
+            ],
+            columns: vec![
+                ColumnData {
+                    name: Some("id".into()),
+                    non_null: Some(true),
+                    type_: Ok("i64".into()),
+                },
+                ColumnData {
+                    name: Some("name: \"quoted\"".into()),
+                    non_null: Some(false),
+                    type_: Ok("String".into()),
+                },
+                ColumnData {
+                    name: None,
+                    non_null: None,
+                    type_: Err("unsupported type CIRCLE of column #3".into()),
+                },
+            ],
+        };
+
+        let string = data.to_token_stream().to_string();
+
+        assert_eq!(syn::parse_str::<QueryData>(&string).unwrap(), data);
+    }
+
+    #[test]
+    fn it_rejects_what_it_did_not_write() {
+        assert!(syn::parse_str::<QueryData>("param Some \"i32\"").is_err());
+        assert!(syn::parse_str::<QueryData>("column None None").is_err());
+        assert!(syn::parse_str::<QueryData>("macro_rules! macro_result {}").is_err());
+    }
+}
@@ -6,13 +6,15 @@ use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Group;
-use syn::{Expr, ExprLit, ExprPath, Lit};
+use syn::{Attribute, Expr, ExprLit, ExprPath, Lit, Path};
 use syn::{ExprGroup, Token};
 
 use sqlx::connection::Connection;
-use sqlx::describe::Describe;
 use sqlx::runtime::fs;
 
+use super::QueryData;
+use crate::database::DatabaseExt;
+
 /// Macro input shared by `query!()` and `query_file!()`
 pub struct QueryMacroInput {
     pub(super) source: String,
@@ -20,6 +22,8 @@ pub struct QueryMacroInput {
     // `arg0 .. argN` for N arguments
     pub(super) arg_names: Vec<Ident>,
     pub(super) arg_exprs: Vec<Expr>,
+    // Traits from `#[derive(..)]` before the SQL, for the record type of `query!()`
+    pub(super) record_derives: Vec<Path>,
 }
 
 impl QueryMacroInput {
@@ -66,6 +70,7 @@ impl QueryMacroInput {
             source_span,
             arg_exprs,
             arg_names,
+            record_derives: Vec::new(),
         })
     }
 
@@ -75,39 +80,74 @@ impl QueryMacroInput {
         Ok(Self { source, ..self })
     }
 
-    /// Run a parse/describe on the query described by this input and validate that it matches the
-    /// passed number of args
-    pub async fn describe_validate<C: Connection>(
-        &self,
-        conn: &mut C,
-    ) -> crate::Result<Describe<C::Database>> {
+    /// The SQL of the query; the path of the file before [expand_file_src] for `query_file!()`.
+    pub fn sql(&self) -> &str {
+        &self.source
+    }
+
+    /// Run a parse/describe on the query described by this input
+    pub async fn describe<C: Connection>(&self, conn: &mut C) -> crate::Result<QueryData>
+    where
+        C::Database: DatabaseExt,
+    {
         let describe = conn
             .describe(&*self.source)
             .await
             .map_err(|e| syn::Error::new(self.source_span, e))?;
 
-        if self.arg_names.len() != describe.param_types.len() {
+        Ok(QueryData::from_describe(&describe))
+    }
+
+    /// Validate that the query takes the passed number of args
+    pub fn validate(&self, data: &QueryData) -> crate::Result<()> {
+        if self.arg_names.len() != data.params.len() {
             return Err(syn::Error::new(
                 Span::call_site(),
                 format!(
                     "expected {} parameters, got {}",
-                    describe.param_types.len(),
+                    data.params.len(),
                     self.arg_names.len()
                 ),
             )
             .into());
         }
 
-        Ok(describe)
+        Ok(())
+    }
+}
+
+impl AsRef<QueryMacroInput> for QueryMacroInput {
+    fn as_ref(&self) -> &QueryMacroInput {
+        self
     }
 }
 
 impl Parse for QueryMacroInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let record_derives = record_derives(&input.call(Attribute::parse_outer)?)?;
+
         let args = Punctuated::<Expr, Token![,]>::parse_terminated(input)?.into_iter();
 
-        Self::from_exprs(input, args)
+        Ok(Self {
+            record_derives,
+            ..Self::from_exprs(input, args)?
+        })
+    }
+}
+
+// The traits of the `#[derive(..)]` attributes before the SQL
+fn record_derives(attrs: &[Attribute]) -> syn::Result<Vec<Path>> {
+    let mut derives = Vec::new();
+
+    for attr in attrs {
+        if !attr.path.is_ident("derive") {
+            return Err(syn::Error::new_spanned(attr, "expected #[derive(..)]"));
+        }
+
+        derives.extend(attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?);
     }
+
+    Ok(derives)
 }
 
 /// Macro input shared by `query_as!()` and `query_file_as!()`
@@ -123,6 +163,16 @@ impl QueryAsMacroInput {
             ..self
         })
     }
+
+    pub fn sql(&self) -> &str {
+        self.query_input.sql()
+    }
+}
+
+impl AsRef<QueryMacroInput> for QueryAsMacroInput {
+    fn as_ref(&self) -> &QueryMacroInput {
+        &self.query_input
+    }
 }
 
 impl Parse for QueryAsMacroInput {
@@ -211,3 +261,26 @@ async fn read_file_src(source: &str, source_span: Span) -> syn::Result<String> {
         )
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::QueryMacroInput;
+
+    #[test]
+    fn it_takes_derives_from_an_attribute_only() {
+        let input: QueryMacroInput =
+            syn::parse_str(r#"#[derive(Clone, serde::Serialize)] "SELECT ?", 1"#).unwrap();
+
+        assert_eq!(input.record_derives.len(), 2);
+        assert!(input.record_derives[0].is_ident("Clone"));
+        assert_eq!(input.arg_exprs.len(), 1);
+
+        // a call to a function named `derive` is an argument like any other
+        let input: QueryMacroInput = syn::parse_str(r#""SELECT ?", derive(Clone)"#).unwrap();
+
+        assert!(input.record_derives.is_empty());
+        assert_eq!(input.arg_exprs.len(), 1);
+
+        assert!(syn::parse_str::<QueryMacroInput>(r#"#[serde(rename = "x")] "SELECT 1""#).is_err());
+    }
+}
@@ -1,43 +1,25 @@
-use std::fmt::Display;
-
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
+pub use data::QueryData;
 pub use input::{QueryAsMacroInput, QueryMacroInput};
 pub use query::expand_query;
 
 use crate::database::DatabaseExt;
 
-use sqlx::connection::Connection;
-use sqlx::database::Database;
-
 mod args;
+mod data;
 mod input;
 mod output;
 mod query;
 
-pub async fn expand_query_file<C: Connection>(
-    input: QueryMacroInput,
-    conn: C,
-) -> crate::Result<TokenStream>
-where
-    C::Database: DatabaseExt + Sized,
-    <C::Database as Database>::TypeInfo: Display,
-{
-    expand_query(input.expand_file_src().await?, conn).await
-}
-
-pub async fn expand_query_as<C: Connection>(
+pub fn expand_query_as<DB: DatabaseExt>(
     input: QueryAsMacroInput,
-    mut conn: C,
-) -> crate::Result<TokenStream>
-where
-    C::Database: DatabaseExt + Sized,
-    <C::Database as Database>::TypeInfo: Display,
-{
-    let describe = input.query_input.describe_validate(&mut conn).await?;
+    data: QueryData,
+) -> crate::Result<TokenStream> {
+    input.query_input.validate(&data)?;
 
-    if describe.result_columns.is_empty() {
+    if data.columns.is_empty() {
         return Err(syn::Error::new(
             input.query_input.source_span,
             "query must output at least one column",
@@ -45,12 +27,12 @@ where
         .into());
     }
 
-    let args_tokens = args::quote_args(&input.query_input, &describe)?;
+    let args_tokens = args::quote_args::<DB>(&input.query_input, &data)?;
 
     let query_args = format_ident!("query_args");
 
-    let columns = output::columns_to_rust(&describe)?;
-    let output = output::quote_query_as::<C::Database>(
+    let columns = output::columns_to_rust(&data)?;
+    let output = output::quote_query_as::<DB>(
         &input.query_input.source,
         &input.as_ty.path,
         &query_args,
@@ -71,14 +53,3 @@ where
         }
     })
 }
-
-pub async fn expand_query_file_as<C: Connection>(
-    input: QueryAsMacroInput,
-    conn: C,
-) -> crate::Result<TokenStream>
-where
-    C::Database: DatabaseExt + Sized,
-    <C::Database as Database>::TypeInfo: Display,
-{
-    expand_query_as(input.expand_file_src().await?, conn).await
-}
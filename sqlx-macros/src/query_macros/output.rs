@@ -1,9 +1,10 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::Path;
+use syn::{Path, Type};
 
-use sqlx::describe::Describe;
+use sqlx::describe::Column;
 
+use super::QueryData;
 use crate::database::DatabaseExt;
 
 use std::fmt::{self, Display, Formatter};
@@ -11,6 +12,9 @@ use std::fmt::{self, Display, Formatter};
 pub struct RustColumn {
     pub(super) ident: Ident,
     pub(super) type_: TokenStream,
+
+    // The type given with the name of the column, e.g. `AS "status: AccountStatus"`
+    pub(super) type_override: Option<Type>,
 }
 
 struct DisplayColumn<'a> {
@@ -31,9 +35,8 @@ impl Display for DisplayColumn<'_> {
     }
 }
 
-pub fn columns_to_rust<DB: DatabaseExt>(describe: &Describe<DB>) -> crate::Result<Vec<RustColumn>> {
-    describe
-        .result_columns
+pub fn columns_to_rust(data: &QueryData) -> crate::Result<Vec<RustColumn>> {
+    data.columns
         .iter()
         .enumerate()
         .map(|(i, column)| -> crate::Result<_> {
@@ -42,41 +45,63 @@ pub fn columns_to_rust<DB: DatabaseExt>(describe: &Describe<DB>) -> crate::Resul
                 .as_deref()
                 .ok_or_else(|| format!("column at position {} must have a name", i))?;
 
+            let (name, type_override) = parse_type_override(name)?;
+            let (name, nullable) = parse_nullability(name);
             let ident = parse_ident(name)?;
 
-            let type_ = <DB as DatabaseExt>::return_type_for_id(&column.type_info)
-                .ok_or_else(|| {
-                    if let Some(feature_gate) =
-                        <DB as DatabaseExt>::get_feature_gate(&column.type_info)
-                    {
-                        format!(
-                            "optional feature `{feat}` required for type {ty} of {col}",
-                            ty = &column.type_info,
-                            feat = feature_gate,
-                            col = DisplayColumn {
-                                idx: i,
-                                name: column.name.as_deref()
-                            }
-                        )
-                    } else {
-                        format!(
-                            "unsupported type {ty} of {col}",
-                            ty = column.type_info,
-                            col = DisplayColumn {
-                                idx: i,
-                                name: column.name.as_deref()
-                            }
-                        )
-                    }
-                })?
-                .parse::<TokenStream>()
-                .unwrap();
-
-            Ok(RustColumn { ident, type_ })
+            let type_ = match &type_override {
+                Some(type_override) => quote!(#type_override),
+                None => column.type_.clone()?.parse::<TokenStream>().unwrap(),
+            };
+
+            // a column the database reports as nullable is decoded as `Option<T>`; an
+            // expression it knows nothing about is taken to be non-null, as before
+            let nullable = nullable.unwrap_or(column.non_null == Some(false));
+
+            let type_ = if nullable {
+                quote!(Option<#type_>)
+            } else {
+                type_
+            };
+
+            Ok(RustColumn {
+                ident,
+                type_,
+                type_override,
+            })
         })
         .collect::<crate::Result<Vec<_>>>()
 }
 
+/// The Rust type of the result column at `i`, or the error to report if it is used without a
+/// type given in its name.
+pub fn return_type<DB: DatabaseExt>(i: usize, column: &Column<DB>) -> Result<String, String> {
+    <DB as DatabaseExt>::return_type_for_column(column)
+        .map(str::to_owned)
+        .ok_or_else(|| {
+            if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(&column.type_info) {
+                format!(
+                    "optional feature `{feat}` required for type {ty} of {col}",
+                    ty = &column.type_info,
+                    feat = feature_gate,
+                    col = DisplayColumn {
+                        idx: i,
+                        name: column.name.as_deref()
+                    }
+                )
+            } else {
+                format!(
+                    "unsupported type {ty} of {col}",
+                    ty = column.type_info,
+                    col = DisplayColumn {
+                        idx: i,
+                        name: column.name.as_deref()
+                    }
+                )
+            }
+        })
+}
+
 pub fn quote_query_as<DB: DatabaseExt>(
     sql: &str,
     out_ty: &Path,
@@ -97,7 +122,28 @@ pub fn quote_query_as<DB: DatabaseExt>(
     let db_path = DB::db_path();
     let row_path = DB::row_path();
 
+    // The `Decode` of a type given for a column is trusted to accept the column; this only
+    // makes sure, with an error that names it, that the type can be decoded at all
+    let type_overrides = columns
+        .iter()
+        .filter_map(|column| column.type_override.as_ref())
+        .collect::<Vec<_>>();
+
+    let assert_type_overrides = if type_overrides.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            fn assert_column_types()
+            where
+                #(#type_overrides: sqlx::types::Type<#db_path>
+                    + for<'de> sqlx::decode::Decode<'de, #db_path>,)*
+            {}
+        }
+    };
+
     quote! {
+        #assert_type_overrides
+
         sqlx::query::<#db_path>(#sql).bind_all(#bind_args).try_map(|row: #row_path| {
             use sqlx::Row as _;
             use sqlx::result_ext::ResultExt as _;
@@ -107,6 +153,33 @@ pub fn quote_query_as<DB: DatabaseExt>(
     }
 }
 
+// A column named like `status: AccountStatus` is decoded as the type after the colon, e.g. an
+// enum that derives `Type`, instead of the type that matches its SQL type.
+fn parse_type_override(name: &str) -> crate::Result<(&str, Option<Type>)> {
+    let colon = match name.find(':') {
+        Some(colon) => colon,
+        None => return Ok((name, None)),
+    };
+
+    let type_override = syn::parse_str(name[colon + 1..].trim())
+        .map_err(|_| format!("invalid type in column name {:?}", name))?;
+
+    Ok((name[..colon].trim_end(), Some(type_override)))
+}
+
+// A column named with a trailing `!`, e.g. `SELECT COALESCE(total, 0) AS "total!"`, is
+// non-null where the database can not tell; a trailing `?` makes it nullable. The marker is not
+// part of the name of the field.
+fn parse_nullability(name: &str) -> (&str, Option<bool>) {
+    let nullable = match name.bytes().last() {
+        Some(b'!') => false,
+        Some(b'?') => true,
+        _ => return (name, None),
+    };
+
+    (&name[..name.len() - 1], Some(nullable))
+}
+
 fn parse_ident(name: &str) -> crate::Result<Ident> {
     // workaround for the following issue (it's semi-fixed but still spits out extra diagnostics)
     // https://github.com/dtolnay/syn/issues/749#issuecomment-575451318
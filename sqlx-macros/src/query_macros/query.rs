@@ -1,34 +1,28 @@
-use std::fmt::Display;
-
 use proc_macro2::Span;
 use proc_macro2::TokenStream;
 use syn::{Ident, Path};
 
 use quote::{format_ident, quote};
-use sqlx::{connection::Connection, database::Database};
 
-use super::{args, output, QueryMacroInput};
+use super::{args, output, QueryData, QueryMacroInput};
 use crate::database::DatabaseExt;
 
 /// Given an input like `query!("SELECT * FROM accounts WHERE account_id > ?", account_id)`,
 /// expand to an anonymous record
-pub async fn expand_query<C: Connection>(
+pub fn expand_query<DB: DatabaseExt>(
     input: QueryMacroInput,
-    mut conn: C,
-) -> crate::Result<TokenStream>
-where
-    C::Database: DatabaseExt + Sized,
-    <C::Database as Database>::TypeInfo: Display,
-{
-    let describe = input.describe_validate(&mut conn).await?;
+    data: QueryData,
+) -> crate::Result<TokenStream> {
+    input.validate(&data)?;
+
     let sql = &input.source;
 
-    let args = args::quote_args(&input, &describe)?;
+    let args = args::quote_args::<DB>(&input, &data)?;
 
     let arg_names = &input.arg_names;
-    let db_path = <C::Database as DatabaseExt>::db_path();
+    let db_path = DB::db_path();
 
-    if describe.result_columns.is_empty() {
+    if data.columns.is_empty() {
         return Ok(quote! {
             macro_rules! macro_result {
                 (#($#arg_names:expr),*) => {{
@@ -42,7 +36,7 @@ where
         });
     }
 
-    let columns = output::columns_to_rust(&describe)?;
+    let columns = output::columns_to_rust(&data)?;
 
     let record_type: Path = Ident::new("Record", Span::call_site()).into();
 
@@ -52,19 +46,26 @@ where
             |&output::RustColumn {
                  ref ident,
                  ref type_,
+                 ..
              }| quote!(#ident: #type_,),
         )
         .collect::<TokenStream>();
 
+    // `Debug` is always derived
+    let record_derives = input
+        .record_derives
+        .iter()
+        .filter(|path| !path.is_ident("Debug"));
+
     let query_args = format_ident!("query_args");
-    let output = output::quote_query_as::<C::Database>(sql, &record_type, &query_args, &columns);
+    let output = output::quote_query_as::<DB>(sql, &record_type, &query_args, &columns);
 
     Ok(quote! {
         macro_rules! macro_result {
             (#($#arg_names:expr),*) => {{
                 use sqlx::arguments::Arguments as _;
 
-                #[derive(Debug)]
+                #[derive(Debug #(, #record_derives)*)]
                 struct #record_type {
                     #record_fields
                 }
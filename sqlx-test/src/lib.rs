@@ -1,3 +1,4 @@
+use sqlx::database::ParameterStyle;
 use sqlx::{Connect, Database};
 
 fn setup_if_needed() {
@@ -16,12 +17,25 @@ where
     Ok(DB::Connection::connect(dotenv::var("DATABASE_URL")?).await?)
 }
 
+// The bind parameter at `index` (from 1) as `DB` writes it
+pub fn placeholder<DB>(index: usize) -> String
+where
+    DB: Database,
+{
+    match DB::PARAMETER_STYLE {
+        ParameterStyle::Dollar => format!("${}", index),
+        ParameterStyle::NumberedQuestion => format!("?{}", index),
+        ParameterStyle::Question => "?".to_owned(),
+    }
+}
+
 // Test type encoding and decoding
 #[macro_export]
 macro_rules! test_type {
     ($name:ident($db:ident, $ty:ty, $($text:literal == $value:expr),+)) => {
         $crate::test_prepared_type!($name($db, $ty, $($text == $value),+));
         $crate::test_unprepared_type!($name($db, $ty, $($text == $value),+));
+        $crate::test_blocking_type!($name($db, $ty, $($text == $value),+));
     }
 }
 
@@ -83,6 +97,536 @@ macro_rules! test_prepared_type {
     }
 }
 
+// Test type encoding and decoding through the blocking wrappers
+#[macro_export]
+macro_rules! test_blocking_type {
+    ($name:ident($db:ident, $ty:ty, $($text:literal == $value:expr),+)) => {
+        paste::item! {
+            #[cfg(feature = "blocking")]
+            #[test]
+            fn [< test_blocking_type_ $name >] () -> anyhow::Result<()> {
+                use sqlx::prelude::*;
+
+                let _ = dotenv::dotenv();
+
+                let mut conn = sqlx::blocking::Connection::<<$db as sqlx::Database>::Connection>::connect(
+                    &dotenv::var("DATABASE_URL")?,
+                )?;
+
+                $(
+                    let query = format!($crate::[< $db _query_for_test_prepared_type >]!(), $text);
+
+                    let rec: (bool, $ty) = sqlx::blocking::query(&query)
+                        .bind($value)
+                        .bind($value)
+                        .try_map(|row: $crate::[< $db _row_for_test_blocking_type >]!()| -> sqlx::Result<(bool, $ty)> {
+                            Ok((row.try_get(0)?, row.try_get(1)?))
+                        })
+                        .fetch_one(&mut conn)?;
+
+                    assert!(rec.0, "value returned from server: {:?}", rec.1);
+                    assert!($value == rec.1);
+                )+
+
+                Ok(())
+            }
+        }
+    }
+}
+
+// Test that a NULL decodes as `None` into an `Option` and fails the same way in every database
+// into anything else: with an "unexpected null" error from `try_get` that names the column, and
+// with a panic carrying that message from `get`
+#[macro_export]
+macro_rules! test_null_decoding {
+    ($name:ident($db:ident, $($ty:ty: $text:literal == $value:expr, $null:literal);+ $(;)?)) => {
+        paste::item! {
+            #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+            #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+            async fn [< test_null_decoding_ $name >] () -> anyhow::Result<()> {
+                use sqlx::prelude::*;
+                use std::panic::{catch_unwind, AssertUnwindSafe};
+
+                let mut conn = sqlx_test::new::<$db>().await?;
+
+                $({
+                    let query = format!("SELECT {} AS value, {} AS missing", $text, $null);
+                    let mut cursor = conn.fetch(&*query);
+                    let row = cursor.next().await?.unwrap();
+
+                    assert!($value == row.try_get::<$ty, _>("value")?);
+                    assert!(row.try_get::<Option<$ty>, _>("value")?.map_or(false, |v| $value == v));
+                    assert!(row.try_get::<Option<$ty>, _>("missing")?.is_none());
+
+                    let expected = format!(
+                        "failed to decode column `missing` as `{}`: unexpected null",
+                        std::any::type_name::<$ty>()
+                    );
+
+                    let err = row.try_get::<$ty, _>("missing").unwrap_err();
+
+                    assert!(err.is_unexpected_null(), "{}", err);
+                    assert!(err.to_string().starts_with(&expected), "{}", err);
+
+                    let panic = catch_unwind(AssertUnwindSafe(|| row.get::<$ty, _>("missing")))
+                        .map(|_| ())
+                        .unwrap_err();
+                    let message = panic.downcast_ref::<String>().map_or("", |m| &**m);
+
+                    assert!(message.starts_with(&expected), "{}", message);
+                })+
+
+                Ok(())
+            }
+        }
+    }
+}
+
+// Test the enums that derive `Type`: stored as their discriminant (written as `$sunny` ..
+// in SQL) or as the names of their variants
+#[macro_export]
+macro_rules! test_derives {
+    ($db:ident, repr: [$sunny:literal, $cloudy:literal, $rainy:literal], unknown: $unknown:literal) => {
+        mod derives {
+            use super::*;
+
+            #[derive(Debug, PartialEq, sqlx::Type)]
+            #[repr(i32)]
+            enum Weather {
+                Sunny = 1,
+                Cloudy = 2,
+                Rainy = 3,
+            }
+
+            #[derive(Debug, PartialEq, sqlx::Type)]
+            #[sqlx(rename_all = "snake_case")]
+            enum Color {
+                Red,
+                DarkGreen,
+                #[sqlx(rename = "BLUE")]
+                Blue,
+            }
+
+            #[derive(Debug, PartialEq, sqlx::Type)]
+            #[sqlx(rename_all = "PascalCase")]
+            #[allow(non_camel_case_types)]
+            enum Shape {
+                Circle,
+                round_square,
+            }
+
+            $crate::test_type!(repr_enum(
+                $db,
+                Weather,
+                $sunny == Weather::Sunny,
+                $cloudy == Weather::Cloudy,
+                $rainy == Weather::Rainy
+            ));
+
+            $crate::test_type!(string_enum(
+                $db,
+                Color,
+                "'red'" == Color::Red,
+                "'dark_green'" == Color::DarkGreen,
+                "'BLUE'" == Color::Blue
+            ));
+
+            $crate::test_type!(pascal_case_enum(
+                $db,
+                Shape,
+                "'Circle'" == Shape::Circle,
+                "'RoundSquare'" == Shape::round_square
+            ));
+
+            #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+            #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+            async fn test_enum_unknown_value() -> anyhow::Result<()> {
+                use sqlx::prelude::*;
+
+                let mut conn = $crate::new::<$db>().await?;
+
+                let query = format!("SELECT {}, 'purple'", $unknown);
+                let mut cursor = conn.fetch(&*query);
+                let row = cursor.next().await?.unwrap();
+
+                match row.try_get::<Weather, _>(0) {
+                    Err(sqlx::Error::Decode(err)) => {
+                        assert!(err.to_string().contains("7"), "{}", err)
+                    }
+                    other => panic!("expected a decode error, got {:?}", other),
+                }
+
+                match row.try_get::<Color, _>(1) {
+                    Err(sqlx::Error::Decode(err)) => {
+                        assert!(err.to_string().contains("purple"), "{}", err)
+                    }
+                    other => panic!("expected a decode error, got {:?}", other),
+                }
+
+                Ok(())
+            }
+
+            #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+            #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+            async fn test_decode_error_names_the_column() -> anyhow::Result<()> {
+                use sqlx::error::ColumnDecodeError;
+                use sqlx::prelude::*;
+
+                let mut conn = $crate::new::<$db>().await?;
+
+                let mut cursor = conn.fetch("SELECT 'red' AS ok, 'purple' AS paint");
+                let row = cursor.next().await?.unwrap();
+
+                for err in vec![
+                    row.try_get::<Color, _>("paint").unwrap_err(),
+                    row.try_get::<Color, _>(1).unwrap_err(),
+                ] {
+                    let message = err.to_string();
+
+                    assert!(
+                        message.starts_with("failed to decode column `paint` as `"),
+                        "{}",
+                        message
+                    );
+                    assert!(message.contains("Color"), "{}", message);
+                    assert!(message.contains("purple"), "{}", message);
+
+                    match err {
+                        sqlx::Error::Decode(err) => assert_eq!(
+                            err.downcast_ref::<ColumnDecodeError>().unwrap().column(),
+                            "paint"
+                        ),
+                        other => panic!("expected a decode error, got {:?}", other),
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+// Test `Query::bind_named`, which rewrites `:name` to the bind parameters of each database
+#[macro_export]
+macro_rules! test_named_parameters {
+    ($db:ident) => {
+        #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+        #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+        async fn it_binds_named_parameters() -> anyhow::Result<()> {
+            use sqlx::prelude::*;
+
+            let mut conn = sqlx_test::new::<$db>().await?;
+
+            conn.execute(
+                "CREATE TEMPORARY TABLE named_users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+            )
+            .await?;
+
+            let cnt = sqlx::query("INSERT INTO named_users (id, name) VALUES (:id, :name)")
+                .bind_named("name", "alice")
+                .bind_named(":id", 1_i32)
+                .execute(&mut conn)
+                .await?;
+
+            assert_eq!(cnt, 1);
+
+            // a name used twice takes the same value
+            let (id, name): (i32, String) = sqlx::query(
+                "SELECT id, name FROM named_users \
+                 WHERE id = :id AND (name = :name OR name = ':name' OR :id < 0)",
+            )
+            .bind_named("id", 1_i32)
+            .bind_named("name", "alice")
+            .fetch_one(&mut conn)
+            .await?;
+
+            assert_eq!((id, &*name), (1, "alice"));
+
+            let rows: Vec<(i32,)> = sqlx::query("SELECT id FROM named_users WHERE name = :name")
+                .bind_named("name", "bob")
+                .fetch_all(&mut conn)
+                .await?;
+
+            assert!(rows.is_empty());
+
+            let row: Option<(i32,)> = sqlx::query("SELECT id FROM named_users WHERE name = :name")
+                .bind_named("name", "alice")
+                .fetch_optional(&mut conn)
+                .await?;
+
+            assert_eq!(row, Some((1,)));
+
+            // a name without a value fails before anything is sent
+            let res = sqlx::query("SELECT id FROM named_users WHERE name = :name")
+                .bind_named("id", 1_i32)
+                .execute(&mut conn)
+                .await;
+
+            assert!(res.is_err());
+
+            // and so do named parameters bound after positional ones
+            let res = sqlx::query("SELECT id FROM named_users WHERE name = :name")
+                .bind(1_i32)
+                .bind_named("name", "alice")
+                .execute(&mut conn)
+                .await;
+
+            assert!(res.is_err());
+
+            Ok(())
+        }
+    };
+}
+
+// Test that a query with more parameters than the database takes fails before it is sent, with
+// `$limit` in the error
+#[macro_export]
+macro_rules! test_parameter_limit {
+    ($db:ident, $limit:expr) => {
+        #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+        #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+        async fn it_refuses_more_parameters_than_a_statement_can_have() -> anyhow::Result<()> {
+            use sqlx::prelude::*;
+            use sqlx::Database;
+
+            let mut conn = $crate::new::<$db>().await?;
+
+            assert_eq!($db::PARAMETER_LIMIT, $limit);
+
+            let count = $db::PARAMETER_LIMIT + 1;
+            let params = (1..=count)
+                .map($crate::placeholder::<$db>)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let statement = format!("SELECT 1 WHERE 1 IN ({})", params);
+
+            let query =
+                || (0..count).fold(sqlx::query(&statement), |query, i| query.bind(i as i32));
+
+            match query().execute(&mut conn).await {
+                Err(sqlx::Error::ParameterLimitExceeded { limit, got }) => {
+                    assert_eq!(limit, $limit);
+                    assert_eq!(got, count);
+                }
+                res => panic!("expected a parameter limit error, got {:?}", res),
+            }
+
+            // the same for a cursor
+            let mut cursor = query().fetch(&mut conn);
+
+            match cursor.next().await {
+                Err(sqlx::Error::ParameterLimitExceeded { .. }) => {}
+                res => panic!(
+                    "expected a parameter limit error, got {:?}",
+                    res.map(|_| ())
+                ),
+            }
+
+            drop(cursor);
+
+            // nothing was sent, so the connection carries on as normal
+            let statement = format!("SELECT {}", $crate::placeholder::<$db>(1));
+            let (one,): (i32,) = sqlx::query_as(&statement)
+                .bind(1_i32)
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!(one, 1);
+
+            Ok(())
+        }
+    };
+}
+
+// Test `Connection::statement_cache_stats`
+#[macro_export]
+macro_rules! test_statement_cache_stats {
+    ($db:ident) => {
+        #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+        #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+        async fn it_counts_the_hits_of_the_statement_cache() -> anyhow::Result<()> {
+            use sqlx::prelude::*;
+
+            let mut conn = $crate::new::<$db>().await?;
+            let param = $crate::placeholder::<$db>(1);
+
+            let before = conn.statement_cache_stats();
+
+            for id in 0..3_i32 {
+                let _ = sqlx::query(&format!("SELECT {} + 1", param))
+                    .bind(id)
+                    .execute(&mut conn)
+                    .await?;
+            }
+
+            let _ = sqlx::query(&format!("SELECT {} + 2", param))
+                .bind(0_i32)
+                .execute(&mut conn)
+                .await?;
+
+            let after = conn.statement_cache_stats();
+
+            assert_eq!(after.hits - before.hits, 2);
+            assert_eq!(after.misses - before.misses, 2);
+            assert_eq!(after.size - before.size, 2);
+
+            Ok(())
+        }
+    };
+}
+
+// Test `MsgPack`, with a struct that gained a field since the value was stored
+#[macro_export]
+macro_rules! test_message_pack {
+    ($db:ident) => {
+        #[cfg(feature = "messagepack")]
+        #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+        #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+        async fn it_round_trips_message_pack() -> anyhow::Result<()> {
+            use serde::{Deserialize, Serialize};
+            use sqlx::prelude::*;
+            use sqlx::types::MsgPack;
+
+            #[derive(Debug, PartialEq, Serialize, Deserialize)]
+            struct Session {
+                user_id: i64,
+                scopes: Vec<String>,
+            }
+
+            // the same struct in a later version, with a field added
+            #[derive(Debug, PartialEq, Deserialize)]
+            struct SessionV2 {
+                scopes: Vec<String>,
+                user_id: i64,
+                #[serde(default)]
+                expires: Option<i64>,
+            }
+
+            let mut conn = $crate::new::<$db>().await?;
+            let statement = format!("SELECT {}", $crate::placeholder::<$db>(1));
+
+            let session = Session {
+                user_id: 7,
+                scopes: vec!["read".to_owned(), "write".to_owned()],
+            };
+
+            let (MsgPack(stored),): (MsgPack<Session>,) = sqlx::query_as(&statement)
+                .bind(MsgPack(&session))
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!(stored, session);
+
+            let (MsgPack(stored),): (MsgPack<SessionV2>,) = sqlx::query_as(&statement)
+                .bind(MsgPack(&session))
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!(stored.user_id, 7);
+            assert_eq!(stored.expires, None);
+
+            // 0xC1 is never used in MessagePack
+            let err = sqlx::query_as::<_, (MsgPack<Session>,)>(&statement)
+                .bind(vec![0xC1_u8])
+                .fetch_one(&mut conn)
+                .await
+                .unwrap_err()
+                .to_string();
+
+            assert!(err.contains("MessagePack"), "{}", err);
+            assert!(err.contains("Session"), "{}", err);
+
+            Ok(())
+        }
+    };
+}
+
+// Test `query::large_value` on a column of the binary type `$blob`
+#[macro_export]
+macro_rules! test_large_values {
+    ($db:ident, blob: $blob:literal) => {
+        #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+        #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+        async fn it_reads_parts_of_large_values() -> anyhow::Result<()> {
+            use sqlx::prelude::*;
+            use sqlx::quote::Identifier;
+
+            let mut conn = $crate::new::<$db>().await?;
+
+            // the identifiers are quoted
+            let table = Identifier::new("large values");
+
+            conn.execute(&*format!(
+                "CREATE TEMPORARY TABLE {} (id INTEGER PRIMARY KEY, content {}, name TEXT)",
+                table.quote::<$db>()?,
+                $blob
+            ))
+            .await?;
+
+            let content: Vec<u8> = (0..1_000_000).map(|i| i as u8).collect();
+
+            sqlx::query(&format!(
+                "INSERT INTO {} (id, content, name) VALUES (1, {}, 'naïve.bin'), (2, NULL, NULL)",
+                table.quote::<$db>()?,
+                $crate::placeholder::<$db>(1)
+            ))
+            .bind(&content[..])
+            .execute(&mut conn)
+            .await?;
+
+            let file = sqlx::query::large_value(table, "content", "id", 1_i32);
+
+            assert_eq!(file.length(&mut conn).await?, Some(1_000_000));
+            assert_eq!(
+                file.substring(&mut conn, 256, 4).await?,
+                Some(vec![0, 1, 2, 3])
+            );
+
+            // past the end of the value
+            assert_eq!(
+                file.substring(&mut conn, 999_998, 10).await?,
+                Some(content[999_998..].to_vec())
+            );
+            assert_eq!(
+                file.substring(&mut conn, 1_000_100, 10).await?,
+                Some(vec![])
+            );
+
+            // text is counted in characters
+            let name = sqlx::query::large_value(table, "name", "id", 1_i32);
+
+            assert_eq!(name.length(&mut conn).await?, Some(9));
+            assert_eq!(
+                name.substring(&mut conn, 2, 2).await?,
+                Some("ïv".as_bytes().to_vec())
+            );
+            assert_eq!(
+                name.substring(&mut conn, 3, 3).await?,
+                Some("ve.".as_bytes().to_vec())
+            );
+
+            let null = sqlx::query::large_value(table, "content", "id", 2_i32);
+
+            assert_eq!(null.length(&mut conn).await?, None);
+            assert_eq!(null.substring(&mut conn, 0, 1).await?, None);
+
+            let missing = sqlx::query::large_value(table, "content", "id", 3_i32);
+
+            match missing.length(&mut conn).await {
+                Err(sqlx::Error::RowNotFound) => {}
+                res => panic!("expected RowNotFound, got {:?}", res),
+            }
+
+            match file.substring(&mut conn, 0, usize::max_value()).await {
+                Err(sqlx::Error::Encode(_)) => {}
+                res => panic!("expected an encode error, got {:?}", res),
+            }
+
+            Ok(())
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! MySql_query_for_test_prepared_type {
     () => {
@@ -103,3 +647,24 @@ macro_rules! Postgres_query_for_test_prepared_type {
         "SELECT {} is not distinct from $1, $2 as _1"
     };
 }
+
+#[macro_export]
+macro_rules! MySql_row_for_test_blocking_type {
+    () => {
+        sqlx::mysql::MySqlRow
+    };
+}
+
+#[macro_export]
+macro_rules! Sqlite_row_for_test_blocking_type {
+    () => {
+        sqlx::sqlite::SqliteRow
+    };
+}
+
+#[macro_export]
+macro_rules! Postgres_row_for_test_blocking_type {
+    () => {
+        sqlx::postgres::PgRow
+    };
+}
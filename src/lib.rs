@@ -7,20 +7,27 @@ compile_error!("one of 'runtime-async-std' or 'runtime-tokio' features must be e
 compile_error!("only one of 'runtime-async-std' or 'runtime-tokio' features must be enabled");
 
 pub use sqlx_core::arguments;
-pub use sqlx_core::connection::{Connect, Connection};
+pub use sqlx_core::connection::{
+    Connect, ConnectOptions, Connection, StatementCacheStats, TransactionStatus,
+};
 pub use sqlx_core::cursor::Cursor;
 pub use sqlx_core::database::{self, Database};
 pub use sqlx_core::describe;
 pub use sqlx_core::executor::{Execute, Executor, RefExecutor};
+pub use sqlx_core::notice;
 pub use sqlx_core::pool::{self, Pool};
-pub use sqlx_core::query::{self, query, Query};
+pub use sqlx_core::query::{self, query, query_call, NamedQuery, Query, QueryCall};
 pub use sqlx_core::query_as::{query_as, QueryAs};
+pub use sqlx_core::quote;
 pub use sqlx_core::row::{self, FromRow, Row};
-pub use sqlx_core::transaction::Transaction;
+pub use sqlx_core::transaction::{TestTransaction, Transaction};
 
 #[doc(inline)]
 pub use sqlx_core::types::{self, Type};
 
+#[cfg(feature = "macros")]
+pub use sqlx_macros::{FromRow, Type};
+
 #[doc(inline)]
 pub use sqlx_core::error::{self, Error, Result};
 
@@ -36,6 +43,26 @@ pub use sqlx_core::postgres::{self, PgConnection, PgPool, Postgres};
 #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
 pub use sqlx_core::sqlite::{self, Sqlite, SqliteConnection, SqlitePool};
 
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub use sqlx_core::blocking;
+
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub use sqlx_core::explain;
+
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub use sqlx_core::test_util;
+
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub use sqlx_core::instrument;
+
+#[cfg(feature = "bench-internals")]
+#[doc(hidden)]
+pub use sqlx_core::bench;
+
 #[cfg(feature = "macros")]
 #[doc(hidden)]
 pub extern crate sqlx_macros;
@@ -60,7 +87,7 @@ pub mod encode {
 }
 
 pub mod decode {
-    pub use sqlx_core::decode::Decode;
+    pub use sqlx_core::decode::{Decode, DecodeOwned};
 
     #[cfg(feature = "macros")]
     pub use sqlx_macros::Decode;
@@ -64,6 +64,56 @@
 /// * Postgres: `$N` where `N` is the 1-based positional argument index
 /// * MySQL: `?` which matches arguments in order that it appears in the query
 ///
+/// ## Derives
+/// The anonymous struct always derives `Debug`. Start the arguments with `#[derive(..)]` to
+/// derive other traits for it, e.g. to return it from a web handler or compare it in a test:
+///
+/// ```rust,ignore
+/// let account = sqlx::query!(#[derive(Clone, PartialEq, serde::Serialize)] "select (1) as id")
+///     .fetch_one(&mut conn)
+///     .await?;
+/// ```
+///
+/// ## Nullability
+/// A column is decoded as `Option<T>` when the database reports it as nullable, such as a
+/// column of a table without `NOT NULL`, and as `T` otherwise. Postgres only knows this for
+/// columns read straight from a table; SQLite does not report it at all.
+///
+/// A column is decoded as `Option<T>` when its name ends with `?` and as `T` when it ends with
+/// `!`, for expressions whose nullability the database can not infer or gets wrong (e.g. a
+/// `NOT NULL` column of the right side of a `LEFT JOIN`). The marker is dropped from the name
+/// of the field:
+///
+/// ```rust,ignore
+/// let totals = sqlx::query!(
+///         r#"SELECT COALESCE(SUM(amount), 0) AS "total!", MAX(note) AS "note?" FROM orders"#
+///     )
+///     .fetch_one(&mut conn)
+///     .await?;
+///
+/// let total: i64 = totals.total;
+/// let note: Option<String> = totals.note;
+/// ```
+///
+/// ## Type Overrides
+/// A column whose name is followed by `: <type>` is decoded as that type instead of the one that
+/// matches its SQL type, e.g. an enum or a newtype with `#[derive(sqlx::Type)]`. The type must
+/// implement `Type` and `Decode` for the database, which is checked when compiling, but its
+/// `Decode` is trusted to accept the SQL type of the column. The override comes after any
+/// nullability marker and is dropped from the name of the field:
+///
+/// ```rust,ignore
+/// let account = sqlx::query!(
+///         r#"SELECT status AS "status: AccountStatus", closed_by AS "closed_by?: UserId"
+///            FROM accounts"#
+///     )
+///     .fetch_one(&mut conn)
+///     .await?;
+///
+/// let status: AccountStatus = account.status;
+/// let closed_by: Option<UserId> = account.closed_by;
+/// ```
+///
 /// ## Requirements
 /// * The `DATABASE_URL` environment variable must be set at build-time to point to a database
 /// server with the schema that the query string will be checked against. (All variants of
@@ -80,6 +130,14 @@
 ///       determine the database type.
 ///
 /// [dotenv]: https://crates.io/crates/dotenv
+///
+/// ## Caching
+/// The expansion of each query is kept under `target/sqlx/` and reused as long as the query and
+/// the schema of the database are the same, so the database only has to report the version of its
+/// schema on the next build. Set `SQLX_MACRO_CACHE_EPOCH` to any value to use the cached expansions
+/// without connecting at all (change it whenever the schema changes), or `SQLX_MACRO_CACHE=off` to
+/// always check queries against the database.
+///
 /// ## See Also
 /// * [query_as!] if you want to use a struct you can name,
 /// * [query_file!] if you want to define the SQL query out-of-line,
@@ -102,6 +160,13 @@ macro_rules! query (
             $crate::sqlx_macros::query!($query, $($args),*);
         }
         macro_result!($($args),*)
+    });
+    (#[derive($($derive:tt)*)] $query:literal $(, $args:expr)*$(,)?) => ({
+        #[macro_use]
+        mod _macro_result {
+            $crate::sqlx_macros::query!(#[derive($($derive)*)] $query $(, $args)*);
+        }
+        macro_result!($($args),*)
     })
 );
 
@@ -163,6 +228,13 @@ macro_rules! query_file (
             $crate::sqlx_macros::query_file!($query, $($args),*);
         }
         macro_result!($($args),*)
+    });
+    (#[derive($($derive:tt)*)] $query:literal $(, $args:expr)*$(,)?) => (#[allow(dead_code)]{
+        #[macro_use]
+        mod _macro_result {
+            $crate::sqlx_macros::query_file!(#[derive($($derive)*)] $query $(, $args)*);
+        }
+        macro_result!($($args),*)
     })
 );
 
@@ -179,6 +251,9 @@ macro_rules! query_file (
 /// * The query must output at least one column.
 /// * The column names of the query must match the field names of the struct.
 /// * Neither the query nor the struct may have unused fields.
+/// * A field for a column the database reports as nullable must be an `Option`
+///   (see the nullability of [query!]).
+/// * A field for a column with a [type override][query!] must be of that type.
 ///
 /// The only modification to the syntax is that the struct name is given before the SQL string:
 /// ```rust
@@ -1,5 +1,3 @@
-use crate::error::{Error, UnexpectedNullError};
-
 pub trait ResultExt<T>: Sized {
     fn try_unwrap_optional(self) -> crate::Result<T>;
 }
@@ -15,13 +13,7 @@ impl<T> ResultExt<Option<T>> for crate::Result<T> {
         match self {
             Ok(val) => Ok(Some(val)),
 
-            Err(Error::Decode(error)) => {
-                if let Some(UnexpectedNullError) = error.downcast_ref() {
-                    Ok(None)
-                } else {
-                    Err(Error::Decode(error))
-                }
-            }
+            Err(e) if e.is_unexpected_null() => Ok(None),
 
             Err(e) => Err(e),
         }
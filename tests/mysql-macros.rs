@@ -33,6 +33,24 @@ async fn macro_select_from_cte_bind() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn macro_select_binary_and_text_strings() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let record = sqlx::query!("SELECT CAST('hello' AS BINARY) as bin, 'world' as text")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let bin: Vec<u8> = record.bin;
+    let text: String = record.text;
+
+    assert_eq!(bin, b"hello");
+    assert_eq!(text, "world");
+
+    Ok(())
+}
+
 #[derive(Debug)]
 struct RawAccount {
     r#type: i32,
@@ -58,3 +76,139 @@ async fn test_query_as_raw() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[derive(Debug, PartialEq, sqlx::Type)]
+#[sqlx(rename_all = "snake_case")]
+enum AccountStatus {
+    Open,
+    Closed,
+}
+
+#[derive(Debug, PartialEq, sqlx::Type)]
+#[repr(i32)]
+enum Tier {
+    Free = 1,
+    Paid = 2,
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_column_type_overrides() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    #[derive(Debug)]
+    struct Account {
+        status: AccountStatus,
+        tier: Tier,
+        previous_status: Option<AccountStatus>,
+    }
+
+    let account = sqlx::query_as!(
+        Account,
+        "SELECT 'closed' AS `status: AccountStatus`, 2 AS `tier!: Tier`,
+            CAST(NULL AS CHAR) AS `previous_status?: AccountStatus`"
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(account.status, AccountStatus::Closed);
+    assert_eq!(account.tier, Tier::Paid);
+    assert_eq!(account.previous_status, None);
+
+    let record = sqlx::query!("SELECT 'open' AS `status: AccountStatus`, 1 AS `tier!: Tier`")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.status, AccountStatus::Open);
+    assert_eq!(record.tier, Tier::Free);
+
+    Ok(())
+}
+
+// `tweet` is created by tests/setup/mysql.sql
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn macro_insert_and_update() -> anyhow::Result<()> {
+    use sqlx::Connection;
+
+    let mut conn = new::<MySql>().await?;
+    let mut tx = conn.begin().await?;
+
+    let affected = sqlx::query!(
+        "INSERT INTO tweet (text, owner_id) VALUES (?, ?)",
+        "Hello, world!",
+        Some(1_i64)
+    )
+    .execute(&mut tx)
+    .await?;
+
+    assert_eq!(affected, 1);
+
+    let tweet = sqlx::query!("SELECT id, text FROM tweet WHERE id = LAST_INSERT_ID()")
+        .fetch_one(&mut tx)
+        .await?;
+
+    let _: i64 = tweet.id;
+
+    let affected = sqlx::query!(
+        "UPDATE tweet SET text = ? WHERE id = ?",
+        "Goodbye, world!",
+        tweet.id
+    )
+    .execute(&mut tx)
+    .await?;
+
+    assert_eq!(affected, 1);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn macro_nullability_from_describe() -> anyhow::Result<()> {
+    use sqlx::Connection;
+
+    #[derive(Debug)]
+    struct Tweet {
+        id: i64,
+        text: String,
+        owner_id: Option<i64>,
+    }
+
+    let mut conn = new::<MySql>().await?;
+    let mut tx = conn.begin().await?;
+
+    sqlx::query!("INSERT INTO tweet (text) VALUES (?)", "No owner")
+        .execute(&mut tx)
+        .await?;
+
+    // `owner_id` has no `NOT NULL`
+    let tweet = sqlx::query_as!(
+        Tweet,
+        "SELECT id, text, owner_id FROM tweet WHERE id = LAST_INSERT_ID()"
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    assert_eq!(tweet.text, "No owner");
+    assert_eq!(tweet.owner_id, None);
+
+    let record = sqlx::query!(
+        "SELECT text, COALESCE(owner_id, 0) AS `owner_id!` FROM tweet WHERE id = ?",
+        tweet.id
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    let text: String = record.text;
+    let owner_id: i64 = record.owner_id;
+
+    assert_eq!(text, "No owner");
+    assert_eq!(owner_id, 0);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
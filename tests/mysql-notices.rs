@@ -0,0 +1,55 @@
+use sqlx::mysql::MySqlRow;
+use sqlx::notice::{HandleNotices, NoticeSeverity};
+use sqlx::test_util::CaptureNotices;
+use sqlx::{Executor, MySql, Row};
+use sqlx_test::new;
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_captures_warnings() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+    let notices = CaptureNotices::attach(&mut conn);
+
+    // a value that has to be truncated to be converted
+    let value: i64 = sqlx::query("SELECT CAST(? AS SIGNED)")
+        .bind("12abc")
+        .try_map(|row: MySqlRow| row.try_get(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 12);
+
+    let captured = notices.take();
+
+    assert_eq!(captured.len(), 1, "{:?}", captured);
+    assert_eq!(captured[0].severity(), NoticeSeverity::Warning);
+    assert_eq!(captured[0].code(), "1292");
+    assert!(
+        captured[0].message().contains("12abc"),
+        "{}",
+        captured[0].message()
+    );
+
+    // a note, from a statement that returns no rows
+    conn.execute("DROP TEMPORARY TABLE IF EXISTS sqlx_no_such_table")
+        .await?;
+
+    let captured = notices.take();
+
+    assert_eq!(captured.len(), 1, "{:?}", captured);
+    assert_eq!(captured[0].severity(), NoticeSeverity::Notice);
+    assert_eq!(captured[0].code(), "1051");
+
+    // a statement without warnings reports none, and does not ask for them
+    conn.execute("SELECT 1").await?;
+
+    assert!(notices.notices().is_empty());
+
+    // without a handler, warnings are not read
+    conn.remove_notice_handler();
+    conn.execute("SELECT CAST('34xyz' AS SIGNED)").await?;
+
+    assert!(notices.notices().is_empty());
+
+    Ok(())
+}
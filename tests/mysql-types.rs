@@ -1,5 +1,7 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use sqlx::MySql;
-use sqlx_test::test_type;
+use sqlx_test::{test_null_decoding, test_type};
 
 test_type!(null(
     MySql,
@@ -7,6 +9,14 @@ test_type!(null(
     "NULL" == None::<i16>
 ));
 
+test_null_decoding!(null_decoding(
+    MySql,
+    i64: "1" == 1_i64, "CAST(NULL AS SIGNED)";
+    String: "'text'" == "text", "CAST(NULL AS CHAR)";
+    Vec<u8>: "X'BEEF'" == vec![0xBE_u8, 0xEF], "CAST(NULL AS BINARY)";
+    bool: "true" == true, "NULL"
+));
+
 test_type!(bool(MySql, bool, "false" == false, "true" == true));
 
 test_type!(u8(MySql, u8, "253" == 253_u8));
@@ -32,6 +42,21 @@ test_type!(float(
     "3.1410000324249268" == 3.141f32 as f64 as f32
 ));
 
+test_type!(decimal_lossy(
+    MySql,
+    sqlx::types::Lossy<f64>,
+    "CAST(1.5 AS DECIMAL(10, 2))" == sqlx::types::Lossy(1.5_f64),
+    "CAST(-12345.0625 AS DECIMAL(10, 4))" == sqlx::types::Lossy(-12345.0625_f64)
+));
+
+// the exact value is not representable as `f64` and is rounded to the nearest one
+sqlx_test::test_unprepared_type!(decimal_lossy_rounded(
+    MySql,
+    sqlx::types::Lossy<f64>,
+    "CAST(12345678901234567890.123456789 AS DECIMAL(30, 9))"
+        == sqlx::types::Lossy(1.2345678901234567e19_f64)
+));
+
 test_type!(string(
     MySql,
     String,
@@ -39,6 +64,13 @@ test_type!(string(
     "''" == ""
 ));
 
+test_type!(path(
+    MySql,
+    std::path::PathBuf,
+    "'/var/lib/data'" == std::path::PathBuf::from("/var/lib/data"),
+    "'relative/café'" == std::path::PathBuf::from("relative/café")
+));
+
 test_type!(bytes(
     MySql,
     Vec<u8>,
@@ -50,6 +82,92 @@ test_type!(bytes(
         == vec![0_u8, 0, 0, 0, 0x52]
 ));
 
+test_type!(binary_array(
+    MySql,
+    [u8; 4],
+    "X'DEADBEEF'" == [0xDE_u8, 0xAD, 0xBE, 0xEF]
+));
+
+// the SRID, then the WKB of a little-endian POINT(1 2)
+test_type!(geometry(
+    MySql,
+    sqlx::mysql::MySqlGeometry,
+    "ST_GeomFromText('POINT(1 2)')" == sqlx::mysql::MySqlGeometry::new(0, point_wkb()),
+    "ST_GeomFromText('POINT(1 2)', 3857)" == sqlx::mysql::MySqlGeometry::new(3857, point_wkb())
+));
+
+fn point_wkb() -> Vec<u8> {
+    let mut wkb = vec![1, 1, 0, 0, 0];
+    wkb.extend_from_slice(&1_f64.to_le_bytes());
+    wkb.extend_from_slice(&2_f64.to_le_bytes());
+    wkb
+}
+
+#[cfg(feature = "uuid")]
+test_type!(uuid(
+    MySql,
+    sqlx::types::Uuid,
+    "X'b731678f636f4135bc6f19440c13bd19'"
+        == sqlx::types::Uuid::parse_str("b731678f-636f-4135-bc6f-19440c13bd19").unwrap(),
+    "X'00000000000000000000000000000000'"
+        == sqlx::types::Uuid::parse_str("00000000-0000-0000-0000-000000000000").unwrap()
+));
+
+#[cfg(feature = "uuid")]
+test_type!(uuid_hyphenated(
+    MySql,
+    sqlx::types::Hyphenated,
+    "'b731678f-636f-4135-bc6f-19440c13bd19'"
+        == sqlx::types::Uuid::parse_str("b731678f-636f-4135-bc6f-19440c13bd19")
+            .unwrap()
+            .to_hyphenated()
+));
+
+// a `Uuid` is also read from its hyphenated form in a textual column
+#[cfg(feature = "uuid")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_uuid_from_text() -> anyhow::Result<()> {
+    use sqlx::prelude::*;
+    use sqlx::types::Uuid;
+
+    let mut conn = sqlx_test::new::<MySql>().await?;
+    let expected = Uuid::parse_str("b731678f-636f-4135-bc6f-19440c13bd19").unwrap();
+
+    // text protocol
+    let mut cursor = conn.fetch("SELECT 'b731678f-636f-4135-bc6f-19440c13bd19'");
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.try_get::<Uuid, _>(0)?, expected);
+
+    drop(cursor);
+
+    // binary protocol
+    let (uuid,): (Uuid,) = sqlx::query_as("SELECT CAST(? AS CHAR)")
+        .bind("b731678f-636f-4135-bc6f-19440c13bd19")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(uuid, expected);
+
+    let malformed = sqlx::query_as::<_, (Uuid,)>("SELECT CAST(? AS CHAR)")
+        .bind("b731678f-636f-4135-bc6f")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(malformed.is_err());
+
+    Ok(())
+}
+
+test_type!(system_time(
+    MySql,
+    SystemTime,
+    "TIMESTAMP '2019-12-11 11:01:05.115100'"
+        == UNIX_EPOCH + Duration::from_micros(1_576_062_065_115_100),
+    "TIMESTAMP '1960-01-01 00:00:00'" == UNIX_EPOCH - Duration::from_secs(315_619_200)
+));
+
 #[cfg(feature = "chrono")]
 mod chrono {
     use super::*;
@@ -84,3 +202,65 @@ mod chrono {
             )
     ));
 }
+
+#[cfg(feature = "json")]
+mod json {
+    use super::*;
+
+    test_type!(json_array_string(
+        MySql,
+        Vec<String>,
+        "'[\"a\",\"b c\"]'" == vec!["a".to_owned(), "b c".to_owned()],
+        "'[]'" == Vec::<String>::new()
+    ));
+
+    test_type!(json_array_i64(MySql, Vec<i64>, "'[1,-2,3]'" == vec![1_i64, -2, 3]));
+
+    #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+    #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+    async fn test_json_array_column() -> anyhow::Result<()> {
+        use sqlx::prelude::*;
+
+        let mut conn = sqlx_test::new::<MySql>().await?;
+
+        conn.execute("CREATE TEMPORARY TABLE json_array_test (id INT, tags JSON)")
+            .await?;
+
+        sqlx::query("INSERT INTO json_array_test (id, tags) VALUES (1, ?), (2, ?)")
+            .bind(vec!["red".to_owned(), "\"quoted\"".to_owned()])
+            .bind(vec![7_i64])
+            .execute(&mut conn)
+            .await?;
+
+        let (tags,): (Vec<String>,) =
+            sqlx::query_as("SELECT tags FROM json_array_test WHERE id = 1")
+                .fetch_one(&mut conn)
+                .await?;
+
+        assert_eq!(tags, vec!["red", "\"quoted\""]);
+
+        let (numbers,): (Vec<i64>,) =
+            sqlx::query_as("SELECT tags FROM json_array_test WHERE JSON_CONTAINS(tags, ?)")
+                .bind("7")
+                .fetch_one(&mut conn)
+                .await?;
+
+        assert_eq!(numbers, vec![7]);
+
+        // a value that is not an array is an error, not an empty `Vec`
+        let mut cursor = conn.fetch("SELECT CAST('{\"a\": 1}' AS JSON)");
+        let row = cursor.next().await?.unwrap();
+        let err = row.try_get::<Vec<String>, _>(0).unwrap_err().to_string();
+
+        assert!(
+            err.contains("expected a JSON array, found an object"),
+            "{}",
+            err
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "macros")]
+sqlx_test::test_derives!(MySql, repr: ["1", "2", "3"], unknown: "7");
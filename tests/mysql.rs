@@ -1,7 +1,10 @@
 use futures::TryStreamExt;
-use sqlx::{mysql::MySqlQueryAs, Connection, Executor, MySql, MySqlPool};
+use sqlx::{
+    describe::ColumnFlags, mysql::MySqlQueryAs, Connect, Connection, Cursor, Executor, MySql,
+    MySqlConnection, MySqlPool, Row,
+};
 use sqlx_test::new;
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
@@ -23,6 +26,46 @@ async fn it_drops_results_in_affected_rows() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_drains_the_results_of_a_dropped_query() -> anyhow::Result<()> {
+    use sqlx_core::runtime::timeout;
+
+    let mut conn = new::<MySql>().await?;
+
+    // stop reading after the first of many rows, in both protocols
+    let mut cursor = conn.fetch("select * from mysql.time_zone");
+    assert!(cursor.next().await?.is_some());
+    drop(cursor);
+
+    let mut cursor = sqlx::query("select * from mysql.time_zone where ? = 1")
+        .bind(1_i32)
+        .fetch(&mut conn);
+    assert!(cursor.next().await?.is_some());
+    drop(cursor);
+
+    assert!(!conn.is_ready());
+
+    let (value,): (i64,) = sqlx::query_as("SELECT 1 + 1").fetch_one(&mut conn).await?;
+
+    assert_eq!(value, 2);
+
+    // give up on a query before the server has responded
+    let res = timeout(
+        Duration::from_millis(100),
+        sqlx::query("SELECT SLEEP(1)").execute(&mut conn),
+    )
+    .await;
+
+    assert!(res.is_err());
+
+    let (value,): (i64,) = sqlx::query_as("SELECT 2 + 2").fetch_one(&mut conn).await?;
+
+    assert_eq!(value, 4);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn it_executes() -> anyhow::Result<()> {
@@ -55,6 +98,257 @@ CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY)
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_reuses_the_arguments_of_previous_queries() -> anyhow::Result<()> {
+    use sqlx::arguments::Arguments;
+
+    let mut conn = new::<MySql>().await?;
+
+    let _ = conn
+        .execute("CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+
+    for index in 1..=10_i32 {
+        // from the second query on, these are the cleared arguments of the one before
+        let mut arguments = conn.take_arguments();
+        arguments.add(index);
+        arguments.add(if index % 2 == 0 { None } else { Some("odd") });
+
+        let cnt = sqlx::query("INSERT INTO users (id, name) VALUES (?, ?)")
+            .bind_all(arguments)
+            .execute(&mut conn)
+            .await?;
+
+        assert_eq!(cnt, 1);
+    }
+
+    // the NULL bit of a previous query must not carry over
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE name IS NULL")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 5);
+
+    Ok(())
+}
+
+sqlx_test::test_named_parameters!(MySql);
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_echoes_nulls_among_many_parameters() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // the NULL bitmap takes more than one byte past 8 parameters
+    for &(count, nulls) in &[
+        (8_usize, &[7_usize][..]),
+        (9, &[0, 8]),
+        (70, &[3, 9, 65]),
+        (80, &[0, 15, 16, 63, 64, 79]),
+    ] {
+        let sql = format!("SELECT {}", vec!["CAST(? AS SIGNED)"; count].join(", "));
+
+        let mut query = sqlx::query(&sql);
+
+        for index in 0..count {
+            query = query.bind(if nulls.contains(&index) {
+                None
+            } else {
+                Some(index as i64)
+            });
+        }
+
+        let mut cursor = query.fetch(&mut conn);
+        let row = cursor.next().await?.unwrap();
+
+        for index in 0..count {
+            let value: Option<i64> = row.try_get(index)?;
+
+            if nulls.contains(&index) {
+                assert_eq!(value, None, "parameter {} of {}", index, count);
+            } else {
+                assert_eq!(
+                    value,
+                    Some(index as i64),
+                    "parameter {} of {}",
+                    index,
+                    count
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_names_the_column_of_a_decode_error() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let mut cursor =
+        sqlx::query("SELECT CAST(NULL AS SIGNED) AS total, 'x' AS name").fetch(&mut conn);
+    let row = cursor.next().await?.unwrap();
+
+    let err = row.try_get::<i64, _>(0).unwrap_err();
+
+    assert!(err.is_unexpected_null());
+    assert!(
+        err.to_string()
+            .starts_with("failed to decode column `total` as `i64`: unexpected null"),
+        "{}",
+        err
+    );
+
+    let err = row.try_get::<i64, _>("name").unwrap_err();
+
+    assert!(
+        err.to_string()
+            .starts_with("failed to decode column `name` as `i64`: mismatched types"),
+        "{}",
+        err
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_looks_up_tables() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // temporary tables are not listed in `information_schema`
+    conn.execute("CREATE TABLE IF NOT EXISTS table_id_test (id INT)")
+        .await?;
+
+    let schema: String = sqlx::query("SELECT DATABASE()")
+        .try_map(|row: sqlx::mysql::MySqlRow| row.try_get(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(
+        conn.table_id("table_id_test").await?.as_deref(),
+        Some("table_id_test")
+    );
+    assert!(
+        conn.table_exists(&format!("`{}`.`table_id_test`", schema))
+            .await?
+    );
+    assert!(conn.table_exists("information_schema.TABLES").await?);
+
+    assert!(!conn.table_exists("missing").await?);
+    assert!(
+        !conn
+            .table_exists("information_schema.table_id_test")
+            .await?
+    );
+    assert!(!conn.table_exists("no_such_schema.table_id_test").await?);
+
+    conn.execute("DROP TABLE table_id_test").await?;
+
+    assert!(!conn.table_exists("table_id_test").await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_describes_tables() -> anyhow::Result<()> {
+    use sqlx::types::{Type, TypeInfo};
+
+    let mut conn = new::<MySql>().await?;
+
+    // temporary tables are not listed in `information_schema`
+    conn.execute(
+        r#"
+DROP TABLE IF EXISTS `Accounts`;
+CREATE TABLE `Accounts` (
+    id BIGINT NOT NULL,
+    `Name` VARCHAR(64) NOT NULL DEFAULT 'anonymous',
+    note TEXT,
+    PRIMARY KEY (id, `Name`)
+);
+        "#,
+    )
+    .await?;
+
+    let schema: String = sqlx::query("SELECT DATABASE()")
+        .try_map(|row: sqlx::mysql::MySqlRow| row.try_get(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    let columns = conn
+        .describe_table(&format!("`{}`.`Accounts`", schema))
+        .await?
+        .unwrap();
+
+    // (name, ordinal, nullable, has_default, primary_key)
+    let described: Vec<_> = columns
+        .iter()
+        .map(|column| {
+            (
+                &*column.name,
+                column.ordinal,
+                column.nullable,
+                column.has_default,
+                column.primary_key,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        described,
+        vec![
+            ("id", 1, false, false, true),
+            ("Name", 2, false, true, true),
+            ("note", 3, true, false, false),
+        ]
+    );
+
+    assert!(<i64 as Type<MySql>>::type_info().compatible(&columns[0].type_info));
+    assert!(<String as Type<MySql>>::type_info().compatible(&columns[1].type_info));
+    assert!(<String as Type<MySql>>::type_info().compatible(&columns[2].type_info));
+
+    assert!(conn.describe_table("missing").await?.is_none());
+
+    conn.execute("DROP TABLE `Accounts`").await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_returns_the_affected_rows_of_each_statement() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY, active BOOLEAN NOT NULL);
+INSERT INTO users (id, active) VALUES (1, TRUE), (2, TRUE), (3, FALSE);
+            "#,
+        )
+        .await?;
+
+    let counts = conn
+        .execute_many(
+            "UPDATE users SET active = FALSE WHERE active; SELECT * FROM users; DELETE FROM users",
+        )
+        .await?;
+
+    assert_eq!(counts, vec![2, 0, 3]);
+
+    // the connection can still be used
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn it_selects_null() -> anyhow::Result<()> {
@@ -67,6 +361,100 @@ async fn it_selects_null() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_calls_procedures_with_out_parameters() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+DROP PROCEDURE IF EXISTS sqlx_test_sum_product;
+CREATE PROCEDURE sqlx_test_sum_product(IN a INT, IN b INT, OUT sum INT, OUT product INT)
+BEGIN
+    SET sum = a + b;
+    SET product = a * b;
+END;
+DROP PROCEDURE IF EXISTS sqlx_test_set_answer;
+CREATE PROCEDURE sqlx_test_set_answer() SET @answer = 42;
+        "#,
+    )
+    .await?;
+
+    let (sum, product): (i64, i64) = conn
+        .call(
+            sqlx::query("CALL sqlx_test_sum_product(?, ?, @sum, @product)")
+                .bind(3)
+                .bind(4),
+        )
+        .await?;
+
+    assert_eq!((sum, product), (7, 12));
+
+    let (sum,): (i64,) = conn
+        .call("CALL sqlx_test_sum_product(10, 20, @sum, @product)")
+        .await?;
+
+    assert_eq!(sum, 30);
+
+    // the variables are kept for the rest of the session
+    let (product,): (i64,) = sqlx::query_as("SELECT @product")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(product, 200);
+
+    // without user variables the procedure is called all the same, with an empty result
+    let () = conn.call("CALL sqlx_test_set_answer()").await?;
+
+    let (answer,): (i64,) = sqlx::query_as("SELECT @answer")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(answer, 42);
+
+    conn.execute("DROP PROCEDURE sqlx_test_sum_product; DROP PROCEDURE sqlx_test_set_answer")
+        .await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_calls_procedures_with_inout_parameters() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+DROP PROCEDURE IF EXISTS sqlx_test_increment;
+CREATE PROCEDURE sqlx_test_increment(INOUT counter INT, IN step INT)
+BEGIN
+    SET counter = counter + step;
+END;
+        "#,
+    )
+    .await?;
+
+    let (counter,): (i32,) = sqlx::query_call::<MySql>("sqlx_test_increment")
+        .bind(1_i32)
+        .bind(5_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(counter, 6);
+
+    // the status sent after the parameters does not get in the way of the next query
+    let (value,): (i32,) = sqlx::query_as("SELECT ?")
+        .bind(10_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 10);
+
+    conn.execute("DROP PROCEDURE sqlx_test_increment").await?;
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn test_describe() -> anyhow::Result<()> {
@@ -93,7 +481,7 @@ async fn test_describe() -> anyhow::Result<()> {
     assert_eq!(describe.result_columns[1].non_null, Some(true));
     assert_eq!(describe.result_columns[1].type_info.type_name(), "TEXT");
     assert_eq!(describe.result_columns[2].non_null, Some(false));
-    assert_eq!(describe.result_columns[2].type_info.type_name(), "TEXT");
+    assert_eq!(describe.result_columns[2].type_info.type_name(), "BLOB");
     assert_eq!(describe.result_columns[3].non_null, Some(true));
 
     let bool_ty_name = describe.result_columns[3].type_info.type_name();
@@ -108,6 +496,152 @@ async fn test_describe() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_describes_dml_statements() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE describe_dml (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    name TEXT NOT NULL,
+    score INT
+)
+        "#,
+    )
+    .await?;
+
+    let describe = conn
+        .describe("INSERT INTO describe_dml (name, score) VALUES (?, ?)")
+        .await?;
+
+    assert_eq!(describe.param_types.len(), 2);
+    assert!(describe.result_columns.is_empty());
+
+    let describe = conn
+        .describe("UPDATE describe_dml SET score = ? WHERE id = ?")
+        .await?;
+
+    assert_eq!(describe.param_types.len(), 2);
+    assert!(describe.result_columns.is_empty());
+
+    // the connection is left ready for the next query
+    let affected = conn
+        .execute(sqlx::query("UPDATE describe_dml SET score = ?").bind(1_i32))
+        .await?;
+
+    assert_eq!(affected, 0);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_fails_to_encode_an_argument_before_sending_the_query() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // a negative year
+    let time = UNIX_EPOCH - Duration::from_secs(100_000 * 365 * 86_400);
+
+    let res = sqlx::query("SELECT ?, ?")
+        .bind(1_i32)
+        .bind(time)
+        .execute(&mut conn)
+        .await;
+
+    match res {
+        Err(sqlx::Error::Encode(err)) => {
+            assert!(err.to_string().contains("out of range"), "{}", err)
+        }
+        res => panic!("expected an encode error, got {:?}", res),
+    }
+
+    // nothing was sent, so the connection carries on as normal
+    let (one,): (i32,) = sqlx::query_as("SELECT ?")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(one, 1);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_reuses_prepared_statements() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    async fn prepared(conn: &mut MySqlConnection) -> anyhow::Result<u64> {
+        let (_, count): (String, String) =
+            sqlx::query_as("SHOW SESSION STATUS LIKE 'Com_stmt_prepare'")
+                .fetch_one(conn)
+                .await?;
+
+        Ok(count.parse()?)
+    }
+
+    // the status query is prepared on its first use, too
+    let _ = prepared(&mut conn).await?;
+
+    let mut counts = Vec::new();
+
+    for i in 0..3 {
+        let (sum,): (i64,) = sqlx::query_as("SELECT ? + 1")
+            .bind(i)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(sum, i + 1);
+
+        counts.push(prepared(&mut conn).await?);
+    }
+
+    // only the first run sent a COM_STMT_PREPARE
+    assert_eq!(counts[1], counts[0]);
+    assert_eq!(counts[2], counts[0]);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_describes_column_flags() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // temporary tables are not listed in `information_schema.COLUMNS`
+    conn.execute("DROP TABLE IF EXISTS describe_flags_test")
+        .await?;
+    conn.execute(
+        r#"
+        CREATE TABLE describe_flags_test (
+            id int primary key auto_increment,
+            price int not null,
+            doubled int as (price * 2) stored
+        )
+    "#,
+    )
+    .await?;
+
+    let describe = conn
+        .describe("select id, price, doubled as twice from describe_flags_test")
+        .await;
+
+    conn.execute("DROP TABLE describe_flags_test").await?;
+
+    let describe = describe?;
+
+    assert_eq!(
+        describe.result_columns[0].flags,
+        ColumnFlags::PRIMARY_KEY | ColumnFlags::AUTO_INCREMENT
+    );
+    assert_eq!(describe.result_columns[1].flags, ColumnFlags::empty());
+    assert_eq!(describe.result_columns[2].flags, ColumnFlags::GENERATED);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn pool_immediately_fails_with_db_error() -> anyhow::Result<()> {
@@ -131,6 +665,42 @@ async fn pool_immediately_fails_with_db_error() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_rolls_back_a_transaction_left_open_on_a_pool_connection() -> anyhow::Result<()> {
+    use sqlx::TransactionStatus;
+
+    let pool = MySqlPool::builder()
+        .max_size(1)
+        .build(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let mut conn = pool.acquire().await?;
+    conn.execute("CREATE TEMPORARY TABLE pool_rollback_test (id INT) ENGINE=InnoDB")
+        .await?;
+
+    conn.execute("START TRANSACTION").await?;
+    conn.execute("INSERT INTO pool_rollback_test (id) VALUES (1)")
+        .await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Transaction);
+
+    drop(conn);
+
+    // waits for the rollback to release the connection
+    let mut conn = pool.acquire().await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM pool_rollback_test")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
 // run with `cargo test --features mysql -- --ignored --nocapture pool_smoke_test`
 #[ignore]
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
@@ -185,3 +755,550 @@ async fn pool_smoke_test() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_decodes_compatible_types() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let mut cursor =
+        sqlx::query("SELECT CAST(1 AS SIGNED), 300, CAST(2 AS UNSIGNED), 'a'").fetch(&mut conn);
+    let row = cursor.next().await?.unwrap();
+
+    // integers of any width into any integer of the same signedness
+    assert_eq!(row.try_get::<i8, _>(0)?, 1);
+    assert_eq!(row.try_get::<i32, _>(0)?, 1);
+    assert_eq!(row.try_get::<u16, _>(2)?, 2);
+
+    // narrowing is checked
+    assert!(row.try_get::<i8, _>(1).is_err());
+
+    let err = row.try_get::<u64, _>(0).unwrap_err().to_string();
+
+    assert!(err.contains("u64"), "{}", err);
+    assert!(err.contains("BIG_INT"), "{}", err);
+
+    assert_eq!(row.try_get::<String, _>(3)?, "a");
+    assert!(row.try_get::<i32, _>(3).is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_decodes_integers_as_bool() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE bool_test (id INT PRIMARY KEY, flag TINYINT(1) NOT NULL);
+INSERT INTO bool_test (id, flag) VALUES (1, 0), (2, 1), (3, 2);
+        "#,
+    )
+    .await?;
+
+    // prepared, in the binary protocol
+    let flags: Vec<(bool,)> = sqlx::query_as("SELECT flag FROM bool_test ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(flags, [(false,), (true,), (true,)]);
+
+    let (large,): (bool,) = sqlx::query_as("SELECT CAST(256 AS SIGNED)")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(large);
+
+    // unprepared, in the text protocol
+    let mut cursor = conn.fetch("SELECT flag FROM bool_test ORDER BY id");
+    let mut flags = Vec::new();
+
+    while let Some(row) = cursor.next().await? {
+        flags.push(row.try_get::<bool, _>(0)?);
+    }
+
+    assert_eq!(flags, [false, true, true]);
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_explains_queries() -> anyhow::Result<()> {
+    use sqlx::explain::ExplainNode;
+
+    fn relations(node: &ExplainNode, out: &mut Vec<String>) {
+        out.extend(node.relation.clone());
+
+        for child in &node.children {
+            relations(child, out);
+        }
+    }
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE explain_test (id INT PRIMARY KEY, name TEXT)")
+        .await?;
+
+    let plan = sqlx::query("SELECT name FROM explain_test WHERE id = ?")
+        .bind(1_i32)
+        .explain(&mut conn)
+        .await?;
+
+    assert_eq!(plan.nodes.len(), 1);
+    assert_eq!(plan.nodes[0].node_type, "query_block");
+    assert!(plan.raw["query_block"].is_object());
+
+    let mut tables = Vec::new();
+    relations(&plan.nodes[0], &mut tables);
+
+    assert_eq!(tables, vec!["explain_test"]);
+
+    assert!(sqlx::query("SELECT 1")
+        .explain_analyze(&mut conn)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_connects_with_an_empty_password() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let (version, database): (String, String) = sqlx::query_as("SELECT VERSION(), DATABASE()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let mut plugins = vec!["mysql_native_password"];
+
+    // `sha256_password` is specific to MySQL, and `caching_sha2_password` is new in MySQL 8
+    if !version.contains("MariaDB") {
+        plugins.push("sha256_password");
+
+        if !version.starts_with("5.") {
+            plugins.push("caching_sha2_password");
+        }
+    }
+
+    let url = dotenv::var("DATABASE_URL")?;
+    let url = format!(
+        "mysql://sqlx_empty_password{}",
+        &url[url.rfind('@').unwrap()..]
+    );
+
+    for plugin in plugins {
+        conn.execute("DROP USER IF EXISTS sqlx_empty_password")
+            .await?;
+
+        // the server switches to the plugin of the user if it is not its default
+        conn.execute(&*format!(
+            "CREATE USER sqlx_empty_password IDENTIFIED WITH {}",
+            plugin
+        ))
+        .await?;
+
+        conn.execute(&*format!(
+            "GRANT SELECT ON `{}`.* TO sqlx_empty_password",
+            database
+        ))
+        .await?;
+
+        let mut user_conn = MySqlConnection::connect(&*url).await?;
+
+        let (user,): (String,) = sqlx::query_as("SELECT CURRENT_USER()")
+            .fetch_one(&mut user_conn)
+            .await?;
+
+        assert!(
+            user.starts_with("sqlx_empty_password@"),
+            "{}: {}",
+            plugin,
+            user
+        );
+
+        user_conn.close().await?;
+    }
+
+    conn.execute("DROP USER sqlx_empty_password").await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_grows_small_buffers() -> anyhow::Result<()> {
+    let _ = dotenv::dotenv();
+
+    let mut url = dotenv::var("DATABASE_URL")?;
+    url.push(if url.contains('?') { '&' } else { '?' });
+    url.push_str("read-buffer-size=16&write-buffer-size=0");
+
+    let mut conn = MySqlConnection::connect(url).await?;
+
+    let text = "x".repeat(10_000);
+
+    let (value,): (String,) = sqlx::query_as("SELECT CONCAT(?, ?)")
+        .bind(&*text)
+        .bind(&*text)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value.len(), 20_000);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_checks_types_strictly() -> anyhow::Result<()> {
+    let _ = dotenv::dotenv();
+
+    let mut url = dotenv::var("DATABASE_URL")?;
+    url.push(if url.contains('?') { '&' } else { '?' });
+    url.push_str("strict-types=true");
+
+    let mut conn = MySqlConnection::connect(url).await?;
+
+    let mut cursor = sqlx::query("SELECT CAST(1 AS SIGNED)").fetch(&mut conn);
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.try_get::<i64, _>(0)?, 1);
+    assert!(row.try_get::<i32, _>(0).is_err());
+
+    // text values are checked as well
+    let mut cursor = conn.fetch("SELECT CAST(1 AS SIGNED)");
+    let row = cursor.next().await?.unwrap();
+
+    assert!(row.try_get::<i32, _>(0).is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_tells_binary_strings_from_text() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // a binary collation on a text column does not make it a binary string
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE binary_strings (
+    bin VARBINARY(16) NOT NULL,
+    text VARCHAR(16) COLLATE utf8mb4_bin NOT NULL
+)
+        "#,
+    )
+    .await?;
+
+    conn.execute("INSERT INTO binary_strings (bin, text) VALUES ('hello', 'world')")
+        .await?;
+
+    let describe = conn
+        .describe("SELECT bin, text FROM binary_strings")
+        .await?;
+
+    assert_eq!(describe.result_columns[0].type_info.char_set(), 63);
+    assert!(describe.result_columns[0].type_info.is_binary_string());
+    assert_ne!(describe.result_columns[1].type_info.char_set(), 63);
+    assert!(!describe.result_columns[1].type_info.is_binary_string());
+
+    let mut cursor = sqlx::query("SELECT bin, text FROM binary_strings").fetch(&mut conn);
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.try_get::<Vec<u8>, _>(0)?, b"hello");
+    assert_eq!(row.try_get::<Vec<u8>, _>(1)?, b"world");
+    assert_eq!(row.try_get::<String, _>(1)?, "world");
+
+    let err = row.try_get::<String, _>(0).unwrap_err().to_string();
+
+    assert!(err.contains("Vec<u8>"), "{}", err);
+    assert!(err.contains("VAR_BINARY"), "{}", err);
+
+    drop(cursor);
+
+    // binary strings are also checked in the text protocol
+    let mut cursor = conn.fetch("SELECT bin, text FROM binary_strings");
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.try_get::<&[u8], _>(0)?, b"hello");
+    assert_eq!(row.try_get::<&str, _>(1)?, "world");
+    assert!(row.try_get::<&str, _>(0).is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_tracks_the_session_state() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let schema = conn.current_schema().map(str::to_owned);
+
+    assert!(schema.is_some());
+
+    let (version,): (String,) = sqlx::query_as("SELECT VERSION()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    // e.g. `10.1.44-MariaDB`
+    let mut numbers = version.split(|c: char| !c.is_ascii_digit());
+    let major_minor = (
+        numbers.next().and_then(|n| n.parse().ok()).unwrap_or(0_u32),
+        numbers.next().and_then(|n| n.parse().ok()).unwrap_or(0_u32),
+    );
+
+    // the session state is tracked since MySQL 5.7 and MariaDB 10.2
+    let tracked = if version.contains("MariaDB") {
+        major_minor >= (10, 2)
+    } else {
+        major_minor >= (5, 7)
+    };
+
+    if !tracked {
+        assert_eq!(conn.system_variable("time_zone"), None);
+
+        return Ok(());
+    }
+
+    // set when connecting
+    assert_eq!(conn.system_variable("time_zone"), Some("+00:00"));
+
+    conn.execute("USE information_schema").await?;
+
+    assert_eq!(conn.current_schema(), Some("information_schema"));
+
+    conn.execute("SET time_zone = '+01:00'").await?;
+
+    assert_eq!(conn.system_variable("time_zone"), Some("+01:00"));
+
+    // the state is also reported at the end of result sets, in both protocols
+    let query = format!("USE {}; SELECT 1; SELECT 2", schema.unwrap());
+    let mut cursor = conn.fetch(&*query);
+
+    assert!(cursor.next().await?.is_some());
+    assert!(cursor.next().await?.is_some());
+    assert!(cursor.next().await?.is_none());
+
+    drop(cursor);
+
+    let row: (i32, String) = sqlx::query_as("SELECT ?, DATABASE()")
+        .bind(1_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row.0, 1);
+    assert_eq!(conn.current_schema(), Some(&*row.1));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_executes_queries_without_statements() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // MySQL fails these with `1065 (42000): Query was empty`
+    for &query in &["", "  ;  ", "-- comment only"] {
+        assert_eq!(conn.execute(query).await?, 0, "{:?}", query);
+        assert!(conn.fetch(query).next().await?.is_none(), "{:?}", query);
+
+        // prepared
+        assert_eq!(sqlx::query(query).execute(&mut conn).await?, 0);
+        assert!(sqlx::query(query).fetch(&mut conn).next().await?.is_none());
+    }
+
+    // a trailing `;` only ends the last statement
+    let _ = conn
+        .execute("CREATE TEMPORARY TABLE empty_test (id INTEGER); ; -- done")
+        .await?;
+
+    assert_eq!(
+        conn.execute("INSERT INTO empty_test VALUES (1), (2);  ;")
+            .await?,
+        2
+    );
+
+    let mut cursor = conn.fetch("SELECT id FROM empty_test ORDER BY id; ;");
+
+    assert!(cursor.next().await?.is_some());
+    assert!(cursor.next().await?.is_some());
+    assert!(cursor.next().await?.is_none());
+    drop(cursor);
+
+    // the connection is still usable
+    let (value,): (i32,) = sqlx::query_as("SELECT 1").fetch_one(&mut conn).await?;
+
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_counts_the_rows_affected_by_a_cursor() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TEMPORARY TABLE affected_test (id INTEGER);
+DROP PROCEDURE IF EXISTS sqlx_test_affected;
+CREATE PROCEDURE sqlx_test_affected()
+BEGIN
+    SELECT COUNT(*) FROM affected_test;
+    INSERT INTO affected_test VALUES (4), (5);
+END;
+            "#,
+        )
+        .await?;
+
+    let mut cursor =
+        conn.fetch("INSERT INTO affected_test VALUES (1), (2), (3); SELECT id FROM affected_test");
+
+    assert_eq!(cursor.rows_affected(), None);
+
+    let mut ids = Vec::new();
+
+    while let Some(row) = cursor.next().await? {
+        ids.push(row.get::<i32, _>(0));
+    }
+
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(cursor.rows_affected(), Some(3));
+
+    // the count of the final OK packet of a `CALL` is of its last statement
+    let (counts, affected): (Vec<(i64,)>, u64) = sqlx::query_as("CALL sqlx_test_affected()")
+        .fetch_all_with_count(&mut conn)
+        .await?;
+
+    assert_eq!(counts, vec![(3,)]);
+    assert_eq!(affected, 2);
+
+    conn.execute("DROP PROCEDURE sqlx_test_affected").await?;
+
+    Ok(())
+}
+
+#[cfg(all(feature = "json", feature = "macros"))]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_derives_from_row_with_json_fields() -> anyhow::Result<()> {
+    use std::error::Error as _;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Person {
+        name: String,
+        #[sqlx(json)]
+        address: Address,
+        #[sqlx(json)]
+        previous: Option<Address>,
+    }
+
+    let mut conn = new::<MySql>().await?;
+
+    let person: Person = sqlx::query_as(
+        r#"SELECT 'Ann' AS name, JSON_OBJECT('city', 'Oslo') AS address, NULL AS previous"#,
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(person.name, "Ann");
+    assert_eq!(person.address.city, "Oslo");
+    assert_eq!(person.previous, None);
+
+    let res: sqlx::Result<Person> =
+        sqlx::query_as(r#"SELECT 'Bob' AS name, 'not json' AS address, NULL AS previous"#)
+            .fetch_one(&mut conn)
+            .await;
+
+    match res {
+        Err(sqlx::Error::Decode(err)) => {
+            assert!(err.to_string().starts_with("Person.address: "), "{}", err);
+
+            let err = err
+                .downcast_ref::<sqlx::error::FieldDecodeError>()
+                .expect("a FieldDecodeError");
+
+            assert_eq!(err.field(), "Person.address");
+
+            let err = err
+                .source()
+                .and_then(|err| err.downcast_ref::<sqlx::error::ColumnDecodeError>())
+                .expect("a ColumnDecodeError");
+
+            assert_eq!(err.column(), "address");
+            assert!(err.source().unwrap().is::<serde_json::Error>(), "{}", err);
+        }
+        res => panic!("expected a decode error, got {:?}", res),
+    }
+
+    Ok(())
+}
+
+sqlx_test::test_parameter_limit!(MySql, 65535);
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_round_trips_years() -> anyhow::Result<()> {
+    use sqlx::mysql::MySqlYear;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE years (id INT PRIMARY KEY, year YEAR)")
+        .await?;
+
+    for (id, &year) in [0, 1901, 2020, 2155].iter().enumerate() {
+        sqlx::query("INSERT INTO years (id, year) VALUES (?, ?)")
+            .bind(id as i32)
+            .bind(MySqlYear(year))
+            .execute(&mut conn)
+            .await?;
+
+        let (prepared,): (MySqlYear,) = sqlx::query_as("SELECT year FROM years WHERE id = ?")
+            .bind(id as i32)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(prepared, MySqlYear(year));
+
+        // the text protocol sends the year as four digits
+        let unprepared = format!("SELECT year, year FROM years WHERE id = {}", id);
+        let mut cursor = conn.fetch(&*unprepared);
+        let row = cursor.next().await?.unwrap();
+
+        assert_eq!(row.try_get::<MySqlYear, _>(0)?, MySqlYear(year));
+        assert_eq!(row.try_get::<u16, _>(1)?, year);
+    }
+
+    for &year in &[1900, 2156, 20] {
+        let res = sqlx::query("INSERT INTO years (id, year) VALUES (?, ?)")
+            .bind(100_i32)
+            .bind(MySqlYear(year))
+            .execute(&mut conn)
+            .await;
+
+        match res {
+            Err(sqlx::Error::Encode(err)) => {
+                assert!(err.to_string().contains("out of range"), "{}", err)
+            }
+            res => panic!("expected an encode error for {}, got {:?}", year, res),
+        }
+    }
+
+    Ok(())
+}
+
+sqlx_test::test_large_values!(MySql, blob: "LONGBLOB");
+
+sqlx_test::test_message_pack!(MySql);
+
+sqlx_test::test_statement_cache_stats!(MySql);
@@ -0,0 +1,86 @@
+use sqlx::pool::adapter::ConnectionManager;
+use sqlx::postgres::PgQueryAs;
+use sqlx::{Connection, Executor, PgConnection, TransactionStatus};
+
+fn manager() -> anyhow::Result<ConnectionManager<PgConnection>> {
+    let _ = dotenv::dotenv();
+
+    Ok(ConnectionManager::new(&dotenv::var("DATABASE_URL")?))
+}
+
+async fn select_scalars(conn: &mut PgConnection) -> anyhow::Result<()> {
+    let row: (bool, i16, i32, i64, f32, f64, String) = sqlx::query_as(
+        "SELECT true, 16::int2, 32::int4, 64::int8, 1.5::float4, 2.5::float8, 'text'::text",
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    assert_eq!(row, (true, 16, 32, 64, 1.5, 2.5, "text".to_owned()));
+
+    let row: (Option<i32>, Vec<u8>) = sqlx::query_as("SELECT $1::int4, $2::bytea")
+        .bind(None::<i32>)
+        .bind(vec![1_u8, 2, 3])
+        .fetch_one(&mut *conn)
+        .await?;
+
+    assert_eq!(row, (None, vec![1, 2, 3]));
+
+    Ok(())
+}
+
+async fn run_queries_through_bb8() -> anyhow::Result<()> {
+    let pool = bb8::Pool::builder().max_size(2).build(manager()?).await?;
+
+    for _ in 0..4 {
+        let mut conn = pool.get().await?;
+
+        select_scalars(&mut conn).await?;
+    }
+
+    // a connection returned in a transaction is closed
+    pool.get().await?.execute("BEGIN").await?;
+
+    let conn = pool.get().await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+    Ok(())
+}
+
+// bb8 runs its background tasks on Tokio; the connections keep using the runtime of SQLx
+#[cfg(feature = "runtime-async-std")]
+#[test]
+fn it_runs_queries_through_bb8() -> anyhow::Result<()> {
+    tokio::runtime::Builder::new()
+        .basic_scheduler()
+        .enable_all()
+        .build()?
+        .block_on(run_queries_through_bb8())
+}
+
+#[cfg(feature = "runtime-tokio")]
+#[tokio::test]
+async fn it_runs_queries_through_bb8() -> anyhow::Result<()> {
+    run_queries_through_bb8().await
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_runs_queries_through_deadpool() -> anyhow::Result<()> {
+    let pool = deadpool::managed::Pool::new(manager()?, 1);
+
+    for _ in 0..4 {
+        let mut conn = pool.get().await?;
+
+        select_scalars(&mut conn).await?;
+    }
+
+    // a connection returned in a transaction is rolled back when it is handed out again
+    pool.get().await?.execute("BEGIN").await?;
+
+    let conn = pool.get().await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Logs the plans of slow queries to a logger of its own, so it runs in a process of its own.
+
+use sqlx::{Connect, Connection, Executor, PgConnection, TransactionStatus};
+use std::sync::Mutex;
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_logs_the_plans_of_slow_queries() -> anyhow::Result<()> {
+    // Keeps the messages of the `sqlx::auto_explain` records
+    struct Plans(Mutex<Vec<String>>);
+
+    impl log::Log for Plans {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.target() == "sqlx::auto_explain"
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static PLANS: Plans = Plans(Mutex::new(Vec::new()));
+
+    log::set_logger(&PLANS).unwrap();
+    log::set_max_level(log::LevelFilter::Warn);
+
+    let mut url = dotenv::var("DATABASE_URL")?;
+    url.push(if url.contains('?') { '&' } else { '?' });
+    url.push_str("auto_explain=50");
+
+    let mut conn = PgConnection::connect(url).await?;
+
+    conn.execute("CREATE TEMPORARY TABLE slow (id INT)").await?;
+
+    // fast enough
+    sqlx::query("SELECT 1").execute(&mut conn).await?;
+
+    let rows: Vec<(i32,)> = sqlx::query_as("SELECT $1 FROM pg_sleep(0.1)")
+        .bind(7)
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(rows, [(7,)]);
+
+    // writes are never run again
+    sqlx::query("INSERT INTO slow SELECT 1 FROM pg_sleep(0.1)")
+        .execute(&mut conn)
+        .await?;
+
+    let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM slow")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    let plans = PLANS.0.lock().unwrap().clone();
+
+    assert_eq!(plans.len(), 1, "{:?}", plans);
+    assert!(plans[0].contains("SELECT $1 FROM pg_sleep(0.1)"));
+    assert!(plans[0].contains("\"Actual Total Time\""));
+
+    // the connection is left as it was
+    assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+    // in a transaction, the query is explained in a savepoint that is released again
+    conn.execute("BEGIN").await?;
+
+    sqlx::query("SELECT 1 FROM pg_sleep(0.1)")
+        .execute(&mut conn)
+        .await?;
+
+    assert_eq!(PLANS.0.lock().unwrap().len(), 2);
+    assert_eq!(conn.transaction_status(), TransactionStatus::Transaction);
+    assert!(conn
+        .execute("RELEASE SAVEPOINT _sqlx_auto_explain")
+        .await
+        .is_err());
+
+    conn.execute("ROLLBACK").await?;
+
+    Ok(())
+}
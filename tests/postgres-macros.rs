@@ -17,6 +17,63 @@ async fn test_query() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_query_derives() -> anyhow::Result<()> {
+    let mut conn = connect().await?;
+
+    let account = sqlx::query!(
+        #[derive(Clone, PartialEq)]
+        "SELECT * from (VALUES (1, 'Herp Derpinson')) accounts(id, name) where id = $1",
+        1i32
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(account.clone(), account);
+
+    // a call to a function of the same name is an argument like any other
+    fn derive(id: i32) -> i32 {
+        id
+    }
+
+    let account = sqlx::query!(
+        "SELECT * from (VALUES (1, 'Herp Derpinson')) accounts(id, name) where id = $1",
+        derive(1)
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(account.name.as_deref(), Some("Herp Derpinson"));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_query_full_text_search() -> anyhow::Result<()> {
+    use sqlx::postgres::{PgTsQuery, PgTsVector};
+
+    let mut conn = connect().await?;
+
+    let rec = sqlx::query!(
+        "SELECT fts, $1::tsquery AS query, fts @@ $1::tsquery AS matches \
+         FROM (VALUES (to_tsvector('simple', 'fat cat'))) documents(fts)",
+        PgTsQuery("fat & (rat | cat)".into())
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(rec.fts, Some(PgTsVector("'cat':2 'fat':1".into())));
+    assert_eq!(
+        rec.query,
+        Some(PgTsQuery("'fat' & ( 'rat' | 'cat' )".into()))
+    );
+    assert_eq!(rec.matches, Some(true));
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn test_no_result() -> anyhow::Result<()> {
@@ -157,14 +214,103 @@ async fn test_nullable_err() -> anyhow::Result<()> {
     .unwrap_err();
 
     if let sqlx::Error::Decode(err) = &err {
-        if let Some(sqlx::error::UnexpectedNullError) = err.downcast_ref() {
-            return Ok(());
+        if let Some(err) = err.downcast_ref::<sqlx::error::ColumnDecodeError>() {
+            let source = std::error::Error::source(err).unwrap();
+
+            if let Some(sqlx::error::UnexpectedNullError) = source.downcast_ref() {
+                assert_eq!(err.column(), "name");
+
+                return Ok(());
+            }
         }
     }
 
     panic!("expected `UnexpectedNullError`, got {}", err)
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_nullability_overrides() -> anyhow::Result<()> {
+    let mut conn = connect().await?;
+
+    let record = sqlx::query!(
+        r#"SELECT COALESCE(NULL::int, 0) AS "total!", NULL::text AS "note?", 1 + 1 AS "two!""#
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let total: i32 = record.total;
+    let note: Option<String> = record.note;
+    let two: i32 = record.two;
+
+    assert_eq!(total, 0);
+    assert_eq!(note, None);
+    assert_eq!(two, 2);
+
+    #[derive(Debug)]
+    struct Note {
+        id: i32,
+        note: Option<String>,
+    }
+
+    let note = sqlx::query_as!(Note, r#"SELECT 1 AS "id!", 'text' AS "note?""#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(note.id, 1);
+    assert_eq!(note.note.as_deref(), Some("text"));
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, sqlx::Type)]
+#[sqlx(rename_all = "snake_case")]
+enum AccountStatus {
+    Open,
+    Closed,
+}
+
+#[derive(Debug, PartialEq, sqlx::Type)]
+#[repr(i32)]
+enum Tier {
+    Free = 1,
+    Paid = 2,
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_column_type_overrides() -> anyhow::Result<()> {
+    let mut conn = connect().await?;
+
+    #[derive(Debug)]
+    struct Account {
+        status: AccountStatus,
+        tier: Tier,
+        previous_status: Option<AccountStatus>,
+    }
+
+    let account = sqlx::query_as!(
+        Account,
+        r#"SELECT 'closed' AS "status: AccountStatus", 2 AS "tier!: Tier",
+            NULL::text AS "previous_status?: AccountStatus""#
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(account.status, AccountStatus::Closed);
+    assert_eq!(account.tier, Tier::Paid);
+    assert_eq!(account.previous_status, None);
+
+    let record = sqlx::query!(r#"SELECT 'open' AS "status: AccountStatus", 1 AS "tier!: Tier""#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.status, AccountStatus::Open);
+    assert_eq!(record.tier, Tier::Free);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn test_many_args() -> anyhow::Result<()> {
@@ -192,3 +338,93 @@ async fn connect() -> anyhow::Result<PgConnection> {
 
     Ok(PgConnection::connect(dotenv::var("DATABASE_URL")?).await?)
 }
+
+// `tweet` is created by tests/setup/postgres.sql
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_insert_returning_and_update() -> anyhow::Result<()> {
+    use sqlx::Connection;
+
+    let mut conn = connect().await?;
+    let mut tx = conn.begin().await?;
+
+    let tweet = sqlx::query!(
+        "INSERT INTO tweet (text, owner_id) VALUES ($1, $2) RETURNING id, text, owner_id",
+        "Hello, world!",
+        Some(1_i64)
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    let _: i64 = tweet.id;
+    let _: Option<i64> = tweet.owner_id;
+
+    assert_eq!(tweet.text, "Hello, world!");
+
+    let affected = sqlx::query!(
+        "UPDATE tweet SET text = $1 WHERE id = $2",
+        "Goodbye, world!",
+        tweet.id
+    )
+    .execute(&mut tx)
+    .await?;
+
+    assert_eq!(affected, 1);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_nullability_from_describe() -> anyhow::Result<()> {
+    use sqlx::Connection;
+
+    #[derive(Debug)]
+    struct Tweet {
+        id: i64,
+        text: String,
+        owner_id: Option<i64>,
+    }
+
+    let mut conn = connect().await?;
+    let mut tx = conn.begin().await?;
+
+    let id = sqlx::query!(
+        "INSERT INTO tweet (text) VALUES ($1) RETURNING id",
+        "No owner"
+    )
+    .fetch_one(&mut tx)
+    .await?
+    .id;
+
+    // `owner_id` has no `NOT NULL`
+    let tweet = sqlx::query_as!(
+        Tweet,
+        "SELECT id, text, owner_id FROM tweet WHERE id = $1",
+        id
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    assert_eq!(tweet.text, "No owner");
+    assert_eq!(tweet.owner_id, None);
+
+    let record = sqlx::query!(
+        r#"SELECT text, COALESCE(owner_id, 0) AS "owner_id!" FROM tweet WHERE id = $1"#,
+        id
+    )
+    .fetch_one(&mut tx)
+    .await?;
+
+    let text: String = record.text;
+    let owner_id: i64 = record.owner_id;
+
+    assert_eq!(text, "No owner");
+    assert_eq!(owner_id, 0);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
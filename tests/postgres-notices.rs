@@ -0,0 +1,103 @@
+use sqlx::notice::{HandleNotices, NoticeSeverity};
+use sqlx::postgres::PgRow;
+use sqlx::test_util::CaptureNotices;
+use sqlx::{Connect, Executor, PgConnection, Row};
+
+async fn connect(params: &str) -> anyhow::Result<PgConnection> {
+    let _ = dotenv::dotenv();
+
+    let mut url = dotenv::var("DATABASE_URL")?;
+
+    if !params.is_empty() {
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(params);
+    }
+
+    Ok(PgConnection::connect(&*url).await?)
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_captures_raised_notices() -> anyhow::Result<()> {
+    let mut conn = connect("").await?;
+    let notices = CaptureNotices::attach(&mut conn);
+
+    conn.execute(
+        r#"
+DO $$
+BEGIN
+    RAISE NOTICE 'checking balance';
+    RAISE WARNING 'low balance: %', 5 USING ERRCODE = '01L01';
+END
+$$
+        "#,
+    )
+    .await?;
+
+    let captured = notices.take();
+
+    assert_eq!(captured.len(), 2, "{:?}", captured);
+
+    assert_eq!(captured[0].severity(), NoticeSeverity::Notice);
+    assert_eq!(captured[0].code(), "00000");
+    assert_eq!(captured[0].message(), "checking balance");
+
+    assert_eq!(captured[1].severity(), NoticeSeverity::Warning);
+    assert_eq!(captured[1].code(), "01L01");
+    assert_eq!(captured[1].message(), "low balance: 5");
+
+    // a notice raised while rows are read is captured as well, and the rows still arrive
+    conn.execute(
+        r#"
+CREATE OR REPLACE FUNCTION pg_temp.noisy(value INT4) RETURNS INT4 AS $$
+BEGIN
+    RAISE WARNING 'noisy %', value;
+    RETURN value;
+END
+$$ LANGUAGE plpgsql
+        "#,
+    )
+    .await?;
+
+    let value: i32 = sqlx::query("SELECT pg_temp.noisy($1)")
+        .bind(7_i32)
+        .try_map(|row: PgRow| row.try_get(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 7);
+    assert_eq!(
+        notices
+            .take()
+            .iter()
+            .map(|notice| notice.message().to_owned())
+            .collect::<Vec<_>>(),
+        vec!["noisy 7"]
+    );
+
+    // without a handler, notices are dropped
+    conn.remove_notice_handler();
+    conn.execute("DO $$ BEGIN RAISE WARNING 'dropped'; END $$")
+        .await?;
+
+    assert!(notices.notices().is_empty());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_only_receives_notices_down_to_client_min_messages() -> anyhow::Result<()> {
+    let mut conn = connect("client_min_messages=warning").await?;
+    let notices = CaptureNotices::attach(&mut conn);
+
+    conn.execute("DO $$ BEGIN RAISE NOTICE 'quiet'; RAISE WARNING 'loud'; END $$")
+        .await?;
+
+    let captured = notices.take();
+
+    assert_eq!(captured.len(), 1, "{:?}", captured);
+    assert_eq!(captured[0].message(), "loud");
+
+    Ok(())
+}
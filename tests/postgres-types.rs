@@ -1,5 +1,7 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use sqlx::Postgres;
-use sqlx_test::test_type;
+use sqlx_test::{test_null_decoding, test_type};
 
 test_type!(null(
     Postgres,
@@ -7,6 +9,14 @@ test_type!(null(
     "NULL" == None::<i16>
 ));
 
+test_null_decoding!(null_decoding(
+    Postgres,
+    i32: "1::int4" == 1_i32, "NULL::int4";
+    String: "'text'::text" == "text", "NULL::text";
+    Vec<u8>: "'\\xBEEF'::bytea" == vec![0xBE_u8, 0xEF], "NULL::bytea";
+    bool: "true" == true, "NULL::boolean"
+));
+
 test_type!(bool(
     Postgres,
     bool,
@@ -25,6 +35,113 @@ test_type!(f64(
     "939399419.1225182::double precision" == 939399419.1225182_f64
 ));
 
+test_type!(f32_special(
+    Postgres,
+    f32,
+    "'Infinity'::real" == std::f32::INFINITY,
+    "'-Infinity'::real" == std::f32::NEG_INFINITY
+));
+
+test_type!(f64_special(
+    Postgres,
+    f64,
+    "'Infinity'::double precision" == std::f64::INFINITY,
+    "'-Infinity'::double precision" == std::f64::NEG_INFINITY
+));
+
+// NaN never compares equal to itself so it can't go through `test_type!`
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_float_nan() -> anyhow::Result<()> {
+    use sqlx::prelude::*;
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    // text protocol
+    let mut cursor = conn.fetch("SELECT 'NaN'::real, 'NaN'::double precision");
+    let row = cursor.next().await?.unwrap();
+
+    assert!(row.try_get::<f32, _>(0)?.is_nan());
+    assert!(row.try_get::<f64, _>(1)?.is_nan());
+
+    drop(cursor);
+
+    // binary protocol
+    let (is_nan, value): (bool, f64) =
+        sqlx::query_as("SELECT $1 = 'NaN'::double precision, $2::double precision")
+            .bind(std::f64::NAN)
+            .bind(std::f64::NAN)
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert!(is_nan);
+    assert!(value.is_nan());
+
+    Ok(())
+}
+
+test_type!(numeric_lossy(
+    Postgres,
+    sqlx::types::Lossy<f64>,
+    "1.5::numeric" == sqlx::types::Lossy(1.5_f64),
+    "-12345.0625::numeric" == sqlx::types::Lossy(-12345.0625_f64),
+    "0.0001::numeric" == sqlx::types::Lossy(0.0001_f64)
+));
+
+// the exact value is not representable as `f64` and is rounded to the nearest one
+sqlx_test::test_unprepared_type!(numeric_lossy_rounded(
+    Postgres,
+    sqlx::types::Lossy<f64>,
+    "12345678901234567890.123456789::numeric"
+        == sqlx::types::Lossy(1.2345678901234567e19_f64)
+));
+
+// a NUMERIC far beyond the range of an `f64` is an error rather than an infinity
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_numeric_lossy_out_of_range() -> anyhow::Result<()> {
+    use sqlx::prelude::*;
+    use sqlx::types::Lossy;
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    // text protocol
+    let mut cursor = conn.fetch("SELECT '1e400'::numeric, '-1e400'::numeric");
+    let row = cursor.next().await?.unwrap();
+
+    for index in 0..2 {
+        match row.try_get::<Lossy<f64>, _>(index) {
+            Err(sqlx::Error::Decode(err)) => {
+                assert!(err.to_string().contains("out of the range"), "{}", err)
+            }
+            res => panic!("expected a decode error, got {:?}", res),
+        }
+    }
+
+    drop(cursor);
+
+    // binary protocol
+    let res: sqlx::Result<(Lossy<f64>,)> = sqlx::query_as("SELECT '1e400'::numeric")
+        .fetch_one(&mut conn)
+        .await;
+
+    match res {
+        Err(sqlx::Error::Decode(err)) => {
+            assert!(err.to_string().contains("out of the range"), "{}", err)
+        }
+        res => panic!("expected a decode error, got {:?}", res),
+    }
+
+    // while a value that is only imprecise is rounded
+    let (value,): (Lossy<f64>,) = sqlx::query_as("SELECT '1e300'::numeric")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, Lossy(1e300));
+
+    Ok(())
+}
+
 test_type!(string(
     Postgres,
     String,
@@ -32,6 +149,39 @@ test_type!(string(
     "''" == ""
 ));
 
+test_type!(path(
+    Postgres,
+    std::path::PathBuf,
+    "'/var/lib/data'" == std::path::PathBuf::from("/var/lib/data"),
+    "'relative/café'" == std::path::PathBuf::from("relative/café")
+));
+
+// XML has no equality operator, so the prepared query of `test_type!` can't compare it
+sqlx_test::test_unprepared_type!(xml(
+    Postgres,
+    String,
+    "'<item id=\"1\">café</item>'::xml" == "<item id=\"1\">café</item>",
+    "xmlelement(name note, 'a & b')" == "<note>a &amp; b</note>"
+));
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_prepared_type_xml() -> anyhow::Result<()> {
+    use sqlx::prelude::*;
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    // binary protocol; a `String` is sent as text and cast to XML by the query
+    let (value,): (String,) = sqlx::query_as("SELECT $1::xml")
+        .bind("<item>café</item>")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, "<item>café</item>");
+
+    Ok(())
+}
+
 test_type!(bytea(
     Postgres,
     Vec<u8>,
@@ -43,6 +193,182 @@ test_type!(bytea(
         == vec![0_u8, 0, 0, 0, 0x52]
 ));
 
+test_type!(bytea_array(
+    Postgres,
+    [u8; 4],
+    "E'\\\\xDEADBEEF'::bytea" == [0xDE_u8, 0xAD, 0xBE, 0xEF]
+));
+
+sqlx_test::test_unprepared_type!(tsvector(
+    Postgres,
+    sqlx::postgres::PgTsVector,
+    "'''it''''s'' a:1A cat:2,3 fat'::tsvector"
+        == sqlx::postgres::PgTsVector("'a':1A 'cat':2,3 'fat' 'it''s'".into()),
+    "''::tsvector" == sqlx::postgres::PgTsVector::default()
+));
+
+test_type!(tsquery(
+    Postgres,
+    sqlx::postgres::PgTsQuery,
+    "'fat & (rat | cat)'::tsquery" == sqlx::postgres::PgTsQuery("'fat' & ( 'rat' | 'cat' )".into()),
+    "E'\\'it\\'\\'s\\':*AB <2> (a <-> b) | !c'::tsquery"
+        == sqlx::postgres::PgTsQuery("'it''s':*AB <2> ( 'a' <-> 'b' ) | !'c'".into()),
+    "''::tsquery" == sqlx::postgres::PgTsQuery::default()
+));
+
+test_type!(pg_lsn(
+    Postgres,
+    sqlx::postgres::PgLsn,
+    "'16/B374D848'::pg_lsn" == sqlx::postgres::PgLsn(0x16_B374_D848),
+    "'0/0'::pg_lsn" == sqlx::postgres::PgLsn(0),
+    "'FFFFFFFF/FFFFFFFF'::pg_lsn" == sqlx::postgres::PgLsn(u64::max_value())
+));
+
+test_type!(pg_tid(
+    Postgres,
+    sqlx::postgres::PgTid,
+    "'(0,1)'::tid"
+        == sqlx::postgres::PgTid {
+            block: 0,
+            offset: 1
+        },
+    "'(4294967295,65535)'::tid"
+        == sqlx::postgres::PgTid {
+            block: 4_294_967_295,
+            offset: 65535
+        }
+));
+
+test_type!(int4_array(
+    Postgres,
+    Vec<i32>,
+    "'{1,2,3}'::int4[]" == vec![1, 2, 3],
+    "'{}'::int4[]" == Vec::<i32>::new()
+));
+
+test_type!(text_array(
+    Postgres,
+    Vec<Option<String>>,
+    r#"'{"a b","c,\"d\"",NULL}'::text[]"#
+        == vec![Some("a b".to_owned()), Some("c,\"d\"".to_owned()), None]
+));
+
+test_type!(array_of_bytea(
+    Postgres,
+    Vec<Vec<u8>>,
+    "'{\\\\xDEAD,\\\\x}'::bytea[]" == vec![vec![0xDE_u8, 0xAD], vec![]]
+));
+
+test_type!(int4_array_2d(
+    Postgres,
+    Vec<Vec<i32>>,
+    "'{{1,2,3},{4,5,6}}'::int4[]" == vec![vec![1, 2, 3], vec![4, 5, 6]],
+    "'{}'::int4[]" == Vec::<Vec<i32>>::new()
+));
+
+// the lower bounds are dropped when decoding so the round-trip comparison would fail
+sqlx_test::test_unprepared_type!(int4_array_2d_lower_bound(
+    Postgres,
+    Vec<Vec<i32>>,
+    "'[0:1][-1:1]={{1,2,3},{4,5,6}}'::int4[]" == vec![vec![1, 2, 3], vec![4, 5, 6]]
+));
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_array_of_composite_is_unsupported() -> anyhow::Result<()> {
+    use sqlx::prelude::*;
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    // binary protocol
+    let mut cursor = sqlx::query(
+        "SELECT ARRAY[ROW(1, 'a'), ROW(2, 'b')], ARRAY[ROW(1, ARRAY[ROW(2, 'c')])], ARRAY[1, 2]",
+    )
+    .fetch(&mut conn);
+
+    let row = cursor.next().await?.unwrap();
+
+    for index in 0..2 {
+        let err = row
+            .try_get_unchecked::<Vec<String>, _>(index)
+            .unwrap_err()
+            .to_string();
+
+        assert!(
+            err.contains("unsupported: RECORD[] (array of composite `record`)"),
+            "{}",
+            err
+        );
+
+        let err = row
+            .try_get::<Vec<String>, _>(index)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("RECORD[]"), "{}", err);
+    }
+
+    let err = row
+        .try_get_unchecked::<Vec<Vec<String>>, _>(2)
+        .unwrap_err()
+        .to_string();
+
+    assert!(
+        err.contains("unsupported: INT4[] (array of `INT4`) decoded as an array of `TEXT`"),
+        "{}",
+        err
+    );
+
+    assert_eq!(row.try_get_unchecked::<Vec<i64>, _>(2)?, vec![1, 2]);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_array_of_user_defined_type_is_unchecked() -> anyhow::Result<()> {
+    use sqlx::prelude::*;
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    conn.execute("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy')")
+        .await?;
+
+    // binary protocol; the elements are of a type that is not known without a lookup
+    let mut cursor = sqlx::query("SELECT ARRAY['happy', 'sad']::pg_temp.mood[]").fetch(&mut conn);
+
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(
+        row.try_get_unchecked::<Vec<String>, _>(0)?,
+        vec!["happy", "sad"]
+    );
+
+    Ok(())
+}
+
+test_type!(int4range(
+    Postgres,
+    sqlx::postgres::PgRange<i32>,
+    "'[1,5)'::int4range" == sqlx::postgres::PgRange::from(1..5),
+    "'[3,)'::int4range" == sqlx::postgres::PgRange::from(3..),
+    "'empty'::int4range" == sqlx::postgres::PgRange::<i32>::Empty
+));
+
+test_type!(int8range(
+    Postgres,
+    sqlx::postgres::PgRange<i64>,
+    "'(,10)'::int8range" == sqlx::postgres::PgRange::from(..10_i64)
+));
+
+// discrete ranges are canonicalized to an inclusive start and an exclusive end
+sqlx_test::test_unprepared_type!(int4range_canonical(
+    Postgres,
+    sqlx::postgres::PgRange<i32>,
+    "'[1,5]'::int4range" == sqlx::postgres::PgRange::from(1..6),
+    "'(1,5)'::int4range" == sqlx::postgres::PgRange::from(2..5)
+));
+
 #[cfg(feature = "uuid")]
 test_type!(uuid(
     Postgres,
@@ -53,9 +379,65 @@ test_type!(uuid(
         == sqlx::types::Uuid::parse_str("00000000-0000-0000-0000-000000000000").unwrap()
 ));
 
+#[cfg(feature = "uuid")]
+test_type!(uuid_hyphenated(
+    Postgres,
+    sqlx::types::Hyphenated,
+    "'b731678f-636f-4135-bc6f-19440c13bd19'::text"
+        == sqlx::types::Uuid::parse_str("b731678f-636f-4135-bc6f-19440c13bd19")
+            .unwrap()
+            .to_hyphenated()
+));
+
+// a `Uuid` is also read from its hyphenated form in a textual column
+#[cfg(feature = "uuid")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_uuid_from_text() -> anyhow::Result<()> {
+    use sqlx::prelude::*;
+    use sqlx::types::Uuid;
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+    let expected = Uuid::parse_str("b731678f-636f-4135-bc6f-19440c13bd19").unwrap();
+
+    // text protocol
+    let mut cursor = conn.fetch("SELECT 'b731678f-636f-4135-bc6f-19440c13bd19'::varchar");
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.try_get::<Uuid, _>(0)?, expected);
+
+    drop(cursor);
+
+    // binary protocol
+    let (uuid,): (Uuid,) = sqlx::query_as("SELECT $1::text")
+        .bind("b731678f-636f-4135-bc6f-19440c13bd19")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(uuid, expected);
+
+    let malformed = sqlx::query_as::<_, (Uuid,)>("SELECT $1::text")
+        .bind("b731678f-636f-4135-bc6f")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(malformed.is_err());
+
+    Ok(())
+}
+
+test_type!(system_time(
+    Postgres,
+    SystemTime,
+    "TIMESTAMPTZ '2019-12-11 11:01:05.1151+00'"
+        == UNIX_EPOCH + Duration::from_micros(1_576_062_065_115_100),
+    "TIMESTAMPTZ '1960-01-01 00:00:00+00'" == UNIX_EPOCH - Duration::from_secs(315_619_200)
+));
+
 #[cfg(feature = "chrono")]
 mod chrono {
     use super::*;
+    use sqlx::postgres::PgRange;
     use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 
     test_type!(chrono_date(
@@ -77,6 +459,44 @@ mod chrono {
         "'2019-01-02 05:10:20'" == NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20)
     ));
 
+    test_type!(chrono_daterange(
+        Postgres,
+        PgRange<NaiveDate>,
+        "'[2020-01-01,2020-02-01)'::daterange"
+            == PgRange::from(NaiveDate::from_ymd(2020, 1, 1)..NaiveDate::from_ymd(2020, 2, 1)),
+        "'empty'::daterange" == PgRange::<NaiveDate>::Empty
+    ));
+
+    sqlx_test::test_unprepared_type!(chrono_daterange_canonical(
+        Postgres,
+        PgRange<NaiveDate>,
+        "'[2020-01-01,2020-01-31]'::daterange"
+            == PgRange::from(NaiveDate::from_ymd(2020, 1, 1)..NaiveDate::from_ymd(2020, 2, 1))
+    ));
+
+    test_type!(chrono_tsrange(
+        Postgres,
+        PgRange<NaiveDateTime>,
+        "'[2020-01-01 00:00:00,2020-01-01 12:30:00]'::tsrange"
+            == PgRange::from(
+                NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0)
+                    ..=NaiveDate::from_ymd(2020, 1, 1).and_hms(12, 30, 0)
+            )
+    ));
+
+    test_type!(chrono_tstzrange(
+        Postgres,
+        PgRange<DateTime<Utc>>,
+        "'[2020-01-01 00:00:00+00,)'::tstzrange"
+            == PgRange::from(
+                DateTime::<Utc>::from_utc(NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0), Utc)..
+            ),
+        "'(,2020-01-01 12:00:00+02)'::tstzrange"
+            == PgRange::from(
+                ..DateTime::<Utc>::from_utc(NaiveDate::from_ymd(2020, 1, 1).and_hms(10, 0, 0), Utc)
+            )
+    ));
+
     test_type!(chrono_date_time_tz(
         Postgres,
         DateTime::<Utc>,
@@ -87,3 +507,63 @@ mod chrono {
             )
     ));
 }
+
+#[cfg(feature = "macros")]
+sqlx_test::test_derives!(
+    Postgres,
+    repr: ["1::int", "2::int", "3::int"],
+    unknown: "7::int"
+);
+
+// Requires the PostGIS extension; `CREATE EXTENSION postgis`
+#[cfg(feature = "geo")]
+mod geo {
+    use sqlx::postgres::{PgGeometry, PgRow};
+    use sqlx::types::geo::{LineString, Point, Polygon};
+
+    use super::*;
+
+    #[cfg_attr(feature = "runtime-async-std", async_std::test)]
+    #[cfg_attr(feature = "runtime-tokio", tokio::test)]
+    async fn test_geometry() -> anyhow::Result<()> {
+        use sqlx::prelude::*;
+
+        let mut conn = sqlx_test::new::<Postgres>().await?;
+
+        let point = PgGeometry::new(4326, Point::new(1.5, -2.0));
+        let line = LineString::from(vec![(0., 0.), (1., 1.), (2., 0.)]);
+        let polygon = Polygon::new(
+            LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 0.)]),
+            vec![],
+        );
+
+        let (srid, text, rec): (i32, String, PgGeometry<Point<f64>>) =
+            sqlx::query_as("SELECT ST_SRID($1::geometry), ST_AsText($1::geometry), $1::geometry")
+                .bind(&point)
+                .fetch_one(&mut conn)
+                .await?;
+
+        assert_eq!(srid, 4326);
+        assert_eq!(text, "POINT(1.5 -2)");
+        assert_eq!(rec, point);
+
+        let (rec_line, rec_polygon): (LineString<f64>, Polygon<f64>) =
+            sqlx::query_as("SELECT $1::geometry, $2::geometry")
+                .bind(&line)
+                .bind(&polygon)
+                .fetch_one(&mut conn)
+                .await?;
+
+        assert_eq!(rec_line, line);
+        assert_eq!(rec_polygon, polygon);
+
+        // unprepared queries return the EWKB in hex
+        let mut cursor = conn.fetch("SELECT 'SRID=3857;LINESTRING(0 0, 1 1, 2 0)'::geometry");
+        let row: PgRow = cursor.next().await?.unwrap();
+        let rec: PgGeometry<LineString<f64>> = row.try_get(0)?;
+
+        assert_eq!(rec, PgGeometry::new(3857, line));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+-- Tables the MySQL macro tests are checked against when they are compiled; run this on the
+-- database in DATABASE_URL first:
+--
+--     mysql --host <host> --port <port> --user root --password sqlx < tests/setup/mysql.sql
+
+CREATE TABLE IF NOT EXISTS tweet (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    text TEXT NOT NULL,
+    owner_id BIGINT
+);
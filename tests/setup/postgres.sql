@@ -0,0 +1,10 @@
+-- Tables the Postgres macro tests are checked against when they are compiled; run this on the
+-- database in DATABASE_URL first:
+--
+--     psql "$DATABASE_URL" -f tests/setup/postgres.sql
+
+CREATE TABLE IF NOT EXISTS tweet (
+    id BIGSERIAL PRIMARY KEY,
+    text TEXT NOT NULL,
+    owner_id BIGINT
+);
@@ -42,11 +42,27 @@ SELECT id, text FROM _sqlx_test;
 
     let row = cursor.next().await?.unwrap();
 
-    let id: i64 = row.try_get("id")?;
+    // `INT PRIMARY KEY` is not an alias of the rowid, so nothing fills in the id
+    let id: Option<i64> = row.try_get("id")?;
     let text: &str = row.try_get("text")?;
 
-    assert_eq!(0, id);
+    assert_eq!(None, id);
     assert_eq!("this is a test", text);
 
     Ok(())
 }
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_get_or_default_for_null() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let mut cursor = conn.fetch("SELECT NULL AS region, 10 AS total, 'north' AS name");
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.try_get_or("region", "(none)".to_owned())?, "(none)");
+    assert_eq!(row.try_get_or::<i32, _>("total", 0)?, 10);
+    assert_eq!(row.try_get_or("name", "(none)")?, "north");
+
+    Ok(())
+}
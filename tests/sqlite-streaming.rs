@@ -0,0 +1,79 @@
+//! Checks that rows are read one at a time instead of being buffered; this lives in its own test
+//! binary as it counts every allocation made by the process.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use sqlx::{Cursor, Executor, Row, Sqlite};
+use sqlx_test::new;
+
+struct Counter;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[allow(unsafe_code)]
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counter = Counter;
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_streams_rows() -> anyhow::Result<()> {
+    const ROWS: i64 = 1_000_000;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TEMPORARY TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+
+WITH RECURSIVE n (id) AS (SELECT 1 UNION ALL SELECT id + 1 FROM n WHERE id < 1000000)
+INSERT INTO items (id, name) SELECT id, 'item number ' || id FROM n;
+            "#,
+        )
+        .await?;
+
+    // SQLite manages its own memory; only what the driver allocates is counted
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let mut cursor = conn.fetch("SELECT id, name FROM items ORDER BY id");
+    let mut count = 0;
+
+    while let Some(row) = cursor.next().await? {
+        count += 1;
+
+        assert_eq!(row.get::<i64, _>(0), count);
+        assert!(row.get::<&str, _>(1).starts_with("item number "));
+    }
+
+    drop(cursor);
+
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+
+    assert_eq!(count, ROWS);
+
+    // the result is upwards of 20 MiB
+    assert!(peak < 1024 * 1024, "{} bytes were allocated at once", peak);
+
+    Ok(())
+}
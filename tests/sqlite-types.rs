@@ -1,5 +1,5 @@
 use sqlx::Sqlite;
-use sqlx_test::test_type;
+use sqlx_test::{test_null_decoding, test_type};
 
 test_type!(null(
     Sqlite,
@@ -7,6 +7,14 @@ test_type!(null(
     "NULL" == None::<i32>
 ));
 
+test_null_decoding!(null_decoding(
+    Sqlite,
+    i64: "1" == 1_i64, "NULL";
+    String: "'text'" == "text", "NULL";
+    Vec<u8>: "X'BEEF'" == vec![0xBE_u8, 0xEF], "NULL";
+    bool: "TRUE" == true, "NULL"
+));
+
 test_type!(bool(Sqlite, bool, "FALSE" == false, "TRUE" == true));
 
 test_type!(i32(Sqlite, i32, "94101" == 94101_i32));
@@ -34,6 +42,13 @@ test_type!(string(
     "''" == ""
 ));
 
+test_type!(path(
+    Sqlite,
+    std::path::PathBuf,
+    "'/var/lib/data'" == std::path::PathBuf::from("/var/lib/data"),
+    "'relative/café'" == std::path::PathBuf::from("relative/café")
+));
+
 test_type!(bytes(
     Sqlite,
     Vec<u8>,
@@ -44,3 +59,12 @@ test_type!(bytes(
     "X'0000000052'"
         == vec![0_u8, 0, 0, 0, 0x52]
 ));
+
+test_type!(byte_array(
+    Sqlite,
+    [u8; 4],
+    "X'DEADBEEF'" == [0xDE_u8, 0xAD, 0xBE, 0xEF]
+));
+
+#[cfg(feature = "macros")]
+sqlx_test::test_derives!(Sqlite, repr: ["1", "2", "3"], unknown: "7");
@@ -143,3 +143,1021 @@ CREATE TEMPORARY TABLE describe_test (
 
     Ok(())
 }
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_describes_columns_of_tables() -> anyhow::Result<()> {
+    use sqlx::describe::ColumnFlags;
+    use sqlx::types::{Type, TypeInfo};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TEMPORARY TABLE rowid_test (id INTEGER PRIMARY KEY, name TEXT NOT NULL, note TEXT)
+            "#,
+        )
+        .await?;
+
+    let describe = conn
+        .describe("SELECT id, name, note, id + 1 FROM rowid_test")
+        .await?;
+
+    let columns = &describe.result_columns;
+
+    // the rowid alias is always an integer, as `i64` in the macros
+    assert!(<i64 as Type<Sqlite>>::type_info().compatible(&columns[0].type_info));
+    assert_eq!(columns[0].non_null, Some(true));
+    assert_eq!(
+        columns[0].flags,
+        ColumnFlags::PRIMARY_KEY | ColumnFlags::AUTO_INCREMENT
+    );
+
+    // a column of a table that is not STRICT may hold a value of any type
+    assert!(!<String as Type<Sqlite>>::type_info().compatible(&columns[1].type_info));
+    assert_eq!(columns[1].non_null, Some(true));
+    assert_eq!(columns[2].non_null, Some(false));
+
+    // expressions can not be described
+    assert_eq!(columns[3].non_null, None);
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TEMPORARY TABLE strict_test (
+    id INT PRIMARY KEY,
+    weight REAL,
+    name TEXT NOT NULL,
+    data BLOB,
+    anything ANY
+) STRICT, WITHOUT ROWID
+            "#,
+        )
+        .await?;
+
+    let describe = conn
+        .describe("SELECT id, weight, name, data, anything FROM strict_test")
+        .await?;
+
+    let columns = &describe.result_columns;
+
+    assert!(<i64 as Type<Sqlite>>::type_info().compatible(&columns[0].type_info));
+    assert!(<f64 as Type<Sqlite>>::type_info().compatible(&columns[1].type_info));
+    assert!(<String as Type<Sqlite>>::type_info().compatible(&columns[2].type_info));
+    assert!(<Vec<u8> as Type<Sqlite>>::type_info().compatible(&columns[3].type_info));
+    assert!(!<String as Type<Sqlite>>::type_info().compatible(&columns[4].type_info));
+
+    let non_null: Vec<_> = columns.iter().map(|column| column.non_null).collect();
+
+    assert_eq!(
+        non_null,
+        [
+            Some(true),
+            Some(false),
+            Some(true),
+            Some(false),
+            Some(false)
+        ]
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_resets_the_statement_of_a_dropped_cursor() -> anyhow::Result<()> {
+    use sqlx::{Cursor, Row};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TEMPORARY TABLE cursor_test (id INTEGER PRIMARY KEY);
+INSERT INTO cursor_test (id) VALUES (1), (2), (3);
+            "#,
+        )
+        .await?;
+
+    let mut cursor = sqlx::query("SELECT id FROM cursor_test WHERE id > ?")
+        .bind(0_i32)
+        .fetch(&mut conn);
+
+    let row = cursor.next().await?.unwrap();
+    assert_eq!(row.get::<i32, _>(0), 1);
+
+    drop(cursor);
+
+    // fails with "database table is locked" while the statement is still active
+    let _ = conn.execute("DROP TABLE cursor_test").await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_keeps_owned_rows_while_the_cursor_advances() -> anyhow::Result<()> {
+    use sqlx::{sqlite::SqliteRow, Cursor, Row};
+    use sqlx_core::runtime::sleep;
+    use std::time::Duration;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let mut cursor = sqlx::query(
+        "SELECT 1 AS id, 'one' AS name, X'0102' AS data \
+         UNION ALL SELECT 2, NULL, X''",
+    )
+    .fetch(&mut conn);
+
+    let mut rows: Vec<SqliteRow<'static>> = Vec::new();
+
+    while let Some(row) = cursor.next_owned().await? {
+        // the row no longer borrows the cursor, so it may be held across an `.await`
+        sleep(Duration::from_millis(1)).await;
+
+        rows.push(row);
+    }
+
+    drop(cursor);
+
+    assert_eq!(rows.len(), 2);
+
+    assert_eq!(rows[0].get::<i32, _>("id"), 1);
+    assert_eq!(rows[0].get::<String, _>("name"), "one");
+    assert_eq!(rows[0].get::<Vec<u8>, _>("data"), [1, 2]);
+
+    assert_eq!(rows[1].get::<i32, _>(0), 2);
+    assert_eq!(rows[1].get::<Option<String>, _>(1), None);
+    assert_eq!(rows[1].get::<Vec<u8>, _>(2), Vec::<u8>::new());
+
+    // the connection is free for the next query while the rows are still around
+    let (count,): (i32,) = sqlx::query_as("SELECT ?")
+        .bind(rows.len() as i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+sqlx_test::test_large_values!(Sqlite, blob: "BLOB");
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_decodes_byte_arrays_of_the_same_length() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let (hash,): ([u8; 2],) = sqlx::query_as("SELECT X'BEEF'")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(hash, [0xBE, 0xEF]);
+
+    let res = sqlx::query_as::<_, ([u8; 4],)>("SELECT X'BEEF'")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("expected 4 bytes but the value has 2"));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_decodes_integers_as_bool() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let flags: (bool, bool, bool, bool) = sqlx::query_as("SELECT 0, 1, 2, 4294967296")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(flags, (false, true, true, true));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_quotes_identifiers() -> anyhow::Result<()> {
+    use sqlx::quote::Identifier;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let table = Identifier::qualified("temp", r#"t"; DROP TABLE x; --"#).quote::<Sqlite>()?;
+    let column = Identifier::new("c.\"ö\"").quote::<Sqlite>()?;
+
+    let _ = conn
+        .execute(&*format!(
+            "CREATE TABLE {} ({} INTEGER); INSERT INTO {} VALUES (1)",
+            table, column, table
+        ))
+        .await?;
+
+    let (value,): (i32,) = sqlx::query_as(&format!("SELECT {} FROM {}", column, table))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_looks_up_tables() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TABLE accounts (id INTEGER);
+CREATE VIEW "Active Accounts" AS SELECT id FROM accounts;
+CREATE TEMP TABLE scratch (id INTEGER);
+ATTACH DATABASE ':memory:' AS archive;
+CREATE TABLE archive.old_accounts (id INTEGER);
+            "#,
+        )
+        .await?;
+
+    assert_eq!(
+        conn.table_id("accounts").await?.as_deref(),
+        Some("accounts")
+    );
+    assert_eq!(
+        conn.table_id("ACCOUNTS").await?.as_deref(),
+        Some("accounts")
+    );
+    assert_eq!(
+        conn.table_id(r#"main."Active Accounts""#).await?.as_deref(),
+        Some("Active Accounts")
+    );
+
+    assert!(conn.table_exists("main.accounts").await?);
+    assert!(conn.table_exists("[main].`accounts`").await?);
+    assert!(conn.table_exists("scratch").await?);
+    assert!(conn.table_exists("temp.scratch").await?);
+    assert!(conn.table_exists("archive.old_accounts").await?);
+
+    assert!(!conn.table_exists("missing").await?);
+    assert!(!conn.table_exists("main.scratch").await?);
+    assert!(!conn.table_exists("temp.accounts").await?);
+    assert!(!conn.table_exists("nowhere.accounts").await?);
+    assert!(!conn.table_exists("Active Accounts").await?);
+
+    // attached databases are only searched when named
+    assert!(!conn.table_exists("old_accounts").await?);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_describes_tables() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let _ = conn
+        .execute(
+            r#"
+CREATE TEMPORARY TABLE "Accounts" (
+    id BIGINT NOT NULL,
+    "Name" VARCHAR(64) NOT NULL DEFAULT 'anonymous',
+    note TEXT,
+    PRIMARY KEY (id, "Name")
+);
+CREATE TEMPORARY TABLE rowid_test (id INTEGER PRIMARY KEY, note TEXT DEFAULT NULL);
+            "#,
+        )
+        .await?;
+
+    let columns = conn.describe_table(r#"temp."Accounts""#).await?.unwrap();
+
+    // (name, ordinal, nullable, has_default, primary_key)
+    let described: Vec<_> = columns
+        .iter()
+        .map(|column| {
+            (
+                &*column.name,
+                column.ordinal,
+                column.nullable,
+                column.has_default,
+                column.primary_key,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        described,
+        vec![
+            ("id", 1, false, false, true),
+            ("Name", 2, false, true, true),
+            ("note", 3, true, false, false),
+        ]
+    );
+
+    assert_eq!(columns[0].type_info.to_string(), "INTEGER");
+    assert_eq!(columns[1].type_info.to_string(), "TEXT");
+
+    // a row inserted without the rowid alias is given the next rowid
+    let columns = conn.describe_table("ROWID_TEST").await?.unwrap();
+
+    assert!(!columns[0].nullable);
+    assert!(columns[0].has_default);
+    assert!(!columns[1].has_default);
+
+    assert!(conn.describe_table("main.Accounts").await?.is_none());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_reports_the_transaction_status() -> anyhow::Result<()> {
+    use sqlx::TransactionStatus;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+    conn.execute("BEGIN").await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Transaction);
+
+    // continues the open transaction with a savepoint
+    let tx = conn.begin().await?;
+    let mut conn = tx.commit().await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Transaction);
+
+    conn.execute("COMMIT").await?;
+
+    assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_explains_queries() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE explain_test (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+
+    let plan = sqlx::query("SELECT name FROM explain_test WHERE id = ?")
+        .bind(1_i32)
+        .explain(&mut conn)
+        .await?;
+
+    assert_eq!(plan.nodes.len(), 1);
+    assert!(
+        plan.nodes[0].node_type.contains("explain_test"),
+        "{}",
+        plan.nodes[0].node_type
+    );
+    assert!(plan.raw[0]["detail"].is_string());
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+#[derive(Debug)]
+struct ParseVersionError(String);
+
+impl std::fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid version: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseVersionError {}
+
+impl std::str::FromStr for Version {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '.').map(str::parse);
+
+        match (parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor))) => Ok(Version { major, minor }),
+            _ => Err(ParseVersionError(s.to_owned())),
+        }
+    }
+}
+
+impl<'c> sqlx::FromRow<'c, sqlx::sqlite::SqliteRow<'c>> for Version {
+    fn from_row(row: sqlx::sqlite::SqliteRow<'c>) -> sqlx::Result<Self> {
+        use sqlx::Row;
+
+        row.try_get::<&str, _>(0)?
+            .parse()
+            .map_err(sqlx::Error::custom)
+    }
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_returns_errors_of_user_code() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let version: Version = sqlx::query_as("SELECT '1.2'").fetch_one(&mut conn).await?;
+
+    assert_eq!(version, Version { major: 1, minor: 2 });
+
+    let err = sqlx::query_as::<_, Version>("SELECT 'one.two'")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.to_string(), "invalid version: \"one.two\"");
+
+    let source = std::error::Error::source(&err).unwrap();
+
+    assert!(source.downcast_ref::<ParseVersionError>().is_some());
+
+    // boxed errors are converted by `?`
+    let version = sqlx::query("SELECT '3.4'")
+        .try_map(|row: sqlx::sqlite::SqliteRow| {
+            use sqlx::Row;
+
+            let version = row.try_get::<&str, _>(0)?;
+            let parsed: Result<Version, Box<dyn std::error::Error + Send + Sync>> =
+                version.parse().map_err(Into::into);
+
+            Ok(parsed?)
+        })
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(version, Version { major: 3, minor: 4 });
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_reuses_the_arguments_of_previous_queries() -> anyhow::Result<()> {
+    use sqlx::arguments::Arguments;
+    use sqlx::Row;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    for index in 1..=10_i32 {
+        // from the second query on, these are the cleared arguments of the one before
+        let mut arguments = conn.take_arguments();
+        arguments.add(index);
+        arguments.add(format!("user {}", index));
+
+        let cnt = sqlx::query("INSERT INTO users (id, name) VALUES (?, ?)")
+            .bind_all(arguments)
+            .execute(&mut conn)
+            .await?;
+
+        assert_eq!(cnt, 1);
+    }
+
+    // the arguments of a fetched query are kept as well
+    let mut arguments = conn.take_arguments();
+    arguments.add(5_i32);
+
+    let name = sqlx::query("SELECT name FROM users WHERE id = ?")
+        .bind_all(arguments)
+        .try_map(|row: sqlx::sqlite::SqliteRow| row.try_get::<String, _>(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(name, "user 5");
+
+    let mut arguments = conn.take_arguments();
+    arguments.add(7_i32);
+
+    let count = sqlx::query("SELECT COUNT(*) FROM users WHERE id <= ?")
+        .bind_all(arguments)
+        .try_map(|row: sqlx::sqlite::SqliteRow| row.try_get::<i32, _>(0))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 7);
+
+    Ok(())
+}
+
+sqlx_test::test_named_parameters!(Sqlite);
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_executes_queries_without_statements() -> anyhow::Result<()> {
+    use sqlx::Cursor;
+
+    let mut conn = new::<Sqlite>().await?;
+
+    for &query in &["", "  ;  ", "-- comment only"] {
+        assert_eq!(conn.execute(query).await?, 0, "{:?}", query);
+        assert!(conn.fetch(query).next().await?.is_none(), "{:?}", query);
+
+        // prepared
+        assert_eq!(sqlx::query(query).execute(&mut conn).await?, 0);
+        assert!(sqlx::query(query).fetch(&mut conn).next().await?.is_none());
+
+        let describe = conn.describe(query).await?;
+
+        assert!(describe.param_types.is_empty());
+        assert!(describe.result_columns.is_empty());
+    }
+
+    // a trailing `;` only ends the last statement
+    let _ = conn
+        .execute("CREATE TEMP TABLE empty_test (id INTEGER); ; -- done")
+        .await?;
+
+    assert_eq!(
+        conn.execute("INSERT INTO empty_test VALUES (1), (2);  ;")
+            .await?,
+        2
+    );
+
+    let mut cursor = conn.fetch("SELECT id FROM empty_test ORDER BY id; ;");
+
+    assert!(cursor.next().await?.is_some());
+    assert!(cursor.next().await?.is_some());
+    assert!(cursor.next().await?.is_none());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_connects_lazily() -> anyhow::Result<()> {
+    let pool = sqlx::SqlitePool::connect_lazy(&dotenv::var("DATABASE_URL")?);
+
+    assert_eq!(pool.size(), 0);
+
+    let (value,): (i32,) = sqlx::query_as("SELECT 1").fetch_one(&pool).await?;
+
+    assert_eq!(value, 1);
+    assert_eq!(pool.size(), 1);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_hands_connections_to_waiters_in_order() -> anyhow::Result<()> {
+    use sqlx_core::runtime::sleep;
+    use std::time::{Duration, Instant};
+
+    let pool = sqlx::SqlitePool::builder()
+        .max_size(2)
+        .connect_timeout(Duration::from_secs(60))
+        .build(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    // 100 tasks contend for the 2 connections, each taking its turn 10 times
+    let tasks = (0..100).map(|_| {
+        let pool = &pool;
+
+        async move {
+            let mut waits = Vec::new();
+
+            for _ in 0..10 {
+                let start = Instant::now();
+                let conn = pool.acquire().await?;
+
+                waits.push(start.elapsed());
+                sleep(Duration::from_millis(1)).await;
+                drop(conn);
+            }
+
+            Ok::<_, sqlx::Error>(waits)
+        }
+    });
+
+    let mut waits = futures::future::try_join_all(tasks).await?.concat();
+    waits.sort();
+
+    let mean = waits.iter().sum::<Duration>() / waits.len() as u32;
+    let p99 = waits[waits.len() * 99 / 100];
+
+    // every task waits for about as long as the others ahead of it hold the connections
+    assert!(p99 <= mean * 3, "p99 {:?}, mean {:?}", p99, mean);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_keeps_a_connection_handed_to_a_dropped_waiter() -> anyhow::Result<()> {
+    let pool = sqlx::SqlitePool::builder()
+        .max_size(1)
+        .build(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let conn = pool.acquire().await?;
+
+    // the connection is handed to the waiting `acquire`, which is dropped before it is polled
+    // again to take it
+    let mut acquire = Box::pin(pool.acquire());
+    assert!(futures::poll!(&mut acquire).is_pending());
+
+    drop(conn);
+    drop(acquire);
+
+    assert_eq!(pool.size(), 1);
+    assert!(pool.try_acquire().is_some());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_opens_connections_ahead_of_time() -> anyhow::Result<()> {
+    let pool = sqlx::SqlitePool::builder()
+        .min_size(2)
+        .max_size(4)
+        .build_lazy(&dotenv::var("DATABASE_URL")?);
+
+    assert_eq!(pool.size(), 0);
+
+    pool.ready(std::time::Duration::from_secs(5)).await?;
+
+    assert_eq!(pool.size(), 2);
+    assert_eq!(pool.idle(), 2);
+
+    pool.ensure(3).await?;
+
+    assert_eq!(pool.size(), 3);
+    assert_eq!(pool.idle(), 3);
+
+    // never more than `max_size`
+    pool.ensure(10).await?;
+
+    assert_eq!(pool.size(), 4);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_validates_idle_connections_before_acquire() -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicU8, Ordering};
+    use std::sync::Arc;
+
+    // 0 keeps the connection, 1 discards it and 2 fails the acquire
+    let verdict = Arc::new(AtomicU8::new(0));
+
+    let pool = sqlx::SqlitePool::builder()
+        .max_size(1)
+        .before_acquire({
+            let verdict = Arc::clone(&verdict);
+
+            move |conn, meta| {
+                let verdict = verdict.load(Ordering::SeqCst);
+
+                Box::pin(async move {
+                    assert!(meta.age >= meta.idle_for);
+
+                    match verdict {
+                        0 => Ok(conn.execute("SELECT 1").await.is_ok()),
+                        1 => Ok(false),
+                        _ => Err(sqlx::Error::RowNotFound),
+                    }
+                })
+            }
+        })
+        .build(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    // a new connection is not validated
+    let mut conn = pool.acquire().await?;
+    conn.execute("CREATE TEMP TABLE acquired (id INTEGER)")
+        .await?;
+    drop(conn);
+
+    let mut conn = pool.acquire().await?;
+    assert!(conn.execute("SELECT * FROM acquired").await.is_ok());
+    drop(conn);
+
+    // the connection is replaced by a new one, without the temporary table
+    verdict.store(1, Ordering::SeqCst);
+
+    let mut conn = pool.acquire().await?;
+    assert!(conn.execute("SELECT * FROM acquired").await.is_err());
+    drop(conn);
+
+    verdict.store(2, Ordering::SeqCst);
+
+    match pool.acquire().await {
+        Err(sqlx::Error::RowNotFound) => {}
+        other => panic!(
+            "expected the error of the callback, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+
+    assert_eq!(pool.size(), 0);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_counts_the_rows_affected_by_a_cursor() -> anyhow::Result<()> {
+    use sqlx::{Cursor, Row};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let _ = conn
+        .execute("CREATE TEMP TABLE affected_test (id INTEGER)")
+        .await?;
+
+    let mut cursor =
+        conn.fetch("INSERT INTO affected_test VALUES (1), (2), (3); SELECT id FROM affected_test");
+
+    assert_eq!(cursor.rows_affected(), None);
+
+    let mut ids = Vec::new();
+
+    while let Some(row) = cursor.next().await? {
+        ids.push(row.get::<i32, _>(0));
+    }
+
+    assert_eq!(ids, vec![1, 2, 3]);
+
+    // the rows selected are not counted, even though `sqlite3_changes` is still 3
+    assert_eq!(cursor.rows_affected(), Some(3));
+
+    let (ids, count): (Vec<(i32,)>, u64) = sqlx::query_as("SELECT id FROM affected_test")
+        .fetch_all_with_count(&mut conn)
+        .await?;
+
+    assert_eq!(ids.len(), 3);
+    assert_eq!(count, 0);
+
+    let (ids, count) = sqlx::query("DELETE FROM affected_test WHERE id > ?")
+        .bind(1_i32)
+        .try_map(|row: sqlx::sqlite::SqliteRow| row.try_get::<i32, _>(0))
+        .fetch_all_with_count(&mut conn)
+        .await?;
+
+    assert!(ids.is_empty());
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "json", feature = "macros"))]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_derives_from_row_with_json_fields() -> anyhow::Result<()> {
+    use std::error::Error as _;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Prefs {
+        theme: String,
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct User {
+        name: String,
+        #[sqlx(json)]
+        prefs: Prefs,
+        #[sqlx(json, rename = "previous")]
+        previous_prefs: Option<Prefs>,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let user: User = sqlx::query_as(
+        r#"SELECT 'ann' AS name, '{"theme": "dark", "tags": ["a"]}' AS prefs, NULL AS previous"#,
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(user.name, "ann");
+    assert_eq!(
+        user.prefs,
+        Prefs {
+            theme: "dark".into(),
+            tags: vec!["a".into()]
+        }
+    );
+    assert_eq!(user.previous_prefs, None);
+
+    let res: sqlx::Result<User> =
+        sqlx::query_as(r#"SELECT 'bob' AS name, '{"theme": 1}' AS prefs, NULL AS previous"#)
+            .fetch_one(&mut conn)
+            .await;
+
+    match res {
+        Err(sqlx::Error::Decode(err)) => {
+            let message = err.to_string();
+
+            assert!(message.starts_with("User.prefs: "), "{}", message);
+            assert!(message.contains("invalid type: integer `1`"), "{}", message);
+
+            let err = err
+                .downcast_ref::<sqlx::error::FieldDecodeError>()
+                .expect("a FieldDecodeError");
+
+            assert_eq!(err.field(), "User.prefs");
+
+            let err = err
+                .source()
+                .and_then(|err| err.downcast_ref::<sqlx::error::ColumnDecodeError>())
+                .expect("a ColumnDecodeError");
+
+            assert_eq!(err.column(), "prefs");
+            assert!(err.source().unwrap().is::<serde_json::Error>(), "{}", err);
+        }
+        res => panic!("expected a decode error, got {:?}", res),
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_folds_over_borrowed_rows() -> anyhow::Result<()> {
+    use sqlx::{sqlite::SqliteRow, Row};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let (sum, longest) = sqlx::query(
+        "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 1000) \
+         SELECT x, printf('%.*c', x % 7, 'a') FROM n",
+    )
+    .fetch_scan(
+        &mut conn,
+        (0_i64, 0_usize),
+        |(sum, longest), row: &SqliteRow| {
+            *sum += row.try_get::<i64, _>(0)?;
+            *longest = (*longest).max(row.try_get::<&str, _>(1)?.len());
+
+            Ok(())
+        },
+    )
+    .await?;
+
+    assert_eq!(sum, 500_500);
+    assert_eq!(longest, 6);
+
+    // an error of the fold ends the query, and the connection can still be used
+    let res = sqlx::query("SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3")
+        .fetch_scan(&mut conn, 0_i64, |sum, row: &SqliteRow| {
+            *sum += row.try_get::<i64, _>(0)?;
+
+            if *sum > 2 {
+                return Err(sqlx::Error::Decode("too large".into()));
+            }
+
+            Ok(())
+        })
+        .await;
+
+    assert!(res.is_err());
+    assert_eq!(conn.execute("SELECT 1").await?, 0);
+
+    Ok(())
+}
+
+// the bundled SQLite accepts no more than this
+sqlx_test::test_parameter_limit!(Sqlite, 32766);
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_interrupts_a_query_from_another_thread() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+    let interrupt = conn.interrupt_handle();
+
+    let interrupter = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        interrupt.interrupt();
+    });
+
+    // counts forever
+    let res = sqlx::query(
+        "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) SELECT count(*) FROM n",
+    )
+    .execute(&mut conn)
+    .await;
+
+    interrupter.join().unwrap();
+
+    let err = res.unwrap_err();
+    assert!(err.is_interrupted(), "{:?}", err);
+
+    // the connection is still usable
+    let (value,): (i32,) = sqlx::query_as("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    // and interrupting after it is closed does nothing
+    let interrupt = conn.interrupt_handle();
+    drop(conn);
+    interrupt.interrupt();
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_classifies_a_busy_database_as_retryable() -> anyhow::Result<()> {
+    let path = std::env::temp_dir().join(format!("sqlx-busy-{}.db", std::process::id()));
+    let url = format!("sqlite://{}", path.display());
+
+    let mut writer = SqliteConnection::connect(&url).await?;
+    let mut other = SqliteConnection::connect(&url).await?;
+
+    writer.execute("BEGIN IMMEDIATE").await?;
+
+    let err = other.execute("BEGIN IMMEDIATE").await.unwrap_err();
+
+    assert!(err.is_retryable(), "{:?}", err);
+
+    let err = other.execute("SELEC 1").await.unwrap_err();
+
+    assert!(!err.is_retryable(), "{:?}", err);
+
+    writer.execute("COMMIT").await?;
+    other.execute("BEGIN IMMEDIATE; COMMIT").await?;
+
+    drop((writer, other));
+    let _ = std::fs::remove_file(&path);
+
+    Ok(())
+}
+
+sqlx_test::test_message_pack!(Sqlite);
+
+sqlx_test::test_statement_cache_stats!(Sqlite);
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_deserializes_a_database_from_bytes() -> anyhow::Result<()> {
+    static CITIES: &[u8] = include_bytes!("fixtures/cities.db");
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.deserialize("cities", CITIES.to_vec(), true).await?;
+
+    let cities: Vec<(String, i64)> =
+        sqlx::query_as("SELECT name, population FROM cities.city ORDER BY population DESC")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(cities.len(), 3);
+    assert_eq!(cities[0], ("Tokyo".to_owned(), 37_400_068));
+
+    // read only
+    let err = conn.execute("DELETE FROM cities.city").await.unwrap_err();
+
+    assert!(err.to_string().contains("readonly"), "{}", err);
+
+    // the name of an attached database is matched ignoring case, as SQLite does
+    conn.deserialize("Cities", CITIES.to_vec(), false).await?;
+    conn.execute("DELETE FROM cities.city").await?;
+
+    assert!(!conn.serialize("CITIES").await?.is_empty());
+
+    // a writable copy grows past the size of the file
+    conn.deserialize("main", CITIES.to_vec(), false).await?;
+
+    for _ in 0..100 {
+        conn.execute("INSERT INTO city VALUES ('Dhaka', 21006000)")
+            .await?;
+    }
+
+    let data = conn.serialize("main").await?;
+
+    assert!(data.len() > CITIES.len());
+
+    let mut copy = new::<Sqlite>().await?;
+
+    copy.deserialize("main", data, true).await?;
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM city")
+        .fetch_one(&mut copy)
+        .await?;
+
+    assert_eq!(count, 103);
+
+    copy.execute("ATTACH DATABASE ':memory:' AS empty").await?;
+
+    assert_eq!(copy.serialize("empty").await?, Vec::<u8>::new());
+    assert!(copy.serialize("missing").await.is_err());
+
+    Ok(())
+}